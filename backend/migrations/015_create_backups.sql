@@ -0,0 +1,37 @@
+CREATE TABLE IF NOT EXISTS backups (
+    id TEXT PRIMARY KEY NOT NULL,
+    database_id TEXT NOT NULL,
+    status TEXT NOT NULL DEFAULT 'running',
+    kind TEXT NOT NULL DEFAULT 'manual',
+    format TEXT NOT NULL DEFAULT 'custom',
+    file_path TEXT,
+    size_bytes INTEGER,
+    duration_ms INTEGER,
+    checksum TEXT,  -- SHA-256 of the backup file
+    error TEXT,
+    created_at TEXT NOT NULL DEFAULT (datetime('now')),
+    completed_at TEXT,
+    FOREIGN KEY (database_id) REFERENCES databases(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_backups_database_id ON backups(database_id, created_at DESC);
+CREATE INDEX IF NOT EXISTS idx_backups_status ON backups(status);
+
+CREATE TABLE IF NOT EXISTS backup_schedules (
+    database_id TEXT PRIMARY KEY NOT NULL,
+    enabled INTEGER NOT NULL DEFAULT 1,
+    interval_hours INTEGER NOT NULL DEFAULT 24,
+    retention_count INTEGER,
+    retention_days INTEGER,
+    last_run_at TEXT,
+    created_at TEXT NOT NULL DEFAULT (datetime('now')),
+    updated_at TEXT NOT NULL DEFAULT (datetime('now')),
+    FOREIGN KEY (database_id) REFERENCES databases(id) ON DELETE CASCADE
+);
+
+CREATE TRIGGER IF NOT EXISTS backup_schedules_updated_at
+    AFTER UPDATE ON backup_schedules
+    FOR EACH ROW
+BEGIN
+    UPDATE backup_schedules SET updated_at = datetime('now') WHERE database_id = OLD.database_id;
+END;
@@ -0,0 +1,17 @@
+CREATE TABLE IF NOT EXISTS operations (
+    id TEXT PRIMARY KEY NOT NULL,
+    database_id TEXT NOT NULL,
+    kind TEXT NOT NULL,
+    status TEXT NOT NULL DEFAULT 'running',
+    backup_id TEXT,
+    target_database_id TEXT,
+    error TEXT,
+    created_at TEXT NOT NULL DEFAULT (datetime('now')),
+    completed_at TEXT,
+    FOREIGN KEY (database_id) REFERENCES databases(id) ON DELETE CASCADE,
+    FOREIGN KEY (backup_id) REFERENCES backups(id) ON DELETE SET NULL,
+    FOREIGN KEY (target_database_id) REFERENCES databases(id) ON DELETE SET NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_operations_database_id ON operations(database_id, created_at DESC);
+CREATE INDEX IF NOT EXISTS idx_operations_status ON operations(status);
@@ -0,0 +1,5 @@
+-- Opt-in WAL archiving for point-in-time recovery of Postgres databases
+ALTER TABLE databases ADD COLUMN wal_archiving INTEGER NOT NULL DEFAULT 0;
+
+-- Scheduled backups can produce logical dumps ('custom') or physical base backups ('base')
+ALTER TABLE backup_schedules ADD COLUMN format TEXT NOT NULL DEFAULT 'custom';
@@ -0,0 +1,3 @@
+ALTER TABLE databases ADD COLUMN persistence TEXT;
+
+UPDATE databases SET persistence = 'aof' WHERE database_type IN ('redis', 'valkey');
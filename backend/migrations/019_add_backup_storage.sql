@@ -0,0 +1,2 @@
+ALTER TABLE backups ADD COLUMN storage TEXT NOT NULL DEFAULT 'local';
+ALTER TABLE backups ADD COLUMN encrypted INTEGER NOT NULL DEFAULT 0;
@@ -0,0 +1,2 @@
+ALTER TABLE operations ADD COLUMN progress_bytes INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE operations ADD COLUMN total_bytes INTEGER;
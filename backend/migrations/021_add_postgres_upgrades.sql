@@ -0,0 +1,3 @@
+-- The data directory from before a major-version upgrade is kept until the rollback window ends
+ALTER TABLE databases ADD COLUMN previous_postgres_version TEXT;
+ALTER TABLE databases ADD COLUMN rollback_expires_at TIMESTAMP;
@@ -0,0 +1,3 @@
+-- Newer image detected for a database: a later minor version ('minor') or a rebuilt tag ('patch')
+ALTER TABLE databases ADD COLUMN update_image TEXT;
+ALTER TABLE databases ADD COLUMN update_kind TEXT;
@@ -0,0 +1,3 @@
+-- Measured size of each database's data directory and the storage limit state derived from it
+ALTER TABLE databases ADD COLUMN storage_used_bytes INTEGER;
+ALTER TABLE databases ADD COLUMN storage_state TEXT NOT NULL DEFAULT 'ok';
@@ -0,0 +1,3 @@
+-- Ephemeral branches are deleted by the branch reaper once they expire
+ALTER TABLE databases ADD COLUMN expires_at TIMESTAMP;
+CREATE INDEX idx_databases_expires_at ON databases(expires_at);
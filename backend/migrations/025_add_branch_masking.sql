@@ -0,0 +1,4 @@
+-- Masking rule set applied to a branch's data when it was last copied from its parent
+ALTER TABLE databases ADD COLUMN masking_rule_set TEXT;
+ALTER TABLE databases ADD COLUMN masking_rules TEXT;
+ALTER TABLE databases ADD COLUMN masked_at TIMESTAMP;
@@ -0,0 +1,2 @@
+-- Replica branches keep following their parent until they are detached
+ALTER TABLE databases ADD COLUMN is_replica BOOLEAN NOT NULL DEFAULT 0;
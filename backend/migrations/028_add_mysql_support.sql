@@ -0,0 +1,2 @@
+-- MySQL and MariaDB databases keep their release line in the engine-wide
+-- version column added by 034, so they need no columns of their own.
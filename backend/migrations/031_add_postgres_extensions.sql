@@ -0,0 +1,4 @@
+-- Curated image a PostgreSQL database runs on, NULL for the official image
+ALTER TABLE databases ADD COLUMN postgres_variant TEXT;
+-- Comma-separated shared_preload_libraries, NULL for the default of pg_stat_statements
+ALTER TABLE databases ADD COLUMN shared_preload_libraries TEXT;
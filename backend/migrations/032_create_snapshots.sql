@@ -0,0 +1,8 @@
+CREATE TABLE IF NOT EXISTS snapshots (
+    id TEXT PRIMARY KEY NOT NULL,
+    database_id TEXT NOT NULL,
+    name TEXT NOT NULL,
+    created_at TEXT NOT NULL DEFAULT (datetime('now')),
+    FOREIGN KEY (database_id) REFERENCES databases(id) ON DELETE CASCADE,
+    UNIQUE (database_id, name)
+);
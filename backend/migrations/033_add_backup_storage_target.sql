@@ -0,0 +1,3 @@
+-- The storage target a backup was written to, encrypted, so it stays readable after the
+-- project's backup storage settings change
+ALTER TABLE backups ADD COLUMN storage_target TEXT;
@@ -0,0 +1,14 @@
+-- One version column for every engine instead of one per engine
+ALTER TABLE databases ADD COLUMN version TEXT NOT NULL DEFAULT '';
+
+-- Rows without a version of their engine ran its default version
+UPDATE databases SET version = CASE database_type
+    WHEN 'valkey' THEN COALESCE(valkey_version, '8.0')
+    WHEN 'redis' THEN COALESCE(redis_version, '7.4')
+    ELSE postgres_version
+END;
+
+DROP INDEX IF EXISTS idx_databases_redis_version;
+ALTER TABLE databases DROP COLUMN postgres_version;
+ALTER TABLE databases DROP COLUMN valkey_version;
+ALTER TABLE databases DROP COLUMN redis_version;
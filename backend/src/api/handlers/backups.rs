@@ -0,0 +1,529 @@
+use std::sync::Arc;
+
+use axum::{
+    body::Body,
+    extract::{Path, State},
+    http::{header, HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+    Extension, Json,
+};
+use tokio_util::io::ReaderStream;
+
+use crate::api::extractors::AuthUser;
+use crate::domain::models::{
+    AuditAction, AuditEntityType, AuditStatus, BackupResponse, BackupScheduleResponse,
+    CreateBackupRequest, OperationResponse, PitrStatusResponse, PointInTimeBranchRequest,
+    PointInTimeRestoreRequest, RestoreBackupToBranchRequest, UpdateBackupScheduleRequest,
+};
+use crate::domain::services::{AuditLogService, BackupService};
+use crate::error::AppResult;
+
+fn get_client_ip(headers: &HeaderMap) -> Option<String> {
+    headers
+        .get("x-forwarded-for")
+        .and_then(|v| v.to_str().ok())
+        .and_then(|s| s.split(',').next())
+        .map(|s| s.trim().to_string())
+}
+
+fn get_user_agent(headers: &HeaderMap) -> Option<String> {
+    headers
+        .get(header::USER_AGENT)
+        .and_then(|v| v.to_str().ok())
+        .map(|s| s.to_string())
+}
+
+pub type BackupServiceState = Arc<BackupService>;
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/databases/{id}/backups",
+    params(
+        ("id" = String, Path, description = "Database ID")
+    ),
+    responses(
+        (status = 200, description = "List of backups, newest first", body = Vec<BackupResponse>),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden - no access to database"),
+        (status = 404, description = "Database not found")
+    ),
+    tag = "Backups",
+    security(("bearer" = []))
+)]
+pub async fn list_backups(
+    State(backup_service): State<BackupServiceState>,
+    auth_user: AuthUser,
+    Path(id): Path<String>,
+) -> AppResult<Json<Vec<BackupResponse>>> {
+    let backups = backup_service
+        .list(&id, auth_user.id(), auth_user.is_admin())
+        .await?;
+    Ok(Json(backups))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/databases/{id}/backups",
+    params(
+        ("id" = String, Path, description = "Database ID")
+    ),
+    request_body(content = Option<CreateBackupRequest>, description = "Backup format (defaults to `custom` for PostgreSQL and `rdb` for Redis/Valkey)"),
+    responses(
+        (status = 202, description = "Backup started", body = BackupResponse),
+        (status = 400, description = "Backup format not supported for this database"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden - no access to database"),
+        (status = 404, description = "Database not found"),
+        (status = 409, description = "Database not running or backup already in progress")
+    ),
+    tag = "Backups",
+    security(("bearer" = []))
+)]
+pub async fn create_backup(
+    State(backup_service): State<BackupServiceState>,
+    Extension(audit_service): Extension<Arc<AuditLogService>>,
+    headers: HeaderMap,
+    auth_user: AuthUser,
+    Path(id): Path<String>,
+    payload: Option<Json<CreateBackupRequest>>,
+) -> AppResult<(StatusCode, Json<BackupResponse>)> {
+    let format = payload.and_then(|Json(payload)| payload.format);
+    let backup = backup_service
+        .trigger(&id, auth_user.id(), auth_user.is_admin(), format.as_deref())
+        .await?;
+
+    audit_service.log(
+        auth_user.id().to_string(),
+        AuditAction::CreateBackup,
+        AuditEntityType::Backup,
+        Some(backup.id.clone()),
+        Some(serde_json::json!({ "database_id": id, "format": backup.format })),
+        AuditStatus::Success,
+        get_client_ip(&headers),
+        get_user_agent(&headers),
+    );
+
+    Ok((StatusCode::ACCEPTED, Json(backup)))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/databases/{id}/backups/{backup_id}",
+    params(
+        ("id" = String, Path, description = "Database ID"),
+        ("backup_id" = String, Path, description = "Backup ID")
+    ),
+    responses(
+        (status = 200, description = "Backup details", body = BackupResponse),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden - no access to database"),
+        (status = 404, description = "Database or backup not found")
+    ),
+    tag = "Backups",
+    security(("bearer" = []))
+)]
+pub async fn get_backup(
+    State(backup_service): State<BackupServiceState>,
+    auth_user: AuthUser,
+    Path((id, backup_id)): Path<(String, String)>,
+) -> AppResult<Json<BackupResponse>> {
+    let backup = backup_service
+        .get(&id, &backup_id, auth_user.id(), auth_user.is_admin())
+        .await?;
+    Ok(Json(backup))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/databases/{id}/backups/{backup_id}/download",
+    params(
+        ("id" = String, Path, description = "Database ID"),
+        ("backup_id" = String, Path, description = "Backup ID")
+    ),
+    responses(
+        (status = 200, description = "Backup file (pg_dump archive, base backup tar or RDB snapshot)", content_type = "application/octet-stream"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden - no access to database"),
+        (status = 404, description = "Database or backup not found"),
+        (status = 409, description = "Backup not completed")
+    ),
+    tag = "Backups",
+    security(("bearer" = []))
+)]
+pub async fn download_backup(
+    State(backup_service): State<BackupServiceState>,
+    auth_user: AuthUser,
+    Path((id, backup_id)): Path<(String, String)>,
+) -> AppResult<Response> {
+    let download = backup_service
+        .open(&id, &backup_id, auth_user.id(), auth_user.is_admin())
+        .await?;
+
+    let mut response = Body::from_stream(ReaderStream::new(download.file)).into_response();
+    let headers = response.headers_mut();
+    headers.insert(
+        header::CONTENT_TYPE,
+        "application/octet-stream".parse().unwrap(),
+    );
+    if let Ok(value) = format!("attachment; filename=\"{}\"", download.file_name).parse() {
+        headers.insert(header::CONTENT_DISPOSITION, value);
+    }
+    if let Some(size) = download.size_bytes {
+        headers.insert(header::CONTENT_LENGTH, size.into());
+    }
+
+    Ok(response)
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/v1/databases/{id}/backups/{backup_id}",
+    params(
+        ("id" = String, Path, description = "Database ID"),
+        ("backup_id" = String, Path, description = "Backup ID")
+    ),
+    responses(
+        (status = 204, description = "Backup deleted successfully"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden - no access to database"),
+        (status = 404, description = "Database or backup not found"),
+        (status = 409, description = "Backup still running")
+    ),
+    tag = "Backups",
+    security(("bearer" = []))
+)]
+pub async fn delete_backup(
+    State(backup_service): State<BackupServiceState>,
+    Extension(audit_service): Extension<Arc<AuditLogService>>,
+    headers: HeaderMap,
+    auth_user: AuthUser,
+    Path((id, backup_id)): Path<(String, String)>,
+) -> AppResult<StatusCode> {
+    backup_service
+        .delete(&id, &backup_id, auth_user.id(), auth_user.is_admin())
+        .await?;
+
+    audit_service.log(
+        auth_user.id().to_string(),
+        AuditAction::DeleteBackup,
+        AuditEntityType::Backup,
+        Some(backup_id),
+        Some(serde_json::json!({ "database_id": id })),
+        AuditStatus::Success,
+        get_client_ip(&headers),
+        get_user_agent(&headers),
+    );
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/databases/{id}/backups/{backup_id}/restore",
+    params(
+        ("id" = String, Path, description = "Database ID"),
+        ("backup_id" = String, Path, description = "Backup ID")
+    ),
+    responses(
+        (status = 202, description = "Restore started", body = OperationResponse),
+        (status = 400, description = "Backup format cannot be restored"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden - no access to database"),
+        (status = 404, description = "Database or backup not found"),
+        (status = 409, description = "Backup not completed or another operation in progress")
+    ),
+    tag = "Backups",
+    security(("bearer" = []))
+)]
+pub async fn restore_backup(
+    State(backup_service): State<BackupServiceState>,
+    Extension(audit_service): Extension<Arc<AuditLogService>>,
+    headers: HeaderMap,
+    auth_user: AuthUser,
+    Path((id, backup_id)): Path<(String, String)>,
+) -> AppResult<(StatusCode, Json<OperationResponse>)> {
+    let operation = backup_service
+        .restore(&id, &backup_id, auth_user.id(), auth_user.is_admin())
+        .await?;
+
+    audit_service.log(
+        auth_user.id().to_string(),
+        AuditAction::RestoreBackup,
+        AuditEntityType::Database,
+        Some(id),
+        Some(serde_json::json!({
+            "backup_id": backup_id,
+            "operation_id": operation.id,
+        })),
+        AuditStatus::Success,
+        get_client_ip(&headers),
+        get_user_agent(&headers),
+    );
+
+    Ok((StatusCode::ACCEPTED, Json(operation)))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/databases/{id}/backups/{backup_id}/branch",
+    params(
+        ("id" = String, Path, description = "Database ID"),
+        ("backup_id" = String, Path, description = "Backup ID")
+    ),
+    request_body = RestoreBackupToBranchRequest,
+    responses(
+        (status = 202, description = "Branch creation from backup started", body = OperationResponse),
+        (status = 400, description = "Invalid branch name or backup format"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden - no access to database"),
+        (status = 404, description = "Database or backup not found"),
+        (status = 409, description = "Backup not completed or branch already exists")
+    ),
+    tag = "Backups",
+    security(("bearer" = []))
+)]
+pub async fn restore_backup_to_branch(
+    State(backup_service): State<BackupServiceState>,
+    Extension(audit_service): Extension<Arc<AuditLogService>>,
+    headers: HeaderMap,
+    auth_user: AuthUser,
+    Path((id, backup_id)): Path<(String, String)>,
+    Json(payload): Json<RestoreBackupToBranchRequest>,
+) -> AppResult<(StatusCode, Json<OperationResponse>)> {
+    let operation = backup_service
+        .restore_to_branch(
+            &id,
+            &backup_id,
+            auth_user.id(),
+            auth_user.is_admin(),
+            &payload.name,
+        )
+        .await?;
+
+    audit_service.log(
+        auth_user.id().to_string(),
+        AuditAction::RestoreBackupToBranch,
+        AuditEntityType::Branch,
+        operation.target_database_id.clone(),
+        Some(serde_json::json!({
+            "name": payload.name,
+            "parent_id": id,
+            "backup_id": backup_id,
+            "operation_id": operation.id,
+        })),
+        AuditStatus::Success,
+        get_client_ip(&headers),
+        get_user_agent(&headers),
+    );
+
+    Ok((StatusCode::ACCEPTED, Json(operation)))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/databases/{id}/backups/schedule",
+    params(
+        ("id" = String, Path, description = "Database ID")
+    ),
+    responses(
+        (status = 200, description = "Backup schedule and retention policy", body = BackupScheduleResponse),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden - no access to database"),
+        (status = 404, description = "Database not found")
+    ),
+    tag = "Backups",
+    security(("bearer" = []))
+)]
+pub async fn get_backup_schedule(
+    State(backup_service): State<BackupServiceState>,
+    auth_user: AuthUser,
+    Path(id): Path<String>,
+) -> AppResult<Json<BackupScheduleResponse>> {
+    let schedule = backup_service
+        .get_schedule(&id, auth_user.id(), auth_user.is_admin())
+        .await?;
+    Ok(Json(schedule))
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/v1/databases/{id}/backups/schedule",
+    params(
+        ("id" = String, Path, description = "Database ID")
+    ),
+    request_body = UpdateBackupScheduleRequest,
+    responses(
+        (status = 200, description = "Backup schedule updated", body = BackupScheduleResponse),
+        (status = 400, description = "Invalid schedule"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden - no access to database"),
+        (status = 404, description = "Database not found")
+    ),
+    tag = "Backups",
+    security(("bearer" = []))
+)]
+pub async fn update_backup_schedule(
+    State(backup_service): State<BackupServiceState>,
+    Extension(audit_service): Extension<Arc<AuditLogService>>,
+    headers: HeaderMap,
+    auth_user: AuthUser,
+    Path(id): Path<String>,
+    Json(payload): Json<UpdateBackupScheduleRequest>,
+) -> AppResult<Json<BackupScheduleResponse>> {
+    let schedule = backup_service
+        .update_schedule(&id, auth_user.id(), auth_user.is_admin(), payload)
+        .await?;
+
+    audit_service.log(
+        auth_user.id().to_string(),
+        AuditAction::UpdateBackupSchedule,
+        AuditEntityType::Database,
+        Some(id),
+        Some(serde_json::json!({
+            "enabled": schedule.enabled,
+            "interval_hours": schedule.interval_hours,
+            "retention_count": schedule.retention_count,
+            "retention_days": schedule.retention_days,
+            "format": schedule.format,
+        })),
+        AuditStatus::Success,
+        get_client_ip(&headers),
+        get_user_agent(&headers),
+    );
+
+    Ok(Json(schedule))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/databases/{id}/pitr",
+    params(
+        ("id" = String, Path, description = "Database ID")
+    ),
+    responses(
+        (status = 200, description = "Point-in-time recovery window", body = PitrStatusResponse),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden - no access to database"),
+        (status = 404, description = "Database not found")
+    ),
+    tag = "Backups",
+    security(("bearer" = []))
+)]
+pub async fn get_pitr_status(
+    State(backup_service): State<BackupServiceState>,
+    auth_user: AuthUser,
+    Path(id): Path<String>,
+) -> AppResult<Json<PitrStatusResponse>> {
+    let status = backup_service
+        .pitr_status(&id, auth_user.id(), auth_user.is_admin())
+        .await?;
+    Ok(Json(status))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/databases/{id}/pitr/restore",
+    params(
+        ("id" = String, Path, description = "Database ID")
+    ),
+    request_body = PointInTimeRestoreRequest,
+    responses(
+        (status = 202, description = "Point-in-time restore started", body = OperationResponse),
+        (status = 400, description = "WAL archiving disabled or target time outside the recovery window"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden - no access to database"),
+        (status = 404, description = "Database not found"),
+        (status = 409, description = "Another operation in progress")
+    ),
+    tag = "Backups",
+    security(("bearer" = []))
+)]
+pub async fn restore_to_point_in_time(
+    State(backup_service): State<BackupServiceState>,
+    Extension(audit_service): Extension<Arc<AuditLogService>>,
+    headers: HeaderMap,
+    auth_user: AuthUser,
+    Path(id): Path<String>,
+    Json(payload): Json<PointInTimeRestoreRequest>,
+) -> AppResult<(StatusCode, Json<OperationResponse>)> {
+    let operation = backup_service
+        .restore_to_point_in_time(
+            &id,
+            auth_user.id(),
+            auth_user.is_admin(),
+            payload.target_time,
+        )
+        .await?;
+
+    audit_service.log(
+        auth_user.id().to_string(),
+        AuditAction::PointInTimeRestore,
+        AuditEntityType::Database,
+        Some(id),
+        Some(serde_json::json!({
+            "target_time": payload.target_time,
+            "backup_id": operation.backup_id,
+            "operation_id": operation.id,
+        })),
+        AuditStatus::Success,
+        get_client_ip(&headers),
+        get_user_agent(&headers),
+    );
+
+    Ok((StatusCode::ACCEPTED, Json(operation)))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/databases/{id}/pitr/branch",
+    params(
+        ("id" = String, Path, description = "Database ID")
+    ),
+    request_body = PointInTimeBranchRequest,
+    responses(
+        (status = 202, description = "Branch creation at point in time started", body = OperationResponse),
+        (status = 400, description = "Invalid branch name or target time outside the recovery window"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden - no access to database"),
+        (status = 404, description = "Database not found"),
+        (status = 409, description = "Branch already exists")
+    ),
+    tag = "Backups",
+    security(("bearer" = []))
+)]
+pub async fn branch_from_point_in_time(
+    State(backup_service): State<BackupServiceState>,
+    Extension(audit_service): Extension<Arc<AuditLogService>>,
+    headers: HeaderMap,
+    auth_user: AuthUser,
+    Path(id): Path<String>,
+    Json(payload): Json<PointInTimeBranchRequest>,
+) -> AppResult<(StatusCode, Json<OperationResponse>)> {
+    let operation = backup_service
+        .branch_from_point_in_time(
+            &id,
+            auth_user.id(),
+            auth_user.is_admin(),
+            &payload.name,
+            payload.target_time,
+        )
+        .await?;
+
+    audit_service.log(
+        auth_user.id().to_string(),
+        AuditAction::PointInTimeBranch,
+        AuditEntityType::Branch,
+        operation.target_database_id.clone(),
+        Some(serde_json::json!({
+            "name": payload.name,
+            "parent_id": id,
+            "target_time": payload.target_time,
+            "operation_id": operation.id,
+        })),
+        AuditStatus::Success,
+        get_client_ip(&headers),
+        get_user_agent(&headers),
+    );
+
+    Ok((StatusCode::ACCEPTED, Json(operation)))
+}
@@ -0,0 +1,202 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::{Path, State},
+    http::{header, HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+    Extension, Json,
+};
+
+use crate::api::extractors::AuthUser;
+use crate::domain::models::{
+    AuditAction, AuditEntityType, AuditStatus, CreateSnapshotRequest, OperationResponse,
+    ResetBranchRequest, ResetPlan, SnapshotResponse,
+};
+use crate::domain::services::{AuditLogService, BranchService};
+use crate::error::AppResult;
+
+fn get_client_ip(headers: &HeaderMap) -> Option<String> {
+    headers
+        .get("x-forwarded-for")
+        .and_then(|v| v.to_str().ok())
+        .and_then(|s| s.split(',').next())
+        .map(|s| s.trim().to_string())
+}
+
+fn get_user_agent(headers: &HeaderMap) -> Option<String> {
+    headers
+        .get(header::USER_AGENT)
+        .and_then(|v| v.to_str().ok())
+        .map(|s| s.to_string())
+}
+
+pub type BranchServiceState = Arc<BranchService>;
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/databases/{id}/reset",
+    params(
+        ("id" = String, Path, description = "Database ID")
+    ),
+    request_body = ResetBranchRequest,
+    responses(
+        (status = 200, description = "Dry run: what the reset would discard", body = ResetPlan),
+        (status = 202, description = "Reset started", body = OperationResponse),
+        (status = 400, description = "Invalid request - database is root or not running"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden - no access to database"),
+        (status = 404, description = "Database or backup not found"),
+        (status = 409, description = "Another operation in progress")
+    ),
+    tag = "Branches",
+    security(("bearer" = []))
+)]
+pub async fn reset_branch(
+    State(branch_service): State<BranchServiceState>,
+    Extension(audit_service): Extension<Arc<AuditLogService>>,
+    headers: HeaderMap,
+    auth_user: AuthUser,
+    Path(id): Path<String>,
+    Json(payload): Json<ResetBranchRequest>,
+) -> AppResult<Response> {
+    if payload.dry_run {
+        let plan = branch_service
+            .plan_reset(&id, auth_user.id(), auth_user.is_admin(), &payload)
+            .await?;
+        return Ok(Json(plan).into_response());
+    }
+
+    let operation = branch_service
+        .reset(&id, auth_user.id(), auth_user.is_admin(), &payload)
+        .await?;
+
+    audit_service.log(
+        auth_user.id().to_string(),
+        AuditAction::ResetBranch,
+        AuditEntityType::Branch,
+        Some(id),
+        Some(serde_json::json!({
+            "mode": payload.mode,
+            "backup_id": payload.backup_id,
+            "snapshot_name": payload.snapshot_name,
+            "operation_id": operation.id,
+        })),
+        AuditStatus::Success,
+        get_client_ip(&headers),
+        get_user_agent(&headers),
+    );
+
+    Ok((StatusCode::ACCEPTED, Json(operation)).into_response())
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/databases/{id}/snapshots",
+    params(
+        ("id" = String, Path, description = "Database ID")
+    ),
+    responses(
+        (status = 200, description = "Named snapshots of the database, newest first", body = Vec<SnapshotResponse>),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden - no access to database"),
+        (status = 404, description = "Database not found")
+    ),
+    tag = "Branches",
+    security(("bearer" = []))
+)]
+pub async fn list_snapshots(
+    State(branch_service): State<BranchServiceState>,
+    auth_user: AuthUser,
+    Path(id): Path<String>,
+) -> AppResult<Json<Vec<SnapshotResponse>>> {
+    let snapshots = branch_service
+        .list_snapshots(&id, auth_user.id(), auth_user.is_admin())
+        .await?;
+    Ok(Json(snapshots))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/databases/{id}/snapshots",
+    params(
+        ("id" = String, Path, description = "Database ID")
+    ),
+    request_body = CreateSnapshotRequest,
+    responses(
+        (status = 201, description = "Snapshot taken", body = SnapshotResponse),
+        (status = 400, description = "Invalid name, or snapshots are not available for this database"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden - no access to database"),
+        (status = 404, description = "Database not found"),
+        (status = 409, description = "Snapshot name taken or another operation in progress")
+    ),
+    tag = "Branches",
+    security(("bearer" = []))
+)]
+pub async fn create_snapshot(
+    State(branch_service): State<BranchServiceState>,
+    Extension(audit_service): Extension<Arc<AuditLogService>>,
+    headers: HeaderMap,
+    auth_user: AuthUser,
+    Path(id): Path<String>,
+    Json(payload): Json<CreateSnapshotRequest>,
+) -> AppResult<(StatusCode, Json<SnapshotResponse>)> {
+    let snapshot = branch_service
+        .create_snapshot(&id, auth_user.id(), auth_user.is_admin(), &payload.name)
+        .await?;
+
+    audit_service.log(
+        auth_user.id().to_string(),
+        AuditAction::CreateSnapshot,
+        AuditEntityType::Branch,
+        Some(id),
+        Some(serde_json::json!({ "name": snapshot.name, "snapshot_id": snapshot.id })),
+        AuditStatus::Success,
+        get_client_ip(&headers),
+        get_user_agent(&headers),
+    );
+
+    Ok((StatusCode::CREATED, Json(snapshot)))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/v1/databases/{id}/snapshots/{name}",
+    params(
+        ("id" = String, Path, description = "Database ID"),
+        ("name" = String, Path, description = "Snapshot name")
+    ),
+    responses(
+        (status = 204, description = "Snapshot deleted"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden - no access to database"),
+        (status = 404, description = "Database or snapshot not found"),
+        (status = 409, description = "Another operation in progress")
+    ),
+    tag = "Branches",
+    security(("bearer" = []))
+)]
+pub async fn delete_snapshot(
+    State(branch_service): State<BranchServiceState>,
+    Extension(audit_service): Extension<Arc<AuditLogService>>,
+    headers: HeaderMap,
+    auth_user: AuthUser,
+    Path((id, name)): Path<(String, String)>,
+) -> AppResult<StatusCode> {
+    branch_service
+        .delete_snapshot(&id, auth_user.id(), auth_user.is_admin(), &name)
+        .await?;
+
+    audit_service.log(
+        auth_user.id().to_string(),
+        AuditAction::DeleteSnapshot,
+        AuditEntityType::Branch,
+        Some(id),
+        Some(serde_json::json!({ "name": name })),
+        AuditStatus::Success,
+        get_client_ip(&headers),
+        get_user_agent(&headers),
+    );
+
+    Ok(StatusCode::NO_CONTENT)
+}
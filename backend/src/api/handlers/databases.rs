@@ -1,15 +1,17 @@
 use std::sync::Arc;
 
 use axum::{
-    extract::{Path, State},
+    extract::{Path, Query, Request, State},
     http::{header, HeaderMap, StatusCode},
-    Extension, Json,
+    Extension, Json, RequestExt,
 };
 
 use crate::api::extractors::{AuthUser, PaginatedResponse, Pagination};
 use crate::domain::models::{
-    AuditAction, AuditEntityType, AuditStatus, BranchResponse, ChangePasswordRequest,
-    CreateBranchRequest, CreateDatabaseRequest, DatabaseResponse, UpdateDatabaseRequest,
+    AuditAction, AuditEntityType, AuditStatus, BranchResponse, BranchTreeNode,
+    ChangePasswordRequest, ChildBranchPolicy, CreateBranchRequest, CreateDatabaseRequest,
+    DatabaseResponse, DeleteDatabaseQuery, ExtendBranchRequest, ImportUploadResponse,
+    OperationResponse, PromoteBranchRequest, UpdateDatabaseRequest,
 };
 use crate::domain::services::{AuditLogService, DatabaseService};
 use crate::error::{AppError, AppResult};
@@ -62,14 +64,16 @@ pub async fn create_database(
             auth_user.is_admin(),
             &payload.name,
             &payload.database_type,
-            &payload.postgres_version,
-            payload.valkey_version.as_deref(),
-            payload.redis_version.as_deref(),
+            payload.version.as_deref(),
+            payload.postgres_variant,
             payload.password.as_deref(),
             payload.public_exposed,
             payload.cpu_limit,
             payload.memory_limit_mb,
             payload.storage_limit_mb,
+            payload.wal_archiving,
+            payload.persistence.as_deref(),
+            payload.import.as_ref(),
         )
         .await?;
 
@@ -78,7 +82,11 @@ pub async fn create_database(
         AuditAction::CreateDatabase,
         AuditEntityType::Database,
         Some(database.id.clone()),
-        Some(serde_json::json!({ "name": database.name, "type": database.database_type })),
+        Some(serde_json::json!({
+            "name": database.name,
+            "type": database.database_type,
+            "import_operation_id": database.import_operation_id,
+        })),
         AuditStatus::Success,
         get_client_ip(&headers),
         get_user_agent(&headers),
@@ -87,6 +95,57 @@ pub async fn create_database(
     Ok((StatusCode::CREATED, Json(database)))
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/v1/projects/{project_id}/databases/imports",
+    params(
+        ("project_id" = String, Path, description = "Project ID")
+    ),
+    request_body(content = Vec<u8>, description = "pg_dump archive (custom or tar), plain SQL file or RDB snapshot", content_type = "application/octet-stream"),
+    responses(
+        (status = 201, description = "File uploaded; pass upload_id as import.upload_id when creating a database", body = ImportUploadResponse),
+        (status = 400, description = "Empty, interrupted or oversized upload"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden - not project owner")
+    ),
+    tag = "Databases",
+    security(("bearer" = []))
+)]
+pub async fn upload_import(
+    State(database_service): State<DatabaseServiceState>,
+    Extension(audit_service): Extension<Arc<AuditLogService>>,
+    headers: HeaderMap,
+    auth_user: AuthUser,
+    Path(project_id): Path<String>,
+    request: Request,
+) -> AppResult<(StatusCode, Json<ImportUploadResponse>)> {
+    let upload = database_service
+        .upload_import(
+            &project_id,
+            auth_user.id(),
+            auth_user.is_admin(),
+            request.into_limited_body().into_data_stream(),
+        )
+        .await?;
+
+    audit_service.log(
+        auth_user.id().to_string(),
+        AuditAction::UploadImport,
+        AuditEntityType::Project,
+        Some(project_id),
+        Some(serde_json::json!({
+            "upload_id": upload.upload_id,
+            "format": upload.format,
+            "size_bytes": upload.size_bytes,
+        })),
+        AuditStatus::Success,
+        get_client_ip(&headers),
+        get_user_agent(&headers),
+    );
+
+    Ok((StatusCode::CREATED, Json(upload)))
+}
+
 #[utoipa::path(
     get,
     path = "/api/v1/projects/{project_id}/databases",
@@ -265,13 +324,16 @@ pub async fn change_database_password(
     delete,
     path = "/api/v1/databases/{id}",
     params(
-        ("id" = String, Path, description = "Database ID")
+        ("id" = String, Path, description = "Database ID"),
+        ("children" = Option<ChildBranchPolicy>, Query, description = "What to do with the database's branches: refuse (default), cascade or reparent")
     ),
     responses(
         (status = 204, description = "Database deleted successfully"),
+        (status = 400, description = "Default branch cannot re-parent its branches"),
         (status = 401, description = "Unauthorized"),
         (status = 403, description = "Forbidden - no access to database"),
-        (status = 404, description = "Database not found")
+        (status = 404, description = "Database not found"),
+        (status = 409, description = "Database has branches and the policy is refuse")
     ),
     tag = "Databases",
     security(("bearer" = []))
@@ -282,9 +344,10 @@ pub async fn delete_database(
     headers: HeaderMap,
     auth_user: AuthUser,
     Path(id): Path<String>,
+    Query(query): Query<DeleteDatabaseQuery>,
 ) -> AppResult<StatusCode> {
-    database_service
-        .delete(&id, auth_user.id(), auth_user.is_admin())
+    let deleted = database_service
+        .delete(&id, auth_user.id(), auth_user.is_admin(), query.children)
         .await?;
 
     audit_service.log(
@@ -292,7 +355,7 @@ pub async fn delete_database(
         AuditAction::DeleteDatabase,
         AuditEntityType::Database,
         Some(id),
-        None,
+        Some(serde_json::json!({ "children": query.children, "deleted": deleted })),
         AuditStatus::Success,
         get_client_ip(&headers),
         get_user_agent(&headers),
@@ -409,6 +472,32 @@ pub async fn list_branches(
     Ok(Json(branches))
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/v1/databases/{id}/branch-tree",
+    params(
+        ("id" = String, Path, description = "Database ID")
+    ),
+    responses(
+        (status = 200, description = "All branches of the database's lineage, from the root", body = BranchTreeNode),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden - no access to database"),
+        (status = 404, description = "Database not found")
+    ),
+    tag = "Branches",
+    security(("bearer" = []))
+)]
+pub async fn get_branch_tree(
+    State(database_service): State<DatabaseServiceState>,
+    auth_user: AuthUser,
+    Path(id): Path<String>,
+) -> AppResult<Json<BranchTreeNode>> {
+    let tree = database_service
+        .get_branch_tree(&id, auth_user.id(), auth_user.is_admin())
+        .await?;
+    Ok(Json(tree))
+}
+
 #[utoipa::path(
     post,
     path = "/api/v1/databases/{id}/branches",
@@ -417,7 +506,7 @@ pub async fn list_branches(
     ),
     request_body = CreateBranchRequest,
     responses(
-        (status = 201, description = "Branch created successfully", body = DatabaseResponse),
+        (status = 202, description = "Branch creation started; the operation is on the new branch", body = OperationResponse),
         (status = 400, description = "Invalid request"),
         (status = 401, description = "Unauthorized"),
         (status = 403, description = "Forbidden - no access to database"),
@@ -434,14 +523,17 @@ pub async fn create_branch(
     auth_user: AuthUser,
     Path(id): Path<String>,
     Json(payload): Json<CreateBranchRequest>,
-) -> AppResult<(StatusCode, Json<DatabaseResponse>)> {
-    let database = database_service
+) -> AppResult<(StatusCode, Json<OperationResponse>)> {
+    let data = payload.data_options();
+    let operation = database_service
         .create_branch(
             &id,
             auth_user.id(),
             auth_user.is_admin(),
             &payload.name,
-            payload.include_data,
+            &data,
+            payload.expires_at.as_deref(),
+            payload.ttl_seconds,
         )
         .await?;
 
@@ -449,14 +541,21 @@ pub async fn create_branch(
         auth_user.id().to_string(),
         AuditAction::CreateBranch,
         AuditEntityType::Branch,
-        Some(database.id.clone()),
-        Some(serde_json::json!({ "name": payload.name, "parent_id": id })),
+        Some(operation.database_id.clone()),
+        Some(serde_json::json!({
+            "name": payload.name,
+            "parent_id": id,
+            "data_mode": data.mode,
+            "expires_at": payload.expires_at,
+            "ttl_seconds": payload.ttl_seconds,
+            "operation_id": operation.id,
+        })),
         AuditStatus::Success,
         get_client_ip(&headers),
         get_user_agent(&headers),
     );
 
-    Ok((StatusCode::CREATED, Json(database)))
+    Ok((StatusCode::ACCEPTED, Json(operation)))
 }
 
 #[utoipa::path(
@@ -466,11 +565,12 @@ pub async fn create_branch(
         ("id" = String, Path, description = "Database ID")
     ),
     responses(
-        (status = 200, description = "Sync completed successfully", body = DatabaseResponse),
+        (status = 202, description = "Sync started", body = OperationResponse),
         (status = 400, description = "Invalid request - database is root or not running"),
         (status = 401, description = "Unauthorized"),
         (status = 403, description = "Forbidden - no access to database"),
-        (status = 404, description = "Database not found")
+        (status = 404, description = "Database not found"),
+        (status = 409, description = "Another operation is running for the branch")
     ),
     tag = "Branches",
     security(("bearer" = []))
@@ -481,8 +581,8 @@ pub async fn sync_from_parent(
     headers: HeaderMap,
     auth_user: AuthUser,
     Path(id): Path<String>,
-) -> AppResult<Json<DatabaseResponse>> {
-    let database = database_service
+) -> AppResult<(StatusCode, Json<OperationResponse>)> {
+    let operation = database_service
         .sync_from_parent(&id, auth_user.id(), auth_user.is_admin())
         .await?;
 
@@ -491,6 +591,144 @@ pub async fn sync_from_parent(
         AuditAction::SyncFromParent,
         AuditEntityType::Branch,
         Some(id),
+        Some(serde_json::json!({ "operation_id": operation.id })),
+        AuditStatus::Success,
+        get_client_ip(&headers),
+        get_user_agent(&headers),
+    );
+
+    Ok((StatusCode::ACCEPTED, Json(operation)))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/databases/{id}/promote",
+    params(
+        ("id" = String, Path, description = "Database ID")
+    ),
+    request_body = PromoteBranchRequest,
+    responses(
+        (status = 200, description = "Branch promoted to default", body = DatabaseResponse),
+        (status = 400, description = "Invalid request - database is already the default or not running"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden - no access to database"),
+        (status = 404, description = "Database not found")
+    ),
+    tag = "Branches",
+    security(("bearer" = []))
+)]
+pub async fn promote_branch(
+    State(database_service): State<DatabaseServiceState>,
+    Extension(audit_service): Extension<Arc<AuditLogService>>,
+    headers: HeaderMap,
+    auth_user: AuthUser,
+    Path(id): Path<String>,
+    Json(payload): Json<PromoteBranchRequest>,
+) -> AppResult<Json<DatabaseResponse>> {
+    let database = database_service
+        .promote_branch(
+            &id,
+            auth_user.id(),
+            auth_user.is_admin(),
+            payload.keep_endpoint,
+        )
+        .await?;
+
+    audit_service.log(
+        auth_user.id().to_string(),
+        AuditAction::PromoteBranch,
+        AuditEntityType::Branch,
+        Some(id),
+        Some(serde_json::json!({ "keep_endpoint": payload.keep_endpoint })),
+        AuditStatus::Success,
+        get_client_ip(&headers),
+        get_user_agent(&headers),
+    );
+
+    Ok(Json(database))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/databases/{id}/extend",
+    params(
+        ("id" = String, Path, description = "Database ID")
+    ),
+    request_body = ExtendBranchRequest,
+    responses(
+        (status = 200, description = "Branch expiry updated", body = DatabaseResponse),
+        (status = 400, description = "Invalid expiry or database is the default branch"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden - no access to database"),
+        (status = 404, description = "Database not found")
+    ),
+    tag = "Branches",
+    security(("bearer" = []))
+)]
+pub async fn extend_branch(
+    State(database_service): State<DatabaseServiceState>,
+    Extension(audit_service): Extension<Arc<AuditLogService>>,
+    headers: HeaderMap,
+    auth_user: AuthUser,
+    Path(id): Path<String>,
+    Json(payload): Json<ExtendBranchRequest>,
+) -> AppResult<Json<DatabaseResponse>> {
+    let database = database_service
+        .extend_branch(
+            &id,
+            auth_user.id(),
+            auth_user.is_admin(),
+            payload.expires_at.as_deref(),
+            payload.ttl_seconds,
+        )
+        .await?;
+
+    audit_service.log(
+        auth_user.id().to_string(),
+        AuditAction::ExtendBranch,
+        AuditEntityType::Branch,
+        Some(id),
+        Some(serde_json::json!({ "expires_at": database.branch.expires_at })),
+        AuditStatus::Success,
+        get_client_ip(&headers),
+        get_user_agent(&headers),
+    );
+
+    Ok(Json(database))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/databases/{id}/detach",
+    params(
+        ("id" = String, Path, description = "Database ID")
+    ),
+    responses(
+        (status = 200, description = "Replica promoted to an independent branch", body = DatabaseResponse),
+        (status = 400, description = "Database is not a running replica"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden - no access to database"),
+        (status = 404, description = "Database not found")
+    ),
+    tag = "Branches",
+    security(("bearer" = []))
+)]
+pub async fn detach_replica(
+    State(database_service): State<DatabaseServiceState>,
+    Extension(audit_service): Extension<Arc<AuditLogService>>,
+    headers: HeaderMap,
+    auth_user: AuthUser,
+    Path(id): Path<String>,
+) -> AppResult<Json<DatabaseResponse>> {
+    let database = database_service
+        .detach_replica(&id, auth_user.id(), auth_user.is_admin())
+        .await?;
+
+    audit_service.log(
+        auth_user.id().to_string(),
+        AuditAction::DetachReplica,
+        AuditEntityType::Branch,
+        Some(id),
         None,
         AuditStatus::Success,
         get_client_ip(&headers),
@@ -0,0 +1,84 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::{Path, State},
+    http::{header, HeaderMap},
+    Extension, Json,
+};
+
+use crate::api::extractors::AuthUser;
+use crate::api::handlers::DatabaseServiceState;
+use crate::domain::models::{
+    AuditAction, AuditEntityType, AuditStatus, DocumentQueryResult, ExecuteDocumentQueryRequest,
+};
+use crate::domain::services::AuditLogService;
+use crate::error::AppResult;
+
+fn get_client_ip(headers: &HeaderMap) -> Option<String> {
+    headers
+        .get("x-forwarded-for")
+        .and_then(|v| v.to_str().ok())
+        .and_then(|s| s.split(',').next())
+        .map(|s| s.trim().to_string())
+}
+
+fn get_user_agent(headers: &HeaderMap) -> Option<String> {
+    headers
+        .get(header::USER_AGENT)
+        .and_then(|v| v.to_str().ok())
+        .map(|s| s.to_string())
+}
+
+fn summarize_document_query_for_audit(request: &ExecuteDocumentQueryRequest) -> serde_json::Value {
+    serde_json::json!({
+        "operation": request.operation,
+        "database": request.database,
+        "collection": request.collection,
+        "stages": request.pipeline.len(),
+        "timeout_ms": request.timeout_ms,
+    })
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/databases/{id}/documents",
+    params(
+        ("id" = String, Path, description = "Database ID")
+    ),
+    request_body = ExecuteDocumentQueryRequest,
+    responses(
+        (status = 200, description = "Query executed successfully", body = DocumentQueryResult),
+        (status = 400, description = "Query validation failed or database is not MongoDB"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden - no access to database"),
+        (status = 404, description = "Database not found"),
+        (status = 409, description = "Container not running")
+    ),
+    tag = "Documents",
+    security(("bearer" = []))
+)]
+pub async fn execute_document_query(
+    State(database_service): State<DatabaseServiceState>,
+    Extension(audit_service): Extension<Arc<AuditLogService>>,
+    headers: HeaderMap,
+    auth_user: AuthUser,
+    Path(id): Path<String>,
+    Json(payload): Json<ExecuteDocumentQueryRequest>,
+) -> AppResult<Json<DocumentQueryResult>> {
+    let result = database_service
+        .execute_document_query(&id, auth_user.id(), auth_user.is_admin(), &payload)
+        .await?;
+
+    audit_service.log(
+        auth_user.id().to_string(),
+        AuditAction::ExecuteQuery,
+        AuditEntityType::Query,
+        Some(id),
+        Some(summarize_document_query_for_audit(&payload)),
+        AuditStatus::Success,
+        get_client_ip(&headers),
+        get_user_agent(&headers),
+    );
+
+    Ok(Json(result))
+}
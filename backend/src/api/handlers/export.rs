@@ -0,0 +1,100 @@
+use std::sync::Arc;
+
+use axum::{
+    body::Body,
+    extract::{Path, Query, State},
+    http::{header, HeaderMap, HeaderValue},
+    response::{IntoResponse, Response},
+    Extension,
+};
+use futures::TryStreamExt;
+
+use crate::api::extractors::AuthUser;
+use crate::domain::models::{AuditAction, AuditEntityType, AuditStatus, ExportQuery};
+use crate::domain::services::{AuditLogService, ExportService};
+use crate::error::{AppError, AppResult};
+
+fn get_client_ip(headers: &HeaderMap) -> Option<String> {
+    headers
+        .get("x-forwarded-for")
+        .and_then(|v| v.to_str().ok())
+        .and_then(|s| s.split(',').next())
+        .map(|s| s.trim().to_string())
+}
+
+fn get_user_agent(headers: &HeaderMap) -> Option<String> {
+    headers
+        .get(header::USER_AGENT)
+        .and_then(|v| v.to_str().ok())
+        .map(|s| s.to_string())
+}
+
+pub type ExportServiceState = Arc<ExportService>;
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/databases/{id}/export",
+    params(
+        ("id" = String, Path, description = "Database ID"),
+        ("format" = Option<String>, Query, description = "sql (default), custom or csv for PostgreSQL; rdb (default) or json for Redis/Valkey"),
+        ("schema_only" = Option<bool>, Query, description = "Export only the schema (PostgreSQL)"),
+        ("data_only" = Option<bool>, Query, description = "Export only the data (PostgreSQL)"),
+        ("tables" = Option<String>, Query, description = "Comma-separated table patterns to include, with `*` matching any characters in `table` or `schema.table` (PostgreSQL)"),
+        ("exclude_tables" = Option<String>, Query, description = "Comma-separated table patterns to exclude, matched like `tables` (PostgreSQL)")
+    ),
+    responses(
+        (status = 200, description = "Export streamed as a file download", content_type = "application/octet-stream"),
+        (status = 400, description = "Database not running or invalid export options"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden - no access to database"),
+        (status = 404, description = "Database not found")
+    ),
+    tag = "Export",
+    security(("bearer" = []))
+)]
+pub async fn export_database(
+    State(export_service): State<ExportServiceState>,
+    Extension(audit_service): Extension<Arc<AuditLogService>>,
+    headers: HeaderMap,
+    auth_user: AuthUser,
+    Path(id): Path<String>,
+    Query(query): Query<ExportQuery>,
+) -> AppResult<Response> {
+    let export = export_service
+        .export(&id, auth_user.id(), auth_user.is_admin(), &query)
+        .await?;
+    let disposition =
+        HeaderValue::from_str(&format!("attachment; filename=\"{}\"", export.file_name))
+            .map_err(|e| AppError::Internal(format!("Invalid export file name: {}", e)))?;
+
+    audit_service.log(
+        auth_user.id().to_string(),
+        AuditAction::ExportDatabase,
+        AuditEntityType::Database,
+        Some(id),
+        Some(serde_json::json!({
+            "file_name": export.file_name,
+            "format": query.format,
+            "schema_only": query.schema_only,
+            "data_only": query.data_only,
+            "tables": query.tables,
+            "exclude_tables": query.exclude_tables,
+        })),
+        AuditStatus::Success,
+        get_client_ip(&headers),
+        get_user_agent(&headers),
+    );
+
+    let stream = export
+        .stream
+        .map_err(|e| std::io::Error::other(e.to_string()));
+    let mut response = Body::from_stream(stream).into_response();
+    let headers = response.headers_mut();
+    headers.insert(
+        header::CONTENT_TYPE,
+        HeaderValue::from_static(export.content_type),
+    );
+    headers.insert(header::CONTENT_DISPOSITION, disposition);
+
+    Ok(response)
+}
@@ -0,0 +1,215 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::{Path, Query, State},
+    http::{header, HeaderMap, StatusCode},
+    Extension, Json,
+};
+
+use crate::api::extractors::AuthUser;
+use crate::domain::models::{
+    AuditAction, AuditEntityType, AuditStatus, ExtensionInfo, ExtensionsResponse,
+    InstallExtensionRequest, OperationResponse, RemoveExtensionQuery,
+    UpdatePreloadLibrariesRequest,
+};
+use crate::domain::services::{AuditLogService, ExtensionService};
+use crate::error::AppResult;
+
+fn get_client_ip(headers: &HeaderMap) -> Option<String> {
+    headers
+        .get("x-forwarded-for")
+        .and_then(|v| v.to_str().ok())
+        .and_then(|s| s.split(',').next())
+        .map(|s| s.trim().to_string())
+}
+
+fn get_user_agent(headers: &HeaderMap) -> Option<String> {
+    headers
+        .get(header::USER_AGENT)
+        .and_then(|v| v.to_str().ok())
+        .map(|s| s.to_string())
+}
+
+pub type ExtensionServiceState = Arc<ExtensionService>;
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/databases/{id}/extensions",
+    params(
+        ("id" = String, Path, description = "Database ID")
+    ),
+    responses(
+        (status = 200, description = "Extensions the database's image can install", body = ExtensionsResponse),
+        (status = 400, description = "Not a PostgreSQL database"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden - no access to database"),
+        (status = 404, description = "Database not found"),
+        (status = 409, description = "Database is not running")
+    ),
+    tag = "Extensions",
+    security(("bearer" = []))
+)]
+pub async fn list_extensions(
+    State(extension_service): State<ExtensionServiceState>,
+    auth_user: AuthUser,
+    Path(id): Path<String>,
+) -> AppResult<Json<ExtensionsResponse>> {
+    let response = extension_service
+        .list(&id, auth_user.id(), auth_user.is_admin())
+        .await?;
+    Ok(Json(response))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/databases/{id}/extensions",
+    params(
+        ("id" = String, Path, description = "Database ID")
+    ),
+    request_body = InstallExtensionRequest,
+    responses(
+        (status = 201, description = "Extension installed", body = ExtensionInfo),
+        (status = 400, description = "Invalid request or the extension's library is not preloaded"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden - no access to database"),
+        (status = 404, description = "Database not found or extension not available in its image"),
+        (status = 409, description = "Extension already installed or database is not running")
+    ),
+    tag = "Extensions",
+    security(("bearer" = []))
+)]
+pub async fn install_extension(
+    State(extension_service): State<ExtensionServiceState>,
+    Extension(audit_service): Extension<Arc<AuditLogService>>,
+    headers: HeaderMap,
+    auth_user: AuthUser,
+    Path(id): Path<String>,
+    Json(payload): Json<InstallExtensionRequest>,
+) -> AppResult<(StatusCode, Json<ExtensionInfo>)> {
+    let extension = extension_service
+        .install(&id, auth_user.id(), auth_user.is_admin(), &payload)
+        .await?;
+
+    audit_service.log(
+        auth_user.id().to_string(),
+        AuditAction::InstallExtension,
+        AuditEntityType::Database,
+        Some(id),
+        Some(serde_json::json!({
+            "extension": extension.name,
+            "version": extension.installed_version,
+            "cascade": payload.cascade,
+        })),
+        AuditStatus::Success,
+        get_client_ip(&headers),
+        get_user_agent(&headers),
+    );
+
+    Ok((StatusCode::CREATED, Json(extension)))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/v1/databases/{id}/extensions/{name}",
+    params(
+        ("id" = String, Path, description = "Database ID"),
+        ("name" = String, Path, description = "Extension name"),
+        ("cascade" = Option<bool>, Query, description = "Also drop objects that depend on the extension")
+    ),
+    responses(
+        (status = 204, description = "Extension removed"),
+        (status = 400, description = "Extension is required by the platform"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden - no access to database"),
+        (status = 404, description = "Database not found or extension not installed"),
+        (status = 409, description = "Objects depend on the extension or database is not running")
+    ),
+    tag = "Extensions",
+    security(("bearer" = []))
+)]
+pub async fn remove_extension(
+    State(extension_service): State<ExtensionServiceState>,
+    Extension(audit_service): Extension<Arc<AuditLogService>>,
+    headers: HeaderMap,
+    auth_user: AuthUser,
+    Path((id, name)): Path<(String, String)>,
+    Query(query): Query<RemoveExtensionQuery>,
+) -> AppResult<StatusCode> {
+    extension_service
+        .remove(
+            &id,
+            auth_user.id(),
+            auth_user.is_admin(),
+            &name,
+            query.cascade,
+        )
+        .await?;
+
+    audit_service.log(
+        auth_user.id().to_string(),
+        AuditAction::RemoveExtension,
+        AuditEntityType::Database,
+        Some(id),
+        Some(serde_json::json!({
+            "extension": name,
+            "cascade": query.cascade,
+        })),
+        AuditStatus::Success,
+        get_client_ip(&headers),
+        get_user_agent(&headers),
+    );
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/v1/databases/{id}/extensions/preload",
+    params(
+        ("id" = String, Path, description = "Database ID")
+    ),
+    request_body = UpdatePreloadLibrariesRequest,
+    responses(
+        (status = 202, description = "Change started; a running database is restarted", body = OperationResponse),
+        (status = 400, description = "Invalid library name"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden - no access to database"),
+        (status = 404, description = "Database not found"),
+        (status = 409, description = "Database is busy or another operation in progress")
+    ),
+    tag = "Extensions",
+    security(("bearer" = []))
+)]
+pub async fn update_preload_libraries(
+    State(extension_service): State<ExtensionServiceState>,
+    Extension(audit_service): Extension<Arc<AuditLogService>>,
+    headers: HeaderMap,
+    auth_user: AuthUser,
+    Path(id): Path<String>,
+    Json(payload): Json<UpdatePreloadLibrariesRequest>,
+) -> AppResult<(StatusCode, Json<OperationResponse>)> {
+    let operation = extension_service
+        .update_preload_libraries(
+            &id,
+            auth_user.id(),
+            auth_user.is_admin(),
+            &payload.libraries,
+        )
+        .await?;
+
+    audit_service.log(
+        auth_user.id().to_string(),
+        AuditAction::UpdatePreloadLibraries,
+        AuditEntityType::Database,
+        Some(id),
+        Some(serde_json::json!({
+            "libraries": payload.libraries,
+            "operation_id": operation.id,
+        })),
+        AuditStatus::Success,
+        get_client_ip(&headers),
+        get_user_agent(&headers),
+    );
+
+    Ok((StatusCode::ACCEPTED, Json(operation)))
+}
@@ -172,7 +172,8 @@ async fn handle_log_stream(socket: WebSocket, state: LogsState, container_id: St
                 match msg {
                     Ok(Message::Close(_)) => break,
                     Ok(Message::Ping(data)) => {
-                        if sender.send(Message::Pong(data)).await.is_err() {
+                        let pong = sender.send(Message::Pong(data)).await;
+                        if pong.is_err() {
                             break;
                         }
                     }
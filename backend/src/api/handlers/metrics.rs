@@ -205,7 +205,8 @@ async fn handle_metrics_stream(socket: WebSocket, state: MetricsState, database_
                 match msg {
                     Ok(Message::Close(_)) => break,
                     Ok(Message::Ping(data)) => {
-                        if sender.send(Message::Pong(data)).await.is_err() {
+                        let pong = sender.send(Message::Pong(data)).await;
+                        if pong.is_err() {
                             break;
                         }
                     }
@@ -218,7 +219,7 @@ async fn handle_metrics_stream(socket: WebSocket, state: MetricsState, database_
                 match state.metrics_service.get_current_metrics(&database_id).await {
                     Ok(response) => {
                         let msg = MetricsStreamMessage::Metrics {
-                            metrics: response.metrics,
+                            metrics: Box::new(response.metrics),
                         };
                         let json = serde_json::to_string(&msg).unwrap();
                         if sender.send(Message::Text(json.into())).await.is_err() {
@@ -1,27 +1,43 @@
 mod audit_logs;
 mod auth;
+mod backups;
+mod branches;
 mod config;
 mod databases;
+mod documents;
+mod export;
+mod extensions;
 mod health;
 mod kv;
 mod logs;
 mod metrics;
+mod operations;
 mod projects;
 mod sql;
 mod system;
 mod terminal;
+mod updates;
+mod upgrades;
 mod user_admin;
 
 pub use audit_logs::*;
 pub use auth::*;
+pub use backups::*;
+pub use branches::*;
 pub use config::*;
 pub use databases::*;
+pub use documents::*;
+pub use export::*;
+pub use extensions::*;
 pub use health::*;
 pub use kv::*;
 pub use logs::*;
 pub use metrics::*;
+pub use operations::*;
 pub use projects::*;
 pub use sql::*;
 pub use system::*;
 pub use terminal::*;
+pub use updates::*;
+pub use upgrades::*;
 pub use user_admin::*;
@@ -0,0 +1,175 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::{
+        ws::{Message, WebSocket, WebSocketUpgrade},
+        Path, State,
+    },
+    response::IntoResponse,
+    Json,
+};
+use futures::{SinkExt, StreamExt};
+
+use crate::api::extractors::AuthUser;
+use crate::domain::models::{OperationResponse, OperationStreamMessage};
+use crate::domain::services::OperationService;
+use crate::error::AppResult;
+
+pub type OperationServiceState = Arc<OperationService>;
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/databases/{id}/operations",
+    params(
+        ("id" = String, Path, description = "Database ID")
+    ),
+    responses(
+        (status = 200, description = "Operations on the database, newest first", body = Vec<OperationResponse>),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden - no access to database"),
+        (status = 404, description = "Database not found")
+    ),
+    tag = "Operations",
+    security(("bearer" = []))
+)]
+pub async fn list_operations(
+    State(operation_service): State<OperationServiceState>,
+    auth_user: AuthUser,
+    Path(id): Path<String>,
+) -> AppResult<Json<Vec<OperationResponse>>> {
+    let operations = operation_service
+        .list(&id, auth_user.id(), auth_user.is_admin())
+        .await?;
+    Ok(Json(operations))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/databases/{id}/operations/{operation_id}",
+    params(
+        ("id" = String, Path, description = "Database ID"),
+        ("operation_id" = String, Path, description = "Operation ID")
+    ),
+    responses(
+        (status = 200, description = "Operation details", body = OperationResponse),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden - no access to database"),
+        (status = 404, description = "Database or operation not found")
+    ),
+    tag = "Operations",
+    security(("bearer" = []))
+)]
+pub async fn get_operation(
+    State(operation_service): State<OperationServiceState>,
+    auth_user: AuthUser,
+    Path((id, operation_id)): Path<(String, String)>,
+) -> AppResult<Json<OperationResponse>> {
+    let operation = operation_service
+        .get(&id, &operation_id, auth_user.id(), auth_user.is_admin())
+        .await?;
+    Ok(Json(operation))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/databases/{id}/operations/{operation_id}/stream",
+    params(
+        ("id" = String, Path, description = "Database ID"),
+        ("operation_id" = String, Path, description = "Operation ID")
+    ),
+    responses(
+        (status = 101, description = "WebSocket connection established; sends the operation whenever its phase or progress changes and closes once it finishes"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden - no access to database"),
+        (status = 404, description = "Database or operation not found")
+    ),
+    tag = "Operations",
+    security(("bearer" = []))
+)]
+pub async fn stream_operation(
+    State(operation_service): State<OperationServiceState>,
+    auth_user: AuthUser,
+    Path((id, operation_id)): Path<(String, String)>,
+    ws: WebSocketUpgrade,
+) -> AppResult<impl IntoResponse> {
+    operation_service
+        .get(&id, &operation_id, auth_user.id(), auth_user.is_admin())
+        .await?;
+
+    Ok(ws.on_upgrade(move |socket| {
+        handle_operation_stream(socket, operation_service, id, operation_id)
+    }))
+}
+
+async fn handle_operation_stream(
+    socket: WebSocket,
+    operation_service: OperationServiceState,
+    database_id: String,
+    operation_id: String,
+) {
+    let (mut sender, mut receiver) = socket.split();
+
+    let connected_msg = serde_json::to_string(&OperationStreamMessage::Connected {
+        operation_id: operation_id.clone(),
+    })
+    .unwrap();
+
+    if sender
+        .send(Message::Text(connected_msg.into()))
+        .await
+        .is_err()
+    {
+        return;
+    }
+
+    let mut poll_interval = tokio::time::interval(std::time::Duration::from_millis(500));
+    let mut last_sent: Option<String> = None;
+
+    loop {
+        tokio::select! {
+            Some(msg) = receiver.next() => {
+                match msg {
+                    Ok(Message::Close(_)) => break,
+                    Ok(Message::Ping(data)) => {
+                        let pong = sender.send(Message::Pong(data)).await;
+                        if pong.is_err() {
+                            break;
+                        }
+                    }
+                    Err(_) => break,
+                    _ => {}
+                }
+            }
+
+            _ = poll_interval.tick() => {
+                match operation_service.find(&database_id, &operation_id).await {
+                    Ok(operation) => {
+                        let finished = operation.is_finished();
+                        let json = serde_json::to_string(&OperationStreamMessage::Operation {
+                            operation: Box::new(operation),
+                        })
+                        .unwrap();
+                        if last_sent.as_deref() != Some(json.as_str()) {
+                            if sender.send(Message::Text(json.clone().into())).await.is_err() {
+                                break;
+                            }
+                            last_sent = Some(json);
+                        }
+                        if finished {
+                            let _ = sender.send(Message::Close(None)).await;
+                            break;
+                        }
+                    }
+                    Err(e) => {
+                        let msg = OperationStreamMessage::Error {
+                            message: e.to_string(),
+                        };
+                        let json = serde_json::to_string(&msg).unwrap();
+                        let _ = sender.send(Message::Text(json.into())).await;
+                        break;
+                    }
+                }
+            }
+        }
+    }
+}
@@ -9,7 +9,8 @@ use axum::{
 use crate::api::extractors::AuthUser;
 use crate::domain::models::{AuditAction, AuditEntityType, AuditStatus};
 use crate::domain::models::{
-    ExecuteQueryRequest, QueryResult, SchemaInfo, TablePreview, TablePreviewQuery,
+    ExecuteQueryRequest, QueryResult, SchemaDiff, SchemaDiffQuery, SchemaInfo, TablePreview,
+    TablePreviewQuery,
 };
 use crate::domain::services::{AuditLogService, SqlService};
 use crate::error::{AppError, AppResult};
@@ -78,6 +79,44 @@ pub async fn get_database_schema(
     Ok(Json(schema))
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/v1/databases/{id}/diff",
+    params(
+        ("id" = String, Path, description = "Database ID"),
+        ("against" = Option<String>, Query, description = "`parent` (default) or the ID of another database in the same project")
+    ),
+    responses(
+        (status = 200, description = "Schema differences and the DDL to apply them", body = SchemaDiff),
+        (status = 400, description = "Database not running, not a branch or in another project"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden - no access to database"),
+        (status = 404, description = "Database not found")
+    ),
+    tag = "SQL",
+    security(("bearer" = []))
+)]
+pub async fn get_schema_diff(
+    State(state): State<SqlState>,
+    auth_user: AuthUser,
+    Path(id): Path<String>,
+    Query(query): Query<SchemaDiffQuery>,
+) -> AppResult<Json<SchemaDiff>> {
+    if !state
+        .sql_service
+        .check_access(&id, auth_user.id(), auth_user.is_admin())
+        .await?
+    {
+        return Err(AppError::Forbidden);
+    }
+
+    let diff = state
+        .sql_service
+        .diff_schema(&id, query.against.as_deref())
+        .await?;
+    Ok(Json(diff))
+}
+
 #[utoipa::path(
     post,
     path = "/api/v1/databases/{id}/query",
@@ -0,0 +1,70 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::{Path, State},
+    http::{header, HeaderMap, StatusCode},
+    Extension, Json,
+};
+
+use crate::api::extractors::AuthUser;
+use crate::domain::models::{AuditAction, AuditEntityType, AuditStatus, OperationResponse};
+use crate::domain::services::{AuditLogService, UpdateService};
+use crate::error::AppResult;
+
+fn get_client_ip(headers: &HeaderMap) -> Option<String> {
+    headers
+        .get("x-forwarded-for")
+        .and_then(|v| v.to_str().ok())
+        .and_then(|s| s.split(',').next())
+        .map(|s| s.trim().to_string())
+}
+
+fn get_user_agent(headers: &HeaderMap) -> Option<String> {
+    headers
+        .get(header::USER_AGENT)
+        .and_then(|v| v.to_str().ok())
+        .map(|s| s.to_string())
+}
+
+pub type UpdateServiceState = Arc<UpdateService>;
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/databases/{id}/update",
+    params(
+        ("id" = String, Path, description = "Database ID")
+    ),
+    responses(
+        (status = 202, description = "Update started", body = OperationResponse),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden - no access to database"),
+        (status = 404, description = "Database not found"),
+        (status = 409, description = "No update available or another operation in progress")
+    ),
+    tag = "Updates",
+    security(("bearer" = []))
+)]
+pub async fn apply_update(
+    State(update_service): State<UpdateServiceState>,
+    Extension(audit_service): Extension<Arc<AuditLogService>>,
+    headers: HeaderMap,
+    auth_user: AuthUser,
+    Path(id): Path<String>,
+) -> AppResult<(StatusCode, Json<OperationResponse>)> {
+    let operation = update_service
+        .apply(&id, auth_user.id(), auth_user.is_admin())
+        .await?;
+
+    audit_service.log(
+        auth_user.id().to_string(),
+        AuditAction::ApplyUpdate,
+        AuditEntityType::Database,
+        Some(id),
+        Some(serde_json::json!({ "operation_id": operation.id })),
+        AuditStatus::Success,
+        get_client_ip(&headers),
+        get_user_agent(&headers),
+    );
+
+    Ok((StatusCode::ACCEPTED, Json(operation)))
+}
@@ -0,0 +1,124 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::{Path, State},
+    http::{header, HeaderMap, StatusCode},
+    Extension, Json,
+};
+
+use crate::api::extractors::AuthUser;
+use crate::domain::models::{
+    AuditAction, AuditEntityType, AuditStatus, OperationResponse, UpgradeDatabaseRequest,
+};
+use crate::domain::services::{AuditLogService, UpgradeService};
+use crate::error::AppResult;
+
+fn get_client_ip(headers: &HeaderMap) -> Option<String> {
+    headers
+        .get("x-forwarded-for")
+        .and_then(|v| v.to_str().ok())
+        .and_then(|s| s.split(',').next())
+        .map(|s| s.trim().to_string())
+}
+
+fn get_user_agent(headers: &HeaderMap) -> Option<String> {
+    headers
+        .get(header::USER_AGENT)
+        .and_then(|v| v.to_str().ok())
+        .map(|s| s.to_string())
+}
+
+pub type UpgradeServiceState = Arc<UpgradeService>;
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/databases/{id}/upgrade",
+    params(
+        ("id" = String, Path, description = "Database ID")
+    ),
+    request_body = UpgradeDatabaseRequest,
+    responses(
+        (status = 202, description = "Upgrade started", body = OperationResponse),
+        (status = 400, description = "Invalid target version or not a PostgreSQL database"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden - no access to database"),
+        (status = 404, description = "Database not found"),
+        (status = 409, description = "Another operation in progress")
+    ),
+    tag = "Upgrades",
+    security(("bearer" = []))
+)]
+pub async fn upgrade_database(
+    State(upgrade_service): State<UpgradeServiceState>,
+    Extension(audit_service): Extension<Arc<AuditLogService>>,
+    headers: HeaderMap,
+    auth_user: AuthUser,
+    Path(id): Path<String>,
+    Json(payload): Json<UpgradeDatabaseRequest>,
+) -> AppResult<(StatusCode, Json<OperationResponse>)> {
+    let operation = upgrade_service
+        .upgrade(
+            &id,
+            auth_user.id(),
+            auth_user.is_admin(),
+            &payload.target_version,
+        )
+        .await?;
+
+    audit_service.log(
+        auth_user.id().to_string(),
+        AuditAction::UpgradeDatabase,
+        AuditEntityType::Database,
+        Some(id),
+        Some(serde_json::json!({
+            "target_version": payload.target_version,
+            "operation_id": operation.id,
+        })),
+        AuditStatus::Success,
+        get_client_ip(&headers),
+        get_user_agent(&headers),
+    );
+
+    Ok((StatusCode::ACCEPTED, Json(operation)))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/databases/{id}/upgrade/rollback",
+    params(
+        ("id" = String, Path, description = "Database ID")
+    ),
+    responses(
+        (status = 202, description = "Rollback started; changes made since the upgrade are discarded", body = OperationResponse),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden - no access to database"),
+        (status = 404, description = "Database not found"),
+        (status = 409, description = "No upgrade to roll back, rollback window ended or another operation in progress")
+    ),
+    tag = "Upgrades",
+    security(("bearer" = []))
+)]
+pub async fn rollback_upgrade(
+    State(upgrade_service): State<UpgradeServiceState>,
+    Extension(audit_service): Extension<Arc<AuditLogService>>,
+    headers: HeaderMap,
+    auth_user: AuthUser,
+    Path(id): Path<String>,
+) -> AppResult<(StatusCode, Json<OperationResponse>)> {
+    let operation = upgrade_service
+        .rollback(&id, auth_user.id(), auth_user.is_admin())
+        .await?;
+
+    audit_service.log(
+        auth_user.id().to_string(),
+        AuditAction::RollbackUpgrade,
+        AuditEntityType::Database,
+        Some(id),
+        Some(serde_json::json!({ "operation_id": operation.id })),
+        AuditStatus::Success,
+        get_client_ip(&headers),
+        get_user_agent(&headers),
+    );
+
+    Ok((StatusCode::ACCEPTED, Json(operation)))
+}
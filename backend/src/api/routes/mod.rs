@@ -2,8 +2,9 @@ use std::sync::Arc;
 
 use axum::http::{header, HeaderValue, Method};
 use axum::{
+    extract::DefaultBodyLimit,
     middleware,
-    routing::{get, post},
+    routing::{delete, get, post, put},
     Extension, Router,
 };
 use sqlx::sqlite::SqlitePool;
@@ -12,13 +13,13 @@ use tower_http::services::{ServeDir, ServeFile};
 use tower_http::trace::TraceLayer;
 
 use crate::api::handlers::{
-    self, AuditLogServiceState, AuthServiceState, DatabaseServiceState, HealthState, LogsState,
-    MetricsState, ProjectServiceState, SqlState, TerminalState, UserAdminState,
+    self, AuditLogServiceState, AuthServiceState, BackupServiceState, BranchServiceState,
+    DatabaseServiceState, ExportServiceState, ExtensionServiceState, HealthState, LogsState,
+    MetricsState, OperationServiceState, ProjectServiceState, SqlState, TerminalState,
+    UpdateServiceState, UpgradeServiceState, UserAdminState,
 };
 use crate::config::Settings;
-use crate::domain::services::{
-    AuditLogService, AuthService, DatabaseService, MetricsService, ProjectService, SqlService,
-};
+use crate::domain::services::AppServices;
 use crate::infrastructure::docker::DockerManager;
 use crate::middleware::{
     admin_middleware, auth_middleware, auth_rate_limit_middleware, rate_limit_middleware,
@@ -28,49 +29,25 @@ use crate::repositories::Repositories;
 
 pub async fn create_router(
     db_pool: SqlitePool,
-    docker: DockerManager,
+    docker: Arc<DockerManager>,
     settings: Arc<Settings>,
+    services: &AppServices,
 ) -> Router {
     let repositories = Repositories::new(db_pool.clone());
 
-    let auth_service = Arc::new(AuthService::new(
-        repositories.users.clone(),
-        repositories.tokens.clone(),
-        settings.clone(),
-    ));
-
-    let project_service = Arc::new(ProjectService::new(
-        repositories.projects.clone(),
-        repositories.databases.clone(),
-    ));
-
-    let docker = Arc::new(docker);
-
-    let database_service = Arc::new(DatabaseService::new(
-        repositories.databases.clone(),
-        repositories.projects.clone(),
-        docker.clone(),
-        settings.docker.data_dir.clone(),
-        settings.docker.public_host.clone(),
-        &settings.security.encryption_key,
-    ));
-
-    let metrics_service = Arc::new(MetricsService::new(
-        repositories.databases.clone(),
-        repositories.projects.clone(),
-        repositories.metrics.clone(),
-        docker.clone(),
-        &settings.security.encryption_key,
-    ));
-
-    let sql_service = Arc::new(SqlService::new(
-        repositories.databases.clone(),
-        repositories.projects.clone(),
-        docker.clone(),
-        &settings.security.encryption_key,
-    ));
-
-    let audit_log_service = Arc::new(AuditLogService::new(repositories.audit_logs.clone()));
+    let auth_service = services.auth.clone();
+    let project_service = services.project.clone();
+    let database_service = services.database.clone();
+    let metrics_service = services.metrics.clone();
+    let sql_service = services.sql.clone();
+    let backup_service = services.backup.clone();
+    let export_service = services.export.clone();
+    let upgrade_service = services.upgrade.clone();
+    let extension_service = services.extension.clone();
+    let update_service = services.update.clone();
+    let branch_service = services.branch.clone();
+    let operation_service = services.operation.clone();
+    let audit_log_service = services.audit_log.clone();
 
     let auth_state = AuthState {
         auth_service: auth_service.clone(),
@@ -89,8 +66,14 @@ pub async fn create_router(
             get(handlers::system_info).with_state(settings.docker.public_host.clone()),
         )
         .route("/postgres-versions", get(handlers::get_postgres_versions))
+        .route("/postgres-variants", get(handlers::get_postgres_variants))
         .route("/valkey-versions", get(handlers::get_valkey_versions))
-        .route("/redis-versions", get(handlers::get_redis_versions));
+        .route("/redis-versions", get(handlers::get_redis_versions))
+        .route("/mongo-versions", get(handlers::get_mongo_versions))
+        .route(
+            "/clickhouse-versions",
+            get(handlers::get_clickhouse_versions),
+        );
 
     let public_routes = Router::new()
         .route("/health", get(handlers::health))
@@ -134,6 +117,12 @@ pub async fn create_router(
             "/",
             get(handlers::list_databases).post(handlers::create_database),
         )
+        .route(
+            "/imports",
+            post(handlers::upload_import).layer(DefaultBodyLimit::max(
+                settings.server.max_upload_mb * 1024 * 1024,
+            )),
+        )
         .with_state(database_service.clone() as DatabaseServiceState);
 
     let logs_state = LogsState {
@@ -170,6 +159,7 @@ pub async fn create_router(
         .route("/{id}/start", post(handlers::start_database))
         .route("/{id}/stop", post(handlers::stop_database))
         .route("/{id}/kv", post(handlers::execute_kv_command))
+        .route("/{id}/documents", post(handlers::execute_document_query))
         .route(
             "/{id}/change-password",
             post(handlers::change_database_password),
@@ -182,7 +172,11 @@ pub async fn create_router(
             "/{id}/branches",
             get(handlers::list_branches).post(handlers::create_branch),
         )
+        .route("/{id}/branch-tree", get(handlers::get_branch_tree))
         .route("/{id}/sync-from-parent", post(handlers::sync_from_parent))
+        .route("/{id}/promote", post(handlers::promote_branch))
+        .route("/{id}/extend", post(handlers::extend_branch))
+        .route("/{id}/detach", post(handlers::detach_replica))
         .with_state(database_service.clone() as DatabaseServiceState);
 
     let logs_routes = Router::new()
@@ -192,6 +186,7 @@ pub async fn create_router(
 
     let terminal_routes = Router::new()
         .route("/{id}/terminal", get(handlers::database_terminal))
+        .route("/{id}/cli", get(handlers::database_cli))
         .route("/{id}/psql", get(handlers::database_psql))
         .route("/{id}/valkey-cli", get(handlers::database_valkey_cli))
         .route("/{id}/redis-cli", get(handlers::database_redis_cli))
@@ -212,6 +207,7 @@ pub async fn create_router(
 
     let sql_routes = Router::new()
         .route("/{id}/schema", get(handlers::get_database_schema))
+        .route("/{id}/diff", get(handlers::get_schema_diff))
         .route("/{id}/query", post(handlers::execute_query))
         .route(
             "/{id}/tables/{schema}/{table}/preview",
@@ -219,6 +215,91 @@ pub async fn create_router(
         )
         .with_state(sql_state);
 
+    let backup_routes = Router::new()
+        .route(
+            "/{id}/backups",
+            get(handlers::list_backups).post(handlers::create_backup),
+        )
+        .route(
+            "/{id}/backups/schedule",
+            get(handlers::get_backup_schedule).put(handlers::update_backup_schedule),
+        )
+        .route(
+            "/{id}/backups/{backup_id}",
+            get(handlers::get_backup).delete(handlers::delete_backup),
+        )
+        .route(
+            "/{id}/backups/{backup_id}/download",
+            get(handlers::download_backup),
+        )
+        .route(
+            "/{id}/backups/{backup_id}/restore",
+            post(handlers::restore_backup),
+        )
+        .route(
+            "/{id}/backups/{backup_id}/branch",
+            post(handlers::restore_backup_to_branch),
+        )
+        .route("/{id}/pitr", get(handlers::get_pitr_status))
+        .route(
+            "/{id}/pitr/restore",
+            post(handlers::restore_to_point_in_time),
+        )
+        .route(
+            "/{id}/pitr/branch",
+            post(handlers::branch_from_point_in_time),
+        )
+        .with_state(backup_service.clone() as BackupServiceState);
+
+    let export_routes = Router::new()
+        .route("/{id}/export", get(handlers::export_database))
+        .with_state(export_service as ExportServiceState);
+
+    let upgrade_routes = Router::new()
+        .route("/{id}/upgrade", post(handlers::upgrade_database))
+        .route("/{id}/upgrade/rollback", post(handlers::rollback_upgrade))
+        .with_state(upgrade_service as UpgradeServiceState);
+
+    let extension_routes = Router::new()
+        .route(
+            "/{id}/extensions",
+            get(handlers::list_extensions).post(handlers::install_extension),
+        )
+        .route(
+            "/{id}/extensions/preload",
+            put(handlers::update_preload_libraries),
+        )
+        .route(
+            "/{id}/extensions/{name}",
+            delete(handlers::remove_extension),
+        )
+        .with_state(extension_service as ExtensionServiceState);
+
+    let update_routes = Router::new()
+        .route("/{id}/update", post(handlers::apply_update))
+        .with_state(update_service as UpdateServiceState);
+
+    let branch_routes = Router::new()
+        .route("/{id}/reset", post(handlers::reset_branch))
+        .route(
+            "/{id}/snapshots",
+            get(handlers::list_snapshots).post(handlers::create_snapshot),
+        )
+        .route("/{id}/snapshots/{name}", delete(handlers::delete_snapshot))
+        .with_state(branch_service as BranchServiceState);
+
+    let operation_routes = Router::new()
+        .route("/{id}/operations", get(handlers::list_operations))
+        .route(
+            "/{id}/operations/{operation_id}",
+            get(handlers::get_operation),
+        )
+        .route(
+            "/{id}/operations/{operation_id}/stream",
+            get(handlers::stream_operation),
+        )
+        .with_state(operation_service.clone() as OperationServiceState);
+
     let audit_log_routes = Router::new()
         .route("/", get(handlers::list_audit_logs))
         .with_state(audit_log_service.clone() as AuditLogServiceState);
@@ -248,6 +329,13 @@ pub async fn create_router(
         .nest("/databases", terminal_routes)
         .nest("/databases", metrics_routes)
         .nest("/databases", sql_routes)
+        .nest("/databases", backup_routes)
+        .nest("/databases", export_routes)
+        .nest("/databases", upgrade_routes)
+        .nest("/databases", extension_routes)
+        .nest("/databases", update_routes)
+        .nest("/databases", branch_routes)
+        .nest("/databases", operation_routes)
         .nest("/audit-logs", audit_log_routes)
         .nest("/admin", user_admin_routes)
         .layer(Extension(audit_log_service.clone()))
@@ -50,6 +50,9 @@ pub struct ServerSettings {
     pub port: u16,
     #[serde(default = "default_workers")]
     pub workers: usize,
+    /// Largest import file accepted by the upload endpoint.
+    #[serde(default = "default_max_upload_mb")]
+    pub max_upload_mb: usize,
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -92,6 +95,12 @@ pub struct DockerSettings {
     pub data_dir: String,
     #[serde(default = "default_public_host")]
     pub public_host: String,
+    /// `directory`, `reflink`, `btrfs` or `zfs`. All but `directory` branch by snapshotting.
+    #[serde(default = "default_storage_backend")]
+    pub storage_backend: String,
+    /// `none` or `xfs` to back storage limits with XFS project quotas on the data directory.
+    #[serde(default = "default_storage_quota")]
+    pub storage_quota: String,
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -186,6 +195,9 @@ fn default_port() -> u16 {
 fn default_workers() -> usize {
     4
 }
+fn default_max_upload_mb() -> usize {
+    10 * 1024
+}
 fn default_db_url() -> String {
     "sqlite:datify.db?mode=rwc".to_string()
 }
@@ -231,6 +243,12 @@ fn default_data_dir() -> String {
 fn default_public_host() -> String {
     "localhost".to_string()
 }
+fn default_storage_backend() -> String {
+    "directory".to_string()
+}
+fn default_storage_quota() -> String {
+    "none".to_string()
+}
 fn default_requests_per_minute() -> u32 {
     60
 }
@@ -262,6 +280,7 @@ impl Default for ServerSettings {
             host: default_host(),
             port: default_port(),
             workers: default_workers(),
+            max_upload_mb: default_max_upload_mb(),
         }
     }
 }
@@ -287,6 +306,8 @@ impl Default for DockerSettings {
             redis_image: default_redis_image(),
             data_dir: default_data_dir(),
             public_host: default_public_host(),
+            storage_backend: default_storage_backend(),
+            storage_quota: default_storage_quota(),
         }
     }
 }
@@ -351,6 +372,10 @@ impl Settings {
                     .ok()
                     .and_then(|s| s.parse().ok())
                     .unwrap_or_else(default_workers),
+                max_upload_mb: std::env::var("SERVER_MAX_UPLOAD_MB")
+                    .ok()
+                    .and_then(|s| s.parse().ok())
+                    .unwrap_or_else(default_max_upload_mb),
             },
             database: DatabaseSettings {
                 url: std::env::var("DATABASE_URL").unwrap_or_else(|_| default_db_url()),
@@ -394,6 +419,10 @@ impl Settings {
                 public_host: std::env::var("DOCKER_PUBLIC_HOST")
                     .or_else(|_| std::env::var("DOCKER_HOST_IP"))
                     .unwrap_or_else(|_| default_public_host()),
+                storage_backend: std::env::var("DOCKER_STORAGE_BACKEND")
+                    .unwrap_or_else(|_| default_storage_backend()),
+                storage_quota: std::env::var("DOCKER_STORAGE_QUOTA")
+                    .unwrap_or_else(|_| default_storage_quota()),
             },
             rate_limit: RateLimitSettings {
                 requests_per_minute: std::env::var("RATE_LIMIT_REQUESTS_PER_MINUTE")
@@ -447,6 +476,21 @@ impl Settings {
             return Err(ConfigError("Encryption key must be valid hex".to_string()));
         }
 
+        if !matches!(
+            self.docker.storage_backend.as_str(),
+            "directory" | "reflink" | "btrfs" | "zfs"
+        ) {
+            return Err(ConfigError(
+                "Storage backend must be 'directory', 'reflink', 'btrfs' or 'zfs'".to_string(),
+            ));
+        }
+
+        if !matches!(self.docker.storage_quota.as_str(), "none" | "xfs") {
+            return Err(ConfigError(
+                "Storage quota mode must be 'none' or 'xfs'".to_string(),
+            ));
+        }
+
         Ok(())
     }
 
@@ -0,0 +1,198 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use super::{container_id, Engine, ForkSpec};
+use crate::domain::models::{
+    ClickhouseVersion, Database, QueryResult, SchemaInfo, TablePreview, CLICKHOUSE_DEFAULT_DATABASE,
+};
+use crate::domain::services::metrics::{ClickhouseMetricsCollector, MetricsCollector};
+use crate::domain::services::sql_clickhouse::{
+    self, ClickhouseHttp, CLICKHOUSE_BLOCKED_STATEMENTS,
+};
+use crate::error::{AppError, AppResult};
+use crate::infrastructure::docker::containers::{ClickhouseContainer, ContainerProvider};
+use crate::infrastructure::docker::{ContainerConfig, DockerManager};
+
+/// Users config override that puts ClickHouse's default profile into read-only mode. It lives
+/// outside the data volume, so it is gone once the container is recreated.
+const READ_ONLY_CONFIG: &str = "/etc/clickhouse-server/users.d/datify-read-only.xml";
+
+pub struct ClickhouseEngine;
+
+#[async_trait]
+impl Engine for ClickhouseEngine {
+    fn database_type(&self) -> &'static str {
+        "clickhouse"
+    }
+
+    fn display_name(&self) -> &'static str {
+        "ClickHouse"
+    }
+
+    fn default_version(&self) -> &'static str {
+        "25.3"
+    }
+
+    fn is_valid_version(&self, version: &str) -> bool {
+        ClickhouseVersion::is_valid(version)
+    }
+
+    fn image(&self, database: &Database) -> String {
+        ClickhouseContainer::default_image(&database.version)
+    }
+
+    fn internal_port(&self) -> u16 {
+        8123
+    }
+
+    fn container_prefix(&self) -> &'static str {
+        "datify-clickhouse"
+    }
+
+    fn default_username(&self) -> &'static str {
+        "default"
+    }
+
+    fn supports_schema_only(&self) -> bool {
+        true
+    }
+
+    fn connection(
+        &self,
+        username: &str,
+        password: &str,
+        host: &str,
+        port: i32,
+    ) -> (String, String) {
+        (
+            CLICKHOUSE_DEFAULT_DATABASE.to_string(),
+            format!(
+                "http://{}:{}@{}:{}/?database={}",
+                username, password, host, port, CLICKHOUSE_DEFAULT_DATABASE
+            ),
+        )
+    }
+
+    fn cli_name(&self) -> &'static str {
+        "clickhouse-client"
+    }
+
+    /// Logs in as the default user with the password the container was created with.
+    fn cli_command(&self, _database: &Database) -> Vec<String> {
+        vec![
+            "sh".to_string(),
+            "-c".to_string(),
+            "exec clickhouse-client --password \"$CLICKHOUSE_PASSWORD\"".to_string(),
+        ]
+    }
+
+    async fn create_container(
+        &self,
+        docker: &DockerManager,
+        config: ContainerConfig,
+        password: &str,
+    ) -> AppResult<String> {
+        docker.create_clickhouse_container(config, password).await
+    }
+
+    async fn fork(&self, docker: &DockerManager, fork: &ForkSpec<'_>) -> AppResult<()> {
+        docker
+            .fork_clickhouse_database(
+                &fork.source.container_name(),
+                &fork.target.container_name(),
+                fork.source_password,
+                fork.target_password,
+                fork.schema_only,
+            )
+            .await
+    }
+
+    /// Sets `readonly = 2` on the default profile through a users config override, which the
+    /// server picks up without a restart. Reads and settings changes keep working.
+    async fn set_write_block(
+        &self,
+        docker: &DockerManager,
+        database: &Database,
+        _password: &str,
+        blocked: bool,
+    ) -> AppResult<()> {
+        let script = if blocked {
+            "printf '%s' '<clickhouse><profiles><default><readonly>2</readonly></default></profiles></clickhouse>' > \"$CONFIG\""
+        } else {
+            "rm -f \"$CONFIG\""
+        };
+
+        let output = docker
+            .run_exec(
+                container_id(database)?,
+                vec!["sh".to_string(), "-c".to_string(), script.to_string()],
+                Some(vec![format!("CONFIG={}", READ_ONLY_CONFIG)]),
+            )
+            .await?;
+
+        if output.exit_code != Some(0) {
+            return Err(AppError::Docker(format!(
+                "Failed to change readonly profile: {}",
+                output.stderr.trim()
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// The override lives outside the data volume and is gone with a recreated container.
+    async fn reapply_write_block(
+        &self,
+        docker: &DockerManager,
+        database: &Database,
+        password: &str,
+    ) -> AppResult<()> {
+        self.set_write_block(docker, database, password, true).await
+    }
+
+    fn supports_sql(&self) -> bool {
+        true
+    }
+
+    fn blocked_statements(&self) -> &'static [&'static str] {
+        CLICKHOUSE_BLOCKED_STATEMENTS
+    }
+
+    async fn sql_schema(&self, database: &Database, password: &str) -> AppResult<SchemaInfo> {
+        sql_clickhouse::get_schema(&ClickhouseHttp::new(database, password)?).await
+    }
+
+    async fn sql_query(
+        &self,
+        database: &Database,
+        password: &str,
+        sql: &str,
+        limit: i32,
+        timeout_ms: i32,
+    ) -> AppResult<QueryResult> {
+        let http = ClickhouseHttp::new(database, password)?;
+        sql_clickhouse::execute_query(&http, sql, limit, timeout_ms).await
+    }
+
+    async fn sql_preview(
+        &self,
+        database: &Database,
+        password: &str,
+        schema: &str,
+        table: &str,
+        limit: i32,
+        offset: i32,
+    ) -> AppResult<TablePreview> {
+        let http = ClickhouseHttp::new(database, password)?;
+        sql_clickhouse::preview_table(&http, schema, table, limit, offset).await
+    }
+
+    fn metrics_collector(
+        &self,
+        encryption_key: [u8; 32],
+        _docker: &Arc<DockerManager>,
+    ) -> Arc<dyn MetricsCollector> {
+        Arc::new(ClickhouseMetricsCollector::new(encryption_key))
+    }
+}
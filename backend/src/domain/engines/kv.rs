@@ -0,0 +1,416 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use super::{container_id, Engine, EngineConfig, ForkSpec};
+use crate::domain::models::{ConfigFormat, ConfigSource, Database, RedisVersion, ValkeyVersion};
+use crate::domain::services::metrics::{MetricsCollector, RedisMetricsCollector};
+use crate::error::{AppError, AppResult};
+use crate::infrastructure::docker::containers::{
+    kv_info_field, kv_persistence_args, ContainerProvider, RedisContainer, ValkeyContainer,
+};
+use crate::infrastructure::docker::{ContainerConfig, DockerManager};
+use crate::infrastructure::version_catalog;
+
+const KV_SENSITIVE_KEYS: &[&str] = &["requirepass", "masterauth"];
+
+/// Redis and Valkey, which share the protocol, CLI options and configuration commands.
+pub struct KvEngine {
+    is_valkey: bool,
+}
+
+impl KvEngine {
+    pub const REDIS: Self = Self { is_valkey: false };
+    pub const VALKEY: Self = Self { is_valkey: true };
+
+    fn cli(&self) -> &'static str {
+        if self.is_valkey {
+            ValkeyContainer::cli_command()[0]
+        } else {
+            RedisContainer::cli_command()[0]
+        }
+    }
+
+    async fn run_cli(
+        &self,
+        docker: &DockerManager,
+        database: &Database,
+        password: &str,
+        args: &[&str],
+    ) -> AppResult<(bool, String, String)> {
+        let mut cmd = vec![
+            self.cli().to_string(),
+            "-a".to_string(),
+            password.to_string(),
+        ];
+        cmd.extend(args.iter().map(|arg| arg.to_string()));
+
+        let output = docker.run_exec(container_id(database)?, cmd, None).await?;
+        Ok((
+            output.exit_code.unwrap_or(0) == 0,
+            output.stdout,
+            output.stderr,
+        ))
+    }
+}
+
+#[async_trait]
+impl Engine for KvEngine {
+    fn database_type(&self) -> &'static str {
+        if self.is_valkey {
+            "valkey"
+        } else {
+            "redis"
+        }
+    }
+
+    fn display_name(&self) -> &'static str {
+        if self.is_valkey {
+            "Valkey"
+        } else {
+            "Redis"
+        }
+    }
+
+    fn default_version(&self) -> &'static str {
+        if self.is_valkey {
+            "8.0"
+        } else {
+            "7.4"
+        }
+    }
+
+    fn is_valid_version(&self, version: &str) -> bool {
+        if self.is_valkey {
+            ValkeyVersion::is_valid(version)
+        } else {
+            RedisVersion::is_valid(version)
+        }
+    }
+
+    fn image(&self, database: &Database) -> String {
+        if self.is_valkey {
+            ValkeyContainer::default_image(&database.version)
+        } else {
+            RedisContainer::default_image(&database.version)
+        }
+    }
+
+    fn internal_port(&self) -> u16 {
+        6379
+    }
+
+    fn container_prefix(&self) -> &'static str {
+        if self.is_valkey {
+            "datify-valkey"
+        } else {
+            "datify-redis"
+        }
+    }
+
+    /// Key-value servers have no named superuser; the column keeps its historical default.
+    fn default_username(&self) -> &'static str {
+        "postgres"
+    }
+
+    async fn in_place_versions(&self) -> Vec<String> {
+        let versions = if self.is_valkey {
+            version_catalog::valkey_versions()
+                .await
+                .into_iter()
+                .map(|v| v.version)
+                .collect()
+        } else {
+            version_catalog::redis_versions()
+                .await
+                .into_iter()
+                .map(|v| v.version)
+                .collect()
+        };
+        versions
+    }
+
+    fn is_key_value(&self) -> bool {
+        true
+    }
+
+    fn supports_persistence_modes(&self) -> bool {
+        true
+    }
+
+    fn supports_import(&self) -> bool {
+        true
+    }
+
+    fn supports_backups(&self) -> bool {
+        true
+    }
+
+    fn supports_exports(&self) -> bool {
+        true
+    }
+
+    fn supports_replicas(&self) -> bool {
+        true
+    }
+
+    fn connection(
+        &self,
+        _username: &str,
+        password: &str,
+        host: &str,
+        port: i32,
+    ) -> (String, String) {
+        (
+            "0".to_string(),
+            format!("redis://:{}@{}:{}/0", password, host, port),
+        )
+    }
+
+    fn cli_name(&self) -> &'static str {
+        self.cli()
+    }
+
+    fn cli_command(&self, _database: &Database) -> Vec<String> {
+        vec![self.cli().to_string()]
+    }
+
+    fn container_cmd(&self, database: &Database) -> Option<Vec<String>> {
+        kv_persistence_args(database.persistence.as_deref())
+    }
+
+    async fn create_container(
+        &self,
+        docker: &DockerManager,
+        config: ContainerConfig,
+        password: &str,
+    ) -> AppResult<String> {
+        if self.is_valkey {
+            docker.create_valkey_container(config, password).await
+        } else {
+            docker.create_redis_container(config, password).await
+        }
+    }
+
+    async fn fork(&self, docker: &DockerManager, fork: &ForkSpec<'_>) -> AppResult<()> {
+        let source = fork.source.container_name();
+        let target = fork.target.container_name();
+        if self.is_valkey {
+            docker
+                .fork_valkey_database(&source, &target, fork.source_password, fork.target_password)
+                .await
+        } else {
+            docker
+                .fork_redis_database(&source, &target, fork.source_password, fork.target_password)
+                .await
+        }
+    }
+
+    async fn read_config(
+        &self,
+        docker: &DockerManager,
+        database: &Database,
+        password: &str,
+        warnings: &mut Vec<String>,
+    ) -> AppResult<EngineConfig> {
+        let (ok, stdout, stderr) = self
+            .run_cli(docker, database, password, &["--raw", "CONFIG", "GET", "*"])
+            .await?;
+        if !ok {
+            return Err(AppError::Docker(format!(
+                "Failed to fetch config: {}",
+                stderr.trim()
+            )));
+        }
+
+        let lines: Vec<&str> = stdout.lines().collect();
+        let mut content = String::new();
+        let mut skipped = Vec::new();
+
+        for pair in lines.chunks(2) {
+            if pair.len() < 2 {
+                continue;
+            }
+            let key = pair[0].trim();
+            let value = pair[1].trim();
+            if key.is_empty() {
+                continue;
+            }
+            if KV_SENSITIVE_KEYS.contains(&key) {
+                skipped.push(key.to_string());
+                continue;
+            }
+            content.push_str(key);
+            if !value.is_empty() {
+                content.push(' ');
+                content.push_str(value);
+            }
+            content.push('\n');
+        }
+
+        if !skipped.is_empty() {
+            warnings.push(format!("Sensitive keys hidden: {}", skipped.join(", ")));
+        }
+
+        Ok(EngineConfig {
+            format: ConfigFormat::Kv,
+            source: ConfigSource::Runtime,
+            content,
+        })
+    }
+
+    /// Applies each entry with `CONFIG SET`, then persists them with `CONFIG REWRITE`.
+    async fn write_config(
+        &self,
+        docker: &DockerManager,
+        database: &Database,
+        password: &str,
+        content: &str,
+        warnings: &mut Vec<String>,
+    ) -> AppResult<bool> {
+        let mut skipped = Vec::new();
+
+        for entry in parse_kv_config(content)? {
+            if KV_SENSITIVE_KEYS.contains(&entry.key.as_str()) {
+                skipped.push(entry.key);
+                continue;
+            }
+
+            let mut args = vec!["CONFIG", "SET", entry.key.as_str()];
+            args.extend(entry.values.iter().map(String::as_str));
+
+            let (ok, stdout, stderr) = self.run_cli(docker, database, password, &args).await?;
+            if !ok {
+                return Err(AppError::Docker(format!(
+                    "Failed to apply config '{}': {}",
+                    entry.key,
+                    stderr.trim()
+                )));
+            }
+            let response = stdout.trim();
+            if !response.eq_ignore_ascii_case("ok") {
+                return Err(AppError::Docker(format!(
+                    "Failed to apply config '{}': {}",
+                    entry.key, response
+                )));
+            }
+        }
+
+        if !skipped.is_empty() {
+            warnings.push(format!("Ignored sensitive keys: {}", skipped.join(", ")));
+        }
+
+        let (ok, stdout, _) = self
+            .run_cli(docker, database, password, &["CONFIG", "REWRITE"])
+            .await?;
+        if !ok || !stdout.trim().eq_ignore_ascii_case("ok") {
+            warnings.push(
+                "CONFIG REWRITE did not succeed. Changes may not persist after restart."
+                    .to_string(),
+            );
+        }
+
+        Ok(true)
+    }
+
+    /// Caps `maxmemory` at the current usage without eviction, so commands that allocate are
+    /// rejected while reads and deletes keep working.
+    async fn set_write_block(
+        &self,
+        docker: &DockerManager,
+        database: &Database,
+        password: &str,
+        blocked: bool,
+    ) -> AppResult<()> {
+        let container_id = container_id(database)?;
+        let cli = self.cli();
+
+        if !blocked {
+            docker
+                .kv_command(
+                    container_id,
+                    cli,
+                    password,
+                    &["CONFIG", "SET", "maxmemory", "0"],
+                )
+                .await?;
+            return Ok(());
+        }
+
+        let info = docker
+            .kv_command(container_id, cli, password, &["INFO", "memory"])
+            .await?;
+        let used_memory = kv_info_field(&info, "used_memory")
+            .ok_or_else(|| AppError::Docker("INFO memory did not report used_memory".to_string()))?
+            .to_string();
+
+        docker
+            .kv_command(
+                container_id,
+                cli,
+                password,
+                &["CONFIG", "SET", "maxmemory-policy", "noeviction"],
+            )
+            .await?;
+        docker
+            .kv_command(
+                container_id,
+                cli,
+                password,
+                &["CONFIG", "SET", "maxmemory", &used_memory],
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    /// The cap is runtime configuration and does not survive a restart.
+    async fn reapply_write_block(
+        &self,
+        docker: &DockerManager,
+        database: &Database,
+        password: &str,
+    ) -> AppResult<()> {
+        self.set_write_block(docker, database, password, true).await
+    }
+
+    fn metrics_collector(
+        &self,
+        encryption_key: [u8; 32],
+        _docker: &Arc<DockerManager>,
+    ) -> Arc<dyn MetricsCollector> {
+        Arc::new(RedisMetricsCollector::new(encryption_key, self.is_valkey))
+    }
+}
+
+#[derive(Debug, Clone)]
+struct KvConfigEntry {
+    key: String,
+    values: Vec<String>,
+}
+
+fn parse_kv_config(content: &str) -> AppResult<Vec<KvConfigEntry>> {
+    let mut entries = Vec::new();
+
+    for (idx, line) in content.lines().enumerate() {
+        let trimmed = line.trim();
+        if trimmed.is_empty() || trimmed.starts_with('#') || trimmed.starts_with(';') {
+            continue;
+        }
+        let parts: Vec<&str> = trimmed.split_whitespace().collect();
+        if parts.is_empty() {
+            continue;
+        }
+        if parts.len() < 2 {
+            return Err(AppError::Validation(format!(
+                "Invalid config at line {}: missing value",
+                idx + 1
+            )));
+        }
+        let key = parts[0].to_string();
+        let values = parts[1..].iter().map(|v| v.to_string()).collect();
+        entries.push(KvConfigEntry { key, values });
+    }
+
+    Ok(entries)
+}
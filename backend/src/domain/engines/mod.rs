@@ -0,0 +1,364 @@
+mod clickhouse;
+mod kv;
+mod mongo;
+mod mysql;
+mod postgres;
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+pub use clickhouse::*;
+pub use kv::*;
+pub use mongo::*;
+pub use mysql::*;
+pub use postgres::*;
+
+use crate::domain::models::{
+    ConfigFormat, ConfigSource, Database, QueryResult, SchemaInfo, TablePreview,
+};
+use crate::domain::services::metrics::MetricsCollector;
+use crate::error::{AppError, AppResult};
+use crate::infrastructure::docker::{ContainerConfig, DockerManager, ForkProgress};
+
+/// Every engine a database can run, in the order they were added.
+static ENGINES: &[&dyn Engine] = &[
+    &PostgresEngine,
+    &KvEngine::VALKEY,
+    &KvEngine::REDIS,
+    &MysqlEngine::MYSQL,
+    &MysqlEngine::MARIADB,
+    &MongoEngine,
+    &ClickhouseEngine,
+];
+
+pub fn all_engines() -> &'static [&'static dyn Engine] {
+    ENGINES
+}
+
+pub fn find_engine(database_type: &str) -> Option<&'static dyn Engine> {
+    ENGINES
+        .iter()
+        .copied()
+        .find(|engine| engine.database_type() == database_type)
+}
+
+/// The engine of a stored database. Rows written before `database_type` existed are PostgreSQL.
+pub fn engine_for(database_type: &str) -> &'static dyn Engine {
+    find_engine(database_type).unwrap_or(&PostgresEngine)
+}
+
+/// A copy of `source`'s data into `target`, both running.
+pub struct ForkSpec<'a> {
+    pub source: &'a Database,
+    pub target: &'a Database,
+    pub source_password: &'a str,
+    pub target_password: &'a str,
+    pub schema_only: bool,
+    /// Filled in as the copy runs, by engines that can measure it.
+    pub progress: Option<&'a ForkProgress>,
+}
+
+/// Configuration of a running database as shown to users.
+pub struct EngineConfig {
+    pub format: ConfigFormat,
+    pub source: ConfigSource,
+    pub content: String,
+}
+
+/// A database engine: its image, container, clients, branching, configuration and metrics.
+/// Services look the engine up by `database_type` instead of matching on it, so a new engine
+/// only needs a module here and an entry in `ENGINES`.
+#[async_trait]
+pub trait Engine: Send + Sync {
+    /// Value stored in `databases.database_type`.
+    fn database_type(&self) -> &'static str;
+
+    /// Name used in messages, such as "PostgreSQL".
+    fn display_name(&self) -> &'static str;
+
+    fn default_version(&self) -> &'static str;
+
+    fn is_valid_version(&self, version: &str) -> bool;
+
+    /// Release lines a database can move to by swapping its image. Empty for engines whose
+    /// releases need an upgrade of the data.
+    async fn in_place_versions(&self) -> Vec<String> {
+        Vec::new()
+    }
+
+    /// Image `database` runs. Branches share their parent's.
+    fn image(&self, database: &Database) -> String;
+
+    fn internal_port(&self) -> u16;
+
+    fn container_prefix(&self) -> &'static str;
+
+    /// The superuser a new database is created with.
+    fn default_username(&self) -> &'static str;
+
+    /// How long a new container gets to turn healthy.
+    fn startup_timeout_secs(&self) -> u64 {
+        60
+    }
+
+    fn supports_wal_archiving(&self) -> bool {
+        false
+    }
+
+    /// Whether the engine stores keys instead of tables, so its data moves through RDB
+    /// snapshots and replication rather than dumps.
+    fn is_key_value(&self) -> bool {
+        false
+    }
+
+    fn supports_persistence_modes(&self) -> bool {
+        false
+    }
+
+    fn supports_import(&self) -> bool {
+        false
+    }
+
+    fn supports_backups(&self) -> bool {
+        false
+    }
+
+    fn supports_exports(&self) -> bool {
+        false
+    }
+
+    /// Whether `fork` can copy the schema without the data.
+    fn supports_schema_only(&self) -> bool {
+        false
+    }
+
+    /// Whether branches can follow the parent live instead of copying it once.
+    fn supports_replicas(&self) -> bool {
+        false
+    }
+
+    /// Whether project masking rules are applied to data copied into branches.
+    fn supports_masking(&self) -> bool {
+        false
+    }
+
+    /// Whether branches can copy a subset of the parent's tables, or a sample of their rows.
+    fn supports_table_filters(&self) -> bool {
+        false
+    }
+
+    /// Whether a copy-on-write snapshot of the data directory is a consistent copy of the
+    /// database, so branches, resets and named snapshots can skip the logical copy.
+    fn supports_snapshots(&self) -> bool {
+        false
+    }
+
+    /// Whether schemas can be compared and a migration between them generated.
+    fn supports_schema_diff(&self) -> bool {
+        false
+    }
+
+    /// Whether the database can be moved to a new major version.
+    fn supports_upgrades(&self) -> bool {
+        false
+    }
+
+    /// Whether the extension catalog and preload libraries can be managed.
+    fn supports_extensions(&self) -> bool {
+        false
+    }
+
+    /// Whether the document query console can run against the engine.
+    fn supports_document_queries(&self) -> bool {
+        false
+    }
+
+    /// Database clients land in and the connection string for it.
+    fn connection(&self, username: &str, password: &str, host: &str, port: i32)
+        -> (String, String);
+
+    /// Name of the interactive client, used as the terminal session type.
+    fn cli_name(&self) -> &'static str;
+
+    /// Interactive client the terminal starts inside the container.
+    fn cli_command(&self, database: &Database) -> Vec<String>;
+
+    fn container_env(&self, _database: &Database) -> Vec<String> {
+        Vec::new()
+    }
+
+    /// Server arguments on top of the container provider's own.
+    fn container_cmd(&self, _database: &Database) -> Option<Vec<String>> {
+        None
+    }
+
+    async fn create_container(
+        &self,
+        docker: &DockerManager,
+        config: ContainerConfig,
+        password: &str,
+    ) -> AppResult<String>;
+
+    /// Prepares a database once its server is up. Runs on creation and again on later starts,
+    /// so it has to be idempotent. Not run for new branches, which copy everything from their
+    /// parent, nor for replicas, which are read-only.
+    async fn initialize(
+        &self,
+        _docker: &DockerManager,
+        _database: &Database,
+        _password: &str,
+    ) -> AppResult<()> {
+        Ok(())
+    }
+
+    /// Replaces the data of `fork.target` with a copy of `fork.source`.
+    async fn fork(&self, docker: &DockerManager, fork: &ForkSpec<'_>) -> AppResult<()>;
+
+    /// Empties the database before a logical copy is restored into it. Engines whose restore
+    /// already replaces existing data keep the default.
+    async fn clear_data(
+        &self,
+        _docker: &DockerManager,
+        _database: &Database,
+        _password: &str,
+    ) -> AppResult<()> {
+        Ok(())
+    }
+
+    /// Changes the user's password inside the data, for engines that keep it there instead of
+    /// taking it from the container environment on every start.
+    async fn set_user_password(
+        &self,
+        _docker: &DockerManager,
+        _database: &Database,
+        _password: &str,
+        _new_password: &str,
+    ) -> AppResult<()> {
+        Ok(())
+    }
+
+    /// Whether configuration changes only apply after a restart.
+    fn config_requires_restart(&self) -> bool {
+        false
+    }
+
+    async fn read_config(
+        &self,
+        _docker: &DockerManager,
+        _database: &Database,
+        _password: &str,
+        _warnings: &mut Vec<String>,
+    ) -> AppResult<EngineConfig> {
+        Err(unsupported_config())
+    }
+
+    /// Applies `content` and reports whether the server confirmed it.
+    async fn write_config(
+        &self,
+        _docker: &DockerManager,
+        _database: &Database,
+        _password: &str,
+        _content: &str,
+        _warnings: &mut Vec<String>,
+    ) -> AppResult<bool> {
+        Err(unsupported_config())
+    }
+
+    /// Blocks or allows writes while the database is over its storage limit. Reads keep
+    /// working, and deletes too where the engine can tell them apart from writes.
+    async fn set_write_block(
+        &self,
+        docker: &DockerManager,
+        database: &Database,
+        password: &str,
+        blocked: bool,
+    ) -> AppResult<()>;
+
+    /// Applies the write block again on a database that stays over its limit, for engines
+    /// whose block does not survive a restart or a recreated container.
+    async fn reapply_write_block(
+        &self,
+        _docker: &DockerManager,
+        _database: &Database,
+        _password: &str,
+    ) -> AppResult<()> {
+        Ok(())
+    }
+
+    /// Whether the SQL console can browse and query this engine.
+    fn supports_sql(&self) -> bool {
+        false
+    }
+
+    /// Statements the SQL console refuses for this engine, on top of the common ones.
+    fn blocked_statements(&self) -> &'static [&'static str] {
+        &[]
+    }
+
+    async fn sql_schema(&self, _database: &Database, _password: &str) -> AppResult<SchemaInfo> {
+        Err(unsupported_sql())
+    }
+
+    /// Runs one console statement and returns up to `limit` rows.
+    async fn sql_query(
+        &self,
+        _database: &Database,
+        _password: &str,
+        _sql: &str,
+        _limit: i32,
+        _timeout_ms: i32,
+    ) -> AppResult<QueryResult> {
+        Err(unsupported_sql())
+    }
+
+    async fn sql_preview(
+        &self,
+        _database: &Database,
+        _password: &str,
+        _schema: &str,
+        _table: &str,
+        _limit: i32,
+        _offset: i32,
+    ) -> AppResult<TablePreview> {
+        Err(unsupported_sql())
+    }
+
+    fn metrics_collector(
+        &self,
+        encryption_key: [u8; 32],
+        docker: &Arc<DockerManager>,
+    ) -> Arc<dyn MetricsCollector>;
+}
+
+fn unsupported_config() -> AppError {
+    AppError::Validation("Unsupported database type for config".to_string())
+}
+
+fn unsupported_sql() -> AppError {
+    AppError::Validation("The SQL console is not available for this database type".to_string())
+}
+
+fn container_id(database: &Database) -> AppResult<&str> {
+    database
+        .container_id
+        .as_deref()
+        .ok_or_else(|| AppError::NotFound("Database has no container".to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_engine_registry() {
+        for engine in all_engines() {
+            let found = engine_for(engine.database_type());
+            assert_eq!(found.database_type(), engine.database_type());
+            assert_eq!(found.display_name(), engine.display_name());
+            assert!(engine.is_valid_version(engine.default_version()));
+        }
+        assert_eq!(engine_for("unknown").database_type(), "postgres");
+        assert!(find_engine("unknown").is_none());
+    }
+}
@@ -0,0 +1,157 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use super::{container_id, Engine, ForkSpec};
+use crate::domain::models::{Database, MongoVersion, MONGO_DEFAULT_DATABASE};
+use crate::domain::services::metrics::{MetricsCollector, MongoMetricsCollector};
+use crate::error::{AppError, AppResult};
+use crate::infrastructure::docker::containers::{ContainerProvider, MongoContainer};
+use crate::infrastructure::docker::{ContainerConfig, DockerManager};
+
+pub struct MongoEngine;
+
+#[async_trait]
+impl Engine for MongoEngine {
+    fn database_type(&self) -> &'static str {
+        "mongodb"
+    }
+
+    fn display_name(&self) -> &'static str {
+        "MongoDB"
+    }
+
+    fn default_version(&self) -> &'static str {
+        "8.0"
+    }
+
+    fn is_valid_version(&self, version: &str) -> bool {
+        MongoVersion::is_valid(version)
+    }
+
+    fn image(&self, database: &Database) -> String {
+        MongoContainer::default_image(&database.version)
+    }
+
+    fn internal_port(&self) -> u16 {
+        27017
+    }
+
+    fn container_prefix(&self) -> &'static str {
+        "datify-mongo"
+    }
+
+    fn default_username(&self) -> &'static str {
+        "root"
+    }
+
+    fn supports_document_queries(&self) -> bool {
+        true
+    }
+
+    fn connection(
+        &self,
+        username: &str,
+        password: &str,
+        host: &str,
+        port: i32,
+    ) -> (String, String) {
+        (
+            MONGO_DEFAULT_DATABASE.to_string(),
+            format!(
+                "mongodb://{}:{}@{}:{}/{}?authSource=admin",
+                username, password, host, port, MONGO_DEFAULT_DATABASE
+            ),
+        )
+    }
+
+    fn cli_name(&self) -> &'static str {
+        "mongosh"
+    }
+
+    /// Logs in as root with the credentials the container was created with.
+    fn cli_command(&self, _database: &Database) -> Vec<String> {
+        vec![
+            "sh".to_string(),
+            "-c".to_string(),
+            format!(
+                "exec mongosh --quiet -u \"$MONGO_INITDB_ROOT_USERNAME\" \
+                 -p \"$MONGO_INITDB_ROOT_PASSWORD\" --authenticationDatabase admin {}",
+                MONGO_DEFAULT_DATABASE
+            ),
+        ]
+    }
+
+    async fn create_container(
+        &self,
+        docker: &DockerManager,
+        config: ContainerConfig,
+        password: &str,
+    ) -> AppResult<String> {
+        docker.create_mongo_container(config, password).await
+    }
+
+    async fn fork(&self, docker: &DockerManager, fork: &ForkSpec<'_>) -> AppResult<()> {
+        docker
+            .fork_mongo_database(
+                &fork.source.container_name(),
+                &fork.target.container_name(),
+                fork.source_password,
+                fork.target_password,
+            )
+            .await
+    }
+
+    /// Holds MongoDB's `fsyncLock`, which queues writes until it is released while reads keep
+    /// working. The lock nests, so it is only taken when not held and released completely.
+    async fn set_write_block(
+        &self,
+        docker: &DockerManager,
+        database: &Database,
+        password: &str,
+        blocked: bool,
+    ) -> AppResult<()> {
+        let script = if blocked {
+            "if (!db.currentOp().fsyncLock) db.fsyncLock()"
+        } else {
+            "while (db.currentOp().fsyncLock) db.fsyncUnlock()"
+        };
+
+        let mut cmd = MongoContainer::shell_command();
+        cmd.extend(["--eval".to_string(), script.to_string()]);
+        let output = docker
+            .run_exec(
+                container_id(database)?,
+                cmd,
+                Some(MongoContainer::shell_env(password)),
+            )
+            .await?;
+
+        if output.exit_code != Some(0) {
+            return Err(AppError::Docker(format!(
+                "Failed to change fsyncLock: {}",
+                output.stderr.trim()
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// The lock is runtime state and does not survive a restart.
+    async fn reapply_write_block(
+        &self,
+        docker: &DockerManager,
+        database: &Database,
+        password: &str,
+    ) -> AppResult<()> {
+        self.set_write_block(docker, database, password, true).await
+    }
+
+    fn metrics_collector(
+        &self,
+        encryption_key: [u8; 32],
+        docker: &Arc<DockerManager>,
+    ) -> Arc<dyn MetricsCollector> {
+        Arc::new(MongoMetricsCollector::new(encryption_key, docker.clone()))
+    }
+}
@@ -0,0 +1,330 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use super::{container_id, Engine, ForkSpec};
+use crate::domain::models::{
+    Database, MysqlVersion, QueryResult, SchemaInfo, TablePreview, MYSQL_DEFAULT_DATABASE,
+};
+use crate::domain::services::metrics::{MetricsCollector, MysqlMetricsCollector};
+use crate::domain::services::sql_mysql::{self, MYSQL_BLOCKED_STATEMENTS};
+use crate::error::{AppError, AppResult};
+use crate::infrastructure::docker::containers::{ContainerProvider, MysqlContainer, MysqlTools};
+use crate::infrastructure::docker::{ContainerConfig, DockerManager};
+
+/// MySQL initializes its data directory on first start, which takes a while.
+const MYSQL_STARTUP_TIMEOUT_SECS: u64 = 180;
+
+/// MySQL and MariaDB, which share the container layout and dump format.
+pub struct MysqlEngine {
+    is_mariadb: bool,
+}
+
+impl MysqlEngine {
+    pub const MYSQL: Self = Self { is_mariadb: false };
+    pub const MARIADB: Self = Self { is_mariadb: true };
+
+    pub fn tools(&self, database: &Database) -> MysqlTools {
+        MysqlContainer::tools_for_image(&self.image(database))
+    }
+
+    /// Runs statements as the local root user and returns the unformatted output.
+    async fn run_root_sql(
+        &self,
+        docker: &DockerManager,
+        database: &Database,
+        password: &str,
+        sql: &str,
+    ) -> AppResult<String> {
+        let output = docker
+            .run_exec(
+                container_id(database)?,
+                vec![
+                    self.tools(database).client.to_string(),
+                    "-uroot".to_string(),
+                    "-N".to_string(),
+                    "-B".to_string(),
+                    "-e".to_string(),
+                    sql.to_string(),
+                ],
+                Some(vec![format!("MYSQL_PWD={}", password)]),
+            )
+            .await?;
+
+        if output.exit_code != Some(0) {
+            return Err(AppError::Docker(format!(
+                "{} statement failed: {}",
+                self.display_name(),
+                output.stderr.trim()
+            )));
+        }
+
+        Ok(output.stdout)
+    }
+}
+
+#[async_trait]
+impl Engine for MysqlEngine {
+    fn database_type(&self) -> &'static str {
+        if self.is_mariadb {
+            "mariadb"
+        } else {
+            "mysql"
+        }
+    }
+
+    fn display_name(&self) -> &'static str {
+        if self.is_mariadb {
+            "MariaDB"
+        } else {
+            "MySQL"
+        }
+    }
+
+    fn default_version(&self) -> &'static str {
+        if self.is_mariadb {
+            "11.4"
+        } else {
+            "8.4"
+        }
+    }
+
+    fn is_valid_version(&self, version: &str) -> bool {
+        MysqlVersion::is_valid(version)
+    }
+
+    fn image(&self, database: &Database) -> String {
+        if self.is_mariadb {
+            MysqlContainer::mariadb_image(&database.version)
+        } else {
+            MysqlContainer::default_image(&database.version)
+        }
+    }
+
+    fn internal_port(&self) -> u16 {
+        3306
+    }
+
+    fn container_prefix(&self) -> &'static str {
+        if self.is_mariadb {
+            "datify-mariadb"
+        } else {
+            "datify-mysql"
+        }
+    }
+
+    fn default_username(&self) -> &'static str {
+        "root"
+    }
+
+    fn supports_schema_only(&self) -> bool {
+        true
+    }
+
+    fn startup_timeout_secs(&self) -> u64 {
+        MYSQL_STARTUP_TIMEOUT_SECS
+    }
+
+    fn connection(
+        &self,
+        username: &str,
+        password: &str,
+        host: &str,
+        port: i32,
+    ) -> (String, String) {
+        (
+            MYSQL_DEFAULT_DATABASE.to_string(),
+            format!(
+                "mysql://{}:{}@{}:{}/{}",
+                username, password, host, port, MYSQL_DEFAULT_DATABASE
+            ),
+        )
+    }
+
+    fn cli_name(&self) -> &'static str {
+        if self.is_mariadb {
+            "mariadb"
+        } else {
+            "mysql"
+        }
+    }
+
+    /// Logs in as root with the password the container was created with.
+    fn cli_command(&self, database: &Database) -> Vec<String> {
+        vec![
+            "sh".to_string(),
+            "-c".to_string(),
+            format!(
+                "MYSQL_PWD=\"$MYSQL_ROOT_PASSWORD\" exec {} -uroot {}",
+                self.tools(database).client,
+                MYSQL_DEFAULT_DATABASE
+            ),
+        ]
+    }
+
+    fn container_env(&self, _database: &Database) -> Vec<String> {
+        vec![format!("MYSQL_DATABASE={}", MYSQL_DEFAULT_DATABASE)]
+    }
+
+    async fn create_container(
+        &self,
+        docker: &DockerManager,
+        config: ContainerConfig,
+        password: &str,
+    ) -> AppResult<String> {
+        docker.create_mysql_container(config, password).await
+    }
+
+    async fn fork(&self, docker: &DockerManager, fork: &ForkSpec<'_>) -> AppResult<()> {
+        docker
+            .fork_mysql_database(
+                &fork.source.container_name(),
+                &fork.target.container_name(),
+                fork.source_password,
+                fork.target_password,
+                self.tools(fork.source),
+                fork.schema_only,
+            )
+            .await
+    }
+
+    /// Persists `super_read_only` on MySQL, which also holds root back until it is turned off
+    /// again. MariaDB's `read_only` skips holders of `READ_ONLY ADMIN` and is lost on restart,
+    /// so network root sessions lose that privilege while blocked and the open ones are
+    /// killed. The local root account used here keeps it to lift the block again.
+    async fn set_write_block(
+        &self,
+        docker: &DockerManager,
+        database: &Database,
+        password: &str,
+        blocked: bool,
+    ) -> AppResult<()> {
+        self.run_root_sql(
+            docker,
+            database,
+            password,
+            read_only_sql(self.is_mariadb, blocked),
+        )
+        .await?;
+
+        if self.is_mariadb && blocked {
+            let sessions = self
+                .run_root_sql(
+                    docker,
+                    database,
+                    password,
+                    "SELECT id FROM information_schema.processlist \
+                     WHERE user = 'root' AND host NOT LIKE 'localhost%'",
+                )
+                .await?;
+            let kills: Vec<String> = sessions
+                .lines()
+                .filter_map(|line| line.trim().parse::<u64>().ok())
+                .map(|id| format!("KILL {}", id))
+                .collect();
+            if !kills.is_empty() {
+                // A session may already be gone, which is fine.
+                if let Err(e) = self
+                    .run_root_sql(docker, database, password, &kills.join("; "))
+                    .await
+                {
+                    tracing::debug!("Failed to kill sessions on {}: {}", database.id, e);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn reapply_write_block(
+        &self,
+        docker: &DockerManager,
+        database: &Database,
+        password: &str,
+    ) -> AppResult<()> {
+        if self.is_mariadb {
+            self.run_root_sql(docker, database, password, "SET GLOBAL read_only = ON")
+                .await?;
+        }
+        Ok(())
+    }
+
+    fn supports_sql(&self) -> bool {
+        true
+    }
+
+    fn blocked_statements(&self) -> &'static [&'static str] {
+        MYSQL_BLOCKED_STATEMENTS
+    }
+
+    async fn sql_schema(&self, database: &Database, password: &str) -> AppResult<SchemaInfo> {
+        let mut conn = sql_mysql::connect(database, password).await?;
+        sql_mysql::get_schema(&mut conn).await
+    }
+
+    async fn sql_query(
+        &self,
+        database: &Database,
+        password: &str,
+        sql: &str,
+        limit: i32,
+        timeout_ms: i32,
+    ) -> AppResult<QueryResult> {
+        let mut conn = sql_mysql::connect(database, password).await?;
+        sql_mysql::execute_query(
+            &mut conn,
+            database,
+            password,
+            self.is_mariadb,
+            sql,
+            limit,
+            timeout_ms,
+        )
+        .await
+    }
+
+    async fn sql_preview(
+        &self,
+        database: &Database,
+        password: &str,
+        schema: &str,
+        table: &str,
+        limit: i32,
+        offset: i32,
+    ) -> AppResult<TablePreview> {
+        let mut conn = sql_mysql::connect(database, password).await?;
+        sql_mysql::preview_table(&mut conn, schema, table, limit, offset).await
+    }
+
+    fn metrics_collector(
+        &self,
+        encryption_key: [u8; 32],
+        _docker: &Arc<DockerManager>,
+    ) -> Arc<dyn MetricsCollector> {
+        Arc::new(MysqlMetricsCollector::new(encryption_key, self.is_mariadb))
+    }
+}
+
+fn read_only_sql(is_mariadb: bool, read_only: bool) -> &'static str {
+    match (is_mariadb, read_only) {
+        (true, true) => "REVOKE READ_ONLY ADMIN ON *.* FROM 'root'@'%'; SET GLOBAL read_only = ON",
+        (true, false) => "SET GLOBAL read_only = OFF; GRANT READ_ONLY ADMIN ON *.* TO 'root'@'%'",
+        (false, true) => "SET PERSIST super_read_only = ON",
+        (false, false) => "SET PERSIST super_read_only = OFF; SET PERSIST read_only = OFF",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_only_sql() {
+        assert!(read_only_sql(true, true).starts_with("REVOKE READ_ONLY ADMIN"));
+        assert!(read_only_sql(true, false).ends_with("TO 'root'@'%'"));
+        assert_eq!(
+            read_only_sql(false, true),
+            "SET PERSIST super_read_only = ON"
+        );
+    }
+}
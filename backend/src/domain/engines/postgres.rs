@@ -0,0 +1,485 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use base64::{engine::general_purpose, Engine as _};
+
+use super::{container_id, Engine, EngineConfig, ForkSpec};
+use crate::domain::models::{
+    ConfigFormat, ConfigSource, Database, PostgresVersion, QueryResult, SchemaInfo, TablePreview,
+};
+use crate::domain::services::metrics::{MetricsCollector, PostgresMetricsCollector};
+use crate::domain::services::sql_postgres;
+use crate::error::{AppError, AppResult};
+use crate::infrastructure::docker::{ContainerConfig, DockerManager};
+
+pub struct PostgresEngine;
+
+const CLEAR_SCHEMAS_SQL: &str = r#"DO $$
+DECLARE s text;
+BEGIN
+    FOR s IN SELECT nspname FROM pg_namespace
+        WHERE nspname NOT IN ('pg_catalog', 'information_schema', 'pg_toast')
+          AND nspname NOT LIKE 'pg_temp_%' AND nspname NOT LIKE 'pg_toast_temp_%'
+    LOOP
+        EXECUTE format('DROP SCHEMA %I CASCADE', s);
+    END LOOP;
+END $$"#;
+
+impl PostgresEngine {
+    async fn psql_value(
+        docker: &DockerManager,
+        database: &Database,
+        password: &str,
+        statement: &str,
+        action: &str,
+    ) -> AppResult<String> {
+        let output = docker
+            .run_exec(
+                container_id(database)?,
+                vec![
+                    "psql".to_string(),
+                    "-U".to_string(),
+                    database.username.clone(),
+                    "-d".to_string(),
+                    "postgres".to_string(),
+                    "-t".to_string(),
+                    "-A".to_string(),
+                    "-c".to_string(),
+                    statement.to_string(),
+                ],
+                Some(vec![format!("PGPASSWORD={}", password)]),
+            )
+            .await?;
+
+        if let Some(code) = output.exit_code {
+            if code != 0 {
+                return Err(AppError::Docker(format!(
+                    "Failed to {}: {}",
+                    action,
+                    output.stderr.trim()
+                )));
+            }
+        }
+
+        Ok(output.stdout.trim().to_string())
+    }
+
+    async fn config_path(
+        docker: &DockerManager,
+        database: &Database,
+        password: &str,
+    ) -> AppResult<String> {
+        let path = Self::psql_value(
+            docker,
+            database,
+            password,
+            "SHOW config_file;",
+            "read PostgreSQL config path",
+        )
+        .await?;
+        if path.is_empty() {
+            return Err(AppError::Docker(
+                "PostgreSQL returned empty config path".to_string(),
+            ));
+        }
+
+        Ok(path)
+    }
+}
+
+#[async_trait]
+impl Engine for PostgresEngine {
+    fn database_type(&self) -> &'static str {
+        "postgres"
+    }
+
+    fn display_name(&self) -> &'static str {
+        "PostgreSQL"
+    }
+
+    fn default_version(&self) -> &'static str {
+        "16"
+    }
+
+    fn is_valid_version(&self, version: &str) -> bool {
+        PostgresVersion::is_valid(version)
+    }
+
+    fn image(&self, database: &Database) -> String {
+        match database.variant() {
+            Some(variant) => variant.image(&database.version),
+            None => format!("postgres:{}", database.version),
+        }
+    }
+
+    fn internal_port(&self) -> u16 {
+        5432
+    }
+
+    fn container_prefix(&self) -> &'static str {
+        "datify-pg"
+    }
+
+    fn default_username(&self) -> &'static str {
+        "postgres"
+    }
+
+    fn supports_wal_archiving(&self) -> bool {
+        true
+    }
+
+    fn supports_import(&self) -> bool {
+        true
+    }
+
+    fn supports_backups(&self) -> bool {
+        true
+    }
+
+    fn supports_exports(&self) -> bool {
+        true
+    }
+
+    fn supports_schema_only(&self) -> bool {
+        true
+    }
+
+    fn supports_replicas(&self) -> bool {
+        true
+    }
+
+    fn supports_masking(&self) -> bool {
+        true
+    }
+
+    fn supports_table_filters(&self) -> bool {
+        true
+    }
+
+    fn supports_snapshots(&self) -> bool {
+        true
+    }
+
+    fn supports_schema_diff(&self) -> bool {
+        true
+    }
+
+    fn supports_upgrades(&self) -> bool {
+        true
+    }
+
+    fn supports_extensions(&self) -> bool {
+        true
+    }
+
+    fn connection(
+        &self,
+        username: &str,
+        password: &str,
+        host: &str,
+        port: i32,
+    ) -> (String, String) {
+        (
+            "postgres".to_string(),
+            format!(
+                "postgresql://{}:{}@{}:{}/postgres",
+                username, password, host, port
+            ),
+        )
+    }
+
+    fn cli_name(&self) -> &'static str {
+        "psql"
+    }
+
+    fn cli_command(&self, database: &Database) -> Vec<String> {
+        vec![
+            "psql".to_string(),
+            "-U".to_string(),
+            database.username.clone(),
+            "-d".to_string(),
+            "postgres".to_string(),
+        ]
+    }
+
+    fn container_env(&self, database: &Database) -> Vec<String> {
+        vec![
+            format!("POSTGRES_USER={}", database.username),
+            "POSTGRES_DB=postgres".to_string(),
+        ]
+    }
+
+    fn container_cmd(&self, database: &Database) -> Option<Vec<String>> {
+        Some(vec![
+            "postgres".to_string(),
+            "-c".to_string(),
+            format!(
+                "shared_preload_libraries={}",
+                database.preload_libraries().join(",")
+            ),
+            "-c".to_string(),
+            "pg_stat_statements.track=all".to_string(),
+        ])
+    }
+
+    async fn create_container(
+        &self,
+        docker: &DockerManager,
+        config: ContainerConfig,
+        password: &str,
+    ) -> AppResult<String> {
+        docker.create_postgres_container(config, password).await
+    }
+
+    /// Creates `pg_stat_statements` for query metrics. The library is preloaded, but the
+    /// extension only exists in a database once created.
+    async fn initialize(
+        &self,
+        docker: &DockerManager,
+        database: &Database,
+        password: &str,
+    ) -> AppResult<()> {
+        Self::psql_value(
+            docker,
+            database,
+            password,
+            "CREATE EXTENSION IF NOT EXISTS pg_stat_statements",
+            "install pg_stat_statements",
+        )
+        .await?;
+        Ok(())
+    }
+
+    /// A `pg_dump` of the source restored into the target. Filtered and sampled copies go
+    /// through the database service instead.
+    async fn fork(&self, docker: &DockerManager, fork: &ForkSpec<'_>) -> AppResult<()> {
+        let dump_args: &[&str] = if fork.schema_only {
+            &["--schema-only"]
+        } else {
+            &[]
+        };
+        docker
+            .fork_database_with(
+                &fork.source.container_name(),
+                &fork.target.container_name(),
+                &fork.source.username,
+                fork.source_password,
+                &fork.target.username,
+                fork.target_password,
+                dump_args,
+                fork.progress,
+            )
+            .await
+    }
+
+    /// Drops every user schema, with all tables, views and extensions in them, and leaves an
+    /// empty `public` schema.
+    async fn clear_data(
+        &self,
+        docker: &DockerManager,
+        database: &Database,
+        password: &str,
+    ) -> AppResult<()> {
+        Self::psql_value(
+            docker,
+            database,
+            password,
+            CLEAR_SCHEMAS_SQL,
+            "drop user schemas",
+        )
+        .await?;
+        Self::psql_value(
+            docker,
+            database,
+            password,
+            "CREATE SCHEMA IF NOT EXISTS public",
+            "create the public schema",
+        )
+        .await?;
+        Ok(())
+    }
+
+    /// The role password is stored in the cluster, so it moves with the data directory.
+    async fn set_user_password(
+        &self,
+        docker: &DockerManager,
+        database: &Database,
+        password: &str,
+        new_password: &str,
+    ) -> AppResult<()> {
+        let statement = format!(
+            "ALTER ROLE \"{}\" WITH PASSWORD '{}'",
+            database.username.replace('"', "\"\""),
+            new_password.replace('\'', "''")
+        );
+        Self::psql_value(
+            docker,
+            database,
+            password,
+            &statement,
+            "change the role password",
+        )
+        .await?;
+        Ok(())
+    }
+
+    fn config_requires_restart(&self) -> bool {
+        true
+    }
+
+    async fn read_config(
+        &self,
+        docker: &DockerManager,
+        database: &Database,
+        password: &str,
+        _warnings: &mut Vec<String>,
+    ) -> AppResult<EngineConfig> {
+        let config_path = Self::config_path(docker, database, password).await?;
+        let output = docker
+            .run_exec(
+                container_id(database)?,
+                vec!["cat".to_string(), config_path],
+                None,
+            )
+            .await?;
+
+        if let Some(code) = output.exit_code {
+            if code != 0 {
+                return Err(AppError::Docker(format!(
+                    "Failed to read config file: {}",
+                    output.stderr.trim()
+                )));
+            }
+        }
+
+        Ok(EngineConfig {
+            format: ConfigFormat::File,
+            source: ConfigSource::File,
+            content: output.stdout,
+        })
+    }
+
+    async fn write_config(
+        &self,
+        docker: &DockerManager,
+        database: &Database,
+        password: &str,
+        content: &str,
+        warnings: &mut Vec<String>,
+    ) -> AppResult<bool> {
+        let config_path = Self::config_path(docker, database, password).await?;
+        let command = format!(
+            "printf '%s' '{}' | base64 -d > '{}'",
+            shell_escape_single(&general_purpose::STANDARD.encode(content)),
+            shell_escape_single(&config_path)
+        );
+
+        let output = docker
+            .run_exec(
+                container_id(database)?,
+                vec!["sh".to_string(), "-c".to_string(), command],
+                None,
+            )
+            .await?;
+
+        if let Some(code) = output.exit_code {
+            if code != 0 {
+                return Err(AppError::Docker(format!(
+                    "Failed to write config file: {}",
+                    output.stderr.trim()
+                )));
+            }
+        }
+
+        let reloaded = Self::psql_value(
+            docker,
+            database,
+            password,
+            "SELECT pg_reload_conf();",
+            "reload PostgreSQL config",
+        )
+        .await?
+            == "t";
+        if !reloaded {
+            warnings.push("Config saved, but reload did not report success.".to_string());
+        }
+
+        Ok(reloaded)
+    }
+
+    /// Makes new transactions read-only by default. Sessions can still opt out explicitly to
+    /// delete data and get back under the limit.
+    async fn set_write_block(
+        &self,
+        docker: &DockerManager,
+        database: &Database,
+        password: &str,
+        blocked: bool,
+    ) -> AppResult<()> {
+        let setting = if blocked {
+            "ALTER SYSTEM SET default_transaction_read_only = on"
+        } else {
+            "ALTER SYSTEM RESET default_transaction_read_only"
+        };
+        Self::psql_value(
+            docker,
+            database,
+            password,
+            setting,
+            "change default_transaction_read_only",
+        )
+        .await?;
+        Self::psql_value(
+            docker,
+            database,
+            password,
+            "SELECT pg_reload_conf()",
+            "reload PostgreSQL config",
+        )
+        .await?;
+        Ok(())
+    }
+
+    fn supports_sql(&self) -> bool {
+        true
+    }
+
+    async fn sql_schema(&self, database: &Database, password: &str) -> AppResult<SchemaInfo> {
+        sql_postgres::get_schema(database, password).await
+    }
+
+    async fn sql_query(
+        &self,
+        database: &Database,
+        password: &str,
+        sql: &str,
+        limit: i32,
+        timeout_ms: i32,
+    ) -> AppResult<QueryResult> {
+        sql_postgres::execute_query(database, password, sql, limit, timeout_ms).await
+    }
+
+    async fn sql_preview(
+        &self,
+        database: &Database,
+        password: &str,
+        schema: &str,
+        table: &str,
+        limit: i32,
+        offset: i32,
+    ) -> AppResult<TablePreview> {
+        sql_postgres::preview_table(database, password, schema, table, limit, offset).await
+    }
+
+    fn metrics_collector(
+        &self,
+        encryption_key: [u8; 32],
+        _docker: &Arc<DockerManager>,
+    ) -> Arc<dyn MetricsCollector> {
+        Arc::new(PostgresMetricsCollector::new(encryption_key))
+    }
+}
+
+fn shell_escape_single(value: &str) -> String {
+    value.replace('\'', "'\"'\"'")
+}
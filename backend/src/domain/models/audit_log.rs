@@ -21,7 +21,29 @@ pub enum AuditAction {
     ChangePassword,
     CreateBranch,
     SyncFromParent,
+    PromoteBranch,
+    ResetBranch,
+    CreateSnapshot,
+    DeleteSnapshot,
+    ExtendBranch,
+    ExpireBranch,
+    DetachReplica,
     ExecuteQuery,
+    CreateBackup,
+    DeleteBackup,
+    UpdateBackupSchedule,
+    RestoreBackup,
+    RestoreBackupToBranch,
+    PointInTimeRestore,
+    PointInTimeBranch,
+    UploadImport,
+    ExportDatabase,
+    UpgradeDatabase,
+    RollbackUpgrade,
+    ApplyUpdate,
+    InstallExtension,
+    RemoveExtension,
+    UpdatePreloadLibraries,
 }
 
 impl std::fmt::Display for AuditAction {
@@ -43,7 +65,29 @@ impl std::fmt::Display for AuditAction {
             Self::ChangePassword => write!(f, "change_password"),
             Self::CreateBranch => write!(f, "create_branch"),
             Self::SyncFromParent => write!(f, "sync_from_parent"),
+            Self::PromoteBranch => write!(f, "promote_branch"),
+            Self::ResetBranch => write!(f, "reset_branch"),
+            Self::CreateSnapshot => write!(f, "create_snapshot"),
+            Self::DeleteSnapshot => write!(f, "delete_snapshot"),
+            Self::ExtendBranch => write!(f, "extend_branch"),
+            Self::ExpireBranch => write!(f, "expire_branch"),
+            Self::DetachReplica => write!(f, "detach_replica"),
             Self::ExecuteQuery => write!(f, "execute_query"),
+            Self::CreateBackup => write!(f, "create_backup"),
+            Self::DeleteBackup => write!(f, "delete_backup"),
+            Self::UpdateBackupSchedule => write!(f, "update_backup_schedule"),
+            Self::RestoreBackup => write!(f, "restore_backup"),
+            Self::RestoreBackupToBranch => write!(f, "restore_backup_to_branch"),
+            Self::PointInTimeRestore => write!(f, "point_in_time_restore"),
+            Self::PointInTimeBranch => write!(f, "point_in_time_branch"),
+            Self::UploadImport => write!(f, "upload_import"),
+            Self::ExportDatabase => write!(f, "export_database"),
+            Self::UpgradeDatabase => write!(f, "upgrade_database"),
+            Self::RollbackUpgrade => write!(f, "rollback_upgrade"),
+            Self::ApplyUpdate => write!(f, "apply_update"),
+            Self::InstallExtension => write!(f, "install_extension"),
+            Self::RemoveExtension => write!(f, "remove_extension"),
+            Self::UpdatePreloadLibraries => write!(f, "update_preload_libraries"),
         }
     }
 }
@@ -69,7 +113,29 @@ impl TryFrom<&str> for AuditAction {
             "change_password" => Ok(Self::ChangePassword),
             "create_branch" => Ok(Self::CreateBranch),
             "sync_from_parent" => Ok(Self::SyncFromParent),
+            "promote_branch" => Ok(Self::PromoteBranch),
+            "reset_branch" => Ok(Self::ResetBranch),
+            "create_snapshot" => Ok(Self::CreateSnapshot),
+            "delete_snapshot" => Ok(Self::DeleteSnapshot),
+            "extend_branch" => Ok(Self::ExtendBranch),
+            "expire_branch" => Ok(Self::ExpireBranch),
+            "detach_replica" => Ok(Self::DetachReplica),
             "execute_query" => Ok(Self::ExecuteQuery),
+            "create_backup" => Ok(Self::CreateBackup),
+            "delete_backup" => Ok(Self::DeleteBackup),
+            "update_backup_schedule" => Ok(Self::UpdateBackupSchedule),
+            "restore_backup" => Ok(Self::RestoreBackup),
+            "restore_backup_to_branch" => Ok(Self::RestoreBackupToBranch),
+            "point_in_time_restore" => Ok(Self::PointInTimeRestore),
+            "point_in_time_branch" => Ok(Self::PointInTimeBranch),
+            "upload_import" => Ok(Self::UploadImport),
+            "export_database" => Ok(Self::ExportDatabase),
+            "upgrade_database" => Ok(Self::UpgradeDatabase),
+            "rollback_upgrade" => Ok(Self::RollbackUpgrade),
+            "apply_update" => Ok(Self::ApplyUpdate),
+            "install_extension" => Ok(Self::InstallExtension),
+            "remove_extension" => Ok(Self::RemoveExtension),
+            "update_preload_libraries" => Ok(Self::UpdatePreloadLibraries),
             _ => Err(format!("Invalid audit action: {}", value)),
         }
     }
@@ -83,6 +149,7 @@ pub enum AuditEntityType {
     Database,
     Branch,
     Query,
+    Backup,
 }
 
 impl std::fmt::Display for AuditEntityType {
@@ -93,6 +160,7 @@ impl std::fmt::Display for AuditEntityType {
             Self::Database => write!(f, "database"),
             Self::Branch => write!(f, "branch"),
             Self::Query => write!(f, "query"),
+            Self::Backup => write!(f, "backup"),
         }
     }
 }
@@ -107,6 +175,7 @@ impl TryFrom<&str> for AuditEntityType {
             "database" => Ok(Self::Database),
             "branch" => Ok(Self::Branch),
             "query" => Ok(Self::Query),
+            "backup" => Ok(Self::Backup),
             _ => Err(format!("Invalid entity type: {}", value)),
         }
     }
@@ -0,0 +1,172 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use utoipa::ToSchema;
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct Backup {
+    pub id: String,
+    pub database_id: String,
+    pub status: String,
+    pub kind: String,
+    pub format: String,
+    pub file_path: Option<String>,
+    pub size_bytes: Option<i64>,
+    pub duration_ms: Option<i64>,
+    pub checksum: Option<String>,
+    pub error: Option<String>,
+    pub created_at: String,
+    pub completed_at: Option<String>,
+    pub storage: String,
+    pub encrypted: bool,
+    /// Encrypted storage target the file was written to; `None` for local and older backups
+    #[serde(skip)]
+    pub storage_target: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct BackupSchedule {
+    pub database_id: String,
+    pub enabled: bool,
+    pub interval_hours: i32,
+    pub retention_count: Option<i32>,
+    pub retention_days: Option<i32>,
+    pub format: String,
+    pub last_run_at: Option<String>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct BackupResponse {
+    pub id: String,
+    pub database_id: String,
+    #[schema(example = "completed")]
+    pub status: String,
+    #[schema(example = "scheduled")]
+    pub kind: String,
+    #[schema(example = "custom")]
+    pub format: String,
+    pub size_bytes: Option<i64>,
+    pub duration_ms: Option<i64>,
+    pub checksum: Option<String>,
+    pub error: Option<String>,
+    pub created_at: String,
+    pub completed_at: Option<String>,
+    #[schema(example = "s3")]
+    pub storage: String,
+    pub encrypted: bool,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct BackupScheduleResponse {
+    pub database_id: String,
+    pub enabled: bool,
+    pub interval_hours: i32,
+    pub retention_count: Option<i32>,
+    pub retention_days: Option<i32>,
+    #[schema(example = "custom")]
+    pub format: String,
+    pub last_run_at: Option<String>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct UpdateBackupScheduleRequest {
+    pub enabled: Option<bool>,
+    #[schema(example = 24)]
+    pub interval_hours: Option<i32>,
+    #[schema(example = 7)]
+    pub retention_count: Option<i32>,
+    #[schema(example = 30)]
+    pub retention_days: Option<i32>,
+    #[schema(example = "base")]
+    pub format: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize, ToSchema)]
+pub struct CreateBackupRequest {
+    #[schema(example = "base")]
+    pub format: Option<String>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct RestoreBackupToBranchRequest {
+    #[schema(example = "restored")]
+    pub name: String,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct PitrStatusResponse {
+    pub database_id: String,
+    pub enabled: bool,
+    pub earliest_restore_time: Option<DateTime<Utc>>,
+    pub latest_restore_time: Option<DateTime<Utc>>,
+    pub base_backup_count: i64,
+    pub wal_archive_size_bytes: i64,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct PointInTimeRestoreRequest {
+    #[schema(example = "2026-01-15T12:30:00Z")]
+    pub target_time: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct PointInTimeBranchRequest {
+    #[schema(example = "before-migration")]
+    pub name: String,
+    #[schema(example = "2026-01-15T12:30:00Z")]
+    pub target_time: DateTime<Utc>,
+}
+
+impl Backup {
+    pub fn is_finished(&self) -> bool {
+        self.status == "completed" || self.status == "failed"
+    }
+
+    pub fn to_response(&self) -> BackupResponse {
+        BackupResponse {
+            id: self.id.clone(),
+            database_id: self.database_id.clone(),
+            status: self.status.clone(),
+            kind: self.kind.clone(),
+            format: self.format.clone(),
+            size_bytes: self.size_bytes,
+            duration_ms: self.duration_ms,
+            checksum: self.checksum.clone(),
+            error: self.error.clone(),
+            created_at: self.created_at.clone(),
+            completed_at: self.completed_at.clone(),
+            storage: self.storage.clone(),
+            encrypted: self.encrypted,
+        }
+    }
+}
+
+impl BackupSchedule {
+    pub fn disabled(database_id: &str, format: &str) -> Self {
+        Self {
+            database_id: database_id.to_string(),
+            enabled: false,
+            interval_hours: 24,
+            retention_count: Some(7),
+            retention_days: None,
+            format: format.to_string(),
+            last_run_at: None,
+            created_at: String::new(),
+            updated_at: String::new(),
+        }
+    }
+
+    pub fn to_response(&self) -> BackupScheduleResponse {
+        BackupScheduleResponse {
+            database_id: self.database_id.clone(),
+            enabled: self.enabled,
+            interval_hours: self.interval_hours,
+            retention_count: self.retention_count,
+            retention_days: self.retention_days,
+            format: self.format.clone(),
+            last_run_at: self.last_run_at.clone(),
+        }
+    }
+}
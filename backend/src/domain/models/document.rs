@@ -0,0 +1,50 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// Read operation run by the document console
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, ToSchema, PartialEq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum DocumentOperation {
+    #[default]
+    Find,
+    Aggregate,
+}
+
+/// Request body for querying documents (MongoDB). Filters, projections, sorts and stages are
+/// Extended JSON, so `{"$oid": "..."}` and `{"$date": "..."}` values work.
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+pub struct ExecuteDocumentQueryRequest {
+    /// Database holding the collection (default: app)
+    #[serde(default)]
+    pub database: Option<String>,
+    pub collection: String,
+    #[serde(default)]
+    pub operation: DocumentOperation,
+    /// Query filter for `find`
+    #[serde(default)]
+    pub filter: Option<serde_json::Value>,
+    /// Projection for `find`
+    #[serde(default)]
+    pub projection: Option<serde_json::Value>,
+    /// Sort specification for `find`
+    #[serde(default)]
+    pub sort: Option<serde_json::Value>,
+    /// Aggregation stages for `aggregate`
+    #[serde(default)]
+    pub pipeline: Vec<serde_json::Value>,
+    /// Maximum number of documents to return (default: 100, max: 1000)
+    #[serde(default)]
+    pub limit: Option<i32>,
+    /// Query timeout in milliseconds (default: 5000, max: 60000)
+    #[serde(default)]
+    pub timeout_ms: Option<i32>,
+}
+
+/// Documents returned by the document console, as relaxed Extended JSON
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct DocumentQueryResult {
+    pub documents: Vec<serde_json::Value>,
+    pub count: i64,
+    pub execution_time_ms: f64,
+    pub truncated: bool,
+}
@@ -0,0 +1,200 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// Libraries every PostgreSQL database preloads. Query metrics need `pg_stat_statements`.
+pub const DEFAULT_PRELOAD_LIBRARIES: &[&str] = &["pg_stat_statements"];
+
+/// Extensions that only work once their library is in `shared_preload_libraries`.
+pub const PRELOAD_EXTENSIONS: &[&str] = &[
+    "pg_stat_statements",
+    "timescaledb",
+    "pg_cron",
+    "pgaudit",
+    "pg_squeeze",
+];
+
+/// A curated PostgreSQL image that ships extensions the official image does not have.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, ToSchema, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum PostgresVariant {
+    Pgvector,
+    Postgis,
+    Timescaledb,
+}
+
+impl PostgresVariant {
+    pub const ALL: [Self; 3] = [Self::Pgvector, Self::Postgis, Self::Timescaledb];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Pgvector => "pgvector",
+            Self::Postgis => "postgis",
+            Self::Timescaledb => "timescaledb",
+        }
+    }
+
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            Self::Pgvector => "pgvector",
+            Self::Postgis => "PostGIS",
+            Self::Timescaledb => "TimescaleDB",
+        }
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            Self::Pgvector => "Vector similarity search for embeddings",
+            Self::Postgis => "Spatial types, indexes and functions",
+            Self::Timescaledb => "Time-series hypertables and continuous aggregates",
+        }
+    }
+
+    /// Major versions the variant publishes images for.
+    pub fn versions(&self) -> (u32, u32) {
+        match self {
+            Self::Pgvector | Self::Postgis => (13, 17),
+            Self::Timescaledb => (15, 17),
+        }
+    }
+
+    pub fn supports_version(&self, version: &str) -> bool {
+        let (min, max) = self.versions();
+        version
+            .parse::<u32>()
+            .is_ok_and(|major| (min..=max).contains(&major))
+    }
+
+    pub fn image(&self, version: &str) -> String {
+        match self {
+            Self::Pgvector => format!("pgvector/pgvector:pg{}", version),
+            Self::Postgis => format!("postgis/postgis:{}-3.5", version),
+            Self::Timescaledb => format!("timescale/timescaledb:latest-pg{}", version),
+        }
+    }
+
+    /// Extensions the image adds.
+    pub fn extensions(&self) -> &'static [&'static str] {
+        match self {
+            Self::Pgvector => &["vector"],
+            Self::Postgis => &[
+                "postgis",
+                "postgis_topology",
+                "postgis_raster",
+                "fuzzystrmatch",
+                "postgis_tiger_geocoder",
+            ],
+            Self::Timescaledb => &["timescaledb"],
+        }
+    }
+
+    /// Libraries preloaded on top of the defaults when a database is created on the variant.
+    pub fn preload_libraries(&self) -> &'static [&'static str] {
+        match self {
+            Self::Timescaledb => &["timescaledb"],
+            Self::Pgvector | Self::Postgis => &[],
+        }
+    }
+
+    /// The variant that ships `extension`, for hints when it is missing from the image.
+    pub fn providing(extension: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|variant| variant.extensions().contains(&extension))
+    }
+}
+
+impl std::str::FromStr for PostgresVariant {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|variant| variant.as_str() == s)
+            .ok_or_else(|| format!("Unknown PostgreSQL variant: {}", s))
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct PostgresVariantInfo {
+    pub variant: PostgresVariant,
+    #[schema(example = "pgvector")]
+    pub name: String,
+    pub description: String,
+    #[schema(example = "13")]
+    pub min_version: String,
+    #[schema(example = "17")]
+    pub max_version: String,
+    pub extensions: Vec<String>,
+    pub preload_libraries: Vec<String>,
+}
+
+impl From<PostgresVariant> for PostgresVariantInfo {
+    fn from(variant: PostgresVariant) -> Self {
+        let (min, max) = variant.versions();
+        Self {
+            variant,
+            name: variant.display_name().to_string(),
+            description: variant.description().to_string(),
+            min_version: min.to_string(),
+            max_version: max.to_string(),
+            extensions: variant.extensions().iter().map(|e| e.to_string()).collect(),
+            preload_libraries: variant
+                .preload_libraries()
+                .iter()
+                .map(|l| l.to_string())
+                .collect(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct PostgresVariantsResponse {
+    pub variants: Vec<PostgresVariantInfo>,
+}
+
+/// An extension the database's image can install.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ExtensionInfo {
+    #[schema(example = "vector")]
+    pub name: String,
+    pub default_version: Option<String>,
+    /// Set when the extension is installed in the `postgres` database.
+    pub installed_version: Option<String>,
+    pub comment: Option<String>,
+    /// Whether the extension's library must be in `shared_preload_libraries` to work.
+    pub requires_preload: bool,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ExtensionsResponse {
+    pub database_id: String,
+    pub variant: Option<PostgresVariant>,
+    pub shared_preload_libraries: Vec<String>,
+    pub extensions: Vec<ExtensionInfo>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct InstallExtensionRequest {
+    #[schema(example = "vector")]
+    pub name: String,
+    /// Defaults to the image's default version.
+    pub version: Option<String>,
+    /// Defaults to the first schema on the search path.
+    pub schema: Option<String>,
+    /// Also install extensions this one depends on.
+    #[serde(default)]
+    pub cascade: bool,
+}
+
+#[derive(Debug, Default, Deserialize, ToSchema)]
+pub struct RemoveExtensionQuery {
+    /// Also drop objects that depend on the extension.
+    #[serde(default)]
+    pub cascade: bool,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct UpdatePreloadLibrariesRequest {
+    #[schema(example = json!(["pg_stat_statements", "pg_cron"]))]
+    pub libraries: Vec<String>,
+}
@@ -0,0 +1,101 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use sha2::{Digest, Sha256};
+use utoipa::ToSchema;
+
+use crate::error::{AppError, AppResult};
+
+pub const MASKING_SETTINGS_KEY: &str = "masking";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum MaskingStrategy {
+    /// Salted SHA-256 of the value. Equal values hash alike within one run, so joins still match
+    Hash,
+    /// A generated value in the format given by `fake`
+    Fake,
+    Nullify,
+    /// Permutes the column's values between rows
+    Shuffle,
+    /// Leaves the column as it is, marking it as reviewed
+    Keep,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum FakeFormat {
+    Email,
+    Phone,
+    Name,
+    #[default]
+    Text,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct MaskingRule {
+    #[serde(default = "default_schema")]
+    pub schema: String,
+    pub table: String,
+    pub column: String,
+    pub strategy: MaskingStrategy,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fake: Option<FakeFormat>,
+}
+
+fn default_schema() -> String {
+    "public".to_string()
+}
+
+/// Per-project rules applied inside a PostgreSQL branch whenever data is copied into it.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+pub struct MaskingConfig {
+    #[serde(default)]
+    pub rules: Vec<MaskingRule>,
+}
+
+impl MaskingConfig {
+    pub fn from_project_settings(settings: &JsonValue) -> AppResult<Self> {
+        let config: Self = match settings.get(MASKING_SETTINGS_KEY) {
+            None | Some(JsonValue::Null) => return Ok(Self::default()),
+            Some(value) => serde_json::from_value(value.clone())
+                .map_err(|e| AppError::Validation(format!("Invalid masking settings: {}", e)))?,
+        };
+
+        for rule in &config.rules {
+            for name in [&rule.schema, &rule.table, &rule.column] {
+                if name.is_empty() || name.contains(['$', '\0']) {
+                    return Err(AppError::Validation(format!(
+                        "Invalid masking rule for {}.{}: '{}' is not a valid identifier",
+                        rule.table, rule.column, name
+                    )));
+                }
+            }
+            if rule.fake.is_some() && rule.strategy != MaskingStrategy::Fake {
+                return Err(AppError::Validation(format!(
+                    "Masking rule for {}.{} sets a fake format without the fake strategy",
+                    rule.table, rule.column
+                )));
+            }
+        }
+
+        Ok(config)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// Short identifier of the rule set, recorded on the branches it was applied to.
+    pub fn fingerprint(&self) -> String {
+        let rules = serde_json::to_vec(&self.rules).unwrap_or_default();
+        hex::encode(&Sha256::digest(&rules)[..6])
+    }
+}
+
+/// The masking rule set a branch's data went through when it was last copied from its parent.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct AppliedMasking {
+    pub rule_set: String,
+    pub rules: Vec<MaskingRule>,
+    pub applied_at: Option<String>,
+}
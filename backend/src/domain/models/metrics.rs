@@ -85,6 +85,26 @@ pub struct StorageMetrics {
     pub storage_percent: f64,
 }
 
+impl StorageMetrics {
+    /// The limit applies to the measured data directory; the engine-reported size stands in
+    /// until the first measurement.
+    pub fn new(database_size_bytes: i64, data_dir_bytes: Option<i64>, limit_mb: i32) -> Self {
+        let container_storage_bytes = data_dir_bytes.unwrap_or(database_size_bytes);
+        let storage_limit_bytes = limit_mb as i64 * 1024 * 1024;
+
+        Self {
+            database_size_bytes,
+            container_storage_bytes,
+            storage_limit_bytes,
+            storage_percent: if storage_limit_bytes > 0 {
+                container_storage_bytes as f64 / storage_limit_bytes as f64 * 100.0
+            } else {
+                0.0
+            },
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema, Default)]
 pub struct ConnectionMetrics {
     pub active_connections: i32,
@@ -101,6 +121,17 @@ pub struct ResourceMetrics {
     pub memory_percent: f64,
 }
 
+/// Replay state of a PostgreSQL standby
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema, Default)]
+pub struct StandbyMetrics {
+    /// State of the WAL receiver, such as `streaming`, or none while disconnected
+    pub receiver_status: Option<String>,
+    /// Seconds the replayed data is behind the parent; 0 while fully caught up
+    pub lag_seconds: f64,
+    /// WAL received but not replayed yet
+    pub lag_bytes: i64,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema, Default)]
 pub struct DatabaseMetrics {
     pub timestamp: String,
@@ -110,6 +141,8 @@ pub struct DatabaseMetrics {
     pub storage: StorageMetrics,
     pub connections: ConnectionMetrics,
     pub resources: ResourceMetrics,
+    /// Set while the database is a replica
+    pub standby: Option<StandbyMetrics>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
@@ -210,7 +243,7 @@ impl From<MetricsSnapshot> for MetricsHistoryPoint {
 #[serde(tag = "type", rename_all = "snake_case")]
 pub enum MetricsStreamMessage {
     Connected { database_id: String },
-    Metrics { metrics: UnifiedMetrics },
+    Metrics { metrics: Box<UnifiedMetrics> },
     Error { message: String },
 }
 
@@ -251,6 +284,10 @@ pub struct MemoryMetrics {
 pub struct ReplicationMetrics {
     pub role: String,
     pub connected_slaves: i32,
+    /// `up` or `down` on replicas
+    pub master_link_status: Option<String>,
+    /// Bytes of the master's replication stream the replica has not processed yet
+    pub lag_bytes: Option<i64>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema, Default)]
@@ -261,6 +298,57 @@ pub struct KeyValueMetrics {
     pub memory: MemoryMetrics,
     pub clients: ClientMetrics,
     pub replication: ReplicationMetrics,
+    pub storage: StorageMetrics,
+    pub resources: ResourceMetrics,
+}
+
+/// `serverStatus` operation counters, counted since the server started
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema, Default)]
+pub struct OperationCounterMetrics {
+    pub inserts: i64,
+    pub queries: i64,
+    pub updates: i64,
+    pub deletes: i64,
+    pub getmores: i64,
+    pub commands: i64,
+    pub total_operations: i64,
+    pub ops_per_sec: f64,
+    pub avg_latency_ms: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema, Default)]
+pub struct DocumentCounterMetrics {
+    pub returned: i64,
+    pub inserted: i64,
+    pub updated: i64,
+    pub deleted: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema, Default)]
+pub struct CollectionMetrics {
+    pub total_collections: i64,
+    pub total_documents: i64,
+    pub total_indexes: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema, Default)]
+pub struct DocumentMemoryMetrics {
+    pub resident_bytes: i64,
+    pub virtual_bytes: i64,
+    /// Data held in the WiredTiger cache
+    pub cache_used_bytes: i64,
+    pub cache_max_bytes: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema, Default)]
+pub struct DocumentMetrics {
+    pub timestamp: String,
+    pub operations: OperationCounterMetrics,
+    pub documents: DocumentCounterMetrics,
+    pub collections: CollectionMetrics,
+    pub connections: ConnectionMetrics,
+    pub memory: DocumentMemoryMetrics,
+    pub storage: StorageMetrics,
     pub resources: ResourceMetrics,
 }
 
@@ -270,6 +358,10 @@ pub enum UnifiedMetrics {
     Postgres(DatabaseMetrics),
     Redis(KeyValueMetrics),
     Valkey(KeyValueMetrics),
+    Mysql(DatabaseMetrics),
+    Mariadb(DatabaseMetrics),
+    Mongodb(DocumentMetrics),
+    Clickhouse(DatabaseMetrics),
 }
 
 impl Default for UnifiedMetrics {
@@ -281,29 +373,45 @@ impl Default for UnifiedMetrics {
 impl UnifiedMetrics {
     pub fn timestamp(&self) -> &str {
         match self {
-            UnifiedMetrics::Postgres(m) => &m.timestamp,
+            UnifiedMetrics::Postgres(m)
+            | UnifiedMetrics::Mysql(m)
+            | UnifiedMetrics::Mariadb(m)
+            | UnifiedMetrics::Clickhouse(m) => &m.timestamp,
             UnifiedMetrics::Redis(m) | UnifiedMetrics::Valkey(m) => &m.timestamp,
+            UnifiedMetrics::Mongodb(m) => &m.timestamp,
         }
     }
 
     pub fn cpu_percent(&self) -> f64 {
         match self {
-            UnifiedMetrics::Postgres(m) => m.resources.cpu_percent,
+            UnifiedMetrics::Postgres(m)
+            | UnifiedMetrics::Mysql(m)
+            | UnifiedMetrics::Mariadb(m)
+            | UnifiedMetrics::Clickhouse(m) => m.resources.cpu_percent,
             UnifiedMetrics::Redis(m) | UnifiedMetrics::Valkey(m) => m.resources.cpu_percent,
+            UnifiedMetrics::Mongodb(m) => m.resources.cpu_percent,
         }
     }
 
     pub fn memory_percent(&self) -> f64 {
         match self {
-            UnifiedMetrics::Postgres(m) => m.resources.memory_percent,
+            UnifiedMetrics::Postgres(m)
+            | UnifiedMetrics::Mysql(m)
+            | UnifiedMetrics::Mariadb(m)
+            | UnifiedMetrics::Clickhouse(m) => m.resources.memory_percent,
             UnifiedMetrics::Redis(m) | UnifiedMetrics::Valkey(m) => m.resources.memory_percent,
+            UnifiedMetrics::Mongodb(m) => m.resources.memory_percent,
         }
     }
 
     pub fn memory_used_bytes(&self) -> i64 {
         match self {
-            UnifiedMetrics::Postgres(m) => m.resources.memory_used_bytes,
+            UnifiedMetrics::Postgres(m)
+            | UnifiedMetrics::Mysql(m)
+            | UnifiedMetrics::Mariadb(m)
+            | UnifiedMetrics::Clickhouse(m) => m.resources.memory_used_bytes,
             UnifiedMetrics::Redis(m) | UnifiedMetrics::Valkey(m) => m.resources.memory_used_bytes,
+            UnifiedMetrics::Mongodb(m) => m.resources.memory_used_bytes,
         }
     }
 }
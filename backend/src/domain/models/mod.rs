@@ -1,19 +1,31 @@
 mod audit_log;
+mod backup;
 mod config;
 mod database;
+mod document;
+mod extension;
 mod kv;
 mod logs;
+mod masking;
 mod metrics;
+mod operation;
 mod project;
+mod snapshot;
 mod sql;
 mod user;
 
 pub use audit_log::*;
+pub use backup::*;
 pub use config::*;
 pub use database::*;
+pub use document::*;
+pub use extension::*;
 pub use kv::*;
 pub use logs::*;
+pub use masking::*;
 pub use metrics::*;
+pub use operation::*;
 pub use project::*;
+pub use snapshot::*;
 pub use sql::*;
 pub use user::*;
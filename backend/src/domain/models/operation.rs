@@ -0,0 +1,72 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use utoipa::ToSchema;
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct Operation {
+    pub id: String,
+    pub database_id: String,
+    pub kind: String,
+    pub status: String,
+    pub backup_id: Option<String>,
+    pub target_database_id: Option<String>,
+    pub error: Option<String>,
+    pub phase: Option<String>,
+    pub progress_bytes: i64,
+    pub total_bytes: Option<i64>,
+    pub created_at: String,
+    pub completed_at: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct OperationResponse {
+    pub id: String,
+    pub database_id: String,
+    #[schema(example = "restore")]
+    pub kind: String,
+    #[schema(example = "running")]
+    pub status: String,
+    pub backup_id: Option<String>,
+    pub target_database_id: Option<String>,
+    pub error: Option<String>,
+    /// Step a running operation is in, such as `pulling_image` or `copying_data`
+    #[schema(example = "copying_data")]
+    pub phase: Option<String>,
+    pub progress_bytes: i64,
+    pub total_bytes: Option<i64>,
+    pub created_at: String,
+    pub completed_at: Option<String>,
+}
+
+impl OperationResponse {
+    pub fn is_finished(&self) -> bool {
+        self.status != "running"
+    }
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum OperationStreamMessage {
+    Connected { operation_id: String },
+    Operation { operation: Box<OperationResponse> },
+    Error { message: String },
+}
+
+impl Operation {
+    pub fn to_response(&self) -> OperationResponse {
+        OperationResponse {
+            id: self.id.clone(),
+            database_id: self.database_id.clone(),
+            kind: self.kind.clone(),
+            status: self.status.clone(),
+            backup_id: self.backup_id.clone(),
+            target_database_id: self.target_database_id.clone(),
+            error: self.error.clone(),
+            phase: self.phase.clone(),
+            progress_bytes: self.progress_bytes,
+            total_bytes: self.total_bytes,
+            created_at: self.created_at.clone(),
+            completed_at: self.completed_at.clone(),
+        }
+    }
+}
@@ -3,6 +3,11 @@ use serde_json::Value as JsonValue;
 use sqlx::FromRow;
 use utoipa::ToSchema;
 
+pub const REDACTED_SECRET: &str = "********";
+
+/// Settings values that are write-only: `(section, field)` pairs masked in API responses.
+const SECRET_SETTINGS: &[(&str, &str)] = &[("backup_storage", "secret_access_key")];
+
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
 pub struct Project {
     pub id: String,
@@ -24,6 +29,30 @@ impl Project {
     }
 }
 
+pub fn redact_settings_secrets(settings: &mut JsonValue) {
+    for (section, field) in SECRET_SETTINGS {
+        if let Some(value) = settings.get_mut(*section).and_then(|s| s.get_mut(*field)) {
+            *value = JsonValue::String(REDACTED_SECRET.to_string());
+        }
+    }
+}
+
+/// Puts back secrets that a client echoed as redacted, so settings can be round-tripped.
+pub fn restore_settings_secrets(settings: &mut JsonValue, existing: &JsonValue) {
+    for (section, field) in SECRET_SETTINGS {
+        let Some(value) = settings.get_mut(*section).and_then(|s| s.get_mut(*field)) else {
+            continue;
+        };
+        if value.as_str() == Some(REDACTED_SECRET) {
+            *value = existing
+                .get(*section)
+                .and_then(|s| s.get(*field))
+                .cloned()
+                .unwrap_or(JsonValue::Null);
+        }
+    }
+}
+
 #[derive(Debug, Deserialize, ToSchema)]
 pub struct CreateProjectRequest {
     pub name: String,
@@ -51,7 +80,8 @@ pub struct ProjectResponse {
 
 impl From<Project> for ProjectResponse {
     fn from(project: Project) -> Self {
-        let settings = project.settings_json();
+        let mut settings = project.settings_json();
+        redact_settings_secrets(&mut settings);
         Self {
             id: project.id,
             name: project.name,
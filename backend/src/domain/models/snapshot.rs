@@ -0,0 +1,38 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use utoipa::ToSchema;
+
+/// A named copy-on-write snapshot of a PostgreSQL database's data directory. A reset can return
+/// the database to it.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct Snapshot {
+    pub id: String,
+    pub database_id: String,
+    pub name: String,
+    pub created_at: String,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct SnapshotResponse {
+    pub id: String,
+    pub database_id: String,
+    #[schema(example = "before-migration")]
+    pub name: String,
+    pub created_at: String,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct CreateSnapshotRequest {
+    pub name: String,
+}
+
+impl Snapshot {
+    pub fn to_response(&self) -> SnapshotResponse {
+        SnapshotResponse {
+            id: self.id.clone(),
+            database_id: self.database_id.clone(),
+            name: self.name.clone(),
+            created_at: self.created_at.clone(),
+        }
+    }
+}
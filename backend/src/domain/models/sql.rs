@@ -25,6 +25,19 @@ pub struct ViewInfo {
     pub schema: String,
     pub name: String,
     pub columns: Vec<ColumnDetail>,
+    /// The view's SELECT statement
+    #[serde(default)]
+    pub definition: Option<String>,
+    /// Tables and views the view reads from
+    #[serde(default)]
+    pub depends_on: Vec<RelationName>,
+}
+
+/// A table or view, by schema and name
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, ToSchema)]
+pub struct RelationName {
+    pub schema: String,
+    pub name: String,
 }
 
 /// Detailed column information
@@ -35,6 +48,9 @@ pub struct ColumnDetail {
     pub nullable: bool,
     pub default_value: Option<String>,
     pub is_primary_key: bool,
+    /// Full type including modifiers, e.g. `character varying(255)`
+    #[serde(default)]
+    pub sql_type: String,
 }
 
 /// Index information
@@ -44,6 +60,77 @@ pub struct IndexInfo {
     pub columns: Vec<String>,
     pub is_unique: bool,
     pub is_primary: bool,
+    /// The `CREATE INDEX` statement for the index
+    #[serde(default)]
+    pub definition: String,
+}
+
+/// Query parameters for comparing two database schemas
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+pub struct SchemaDiffQuery {
+    /// `parent` (default) or the ID of another database in the same project
+    #[serde(default)]
+    pub against: Option<String>,
+}
+
+/// Kind of change between two schemas
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SchemaChange {
+    Added,
+    Removed,
+    Modified,
+}
+
+/// Column difference. `before` is the compared database, `after` this database
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ColumnDiff {
+    pub name: String,
+    pub change: SchemaChange,
+    pub before: Option<ColumnDetail>,
+    pub after: Option<ColumnDetail>,
+}
+
+/// Index difference
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct IndexDiff {
+    pub name: String,
+    pub change: SchemaChange,
+    pub before: Option<IndexInfo>,
+    pub after: Option<IndexInfo>,
+}
+
+/// Table difference. Added and removed tables list all of their columns and indexes
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct TableDiff {
+    pub schema: String,
+    pub name: String,
+    pub change: SchemaChange,
+    pub columns: Vec<ColumnDiff>,
+    pub indexes: Vec<IndexDiff>,
+}
+
+/// View difference
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ViewDiff {
+    pub schema: String,
+    pub name: String,
+    pub change: SchemaChange,
+    pub before: Option<String>,
+    pub after: Option<String>,
+}
+
+/// Schema differences between a database and the one it is compared against
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct SchemaDiff {
+    pub database_id: String,
+    pub against_id: String,
+    pub tables: Vec<TableDiff>,
+    pub views: Vec<ViewDiff>,
+    /// Ordered DDL that brings the compared database up to this one
+    pub migration: Vec<String>,
+    /// Statements in `migration` that drop data
+    pub warnings: Vec<String>,
 }
 
 /// Request body for executing SQL queries
@@ -0,0 +1,178 @@
+use std::sync::Arc;
+
+use super::{
+    AuditLogService, AuthService, BackupService, BackupStores, BranchService, DatabaseService,
+    ExportService, ExtensionService, MetricsService, OperationService, ProjectService,
+    QuotaService, SqlService, UpdateService, UpgradeService,
+};
+use crate::config::Settings;
+use crate::infrastructure::docker::DockerManager;
+use crate::infrastructure::volumes::build_data_volumes;
+use crate::repositories::Repositories;
+
+/// Every service, built once at startup and shared by the router and the background jobs.
+#[derive(Clone)]
+pub struct AppServices {
+    pub auth: Arc<AuthService>,
+    pub project: Arc<ProjectService>,
+    pub database: Arc<DatabaseService>,
+    pub metrics: Arc<MetricsService>,
+    pub sql: Arc<SqlService>,
+    pub backup: Arc<BackupService>,
+    pub export: Arc<ExportService>,
+    pub upgrade: Arc<UpgradeService>,
+    pub extension: Arc<ExtensionService>,
+    pub update: Arc<UpdateService>,
+    pub branch: Arc<BranchService>,
+    pub operation: Arc<OperationService>,
+    pub audit_log: Arc<AuditLogService>,
+    pub quota: Arc<QuotaService>,
+}
+
+impl AppServices {
+    pub fn new(
+        repositories: &Repositories,
+        docker: Arc<DockerManager>,
+        settings: Arc<Settings>,
+    ) -> Self {
+        let auth_service = Arc::new(AuthService::new(
+            repositories.users.clone(),
+            repositories.tokens.clone(),
+            settings.clone(),
+        ));
+
+        let project_service = Arc::new(ProjectService::new(
+            repositories.projects.clone(),
+            repositories.databases.clone(),
+        ));
+
+        let backup_stores = BackupStores::new(
+            repositories.databases.clone(),
+            repositories.projects.clone(),
+            &settings.docker.data_dir,
+            &settings.security.encryption_key,
+        );
+
+        let database_service = Arc::new(DatabaseService::new(
+            repositories.databases.clone(),
+            repositories.projects.clone(),
+            repositories.operations.clone(),
+            repositories.snapshots.clone(),
+            repositories.backups.clone(),
+            backup_stores.clone(),
+            docker.clone(),
+            build_data_volumes(&settings.docker.storage_backend, &settings.docker.data_dir),
+            settings.docker.data_dir.clone(),
+            settings.docker.public_host.clone(),
+            &settings.security.encryption_key,
+        ));
+
+        let metrics_service = Arc::new(MetricsService::new(
+            repositories.databases.clone(),
+            repositories.projects.clone(),
+            repositories.metrics.clone(),
+            docker.clone(),
+            &settings.security.encryption_key,
+        ));
+
+        let sql_service = Arc::new(SqlService::new(
+            repositories.databases.clone(),
+            repositories.projects.clone(),
+            docker.clone(),
+            &settings.security.encryption_key,
+        ));
+
+        let backup_service = Arc::new(BackupService::new(
+            repositories.backups.clone(),
+            repositories.databases.clone(),
+            repositories.projects.clone(),
+            repositories.operations.clone(),
+            database_service.clone(),
+            backup_stores,
+            docker.clone(),
+            settings.docker.data_dir.clone(),
+            &settings.security.encryption_key,
+        ));
+
+        let export_service = Arc::new(ExportService::new(
+            repositories.databases.clone(),
+            repositories.projects.clone(),
+            docker.clone(),
+            &settings.security.encryption_key,
+        ));
+
+        let upgrade_service = Arc::new(UpgradeService::new(
+            repositories.databases.clone(),
+            repositories.projects.clone(),
+            repositories.backups.clone(),
+            repositories.operations.clone(),
+            database_service.clone(),
+            docker.clone(),
+            settings.docker.data_dir.clone(),
+            &settings.security.encryption_key,
+        ));
+
+        let extension_service = Arc::new(ExtensionService::new(
+            repositories.databases.clone(),
+            repositories.projects.clone(),
+            repositories.operations.clone(),
+            database_service.clone(),
+            docker.clone(),
+            &settings.security.encryption_key,
+        ));
+
+        let update_service = Arc::new(UpdateService::new(
+            repositories.databases.clone(),
+            repositories.projects.clone(),
+            repositories.backups.clone(),
+            repositories.operations.clone(),
+            database_service.clone(),
+            docker.clone(),
+        ));
+
+        let branch_service = Arc::new(BranchService::new(
+            repositories.databases.clone(),
+            repositories.projects.clone(),
+            repositories.backups.clone(),
+            repositories.operations.clone(),
+            database_service.clone(),
+            backup_service.clone(),
+            sql_service.clone(),
+            docker.clone(),
+        ));
+
+        let operation_service = Arc::new(OperationService::new(
+            repositories.operations.clone(),
+            repositories.databases.clone(),
+            repositories.projects.clone(),
+            docker.clone(),
+        ));
+
+        let audit_log_service = Arc::new(AuditLogService::new(repositories.audit_logs.clone()));
+
+        let quota_service = Arc::new(QuotaService::new(
+            repositories.databases.clone(),
+            docker.clone(),
+            settings.docker.data_dir.clone(),
+            &settings.docker.storage_quota,
+            &settings.security.encryption_key,
+        ));
+
+        Self {
+            auth: auth_service,
+            project: project_service,
+            database: database_service,
+            metrics: metrics_service,
+            sql: sql_service,
+            backup: backup_service,
+            export: export_service,
+            upgrade: upgrade_service,
+            extension: extension_service,
+            update: update_service,
+            branch: branch_service,
+            operation: operation_service,
+            audit_log: audit_log_service,
+            quota: quota_service,
+        }
+    }
+}
@@ -0,0 +1,1765 @@
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Instant;
+
+use bollard::container::LogOutput;
+use chrono::{DateTime, Duration, NaiveDateTime, Utc};
+use futures::StreamExt;
+use sha2::{Digest, Sha256};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+use super::common::{owned_database, psql_command};
+use super::{BackupStores, DatabaseService};
+use crate::domain::engines::engine_for;
+use crate::domain::models::{
+    Backup, BackupResponse, BackupSchedule, BackupScheduleResponse, Database, Operation,
+    OperationResponse, PitrStatusResponse, UpdateBackupScheduleRequest,
+};
+use crate::error::{AppError, AppResult};
+use crate::infrastructure::docker::containers::{
+    ContainerProvider, PostgresContainer, RedisContainer, WAL_ARCHIVE_MOUNT_POINT,
+    WAL_RESTORE_MOUNT_POINT,
+};
+use crate::infrastructure::docker::DockerManager;
+use crate::infrastructure::storage::{
+    decrypt_file, derive_backup_key, encrypt_file, ENCRYPTED_EXTENSION,
+};
+use crate::repositories::{
+    BackupRepository, DatabaseRepository, OperationRepository, ProjectRepository,
+};
+use crate::utils::crypto;
+
+pub const RESTORE_BRANCH_OPERATION_KIND: &str = "restore_branch";
+pub const PITR_BRANCH_OPERATION_KIND: &str = "pitr_branch";
+
+const SQLITE_DATETIME_FORMAT: &str = "%Y-%m-%d %H:%M:%S";
+const MAX_INTERVAL_HOURS: i32 = 24 * 30;
+const FORMAT_CUSTOM: &str = "custom";
+const FORMAT_BASE: &str = "base";
+const FORMAT_RDB: &str = "rdb";
+const RECOVERY_TIMEOUT_SECONDS: u64 = 600;
+const SNAPSHOT_TIMEOUT_SECONDS: u64 = 600;
+
+pub struct BackupDownload {
+    pub file_name: String,
+    pub size_bytes: Option<i64>,
+    pub file: tokio::fs::File,
+}
+
+#[derive(Clone)]
+pub struct BackupService {
+    backup_repo: BackupRepository,
+    database_repo: DatabaseRepository,
+    project_repo: ProjectRepository,
+    operation_repo: OperationRepository,
+    database_service: Arc<DatabaseService>,
+    docker: Arc<DockerManager>,
+    data_dir: String,
+    backup_dir: String,
+    encryption_key: [u8; 32],
+    backup_key: [u8; 32],
+    stores: BackupStores,
+}
+
+/// A backup file readable on local disk; fetched or decrypted copies are removed on drop.
+struct LocalBackup {
+    backup: Backup,
+    temp_files: Vec<PathBuf>,
+}
+
+impl Drop for LocalBackup {
+    fn drop(&mut self) {
+        for path in &self.temp_files {
+            let _ = std::fs::remove_file(path);
+        }
+    }
+}
+
+impl BackupService {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        backup_repo: BackupRepository,
+        database_repo: DatabaseRepository,
+        project_repo: ProjectRepository,
+        operation_repo: OperationRepository,
+        database_service: Arc<DatabaseService>,
+        stores: BackupStores,
+        docker: Arc<DockerManager>,
+        data_dir: String,
+        encryption_key_hex: &str,
+    ) -> Self {
+        let encryption_key = crypto::parse_encryption_key(encryption_key_hex);
+
+        Self {
+            backup_repo,
+            database_repo,
+            project_repo,
+            operation_repo,
+            database_service,
+            docker,
+            backup_dir: stores.backup_dir().to_string(),
+            data_dir,
+            encryption_key,
+            backup_key: derive_backup_key(&encryption_key),
+            stores,
+        }
+    }
+
+    fn decrypt_password(&self, encrypted: &str) -> AppResult<String> {
+        crypto::decrypt_password(&self.encryption_key, encrypted)
+    }
+
+    async fn get_database(
+        &self,
+        database_id: &str,
+        user_id: &str,
+        is_admin: bool,
+    ) -> AppResult<Database> {
+        owned_database(
+            &self.database_repo,
+            &self.project_repo,
+            database_id,
+            user_id,
+            is_admin,
+        )
+        .await
+    }
+
+    async fn get_backup(&self, database_id: &str, backup_id: &str) -> AppResult<Backup> {
+        self.backup_repo
+            .find_by_id(backup_id)
+            .await?
+            .filter(|b| b.database_id == database_id)
+            .ok_or_else(|| AppError::NotFound(format!("Backup '{}' not found", backup_id)))
+    }
+
+    async fn get_restorable_backup(&self, database_id: &str, backup_id: &str) -> AppResult<Backup> {
+        let backup = self.get_backup(database_id, backup_id).await?;
+
+        if backup.status != "completed" || backup.file_path.is_none() {
+            return Err(AppError::Conflict(format!(
+                "Backup '{}' is not completed",
+                backup_id
+            )));
+        }
+
+        if backup.format == FORMAT_BASE {
+            return Err(AppError::Validation(format!(
+                "Backups in '{}' format can only be restored to a point in time",
+                backup.format
+            )));
+        }
+
+        Ok(backup)
+    }
+
+    fn backup_path(&self, database_id: &str, backup_id: &str, format: &str) -> String {
+        format!(
+            "{}/{}/{}.{}",
+            self.backup_dir,
+            database_id,
+            backup_id,
+            backup_extension(format)
+        )
+    }
+
+    fn validate_format(database: &Database, format: &str) -> AppResult<()> {
+        let engine = engine_for(&database.database_type);
+        if !engine.supports_backups() {
+            return Err(AppError::Validation(format!(
+                "Backups are not available for {} databases yet",
+                engine.display_name()
+            )));
+        }
+        match (format, engine_for(&database.database_type).is_key_value()) {
+            (FORMAT_RDB, true) | (FORMAT_CUSTOM, false) => Ok(()),
+            (FORMAT_BASE, false) if database.wal_archiving => Ok(()),
+            (FORMAT_BASE, false) => Err(AppError::Validation(
+                "Base backups require WAL archiving to be enabled".to_string(),
+            )),
+            _ => Err(AppError::Validation(format!(
+                "Backup format '{}' is not supported for {} databases",
+                format, database.database_type
+            ))),
+        }
+    }
+
+    pub async fn list(
+        &self,
+        database_id: &str,
+        user_id: &str,
+        is_admin: bool,
+    ) -> AppResult<Vec<BackupResponse>> {
+        self.get_database(database_id, user_id, is_admin).await?;
+
+        let backups = self.backup_repo.find_by_database_id(database_id).await?;
+        Ok(backups.iter().map(|b| b.to_response()).collect())
+    }
+
+    pub async fn get(
+        &self,
+        database_id: &str,
+        backup_id: &str,
+        user_id: &str,
+        is_admin: bool,
+    ) -> AppResult<BackupResponse> {
+        self.get_database(database_id, user_id, is_admin).await?;
+        Ok(self.get_backup(database_id, backup_id).await?.to_response())
+    }
+
+    pub async fn trigger(
+        &self,
+        database_id: &str,
+        user_id: &str,
+        is_admin: bool,
+        format: Option<&str>,
+    ) -> AppResult<BackupResponse> {
+        let database = self.get_database(database_id, user_id, is_admin).await?;
+        let format = format.unwrap_or(default_format(&database)).to_string();
+        let backup = self.start_backup(database, "manual", &format).await?;
+        Ok(backup.to_response())
+    }
+
+    pub async fn open(
+        &self,
+        database_id: &str,
+        backup_id: &str,
+        user_id: &str,
+        is_admin: bool,
+    ) -> AppResult<BackupDownload> {
+        let database = self.get_database(database_id, user_id, is_admin).await?;
+        let backup = self.get_backup(database_id, backup_id).await?;
+
+        if backup.status != "completed" {
+            return Err(AppError::Conflict(format!(
+                "Backup '{}' is not completed",
+                backup_id
+            )));
+        }
+
+        let local = self.materialize(&backup).await?;
+        let file_path = local
+            .backup
+            .file_path
+            .as_deref()
+            .ok_or_else(|| AppError::Internal("Backup has no file".to_string()))?;
+
+        // Temporary copies are unlinked when `local` drops; the open handle keeps them readable.
+        let file = tokio::fs::File::open(file_path).await.map_err(|e| {
+            AppError::Internal(format!("Failed to open backup file {}: {}", file_path, e))
+        })?;
+
+        let timestamp = backup
+            .created_at
+            .replace(|c: char| !c.is_ascii_alphanumeric(), "");
+
+        Ok(BackupDownload {
+            file_name: format!(
+                "{}-{}.{}",
+                database.name,
+                timestamp,
+                backup_extension(&backup.format)
+            ),
+            size_bytes: backup.size_bytes,
+            file,
+        })
+    }
+
+    pub async fn delete(
+        &self,
+        database_id: &str,
+        backup_id: &str,
+        user_id: &str,
+        is_admin: bool,
+    ) -> AppResult<()> {
+        self.get_database(database_id, user_id, is_admin).await?;
+        let backup = self.get_backup(database_id, backup_id).await?;
+
+        if !backup.is_finished() {
+            return Err(AppError::Conflict(format!(
+                "Backup '{}' is still running",
+                backup_id
+            )));
+        }
+
+        self.remove_backup(&backup).await
+    }
+
+    pub async fn restore(
+        &self,
+        database_id: &str,
+        backup_id: &str,
+        user_id: &str,
+        is_admin: bool,
+    ) -> AppResult<OperationResponse> {
+        let database = self.get_database(database_id, user_id, is_admin).await?;
+        let backup = self.get_restorable_backup(database_id, backup_id).await?;
+
+        if database.container_id.is_none() {
+            return Err(AppError::Conflict("Database has no container".to_string()));
+        }
+
+        if self.backup_repo.has_running(&database.id).await?
+            || self.operation_repo.has_running(&database.id).await?
+        {
+            return Err(AppError::Conflict(
+                "Another backup or restore is running for this database".to_string(),
+            ));
+        }
+
+        let operation = self
+            .operation_repo
+            .start(&database.id, "restore", Some(&backup.id), None)
+            .await?;
+        self.database_repo
+            .update_status(&database.id, "restoring")
+            .await?;
+
+        let service = self.clone();
+        let running = operation.clone();
+        tokio::spawn(async move {
+            service.execute_restore(database, backup, running).await;
+        });
+
+        Ok(operation.to_response())
+    }
+
+    pub async fn restore_to_branch(
+        &self,
+        database_id: &str,
+        backup_id: &str,
+        user_id: &str,
+        is_admin: bool,
+        branch_name: &str,
+    ) -> AppResult<OperationResponse> {
+        let database = self.get_database(database_id, user_id, is_admin).await?;
+        let backup = self.get_restorable_backup(database_id, backup_id).await?;
+
+        let branch = self
+            .database_service
+            .create_branch_record(&database, branch_name)
+            .await?;
+
+        let operation = self
+            .operation_repo
+            .create(
+                &database.id,
+                RESTORE_BRANCH_OPERATION_KIND,
+                Some(&backup.id),
+                Some(&branch.id),
+            )
+            .await?;
+
+        let service = self.clone();
+        let running = operation.clone();
+        tokio::spawn(async move {
+            service
+                .execute_restore_to_branch(database, branch, backup, running)
+                .await;
+        });
+
+        Ok(operation.to_response())
+    }
+
+    pub async fn pitr_status(
+        &self,
+        database_id: &str,
+        user_id: &str,
+        is_admin: bool,
+    ) -> AppResult<PitrStatusResponse> {
+        let database = self.get_database(database_id, user_id, is_admin).await?;
+
+        let backups = self.backup_repo.find_by_database_id(database_id).await?;
+        let base_backups: Vec<&Backup> = backups.iter().filter(|b| is_base_backup(b)).collect();
+        let earliest_restore_time = base_backups
+            .iter()
+            .filter_map(|b| b.completed_at.as_deref().and_then(parse_sqlite_datetime))
+            .min()
+            .map(|t| t.and_utc());
+
+        let (wal_archive_size_bytes, latest_wal) = self.wal_archive_stats(database_id).await?;
+        let latest_restore_time = earliest_restore_time.and(latest_wal);
+
+        Ok(PitrStatusResponse {
+            database_id: database.id,
+            enabled: database.wal_archiving,
+            earliest_restore_time,
+            latest_restore_time,
+            base_backup_count: base_backups.len() as i64,
+            wal_archive_size_bytes,
+        })
+    }
+
+    pub async fn restore_to_point_in_time(
+        &self,
+        database_id: &str,
+        user_id: &str,
+        is_admin: bool,
+        target_time: DateTime<Utc>,
+    ) -> AppResult<OperationResponse> {
+        let database = self.get_database(database_id, user_id, is_admin).await?;
+        let backup = self.get_pitr_base_backup(&database, target_time).await?;
+
+        if database.container_id.is_none() {
+            return Err(AppError::Conflict("Database has no container".to_string()));
+        }
+
+        if self.backup_repo.has_running(&database.id).await?
+            || self.operation_repo.has_running(&database.id).await?
+        {
+            return Err(AppError::Conflict(
+                "Another backup or restore is running for this database".to_string(),
+            ));
+        }
+
+        let operation = self
+            .operation_repo
+            .start(&database.id, "pitr_restore", Some(&backup.id), None)
+            .await?;
+        self.database_repo
+            .update_status(&database.id, "restoring")
+            .await?;
+
+        let service = self.clone();
+        let running = operation.clone();
+        tokio::spawn(async move {
+            let result = service
+                .recover_in_place(&database, &backup, target_time)
+                .await;
+            service.finish_restore(&database, &running, result).await;
+        });
+
+        Ok(operation.to_response())
+    }
+
+    pub async fn branch_from_point_in_time(
+        &self,
+        database_id: &str,
+        user_id: &str,
+        is_admin: bool,
+        branch_name: &str,
+        target_time: DateTime<Utc>,
+    ) -> AppResult<OperationResponse> {
+        let database = self.get_database(database_id, user_id, is_admin).await?;
+        let backup = self.get_pitr_base_backup(&database, target_time).await?;
+
+        let branch = self
+            .database_service
+            .create_branch_record(&database, branch_name)
+            .await?;
+
+        let operation = self
+            .operation_repo
+            .create(
+                &database.id,
+                PITR_BRANCH_OPERATION_KIND,
+                Some(&backup.id),
+                Some(&branch.id),
+            )
+            .await?;
+
+        let service = self.clone();
+        let running = operation.clone();
+        tokio::spawn(async move {
+            let branch_id = branch.id.clone();
+            let result = service
+                .recover_into_branch(&database, branch, &backup, target_time)
+                .await;
+
+            if result.is_err() {
+                if let Err(e) = service
+                    .database_repo
+                    .update_status(&branch_id, "error")
+                    .await
+                {
+                    tracing::error!("Failed to update status of branch {}: {}", branch_id, e);
+                }
+            }
+
+            service.operation_repo.finish(&running, result).await;
+        });
+
+        Ok(operation.to_response())
+    }
+
+    pub async fn get_schedule(
+        &self,
+        database_id: &str,
+        user_id: &str,
+        is_admin: bool,
+    ) -> AppResult<BackupScheduleResponse> {
+        let database = self.get_database(database_id, user_id, is_admin).await?;
+
+        let schedule = self
+            .backup_repo
+            .find_schedule(database_id)
+            .await?
+            .unwrap_or_else(|| BackupSchedule::disabled(database_id, default_format(&database)));
+
+        Ok(schedule.to_response())
+    }
+
+    pub async fn update_schedule(
+        &self,
+        database_id: &str,
+        user_id: &str,
+        is_admin: bool,
+        request: UpdateBackupScheduleRequest,
+    ) -> AppResult<BackupScheduleResponse> {
+        let database = self.get_database(database_id, user_id, is_admin).await?;
+
+        let mut schedule = self
+            .backup_repo
+            .find_schedule(database_id)
+            .await?
+            .unwrap_or_else(|| BackupSchedule::disabled(database_id, default_format(&database)));
+
+        if let Some(enabled) = request.enabled {
+            schedule.enabled = enabled;
+        }
+
+        if let Some(interval_hours) = request.interval_hours {
+            if !(1..=MAX_INTERVAL_HOURS).contains(&interval_hours) {
+                return Err(AppError::Validation(format!(
+                    "Backup interval must be between 1 and {} hours",
+                    MAX_INTERVAL_HOURS
+                )));
+            }
+            schedule.interval_hours = interval_hours;
+        }
+
+        if let Some(retention_count) = request.retention_count {
+            if retention_count < 0 {
+                return Err(AppError::Validation(
+                    "Retention count cannot be negative".to_string(),
+                ));
+            }
+            schedule.retention_count = (retention_count > 0).then_some(retention_count);
+        }
+
+        if let Some(retention_days) = request.retention_days {
+            if retention_days < 0 {
+                return Err(AppError::Validation(
+                    "Retention days cannot be negative".to_string(),
+                ));
+            }
+            schedule.retention_days = (retention_days > 0).then_some(retention_days);
+        }
+
+        if let Some(format) = request.format {
+            Self::validate_format(&database, &format)?;
+            schedule.format = format;
+        }
+
+        let schedule = self.backup_repo.upsert_schedule(&schedule).await?;
+        Ok(schedule.to_response())
+    }
+
+    pub async fn run_scheduled_backups(&self) -> AppResult<usize> {
+        let schedules = self.backup_repo.find_due_schedules().await?;
+        let mut started = 0;
+
+        for schedule in schedules {
+            self.backup_repo
+                .touch_schedule(&schedule.database_id)
+                .await?;
+
+            let Some(database) = self.database_repo.find_by_id(&schedule.database_id).await? else {
+                continue;
+            };
+
+            match self
+                .start_backup(database, "scheduled", &schedule.format)
+                .await
+            {
+                Ok(_) => started += 1,
+                Err(e) => tracing::warn!(
+                    "Skipping scheduled backup for database {}: {}",
+                    schedule.database_id,
+                    e
+                ),
+            }
+        }
+
+        Ok(started)
+    }
+
+    pub async fn recover_interrupted(&self) -> AppResult<()> {
+        let count = self.backup_repo.fail_interrupted().await?;
+        if count > 0 {
+            tracing::warn!("Marked {} interrupted backups as failed", count);
+        }
+        Ok(())
+    }
+
+    async fn start_backup(
+        &self,
+        database: Database,
+        kind: &str,
+        format: &str,
+    ) -> AppResult<Backup> {
+        Self::validate_format(&database, format)?;
+
+        if database.container_status != "running" {
+            return Err(AppError::Conflict(
+                "Database must be running to take a backup".to_string(),
+            ));
+        }
+
+        if self.backup_repo.has_running(&database.id).await? {
+            return Err(AppError::Conflict(
+                "A backup is already running for this database".to_string(),
+            ));
+        }
+
+        let backup = self.backup_repo.create(&database.id, kind, format).await?;
+
+        let service = self.clone();
+        let running = backup.clone();
+        tokio::spawn(async move {
+            service.execute_backup(database, running).await;
+        });
+
+        Ok(backup)
+    }
+
+    async fn execute_backup(&self, database: Database, backup: Backup) {
+        let started = Instant::now();
+        let file_path = self.backup_path(&database.id, &backup.id, &backup.format);
+
+        let result = match self
+            .dump_to_file(&database, &backup.format, &file_path)
+            .await
+        {
+            Ok((size_bytes, checksum)) => self
+                .store_backup(&database, &file_path)
+                .await
+                .map(|stored| (size_bytes, checksum, stored)),
+            Err(e) => Err(e),
+        };
+
+        match result {
+            Ok((size_bytes, checksum, (location, storage, encrypted, target))) => {
+                let duration_ms = started.elapsed().as_millis() as i64;
+                tracing::info!(
+                    "Backup {} of database {} completed ({} bytes in {}ms, {} storage)",
+                    backup.id,
+                    database.id,
+                    size_bytes,
+                    duration_ms,
+                    storage
+                );
+                let recorded = match self
+                    .backup_repo
+                    .set_storage(&backup.id, storage, encrypted, target.as_deref())
+                    .await
+                {
+                    Ok(()) => {
+                        self.backup_repo
+                            .mark_completed(
+                                &backup.id,
+                                &location,
+                                size_bytes,
+                                duration_ms,
+                                &checksum,
+                            )
+                            .await
+                    },
+                    Err(e) => Err(e),
+                };
+                if let Err(e) = recorded {
+                    tracing::error!("Failed to record backup {}: {}", backup.id, e);
+                    return;
+                }
+                if let Err(e) = self.apply_retention(&database.id).await {
+                    tracing::warn!(
+                        "Failed to apply backup retention for database {}: {}",
+                        database.id,
+                        e
+                    );
+                }
+            },
+            Err(e) => {
+                tracing::error!(
+                    "Backup {} of database {} failed: {}",
+                    backup.id,
+                    database.id,
+                    e
+                );
+                let _ = tokio::fs::remove_file(&file_path).await;
+                let _ =
+                    tokio::fs::remove_file(format!("{}.{}", file_path, ENCRYPTED_EXTENSION)).await;
+                let duration_ms = started.elapsed().as_millis() as i64;
+                if let Err(e) = self
+                    .backup_repo
+                    .mark_failed(&backup.id, &e.to_string(), duration_ms)
+                    .await
+                {
+                    tracing::error!("Failed to record backup {}: {}", backup.id, e);
+                }
+            },
+        }
+    }
+
+    async fn dump_to_file(
+        &self,
+        database: &Database,
+        format: &str,
+        file_path: &str,
+    ) -> AppResult<(i64, String)> {
+        let container_id = database
+            .container_id
+            .as_ref()
+            .ok_or_else(|| AppError::Internal("Database has no container".to_string()))?;
+
+        let password = self.database_password(database)?;
+
+        let dir = format!("{}/{}", self.backup_dir, database.id);
+        tokio::fs::create_dir_all(&dir)
+            .await
+            .map_err(|e| AppError::Internal(format!("Failed to create backup directory: {}", e)))?;
+
+        let cmd = if format == FORMAT_RDB {
+            self.docker
+                .kv_bgsave(
+                    container_id,
+                    engine_for(&database.database_type).cli_name(),
+                    &password,
+                    SNAPSHOT_TIMEOUT_SECONDS,
+                )
+                .await?;
+            vec![
+                "cat".to_string(),
+                format!("{}/dump.rdb", RedisContainer::data_mount_point()),
+            ]
+        } else if format == FORMAT_BASE {
+            vec![
+                "pg_basebackup".to_string(),
+                "-U".to_string(),
+                database.username.clone(),
+                "-D".to_string(),
+                "-".to_string(),
+                "-Ft".to_string(),
+                "-X".to_string(),
+                "fetch".to_string(),
+                "-c".to_string(),
+                "fast".to_string(),
+            ]
+        } else {
+            vec![
+                "pg_dump".to_string(),
+                "-U".to_string(),
+                database.username.clone(),
+                "-d".to_string(),
+                "postgres".to_string(),
+                "-Fc".to_string(),
+            ]
+        };
+        let env = vec![format!("PGPASSWORD={}", password)];
+
+        let partial_path = format!("{}.partial", file_path);
+        let mut file = tokio::fs::File::create(&partial_path)
+            .await
+            .map_err(|e| AppError::Internal(format!("Failed to create backup file: {}", e)))?;
+
+        let mut exec = self
+            .docker
+            .stream_exec(container_id, cmd, Some(env))
+            .await?;
+
+        let mut hasher = Sha256::new();
+        let mut size_bytes: i64 = 0;
+        let mut stderr = String::new();
+
+        while let Some(chunk) = exec.output.next().await {
+            match chunk? {
+                LogOutput::StdOut { message } => {
+                    hasher.update(&message);
+                    size_bytes += message.len() as i64;
+                    file.write_all(&message).await.map_err(|e| {
+                        AppError::Internal(format!("Failed to write backup file: {}", e))
+                    })?;
+                },
+                LogOutput::StdErr { message } => {
+                    stderr.push_str(&String::from_utf8_lossy(&message));
+                },
+                _ => {},
+            }
+        }
+
+        file.sync_all()
+            .await
+            .map_err(|e| AppError::Internal(format!("Failed to flush backup file: {}", e)))?;
+        drop(file);
+
+        let exit_code = self.docker.exec_exit_code(&exec.exec_id).await?;
+        if exit_code != Some(0) {
+            let _ = tokio::fs::remove_file(&partial_path).await;
+            return Err(AppError::Docker(format!(
+                "{} failed with exit code {:?}: {}",
+                cmd_name(format),
+                exit_code,
+                stderr.trim()
+            )));
+        }
+
+        tokio::fs::rename(&partial_path, file_path)
+            .await
+            .map_err(|e| AppError::Internal(format!("Failed to finalize backup file: {}", e)))?;
+
+        Ok((size_bytes, hex::encode(hasher.finalize())))
+    }
+
+    /// Restores the backup over the database in the background and records how the operation
+    /// ended, putting the database back to the status of its container either way.
+    async fn execute_restore(&self, database: Database, backup: Backup, operation: Operation) {
+        let result = self.restore_in_place(&database, &backup, false).await;
+        self.finish_restore(&database, &operation, result).await;
+    }
+
+    async fn finish_restore(
+        &self,
+        database: &Database,
+        operation: &Operation,
+        result: AppResult<()>,
+    ) {
+        let status = match &database.container_id {
+            Some(container_id) => self
+                .docker
+                .get_container_status(container_id)
+                .await
+                .unwrap_or_else(|_| "error".to_string()),
+            None => "error".to_string(),
+        };
+        let status = if status == "running" {
+            "running"
+        } else {
+            "stopped"
+        };
+        if let Err(e) = self.database_repo.update_status(&database.id, status).await {
+            tracing::error!("Failed to update status of database {}: {}", database.id, e);
+        }
+
+        self.operation_repo.finish(operation, result).await;
+    }
+
+    /// Finds a completed backup of the database or one of its ancestors to reset it to.
+    pub async fn find_reset_backup(
+        &self,
+        database: &Database,
+        backup_id: &str,
+    ) -> AppResult<Backup> {
+        let backup = self
+            .backup_repo
+            .find_by_id(backup_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("Backup '{}' not found", backup_id)))?;
+
+        let mut current = Some(database.clone());
+        while let Some(candidate) = current {
+            if candidate.id == backup.database_id {
+                return self.get_restorable_backup(&candidate.id, backup_id).await;
+            }
+            current = match &candidate.parent_branch_id {
+                Some(parent_id) => self.database_repo.find_by_id(parent_id).await?,
+                None => None,
+            };
+        }
+
+        Err(AppError::NotFound(format!(
+            "Backup '{}' not found",
+            backup_id
+        )))
+    }
+
+    /// Replaces the database's contents with the backup. Unlike a restore, objects that are not
+    /// in the backup are dropped too.
+    pub async fn reset_to_backup(&self, database: &Database, backup: &Backup) -> AppResult<()> {
+        self.restore_in_place(database, backup, true).await
+    }
+
+    async fn restore_in_place(
+        &self,
+        database: &Database,
+        backup: &Backup,
+        reset: bool,
+    ) -> AppResult<()> {
+        let local = self.materialize(backup).await?;
+        let backup = &local.backup;
+        self.verify_checksum(backup).await?;
+
+        let container_id = database
+            .container_id
+            .as_ref()
+            .ok_or_else(|| AppError::Internal("Database has no container".to_string()))?;
+
+        if engine_for(&database.database_type).is_key_value() {
+            tracing::info!(
+                "Restoring snapshot {} into database {}",
+                backup.id,
+                database.id
+            );
+            self.docker.stop_container(container_id).await?;
+            self.load_kv_snapshot(database, &database.id, backup)
+                .await?;
+            return self.start_kv_and_wait(container_id).await;
+        }
+
+        tracing::info!(
+            "Restoring backup {} into database {}",
+            backup.id,
+            database.id
+        );
+
+        // Restarting the container drops every client connection before the restore begins.
+        let _ = self.docker.stop_container(container_id).await;
+        self.docker
+            .start_postgres(container_id, &database.username, 60)
+            .await?;
+
+        if reset {
+            self.database_service.clear_data(database).await?;
+        }
+
+        let password = self.database_password(database)?;
+        self.pg_restore(container_id, &database.username, &password, backup, !reset)
+            .await?;
+
+        self.docker.stop_container(container_id).await?;
+        self.docker
+            .start_postgres(container_id, &database.username, 60)
+            .await
+    }
+
+    async fn execute_restore_to_branch(
+        &self,
+        source: Database,
+        branch: Database,
+        backup: Backup,
+        operation: Operation,
+    ) {
+        let branch_id = branch.id.clone();
+        let result = self.restore_into_branch(&source, branch, &backup).await;
+
+        if result.is_err() {
+            if let Err(e) = self.database_repo.update_status(&branch_id, "error").await {
+                tracing::error!("Failed to update status of branch {}: {}", branch_id, e);
+            }
+        }
+
+        self.operation_repo.finish(&operation, result).await;
+    }
+
+    async fn restore_into_branch(
+        &self,
+        source: &Database,
+        branch: Database,
+        backup: &Backup,
+    ) -> AppResult<()> {
+        let local = self.materialize(backup).await?;
+        let backup = &local.backup;
+        self.verify_checksum(backup).await?;
+
+        let (branch, password) = self
+            .database_service
+            .provision_branch_container(source, branch)
+            .await?;
+
+        let container_id = branch
+            .container_id
+            .as_ref()
+            .ok_or_else(|| AppError::Internal("Branch has no container".to_string()))?;
+
+        tracing::info!(
+            "Restoring backup {} into new branch {}",
+            backup.id,
+            branch.id
+        );
+
+        if engine_for(&source.database_type).is_key_value() {
+            self.docker.stop_container(container_id).await?;
+            self.load_kv_snapshot(source, &branch.id, backup).await?;
+            return self.start_kv_and_wait(container_id).await;
+        }
+
+        if !self
+            .docker
+            .wait_for_postgres_ready(container_id, &branch.username, 60)
+            .await?
+        {
+            return Err(AppError::Docker(
+                "Branch did not become ready in time".to_string(),
+            ));
+        }
+
+        self.pg_restore(container_id, &branch.username, &password, backup, false)
+            .await
+    }
+
+    async fn get_pitr_base_backup(
+        &self,
+        database: &Database,
+        target_time: DateTime<Utc>,
+    ) -> AppResult<Backup> {
+        if !database.wal_archiving {
+            return Err(AppError::Validation(
+                "Point-in-time recovery requires WAL archiving to be enabled".to_string(),
+            ));
+        }
+
+        if target_time > Utc::now() {
+            return Err(AppError::Validation(
+                "Target time cannot be in the future".to_string(),
+            ));
+        }
+
+        let backups = self.backup_repo.find_by_database_id(&database.id).await?;
+        select_base_backup(&backups, target_time.naive_utc())
+            .cloned()
+            .ok_or_else(|| {
+                AppError::Validation(
+                    "No base backup was completed before the requested target time".to_string(),
+                )
+            })
+    }
+
+    async fn recover_in_place(
+        &self,
+        database: &Database,
+        backup: &Backup,
+        target_time: DateTime<Utc>,
+    ) -> AppResult<()> {
+        let local = self.materialize(backup).await?;
+        let backup = &local.backup;
+        self.verify_checksum(backup).await?;
+
+        let container_id = database
+            .container_id
+            .as_ref()
+            .ok_or_else(|| AppError::Internal("Database has no container".to_string()))?;
+
+        tracing::info!(
+            "Recovering database {} to {} from base backup {}",
+            database.id,
+            target_time,
+            backup.id
+        );
+
+        self.docker.stop_container(container_id).await?;
+        self.prepare_recovery(
+            database,
+            &database.id,
+            backup,
+            target_time,
+            WAL_ARCHIVE_MOUNT_POINT,
+        )
+        .await?;
+
+        let password = self.database_password(database)?;
+        self.complete_recovery(container_id, &database.username, &password)
+            .await
+    }
+
+    async fn recover_into_branch(
+        &self,
+        source: &Database,
+        branch: Database,
+        backup: &Backup,
+        target_time: DateTime<Utc>,
+    ) -> AppResult<()> {
+        let local = self.materialize(backup).await?;
+        let backup = &local.backup;
+        self.verify_checksum(backup).await?;
+
+        let (branch, password) = self
+            .database_service
+            .provision_branch_container(source, branch)
+            .await?;
+
+        tracing::info!(
+            "Recovering database {} to {} into new branch {}",
+            source.id,
+            target_time,
+            branch.id
+        );
+
+        self.database_service
+            .recreate_container_for_recovery(&branch, &source.id)
+            .await?;
+        self.prepare_recovery(
+            source,
+            &branch.id,
+            backup,
+            target_time,
+            WAL_RESTORE_MOUNT_POINT,
+        )
+        .await?;
+
+        let container_id = self.container_id(&branch.id).await?;
+        self.complete_recovery(&container_id, &branch.username, &password)
+            .await?;
+
+        // The source archive keeps growing after the target time; unmount it from the branch.
+        self.database_service.recreate_container(&branch).await?;
+        let container_id = self.container_id(&branch.id).await?;
+        self.docker.start_container(&container_id).await?;
+        if !self
+            .docker
+            .wait_for_healthy(&container_id, RECOVERY_TIMEOUT_SECONDS)
+            .await?
+        {
+            return Err(AppError::Docker(
+                "Branch did not become healthy after recovery".to_string(),
+            ));
+        }
+        self.database_repo
+            .update_status(&branch.id, "running")
+            .await?;
+        Ok(())
+    }
+
+    async fn container_id(&self, database_id: &str) -> AppResult<String> {
+        self.database_repo
+            .find_by_id(database_id)
+            .await?
+            .and_then(|database| database.container_id)
+            .ok_or_else(|| AppError::Internal("Database has no container".to_string()))
+    }
+
+    /// Replaces the data directory of `target_id` with the base backup and configures
+    /// Postgres to replay the WAL archived by `source` up to `target_time` on next start.
+    /// `archive_dir` is where the target container mounts that archive.
+    async fn prepare_recovery(
+        &self,
+        source: &Database,
+        target_id: &str,
+        backup: &Backup,
+        target_time: DateTime<Utc>,
+        archive_dir: &str,
+    ) -> AppResult<()> {
+        let backup_file = backup
+            .file_path
+            .as_deref()
+            .ok_or_else(|| AppError::Internal("Backup has no file".to_string()))?;
+
+        let image = engine_for(&source.database_type).image(source);
+        let binds = vec![
+            format!(
+                "{}/{}:{}",
+                self.data_dir,
+                target_id,
+                PostgresContainer::get_mount_point(&image)
+            ),
+            format!("{}:/restore/base.tar:ro", backup_file),
+        ];
+
+        let script = format!(
+            r#"set -e
+mkdir -p "$PGDATA"
+find "$PGDATA" -mindepth 1 -delete
+tar -xf /restore/base.tar -C "$PGDATA"
+rm -f "$PGDATA/postmaster.pid"
+touch "$PGDATA/recovery.signal"
+cat >> "$PGDATA/postgresql.auto.conf" <<'EOF'
+restore_command = 'cp {}/%f "%p"'
+recovery_target_time = '{}'
+recovery_target_action = 'promote'
+EOF
+chown -R postgres:postgres "$PGDATA"
+chmod 700 "$PGDATA""#,
+            archive_dir,
+            target_time.format("%Y-%m-%d %H:%M:%S%.6f+00")
+        );
+
+        let output = self
+            .docker
+            .run_oneoff_container(
+                &format!("datify-pitr-{}", target_id),
+                &image,
+                binds,
+                &script,
+            )
+            .await?;
+
+        if output.exit_code != Some(0) {
+            return Err(AppError::Docker(format!(
+                "Preparing recovery failed with exit code {:?}: {}",
+                output.exit_code,
+                output.stderr.trim()
+            )));
+        }
+
+        Ok(())
+    }
+
+    async fn complete_recovery(
+        &self,
+        container_id: &str,
+        username: &str,
+        password: &str,
+    ) -> AppResult<()> {
+        self.docker.start_container(container_id).await?;
+
+        let env = vec![format!("PGPASSWORD={}", password)];
+        let started = Instant::now();
+        loop {
+            if started.elapsed().as_secs() > RECOVERY_TIMEOUT_SECONDS {
+                return Err(AppError::Docker(
+                    "Recovery did not finish in time".to_string(),
+                ));
+            }
+
+            if self.docker.get_container_status(container_id).await? != "running" {
+                return Err(AppError::Docker(
+                    "Database stopped during recovery; the target time may be outside the archived WAL"
+                        .to_string(),
+                ));
+            }
+
+            let output = self
+                .docker
+                .run_exec(
+                    container_id,
+                    psql_command(username, "postgres", &["SELECT pg_is_in_recovery()"]),
+                    Some(env.clone()),
+                )
+                .await?;
+            if output.exit_code == Some(0) && output.stdout.trim() == "f" {
+                break;
+            }
+
+            tokio::time::sleep(std::time::Duration::from_secs(2)).await;
+        }
+
+        let set_password = format!(
+            "ALTER USER \"{}\" PASSWORD '{}'",
+            username.replace('"', "\"\""),
+            password.replace('\'', "''")
+        );
+        let output = self
+            .docker
+            .run_exec(
+                container_id,
+                psql_command(
+                    username,
+                    "postgres",
+                    &[
+                        "ALTER SYSTEM RESET restore_command",
+                        "ALTER SYSTEM RESET recovery_target_time",
+                        "ALTER SYSTEM RESET recovery_target_action",
+                        "SELECT pg_reload_conf()",
+                        &set_password,
+                    ],
+                ),
+                Some(env),
+            )
+            .await?;
+        if output.exit_code != Some(0) {
+            return Err(AppError::Docker(format!(
+                "Failed to finalize recovery: {}",
+                output.stderr.trim()
+            )));
+        }
+
+        Ok(())
+    }
+
+    async fn wal_archive_stats(
+        &self,
+        database_id: &str,
+    ) -> AppResult<(i64, Option<DateTime<Utc>>)> {
+        let mut size_bytes = 0;
+        let mut latest = None;
+
+        for (_, metadata) in self.wal_archive_files(database_id).await? {
+            size_bytes += metadata.len() as i64;
+            if let Ok(modified) = metadata.modified() {
+                latest = latest.max(Some(DateTime::<Utc>::from(modified)));
+            }
+        }
+
+        Ok((size_bytes, latest))
+    }
+
+    async fn wal_archive_files(
+        &self,
+        database_id: &str,
+    ) -> AppResult<Vec<(std::path::PathBuf, std::fs::Metadata)>> {
+        let path = self.database_service.wal_archive_path(database_id);
+        let mut entries = match tokio::fs::read_dir(&path).await {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => {
+                return Err(AppError::Internal(format!(
+                    "Failed to read WAL archive {}: {}",
+                    path, e
+                )))
+            },
+        };
+
+        let mut files = Vec::new();
+        while let Some(entry) = entries
+            .next_entry()
+            .await
+            .map_err(|e| AppError::Internal(format!("Failed to read WAL archive: {}", e)))?
+        {
+            let metadata = entry
+                .metadata()
+                .await
+                .map_err(|e| AppError::Internal(format!("Failed to read WAL archive: {}", e)))?;
+            if metadata.is_file() {
+                files.push((entry.path(), metadata));
+            }
+        }
+
+        Ok(files)
+    }
+
+    /// WAL archived before the oldest remaining base backup can no longer be replayed.
+    async fn prune_wal_archive(&self, database_id: &str) -> AppResult<()> {
+        let backups = self.backup_repo.find_by_database_id(database_id).await?;
+        let Some(oldest) = backups
+            .iter()
+            .filter(|b| is_base_backup(b))
+            .filter_map(|b| parse_sqlite_datetime(&b.created_at))
+            .min()
+        else {
+            return Ok(());
+        };
+        let oldest = oldest.and_utc();
+
+        let mut removed = 0;
+        for (path, metadata) in self.wal_archive_files(database_id).await? {
+            let expired = metadata
+                .modified()
+                .is_ok_and(|modified| DateTime::<Utc>::from(modified) < oldest);
+            if expired && tokio::fs::remove_file(&path).await.is_ok() {
+                removed += 1;
+            }
+        }
+
+        if removed > 0 {
+            tracing::info!(
+                "Pruned {} archived WAL files of database {}",
+                removed,
+                database_id
+            );
+        }
+
+        Ok(())
+    }
+
+    async fn load_kv_snapshot(
+        &self,
+        source: &Database,
+        target_id: &str,
+        backup: &Backup,
+    ) -> AppResult<()> {
+        let backup_file = backup
+            .file_path
+            .as_deref()
+            .ok_or_else(|| AppError::Internal("Backup has no file".to_string()))?;
+
+        self.docker
+            .load_kv_snapshot(
+                &format!("datify-restore-{}", target_id),
+                &engine_for(&source.database_type).image(source),
+                engine_for(&source.database_type).cli_name(),
+                &format!("{}/{}", self.data_dir, target_id),
+                backup_file,
+                source.persistence.as_deref() != Some(FORMAT_RDB),
+            )
+            .await
+    }
+
+    async fn start_kv_and_wait(&self, container_id: &str) -> AppResult<()> {
+        self.docker.start_container(container_id).await?;
+
+        if !self.docker.wait_for_healthy(container_id, 60).await? {
+            return Err(AppError::Docker(
+                "Database did not become ready in time".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+
+    async fn pg_restore(
+        &self,
+        container_id: &str,
+        username: &str,
+        password: &str,
+        backup: &Backup,
+        clean: bool,
+    ) -> AppResult<()> {
+        let file_path = backup
+            .file_path
+            .as_deref()
+            .ok_or_else(|| AppError::Internal("Backup has no file".to_string()))?;
+        let file = tokio::fs::File::open(file_path).await.map_err(|e| {
+            AppError::Internal(format!("Failed to open backup file {}: {}", file_path, e))
+        })?;
+
+        let mut cmd = vec![
+            "pg_restore".to_string(),
+            "-U".to_string(),
+            username.to_string(),
+            "-d".to_string(),
+            "postgres".to_string(),
+            "--no-owner".to_string(),
+            "--no-privileges".to_string(),
+            "--single-transaction".to_string(),
+            "--exit-on-error".to_string(),
+        ];
+        if clean {
+            cmd.push("--clean".to_string());
+            cmd.push("--if-exists".to_string());
+        }
+        let env = vec![format!("PGPASSWORD={}", password)];
+
+        let output = self
+            .docker
+            .exec_with_input(container_id, cmd, Some(env), file)
+            .await?;
+
+        if output.exit_code != Some(0) {
+            return Err(AppError::Docker(format!(
+                "pg_restore failed with exit code {:?}: {}",
+                output.exit_code,
+                output.stderr.trim()
+            )));
+        }
+
+        Ok(())
+    }
+
+    async fn verify_checksum(&self, backup: &Backup) -> AppResult<()> {
+        let (Some(file_path), Some(expected)) = (&backup.file_path, &backup.checksum) else {
+            return Ok(());
+        };
+
+        let mut file = tokio::fs::File::open(file_path).await.map_err(|e| {
+            AppError::Internal(format!("Failed to open backup file {}: {}", file_path, e))
+        })?;
+
+        let mut hasher = Sha256::new();
+        let mut buf = vec![0u8; 64 * 1024];
+        loop {
+            let read = file
+                .read(&mut buf)
+                .await
+                .map_err(|e| AppError::Internal(format!("Failed to read backup file: {}", e)))?;
+            if read == 0 {
+                break;
+            }
+            hasher.update(&buf[..read]);
+        }
+
+        if hex::encode(hasher.finalize()) != *expected {
+            return Err(AppError::Internal(format!(
+                "Backup {} failed checksum verification",
+                backup.id
+            )));
+        }
+
+        Ok(())
+    }
+
+    fn database_password(&self, database: &Database) -> AppResult<String> {
+        let encrypted = database
+            .password_encrypted
+            .as_ref()
+            .ok_or_else(|| AppError::Internal("Database password not found".to_string()))?;
+        self.decrypt_password(encrypted)
+    }
+
+    async fn remove_backup(&self, backup: &Backup) -> AppResult<()> {
+        self.stores.remove_file(backup).await?;
+        self.backup_repo.delete(&backup.id).await
+    }
+
+    /// Hands the finished dump to the project's storage target, encrypting it first if
+    /// configured. Returns the stored location, the storage kind, whether it is encrypted and
+    /// the encoded target to read it back from.
+    async fn store_backup(
+        &self,
+        database: &Database,
+        file_path: &str,
+    ) -> AppResult<(String, &'static str, bool, Option<String>)> {
+        let config = self.stores.project_config(&database.project_id).await?;
+        let storage = self.stores.build(&config.target);
+        let target = self.stores.encode_target(&config.target)?;
+
+        let file_name = Path::new(file_path)
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .ok_or_else(|| AppError::Internal("Invalid backup file path".to_string()))?;
+        let mut key = format!("{}/{}", database.id, file_name);
+        let mut source = PathBuf::from(file_path);
+
+        if config.encrypt {
+            let encrypted_path = PathBuf::from(format!("{}.{}", file_path, ENCRYPTED_EXTENSION));
+            encrypt_file(&self.backup_key, &source, &encrypted_path).await?;
+            let _ = tokio::fs::remove_file(&source).await;
+            source = encrypted_path;
+            key = format!("{}.{}", key, ENCRYPTED_EXTENSION);
+        }
+
+        let location = storage.store(&key, &source).await?;
+        Ok((location, storage.kind(), config.encrypt, target))
+    }
+
+    async fn materialize(&self, backup: &Backup) -> AppResult<LocalBackup> {
+        let location = backup
+            .file_path
+            .as_deref()
+            .ok_or_else(|| AppError::Internal("Backup has no file".to_string()))?;
+        let storage = self.stores.for_backup(backup).await?;
+
+        let mut local = LocalBackup {
+            backup: backup.clone(),
+            temp_files: Vec::new(),
+        };
+        let temp_path = |suffix: &str| {
+            PathBuf::from(format!(
+                "{}/{}/{}.{}.{}",
+                self.backup_dir,
+                backup.database_id,
+                backup.id,
+                uuid::Uuid::new_v4(),
+                suffix
+            ))
+        };
+
+        let mut path = match storage.local_path(location) {
+            Some(path) => path,
+            None => {
+                let path = temp_path("download");
+                if let Some(parent) = path.parent() {
+                    tokio::fs::create_dir_all(parent).await.map_err(|e| {
+                        AppError::Internal(format!("Failed to create backup directory: {}", e))
+                    })?;
+                }
+                local.temp_files.push(path.clone());
+                storage.fetch(location, &path).await?;
+                path
+            },
+        };
+
+        if backup.encrypted {
+            let decrypted = temp_path("decrypted");
+            local.temp_files.push(decrypted.clone());
+            decrypt_file(&self.backup_key, &path, &decrypted).await?;
+            path = decrypted;
+        }
+
+        local.backup.file_path = Some(path.to_string_lossy().into_owned());
+        Ok(local)
+    }
+
+    async fn apply_retention(&self, database_id: &str) -> AppResult<()> {
+        let Some(schedule) = self.backup_repo.find_schedule(database_id).await? else {
+            return Ok(());
+        };
+
+        let backups = self.backup_repo.find_by_database_id(database_id).await?;
+        let now = Utc::now().naive_utc();
+
+        for format in [FORMAT_CUSTOM, FORMAT_BASE, FORMAT_RDB] {
+            let same_format: Vec<Backup> = backups
+                .iter()
+                .filter(|b| b.format == format)
+                .cloned()
+                .collect();
+            let expired = expired_backups(
+                &same_format,
+                schedule.retention_count,
+                schedule.retention_days,
+                now,
+            );
+
+            for backup in expired {
+                tracing::info!(
+                    "Removing backup {} of database {} (retention policy)",
+                    backup.id,
+                    database_id
+                );
+                self.remove_backup(backup).await?;
+            }
+        }
+
+        self.prune_wal_archive(database_id).await
+    }
+}
+
+fn default_format(database: &Database) -> &'static str {
+    if engine_for(&database.database_type).is_key_value() {
+        FORMAT_RDB
+    } else {
+        FORMAT_CUSTOM
+    }
+}
+
+fn is_base_backup(backup: &Backup) -> bool {
+    backup.format == FORMAT_BASE && backup.status == "completed" && backup.file_path.is_some()
+}
+
+pub(super) fn parse_sqlite_datetime(value: &str) -> Option<NaiveDateTime> {
+    NaiveDateTime::parse_from_str(value, SQLITE_DATETIME_FORMAT).ok()
+}
+
+/// Picks the newest base backup that finished before `target`.
+fn select_base_backup(backups: &[Backup], target: NaiveDateTime) -> Option<&Backup> {
+    backups
+        .iter()
+        .filter(|b| is_base_backup(b))
+        .filter_map(|b| {
+            let completed = parse_sqlite_datetime(b.completed_at.as_deref()?)?;
+            (completed <= target).then_some((completed, b))
+        })
+        .max_by_key(|(completed, _)| *completed)
+        .map(|(_, b)| b)
+}
+
+fn backup_extension(format: &str) -> &'static str {
+    match format {
+        FORMAT_BASE => "tar",
+        FORMAT_RDB => "rdb",
+        _ => "dump",
+    }
+}
+
+fn cmd_name(format: &str) -> &'static str {
+    match format {
+        FORMAT_BASE => "pg_basebackup",
+        FORMAT_RDB => "Snapshot copy",
+        _ => "pg_dump",
+    }
+}
+
+/// Backups must be ordered newest first. The newest completed backup is always kept.
+fn expired_backups(
+    backups: &[Backup],
+    retention_count: Option<i32>,
+    retention_days: Option<i32>,
+    now: NaiveDateTime,
+) -> Vec<&Backup> {
+    let cutoff = retention_days.map(|days| now - Duration::days(days as i64));
+    let mut completed_seen = 0;
+
+    backups
+        .iter()
+        .filter(|backup| backup.is_finished())
+        .filter(|backup| {
+            let is_completed = backup.status == "completed";
+            if is_completed {
+                completed_seen += 1;
+                if completed_seen == 1 {
+                    return false;
+                }
+            }
+
+            let over_count = is_completed
+                && retention_count.is_some_and(|count| completed_seen > count as usize);
+            let too_old = cutoff.is_some_and(|cutoff| {
+                NaiveDateTime::parse_from_str(&backup.created_at, SQLITE_DATETIME_FORMAT)
+                    .is_ok_and(|created| created < cutoff)
+            });
+
+            over_count || too_old
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn backup(id: &str, status: &str, created_at: &str) -> Backup {
+        Backup {
+            id: id.to_string(),
+            database_id: "db".to_string(),
+            status: status.to_string(),
+            kind: "scheduled".to_string(),
+            format: "custom".to_string(),
+            file_path: None,
+            size_bytes: None,
+            duration_ms: None,
+            checksum: None,
+            error: None,
+            created_at: created_at.to_string(),
+            completed_at: None,
+            storage: "local".to_string(),
+            encrypted: false,
+            storage_target: None,
+        }
+    }
+
+    fn ids(backups: Vec<&Backup>) -> Vec<&str> {
+        backups.iter().map(|b| b.id.as_str()).collect()
+    }
+
+    #[test]
+    fn test_retention_by_count_and_age() {
+        let now =
+            NaiveDateTime::parse_from_str("2025-01-10 00:00:00", SQLITE_DATETIME_FORMAT).unwrap();
+        let backups = vec![
+            backup("running", "running", "2025-01-09 12:00:00"),
+            backup("c1", "completed", "2025-01-09 00:00:00"),
+            backup("f1", "failed", "2025-01-07 12:00:00"),
+            backup("c2", "completed", "2025-01-07 00:00:00"),
+            backup("c3", "completed", "2025-01-01 00:00:00"),
+        ];
+
+        assert_eq!(
+            ids(expired_backups(&backups, Some(2), None, now)),
+            vec!["c3"]
+        );
+        assert_eq!(
+            ids(expired_backups(&backups, None, Some(2), now)),
+            vec!["f1", "c2", "c3"]
+        );
+        assert!(expired_backups(&backups, None, None, now).is_empty());
+    }
+
+    #[test]
+    fn test_retention_keeps_newest_completed() {
+        let now =
+            NaiveDateTime::parse_from_str("2025-06-01 00:00:00", SQLITE_DATETIME_FORMAT).unwrap();
+        let backups = vec![
+            backup("c1", "completed", "2025-01-02 00:00:00"),
+            backup("c2", "completed", "2025-01-01 00:00:00"),
+        ];
+
+        assert_eq!(
+            ids(expired_backups(&backups, Some(1), Some(1), now)),
+            vec!["c2"]
+        );
+    }
+
+    #[test]
+    fn test_select_base_backup_before_target() {
+        let base = |id: &str, status: &str, completed_at: &str| Backup {
+            format: FORMAT_BASE.to_string(),
+            file_path: Some(format!("/backups/{}.tar", id)),
+            completed_at: Some(completed_at.to_string()),
+            ..backup(id, status, completed_at)
+        };
+        let backups = vec![
+            base("b3", "completed", "2025-01-09 00:00:00"),
+            backup("c1", "completed", "2025-01-08 00:00:00"),
+            base("f1", "failed", "2025-01-07 00:00:00"),
+            base("b2", "completed", "2025-01-05 00:00:00"),
+            base("b1", "completed", "2025-01-01 00:00:00"),
+        ];
+        let at =
+            |value: &str| NaiveDateTime::parse_from_str(value, SQLITE_DATETIME_FORMAT).unwrap();
+
+        let selected =
+            |target: &str| select_base_backup(&backups, at(target)).map(|b| b.id.as_str());
+        assert_eq!(selected("2025-01-08 12:00:00"), Some("b2"));
+        assert_eq!(selected("2025-01-09 00:00:00"), Some("b3"));
+        assert_eq!(selected("2025-01-01 00:00:00"), Some("b1"));
+        assert_eq!(selected("2024-12-31 23:59:59"), None);
+    }
+}
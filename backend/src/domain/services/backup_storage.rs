@@ -0,0 +1,164 @@
+use std::sync::Arc;
+
+use crate::domain::models::Backup;
+use crate::error::{AppError, AppResult};
+use crate::infrastructure::storage::{
+    storage_http_client, BackupStorage, BackupStorageConfig, LocalStorage, StorageTarget,
+};
+use crate::repositories::{DatabaseRepository, ProjectRepository};
+use crate::utils::crypto;
+
+/// Finds the storage holding each backup file. Shared by the backup service and database
+/// deletion, so removing a database also removes its files from remote storage.
+#[derive(Clone)]
+pub struct BackupStores {
+    database_repo: DatabaseRepository,
+    project_repo: ProjectRepository,
+    backup_dir: String,
+    encryption_key: [u8; 32],
+    http: reqwest::Client,
+}
+
+impl BackupStores {
+    pub fn new(
+        database_repo: DatabaseRepository,
+        project_repo: ProjectRepository,
+        data_dir: &str,
+        encryption_key_hex: &str,
+    ) -> Self {
+        Self {
+            database_repo,
+            project_repo,
+            backup_dir: format!("{}/backups", data_dir),
+            encryption_key: crypto::parse_encryption_key(encryption_key_hex),
+            http: storage_http_client(),
+        }
+    }
+
+    pub fn backup_dir(&self) -> &str {
+        &self.backup_dir
+    }
+
+    pub async fn project_config(&self, project_id: &str) -> AppResult<BackupStorageConfig> {
+        let project = self
+            .project_repo
+            .find_by_id(project_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("Project '{}' not found", project_id)))?;
+        BackupStorageConfig::from_project_settings(&project.settings_json())
+    }
+
+    pub fn build(&self, target: &StorageTarget) -> Arc<dyn BackupStorage> {
+        target.build(&self.backup_dir, &self.http)
+    }
+
+    /// The target as stored on a backup. It can hold credentials, so it is encrypted like
+    /// database passwords. Local backups need no target.
+    pub fn encode_target(&self, target: &StorageTarget) -> AppResult<Option<String>> {
+        if matches!(target, StorageTarget::Local) {
+            return Ok(None);
+        }
+        let json = serde_json::to_string(target)
+            .map_err(|e| AppError::Internal(format!("Failed to encode storage target: {}", e)))?;
+        crypto::encrypt_password(&self.encryption_key, &json).map(Some)
+    }
+
+    pub async fn for_backup(&self, backup: &Backup) -> AppResult<Arc<dyn BackupStorage>> {
+        if backup.storage == "local" {
+            return Ok(Arc::new(LocalStorage::new(&self.backup_dir)));
+        }
+
+        if let Some(encoded) = &backup.storage_target {
+            let json = crypto::decrypt_password(&self.encryption_key, encoded)?;
+            let target: StorageTarget = serde_json::from_str(&json).map_err(|e| {
+                AppError::Internal(format!(
+                    "Invalid storage target of backup {}: {}",
+                    backup.id, e
+                ))
+            })?;
+            return Ok(self.build(&target));
+        }
+
+        // Backups taken before targets were recorded only know the kind of storage.
+        let project_id = self
+            .database_repo
+            .get_project_id(&backup.database_id)
+            .await?
+            .ok_or_else(|| {
+                AppError::NotFound(format!("Database '{}' not found", backup.database_id))
+            })?;
+        let storage = self.build(&self.project_config(&project_id).await?.target);
+        if storage.kind() != backup.storage {
+            return Err(AppError::Conflict(format!(
+                "Backup storage '{}' is no longer configured for this project",
+                backup.storage
+            )));
+        }
+
+        Ok(storage)
+    }
+
+    /// Removes the backup's file from wherever it is stored.
+    pub async fn remove_file(&self, backup: &Backup) -> AppResult<()> {
+        if let Some(location) = &backup.file_path {
+            self.for_backup(backup).await?.remove(location).await?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infrastructure::storage::S3Config;
+
+    #[tokio::test]
+    async fn test_backup_keeps_its_storage_target() {
+        let pool = crate::repositories::test_pool().await;
+        let stores = BackupStores::new(
+            DatabaseRepository::new(pool.clone()),
+            ProjectRepository::new(pool),
+            "/data",
+            &"ab".repeat(32),
+        );
+        let target = StorageTarget::S3(S3Config {
+            endpoint: "https://s3.example.com".to_string(),
+            region: "eu-west-1".to_string(),
+            bucket: "old-bucket".to_string(),
+            prefix: String::new(),
+            access_key_id: "key".to_string(),
+            secret_access_key: "secret".to_string(),
+            path_style: true,
+        });
+
+        assert_eq!(stores.encode_target(&StorageTarget::Local).unwrap(), None);
+        let encoded = stores.encode_target(&target).unwrap().unwrap();
+        assert!(!encoded.contains("secret"));
+
+        // The database and project no longer exist, so only the recorded target can resolve it.
+        let backup = Backup {
+            id: "b1".to_string(),
+            database_id: "gone".to_string(),
+            status: "completed".to_string(),
+            kind: "manual".to_string(),
+            format: "custom".to_string(),
+            file_path: Some("db/b1.dump".to_string()),
+            size_bytes: None,
+            duration_ms: None,
+            checksum: None,
+            error: None,
+            created_at: "2025-01-01 00:00:00".to_string(),
+            completed_at: None,
+            storage: "s3".to_string(),
+            encrypted: false,
+            storage_target: Some(encoded),
+        };
+        assert_eq!(stores.for_backup(&backup).await.unwrap().kind(), "s3");
+
+        let legacy = Backup {
+            storage_target: None,
+            ..backup
+        };
+        assert!(stores.for_backup(&legacy).await.is_err());
+    }
+}
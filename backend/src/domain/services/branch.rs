@@ -0,0 +1,593 @@
+use std::sync::Arc;
+
+use super::backup::parse_sqlite_datetime;
+use super::common::owned_database;
+use super::schema_diff::diff_schemas;
+use super::{reset_status_from_container, BackupService, DatabaseService, SqlService};
+use crate::domain::engines::engine_for;
+use crate::domain::models::{
+    Backup, ChildBranchPolicy, Database, Operation, OperationResponse, ResetBranchRequest,
+    ResetMode, ResetPlan, SchemaChange, SchemaInfo, Snapshot, SnapshotResponse, TableDiff,
+    ViewDiff,
+};
+use crate::error::{AppError, AppResult};
+use crate::infrastructure::docker::DockerManager;
+use crate::repositories::{
+    BackupRepository, DatabaseRepository, OperationRepository, ProjectRepository,
+};
+
+pub const RESET_OPERATION_KIND: &str = "reset";
+
+/// Where a reset takes the branch's new contents from.
+enum ResetSource {
+    Parent(Box<Database>),
+    Backup(Box<Backup>),
+    Snapshot(Box<Snapshot>),
+}
+
+#[derive(Clone)]
+pub struct BranchService {
+    database_repo: DatabaseRepository,
+    project_repo: ProjectRepository,
+    backup_repo: BackupRepository,
+    operation_repo: OperationRepository,
+    database_service: Arc<DatabaseService>,
+    backup_service: Arc<BackupService>,
+    sql_service: Arc<SqlService>,
+    docker: Arc<DockerManager>,
+}
+
+impl BranchService {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        database_repo: DatabaseRepository,
+        project_repo: ProjectRepository,
+        backup_repo: BackupRepository,
+        operation_repo: OperationRepository,
+        database_service: Arc<DatabaseService>,
+        backup_service: Arc<BackupService>,
+        sql_service: Arc<SqlService>,
+        docker: Arc<DockerManager>,
+    ) -> Self {
+        Self {
+            database_repo,
+            project_repo,
+            backup_repo,
+            operation_repo,
+            database_service,
+            backup_service,
+            sql_service,
+            docker,
+        }
+    }
+
+    /// Deletes branches whose expiry has passed. Returns each deleted branch with the ID of its
+    /// project's owner, for the audit log.
+    pub async fn delete_expired(&self) -> AppResult<Vec<(Database, String)>> {
+        let mut deleted = Vec::new();
+
+        for branch in self.database_repo.find_expired_branches().await? {
+            if branch.parent_branch_id.is_none() {
+                continue;
+            }
+            if self.backup_repo.has_running(&branch.id).await?
+                || self.operation_repo.has_running(&branch.id).await?
+            {
+                continue;
+            }
+            if !self
+                .database_repo
+                .find_children(&branch.id)
+                .await?
+                .is_empty()
+            {
+                tracing::warn!(
+                    "Expired branch {} still has branches of its own, not deleting it",
+                    branch.id
+                );
+                continue;
+            }
+
+            let Some(project) = self.project_repo.find_by_id(&branch.project_id).await? else {
+                continue;
+            };
+            match self
+                .database_service
+                .delete(
+                    &branch.id,
+                    &project.user_id,
+                    true,
+                    ChildBranchPolicy::Refuse,
+                )
+                .await
+            {
+                Ok(_) => deleted.push((branch, project.user_id)),
+                Err(e) => tracing::error!("Failed to delete expired branch {}: {}", branch.id, e),
+            }
+        }
+
+        Ok(deleted)
+    }
+
+    pub async fn plan_reset(
+        &self,
+        database_id: &str,
+        user_id: &str,
+        is_admin: bool,
+        request: &ResetBranchRequest,
+    ) -> AppResult<ResetPlan> {
+        let (branch, source) = self
+            .reset_source(database_id, user_id, is_admin, request)
+            .await?;
+
+        let engine = engine_for(&branch.database_type);
+        let current = if engine.supports_sql() {
+            Some(self.sql_service.get_schema(&branch.id).await?)
+        } else {
+            None
+        };
+        let replaced_data = match &current {
+            Some(schema) => schema
+                .tables
+                .iter()
+                .map(|t| format!("{}.{}", t.schema, t.name))
+                .collect(),
+            None if engine.supports_document_queries() => vec!["all collections".to_string()],
+            None => vec!["all keys".to_string()],
+        };
+
+        let (source_id, backup_id, snapshot_id, target) = match &source {
+            ResetSource::Parent(parent) => {
+                let target = match (&current, parent.container_status.as_str()) {
+                    (Some(_), "running") => Some(self.sql_service.get_schema(&parent.id).await?),
+                    _ => None,
+                };
+                (parent.id.clone(), None, None, target)
+            },
+            ResetSource::Backup(backup) => (
+                backup.database_id.clone(),
+                Some(backup.id.clone()),
+                None,
+                None,
+            ),
+            ResetSource::Snapshot(snapshot) => (
+                snapshot.database_id.clone(),
+                None,
+                Some(snapshot.id.clone()),
+                None,
+            ),
+        };
+
+        let mut plan = ResetPlan {
+            mode: request.mode,
+            source_id,
+            backup_id,
+            snapshot_id,
+            schema_compared: false,
+            removed_objects: Vec::new(),
+            changed_objects: Vec::new(),
+            replaced_data,
+        };
+        if let (Some(current), Some(target)) = (&current, &target) {
+            let (removed, changed) = lost_objects(current, target);
+            plan.schema_compared = true;
+            plan.removed_objects = removed;
+            plan.changed_objects = changed;
+        }
+
+        Ok(plan)
+    }
+
+    pub async fn reset(
+        &self,
+        database_id: &str,
+        user_id: &str,
+        is_admin: bool,
+        request: &ResetBranchRequest,
+    ) -> AppResult<OperationResponse> {
+        let (branch, source) = self
+            .reset_source(database_id, user_id, is_admin, request)
+            .await?;
+
+        if self.backup_repo.has_running(&branch.id).await?
+            || self.operation_repo.has_running(&branch.id).await?
+        {
+            return Err(AppError::Conflict(
+                "Another operation is running for this database".to_string(),
+            ));
+        }
+
+        let backup_id = match &source {
+            ResetSource::Backup(backup) => Some(backup.id.as_str()),
+            ResetSource::Parent(_) | ResetSource::Snapshot(_) => None,
+        };
+        let operation = self
+            .operation_repo
+            .start(&branch.id, RESET_OPERATION_KIND, backup_id, None)
+            .await?;
+        self.database_repo
+            .update_status(&branch.id, "resetting")
+            .await?;
+
+        let service = self.clone();
+        let running = operation.clone();
+        let mode = request.mode;
+        tokio::spawn(async move {
+            service.execute_reset(branch, source, mode, running).await;
+        });
+
+        Ok(operation.to_response())
+    }
+
+    async fn reset_source(
+        &self,
+        database_id: &str,
+        user_id: &str,
+        is_admin: bool,
+        request: &ResetBranchRequest,
+    ) -> AppResult<(Database, ResetSource)> {
+        let branch = self.owned_database(database_id, user_id, is_admin).await?;
+
+        if branch.container_status != "running" {
+            return Err(AppError::Validation(
+                "Branch must be running to reset".to_string(),
+            ));
+        }
+        if branch.is_replica {
+            return Err(AppError::Validation(
+                "Replicas are read-only, detach the replica before resetting it".to_string(),
+            ));
+        }
+
+        if request.mode == ResetMode::Backup {
+            let backup_id = request.backup_id.as_deref().ok_or_else(|| {
+                AppError::Validation("A backup_id is required to reset to a backup".to_string())
+            })?;
+            let backup = self
+                .backup_service
+                .find_reset_backup(&branch, backup_id)
+                .await?;
+            return Ok((branch, ResetSource::Backup(Box::new(backup))));
+        }
+        if request.mode == ResetMode::Snapshot {
+            let name = request.snapshot_name.as_deref().ok_or_else(|| {
+                AppError::Validation(
+                    "A snapshot_name is required to reset to a snapshot".to_string(),
+                )
+            })?;
+            let snapshot = self
+                .database_service
+                .find_snapshot(&branch.id, name)
+                .await?;
+            return Ok((branch, ResetSource::Snapshot(Box::new(snapshot))));
+        }
+
+        let engine = engine_for(&branch.database_type);
+        if request.mode == ResetMode::SchemaOnly && !engine.supports_schema_only() {
+            return Err(AppError::Validation(format!(
+                "Schema-only resets are not available for {} databases",
+                engine.display_name()
+            )));
+        }
+
+        let parent_id = branch.parent_branch_id.as_ref().ok_or_else(|| {
+            AppError::Validation("Cannot reset root database, it has no parent".to_string())
+        })?;
+        let parent = self
+            .database_repo
+            .find_by_id(parent_id)
+            .await?
+            .ok_or_else(|| {
+                AppError::NotFound(format!("Parent database '{}' not found", parent_id))
+            })?;
+
+        // A copy-on-write snapshot also works from a stopped parent.
+        let from_snapshot = request.mode == ResetMode::Full
+            && engine_for(&branch.database_type).supports_snapshots()
+            && self.database_service.volumes().supports_snapshots();
+        let parent_ready = match parent.container_status.as_str() {
+            "running" => true,
+            "stopped" => from_snapshot,
+            _ => false,
+        };
+        if !parent_ready {
+            return Err(AppError::Validation(
+                "Parent branch must be running to reset".to_string(),
+            ));
+        }
+        if request.mode == ResetMode::Full {
+            self.database_service.check_unmasked_copy(&parent).await?;
+        }
+
+        Ok((branch, ResetSource::Parent(Box::new(parent))))
+    }
+
+    async fn execute_reset(
+        &self,
+        branch: Database,
+        source: ResetSource,
+        mode: ResetMode,
+        operation: Operation,
+    ) {
+        let result = self.perform_reset(&branch, &source, mode).await;
+        reset_status_from_container(&self.database_repo, &self.docker, &branch.id).await;
+        self.operation_repo.finish(&operation, result).await;
+    }
+
+    async fn perform_reset(
+        &self,
+        branch: &Database,
+        source: &ResetSource,
+        mode: ResetMode,
+    ) -> AppResult<()> {
+        match source {
+            ResetSource::Parent(parent) => {
+                tracing::info!(
+                    "Resetting branch {} from parent {} ({:?})",
+                    branch.id,
+                    parent.id,
+                    mode
+                );
+                self.database_service
+                    .reset_from_parent(branch, parent, mode == ResetMode::SchemaOnly)
+                    .await?;
+                if mode != ResetMode::SchemaOnly {
+                    self.database_service.mask_branch_data(branch).await?;
+                }
+                self.database_repo.update_forked_at(&branch.id).await
+            },
+            ResetSource::Backup(backup) => {
+                tracing::info!("Resetting database {} to backup {}", branch.id, backup.id);
+                self.backup_service.reset_to_backup(branch, backup).await?;
+                self.database_service.mask_branch_data(branch).await?;
+
+                // A backup of an ancestor marks the point the branch now diverges from.
+                if backup.database_id != branch.id {
+                    let taken_at = backup.completed_at.as_deref().unwrap_or(&backup.created_at);
+                    let forked_at = parse_sqlite_datetime(taken_at)
+                        .map(|t| t.and_utc().to_rfc3339())
+                        .unwrap_or_else(|| taken_at.to_string());
+                    self.database_repo
+                        .set_forked_at(&branch.id, &forked_at)
+                        .await?;
+                }
+                Ok(())
+            },
+            ResetSource::Snapshot(snapshot) => {
+                tracing::info!(
+                    "Resetting database {} to snapshot {}",
+                    branch.id,
+                    snapshot.name
+                );
+                self.database_service
+                    .reset_to_snapshot(branch, snapshot)
+                    .await
+            },
+        }
+    }
+
+    pub async fn create_snapshot(
+        &self,
+        database_id: &str,
+        user_id: &str,
+        is_admin: bool,
+        name: &str,
+    ) -> AppResult<SnapshotResponse> {
+        let database = self.owned_database(database_id, user_id, is_admin).await?;
+        if self.backup_repo.has_running(&database.id).await? {
+            return Err(AppError::Conflict(
+                "Another operation is running for this database".to_string(),
+            ));
+        }
+        let snapshot = self
+            .database_service
+            .create_snapshot(&database, name)
+            .await?;
+        Ok(snapshot.to_response())
+    }
+
+    pub async fn list_snapshots(
+        &self,
+        database_id: &str,
+        user_id: &str,
+        is_admin: bool,
+    ) -> AppResult<Vec<SnapshotResponse>> {
+        let database = self.owned_database(database_id, user_id, is_admin).await?;
+        let snapshots = self.database_service.list_snapshots(&database.id).await?;
+        Ok(snapshots.iter().map(Snapshot::to_response).collect())
+    }
+
+    pub async fn delete_snapshot(
+        &self,
+        database_id: &str,
+        user_id: &str,
+        is_admin: bool,
+        name: &str,
+    ) -> AppResult<()> {
+        let database = self.owned_database(database_id, user_id, is_admin).await?;
+        let snapshot = self
+            .database_service
+            .find_snapshot(&database.id, name)
+            .await?;
+        // A reset reads the snapshot while it runs.
+        if self.operation_repo.has_running(&database.id).await? {
+            return Err(AppError::Conflict(
+                "Another operation is running for this database".to_string(),
+            ));
+        }
+        self.database_service.delete_snapshot(&snapshot).await
+    }
+
+    async fn owned_database(
+        &self,
+        database_id: &str,
+        user_id: &str,
+        is_admin: bool,
+    ) -> AppResult<Database> {
+        owned_database(
+            &self.database_repo,
+            &self.project_repo,
+            database_id,
+            user_id,
+            is_admin,
+        )
+        .await
+    }
+}
+
+/// Objects of `current` that a reset to `target` removes, and those it changes back.
+fn lost_objects(current: &SchemaInfo, target: &SchemaInfo) -> (Vec<String>, Vec<String>) {
+    let (tables, views) = diff_schemas(current, target);
+    let mut removed = Vec::new();
+    let mut changed = Vec::new();
+
+    for table in &tables {
+        describe_table(table, &mut removed, &mut changed);
+    }
+    for view in views.iter().filter(|v| v.change != SchemaChange::Added) {
+        push_change(view_name(view), view.change, &mut removed, &mut changed);
+    }
+
+    (removed, changed)
+}
+
+fn describe_table(table: &TableDiff, removed: &mut Vec<String>, changed: &mut Vec<String>) {
+    let name = format!("{}.{}", table.schema, table.name);
+    match table.change {
+        SchemaChange::Added => {},
+        SchemaChange::Removed => removed.push(format!("table {}", name)),
+        SchemaChange::Modified => {
+            for column in table
+                .columns
+                .iter()
+                .filter(|c| c.change != SchemaChange::Added)
+            {
+                push_change(
+                    format!("column {}.{}", name, column.name),
+                    column.change,
+                    removed,
+                    changed,
+                );
+            }
+            for index in table
+                .indexes
+                .iter()
+                .filter(|i| i.change != SchemaChange::Added)
+            {
+                push_change(
+                    format!("index {}.{}", table.schema, index.name),
+                    index.change,
+                    removed,
+                    changed,
+                );
+            }
+        },
+    }
+}
+
+fn view_name(view: &ViewDiff) -> String {
+    format!("view {}.{}", view.schema, view.name)
+}
+
+fn push_change(
+    object: String,
+    change: SchemaChange,
+    removed: &mut Vec<String>,
+    changed: &mut Vec<String>,
+) {
+    match change {
+        SchemaChange::Removed => removed.push(object),
+        SchemaChange::Modified => changed.push(object),
+        SchemaChange::Added => {},
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::models::{ColumnDetail, IndexInfo, TableInfo, ViewInfo};
+
+    fn column(name: &str, sql_type: &str) -> ColumnDetail {
+        ColumnDetail {
+            name: name.to_string(),
+            data_type: sql_type.to_string(),
+            nullable: true,
+            default_value: None,
+            is_primary_key: false,
+            sql_type: sql_type.to_string(),
+        }
+    }
+
+    fn table(name: &str, columns: Vec<ColumnDetail>, indexes: Vec<IndexInfo>) -> TableInfo {
+        TableInfo {
+            schema: "public".to_string(),
+            name: name.to_string(),
+            columns,
+            indexes,
+            row_count_estimate: 0,
+            size_bytes: 0,
+        }
+    }
+
+    #[test]
+    fn test_lost_objects() {
+        let email_index = IndexInfo {
+            name: "users_email_idx".to_string(),
+            columns: vec!["email".to_string()],
+            is_unique: false,
+            is_primary: false,
+            definition: "CREATE INDEX users_email_idx ON public.users USING btree (email)"
+                .to_string(),
+        };
+        let branch = SchemaInfo {
+            tables: vec![
+                table(
+                    "users",
+                    vec![
+                        column("id", "integer"),
+                        column("email", "character varying(255)"),
+                        column("created_at", "timestamp with time zone"),
+                    ],
+                    vec![email_index],
+                ),
+                table("orders", vec![column("id", "integer")], vec![]),
+            ],
+            views: vec![ViewInfo {
+                schema: "public".to_string(),
+                name: "recent_orders".to_string(),
+                columns: vec![],
+                definition: Some(" SELECT id FROM orders;".to_string()),
+                depends_on: vec![],
+            }],
+        };
+        let parent = SchemaInfo {
+            tables: vec![
+                table(
+                    "users",
+                    vec![
+                        column("id", "integer"),
+                        column("email", "character varying(100)"),
+                        column("nickname", "text"),
+                    ],
+                    vec![],
+                ),
+                table("legacy", vec![column("id", "integer")], vec![]),
+            ],
+            views: vec![],
+        };
+
+        let (mut removed, changed) = lost_objects(&branch, &parent);
+        removed.sort();
+        assert_eq!(
+            removed,
+            vec![
+                "column public.users.created_at",
+                "index public.users_email_idx",
+                "table public.orders",
+                "view public.recent_orders",
+            ]
+        );
+        // Objects only the parent has come back with the reset and are not reported.
+        assert_eq!(changed, vec!["column public.users.email"]);
+    }
+}
@@ -0,0 +1,219 @@
+use shell_words::quote;
+
+use crate::domain::engines::Engine;
+use crate::domain::models::{BranchDataMode, BranchDataOptions};
+use crate::error::{AppError, AppResult};
+
+/// Tables, partitioned tables and sequences of a database, as `relkind<TAB>schema.name`.
+pub(super) const RELATIONS_QUERY: &str = "SELECT c.relkind, format('%I.%I', n.nspname, c.relname) \
+     FROM pg_class c JOIN pg_namespace n ON n.oid = c.relnamespace \
+     WHERE c.relkind IN ('r', 'p', 'S') \
+       AND n.nspname NOT IN ('pg_catalog', 'information_schema') \
+       AND n.nspname NOT LIKE 'pg_toast%' AND n.nspname NOT LIKE 'pg_temp_%' \
+     ORDER BY 2";
+
+/// Foreign keys as `table<TAB>referenced table<TAB>orphan delete<TAB>constraint DDL`.
+pub(super) const FOREIGN_KEYS_QUERY: &str = "SELECT format('%I.%I', cn.nspname, cc.relname), \
+       format('%I.%I', pn.nspname, pc.relname), \
+       format('DELETE FROM %I.%I AS c WHERE %s AND NOT EXISTS (SELECT 1 FROM %I.%I AS p WHERE %s)', \
+           cn.nspname, cc.relname, \
+           (SELECT string_agg(format('c.%I IS NOT NULL', a.attname), ' AND ') \
+              FROM unnest(con.conkey) AS k(n) \
+              JOIN pg_attribute a ON a.attrelid = con.conrelid AND a.attnum = k.n), \
+           pn.nspname, pc.relname, \
+           (SELECT string_agg(format('p.%I = c.%I', pa.attname, ca.attname), ' AND ') \
+              FROM unnest(con.conkey, con.confkey) AS k(c, p) \
+              JOIN pg_attribute ca ON ca.attrelid = con.conrelid AND ca.attnum = k.c \
+              JOIN pg_attribute pa ON pa.attrelid = con.confrelid AND pa.attnum = k.p)), \
+       format('ALTER TABLE %I.%I ADD CONSTRAINT %I %s', cn.nspname, cc.relname, con.conname, \
+           pg_get_constraintdef(con.oid)) \
+     FROM pg_constraint con \
+     JOIN pg_class cc ON cc.oid = con.conrelid JOIN pg_namespace cn ON cn.oid = cc.relnamespace \
+     JOIN pg_class pc ON pc.oid = con.confrelid JOIN pg_namespace pn ON pn.oid = pc.relnamespace \
+     WHERE con.contype = 'f' AND con.conparentid = 0";
+
+/// Sequence positions as `schema.name<TAB>setval statement`.
+pub(super) const SEQUENCES_QUERY: &str = "SELECT format('%I.%I', schemaname, sequencename), \
+       format('SELECT pg_catalog.setval(%L, %s, true)', format('%I.%I', schemaname, sequencename), last_value) \
+     FROM pg_sequences WHERE last_value IS NOT NULL";
+
+pub(super) fn validate_data_options(
+    options: &BranchDataOptions,
+    engine: &dyn Engine,
+) -> AppResult<()> {
+    if options.mode == BranchDataMode::SchemaOnly && !engine.supports_schema_only() {
+        return Err(AppError::Validation(format!(
+            "Schema-only branches are not available for {} databases",
+            engine.display_name()
+        )));
+    }
+    let filtered =
+        !options.tables.is_empty() || !options.exclude_tables.is_empty() || options.is_sampled();
+    if !filtered {
+        return Ok(());
+    }
+    if !engine.supports_table_filters() {
+        return Err(AppError::Validation(format!(
+            "Filtered and sampled branches are not available for {} databases",
+            engine.display_name()
+        )));
+    }
+    if options.mode == BranchDataMode::Replica {
+        return Err(AppError::Validation(
+            "Replica branches follow all of the parent and cannot be filtered or sampled"
+                .to_string(),
+        ));
+    }
+    if options.mode == BranchDataMode::None {
+        return Err(AppError::Validation(
+            "Table filters and samples need data mode full or schema_only".to_string(),
+        ));
+    }
+    if options.mode == BranchDataMode::SchemaOnly && options.is_sampled() {
+        return Err(AppError::Validation(
+            "Schema-only branches cannot be sampled".to_string(),
+        ));
+    }
+    if let Some(percent) = options.sample_percent {
+        if !(percent > 0.0 && percent <= 100.0) {
+            return Err(AppError::Validation(
+                "sample_percent must be greater than 0 and at most 100".to_string(),
+            ));
+        }
+    }
+    if options.sample_rows.is_some_and(|rows| rows <= 0) {
+        return Err(AppError::Validation(
+            "sample_rows must be a positive number".to_string(),
+        ));
+    }
+    if options
+        .tables
+        .iter()
+        .chain(&options.exclude_tables)
+        .any(|pattern| pattern.trim().is_empty())
+    {
+        return Err(AppError::Validation(
+            "Table patterns cannot be empty".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// `pg_dump` table filters, quoted for the shell.
+pub(super) fn dump_filter_args(options: &BranchDataOptions) -> Vec<String> {
+    let tables = options
+        .tables
+        .iter()
+        .map(|pattern| format!("--table={}", quote(pattern)));
+    let excluded = options
+        .exclude_tables
+        .iter()
+        .map(|pattern| format!("--exclude-table={}", quote(pattern)));
+    tables.chain(excluded).collect()
+}
+
+/// `COPY` of the sampled rows of `table`, an already quoted `schema.name`.
+pub(super) fn sample_copy_query(table: &str, options: &BranchDataOptions) -> String {
+    let mut select = format!("SELECT * FROM {}", table);
+    if let Some(percent) = options.sample_percent.filter(|p| *p < 100.0) {
+        select.push_str(&format!(" TABLESAMPLE BERNOULLI ({})", percent));
+    }
+    if let Some(rows) = options.sample_rows {
+        select.push_str(&format!(" LIMIT {}", rows));
+    }
+    format!("COPY ({}) TO STDOUT", select)
+}
+
+/// Pipes one table's rows from the parent into the branch. Runs in the branch container with
+/// the passwords and `COPY` statements in the environment, and fails if either side does.
+pub(super) fn copy_table_script(
+    source_host: &str,
+    source_username: &str,
+    target_username: &str,
+) -> String {
+    format!(
+        "status=$(mktemp) && \
+         {{ PGPASSWORD=\"$SOURCE_PASSWORD\" psql -h {} -U {} -d postgres -v ON_ERROR_STOP=1 -c \"$COPY_OUT\"; echo $? > \"$status\"; }} \
+         | PGPASSWORD=\"$TARGET_PASSWORD\" psql -h localhost -U {} -d postgres -v ON_ERROR_STOP=1 -c \"$COPY_IN\" \
+         && [ \"$(cat \"$status\")\" = 0 ]; rc=$?; rm -f \"$status\"; exit $rc",
+        quote(source_host),
+        quote(source_username),
+        quote(target_username)
+    )
+}
+
+/// Restores the parent's indexes, triggers and other post-data objects except foreign keys,
+/// which are added once orphaned rows are gone.
+pub(super) fn post_data_script(
+    source_host: &str,
+    source_username: &str,
+    target_username: &str,
+    filters: &[String],
+) -> String {
+    format!(
+        "dir=$(mktemp -d) && \
+         PGPASSWORD=\"$SOURCE_PASSWORD\" pg_dump -h {} -U {} -d postgres -Fc --section=post-data {} -f \"$dir/post.dump\" && \
+         pg_restore -l \"$dir/post.dump\" | grep -v ' FK CONSTRAINT ' > \"$dir/post.list\" && \
+         PGPASSWORD=\"$TARGET_PASSWORD\" pg_restore -h localhost -U {} -d postgres --no-owner --no-privileges \
+         -L \"$dir/post.list\" \"$dir/post.dump\"; rc=$?; rm -rf \"$dir\"; exit $rc",
+        quote(source_host),
+        quote(source_username),
+        filters.join(" "),
+        quote(target_username)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::engines::engine_for;
+
+    #[test]
+    fn test_partial_branch_options() {
+        let options = BranchDataOptions {
+            tables: vec!["public.orders*".to_string()],
+            exclude_tables: vec!["audit's log".to_string()],
+            sample_percent: Some(5.0),
+            sample_rows: Some(1000),
+            ..Default::default()
+        };
+        assert!(validate_data_options(&options, engine_for("postgres")).is_ok());
+        assert!(validate_data_options(&options, engine_for("redis")).is_err());
+        assert_eq!(
+            dump_filter_args(&options),
+            vec![
+                "--table='public.orders*'".to_string(),
+                "--exclude-table='audit'\\''s log'".to_string()
+            ]
+        );
+        assert_eq!(
+            sample_copy_query("\"public\".\"orders\"", &options),
+            "COPY (SELECT * FROM \"public\".\"orders\" TABLESAMPLE BERNOULLI (5) LIMIT 1000) TO STDOUT"
+        );
+
+        let schema_only = BranchDataOptions {
+            mode: BranchDataMode::SchemaOnly,
+            sample_rows: Some(10),
+            ..Default::default()
+        };
+        assert!(validate_data_options(&schema_only, engine_for("postgres")).is_err());
+        let plain_schema_only = BranchDataOptions {
+            mode: BranchDataMode::SchemaOnly,
+            ..Default::default()
+        };
+        assert!(validate_data_options(&plain_schema_only, engine_for("mysql")).is_ok());
+        assert!(validate_data_options(&plain_schema_only, engine_for("valkey")).is_err());
+        let too_much = BranchDataOptions {
+            sample_percent: Some(150.0),
+            ..Default::default()
+        };
+        assert!(validate_data_options(&too_much, engine_for("postgres")).is_err());
+        assert!(validate_data_options(&BranchDataOptions::default(), engine_for("valkey")).is_ok());
+        let filtered_replica = BranchDataOptions {
+            mode: BranchDataMode::Replica,
+            tables: vec!["orders".to_string()],
+            ..Default::default()
+        };
+        assert!(validate_data_options(&filtered_replica, engine_for("postgres")).is_err());
+    }
+}
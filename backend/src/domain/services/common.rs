@@ -0,0 +1,70 @@
+use crate::domain::models::Database;
+use crate::error::{AppError, AppResult};
+use crate::repositories::{DatabaseRepository, ProjectRepository};
+
+/// Loads a database the user may manage, as an admin or as the owner of its project.
+pub(crate) async fn owned_database(
+    database_repo: &DatabaseRepository,
+    project_repo: &ProjectRepository,
+    database_id: &str,
+    user_id: &str,
+    is_admin: bool,
+) -> AppResult<Database> {
+    let database = database_repo
+        .find_by_id(database_id)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("Database '{}' not found", database_id)))?;
+
+    if !is_admin && !project_repo.is_owner(&database.project_id, user_id).await? {
+        return Err(AppError::Forbidden);
+    }
+
+    Ok(database)
+}
+
+/// `psql` inside a PostgreSQL container, printing bare values and stopping at the first error.
+/// Each statement runs on its own, since some, like `ALTER SYSTEM`, cannot share a transaction.
+pub(crate) fn psql_command(username: &str, dbname: &str, statements: &[&str]) -> Vec<String> {
+    let mut cmd = vec![
+        "psql".to_string(),
+        "-U".to_string(),
+        username.to_string(),
+        "-d".to_string(),
+        dbname.to_string(),
+        "-tA".to_string(),
+        "-v".to_string(),
+        "ON_ERROR_STOP=1".to_string(),
+    ];
+    for statement in statements {
+        cmd.push("-c".to_string());
+        cmd.push(statement.to_string());
+    }
+    cmd
+}
+
+/// Quotes a PostgreSQL identifier.
+pub(crate) fn quote_ident(name: &str) -> String {
+    format!("\"{}\"", name.replace('"', "\"\""))
+}
+
+/// Quotes a MySQL or MariaDB identifier.
+pub(crate) fn quote_mysql_ident(name: &str) -> String {
+    format!("`{}`", name.replace('`', "``"))
+}
+
+/// Quotes a ClickHouse identifier, which escapes with backslashes instead of doubling.
+pub(crate) fn quote_clickhouse_ident(name: &str) -> String {
+    format!("`{}`", name.replace('\\', "\\\\").replace('`', "\\`"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_quote_ident() {
+        assert_eq!(quote_ident("my \"table\""), "\"my \"\"table\"\"\"");
+        assert_eq!(quote_mysql_ident("my `table`"), "`my ``table```");
+        assert_eq!(quote_clickhouse_ident("a`b\\c"), "`a\\`b\\\\c`");
+    }
+}
@@ -1,103 +1,139 @@
 use std::sync::Arc;
 use std::time::Duration;
 
-use aes_gcm::{
-    aead::{Aead, KeyInit},
-    Aes256Gcm, Nonce,
-};
-use base64::{engine::general_purpose, Engine as _};
+use chrono::{DateTime, TimeDelta, Utc};
 use rand::RngCore;
 use shell_words::split as split_shell_words;
 
+use bytes::Bytes;
+use futures::Stream;
+
+use super::branch_data::{
+    copy_table_script, dump_filter_args, post_data_script, sample_copy_query,
+    validate_data_options, FOREIGN_KEYS_QUERY, RELATIONS_QUERY, SEQUENCES_QUERY,
+};
+use super::document::{document_query_script, DEFAULT_DOCUMENT_LIMIT, MAX_DOCUMENT_LIMIT};
+use super::masking::{masking_statements, masking_type_check, vacuum_statements_query};
+use super::{BackupStores, ImportService};
+use crate::domain::engines::{engine_for, find_engine, Engine, EngineConfig, ForkSpec};
 use crate::domain::models::{
-    BranchResponse, ConfigFormat, ConfigSource, Database, DatabaseConfigResponse, DatabaseResponse,
-    KvCommandResult, PostgresVersion, RedisVersion, UpdateDatabaseConfigResponse, ValkeyVersion,
+    BranchDataMode, BranchDataOptions, BranchResponse, BranchTreeNode, ChildBranchPolicy, Database,
+    DatabaseConfigResponse, DatabaseImportRequest, DatabaseResponse, DocumentQueryResult,
+    ExecuteDocumentQueryRequest, ImportUploadResponse, KvCommandResult, MaskingConfig, Operation,
+    OperationResponse, PostgresVariant, Snapshot, UpdateDatabaseConfigResponse,
+    DEFAULT_PRELOAD_LIBRARIES,
 };
 use crate::error::{AppError, AppResult};
-use crate::infrastructure::docker::{ContainerConfig, DockerManager};
-use crate::repositories::{DatabaseRepository, ProjectRepository};
+use crate::infrastructure::docker::containers::{
+    MongoContainer, PostgresContainer, KV_PERSISTENCE_MODES,
+};
+use crate::infrastructure::docker::{ContainerConfig, DockerManager, ForkProgress};
+use crate::infrastructure::volumes::DataVolumes;
+use crate::repositories::{
+    BackupRepository, DatabaseRepository, OperationRepository, ProjectRepository,
+    SnapshotRepository,
+};
+use crate::utils::crypto;
 
-const KV_SENSITIVE_KEYS: &[&str] = &["requirepass", "masterauth"];
 const MAX_KV_COMMAND_LEN: usize = 4096;
+const MAX_ORPHAN_PASSES: usize = 50;
+const PROGRESS_INTERVAL: Duration = Duration::from_secs(1);
+
+pub const BRANCH_OPERATION_KIND: &str = "branch";
+pub const SYNC_OPERATION_KIND: &str = "sync";
+pub const INITIALIZE_OPERATION_KIND: &str = "initialize";
 
 #[derive(Clone)]
 pub struct DatabaseService {
     database_repo: DatabaseRepository,
     project_repo: ProjectRepository,
+    operation_repo: OperationRepository,
+    snapshot_repo: SnapshotRepository,
+    backup_repo: BackupRepository,
+    backup_stores: BackupStores,
+    import_service: ImportService,
     docker: Arc<DockerManager>,
+    volumes: Arc<dyn DataVolumes>,
     data_dir: String,
     host: String,
     encryption_key: [u8; 32],
 }
 
 impl DatabaseService {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         database_repo: DatabaseRepository,
         project_repo: ProjectRepository,
+        operation_repo: OperationRepository,
+        snapshot_repo: SnapshotRepository,
+        backup_repo: BackupRepository,
+        backup_stores: BackupStores,
         docker: Arc<DockerManager>,
+        volumes: Arc<dyn DataVolumes>,
         data_dir: String,
         host: String,
         encryption_key_hex: &str,
     ) -> Self {
-        let encryption_key = hex::decode(encryption_key_hex)
-            .expect("Invalid encryption key hex")
-            .try_into()
-            .expect("Encryption key must be 32 bytes");
+        let encryption_key = crypto::parse_encryption_key(encryption_key_hex);
 
         Self {
             database_repo,
             project_repo,
+            import_service: ImportService::new(
+                operation_repo.clone(),
+                docker.clone(),
+                data_dir.clone(),
+            ),
+            operation_repo,
+            snapshot_repo,
+            backup_repo,
+            backup_stores,
             docker,
+            volumes,
             data_dir,
             host,
             encryption_key,
         }
     }
 
-    fn internal_port_for_type(database_type: &str) -> i32 {
-        match database_type {
-            "redis" | "valkey" => 6379,
-            _ => 5432,
-        }
+    pub fn volumes(&self) -> &Arc<dyn DataVolumes> {
+        &self.volumes
     }
 
-    fn encrypt_password(&self, password: &str) -> AppResult<String> {
-        let cipher = Aes256Gcm::new_from_slice(&self.encryption_key)
-            .map_err(|e| AppError::Internal(format!("Encryption init failed: {}", e)))?;
-
-        let mut nonce_bytes = [0u8; 12];
-        rand::thread_rng().fill_bytes(&mut nonce_bytes);
-        let nonce = Nonce::from_slice(&nonce_bytes);
-
-        let ciphertext = cipher
-            .encrypt(nonce, password.as_bytes())
-            .map_err(|e| AppError::Internal(format!("Encryption failed: {}", e)))?;
-
-        let mut result = nonce_bytes.to_vec();
-        result.extend(ciphertext);
-        Ok(hex::encode(result))
+    fn data_path(&self, database_id: &str) -> String {
+        format!("{}/{}", self.data_dir, database_id)
     }
 
-    fn decrypt_password(&self, encrypted: &str) -> AppResult<String> {
-        let data = hex::decode(encrypted)
-            .map_err(|e| AppError::Internal(format!("Invalid encrypted data: {}", e)))?;
-
-        if data.len() < 12 {
-            return Err(AppError::Internal("Encrypted data too short".to_string()));
-        }
+    fn snapshot_path(&self, snapshot: &Snapshot) -> String {
+        format!(
+            "{}/{}-snapshot-{}",
+            self.data_dir, snapshot.database_id, snapshot.id
+        )
+    }
 
-        let (nonce_bytes, ciphertext) = data.split_at(12);
-        let nonce = Nonce::from_slice(nonce_bytes);
+    pub fn wal_archive_path(&self, database_id: &str) -> String {
+        format!("{}/wal/{}", self.data_dir, database_id)
+    }
 
-        let cipher = Aes256Gcm::new_from_slice(&self.encryption_key)
-            .map_err(|e| AppError::Internal(format!("Decryption init failed: {}", e)))?;
+    /// Creates the WAL archive directory and makes it writable for postgres, ahead of starting
+    /// a container that archives into it.
+    async fn prepare_wal_archive(&self, database_id: &str, image: &str) -> AppResult<String> {
+        let path = self.wal_archive_path(database_id);
+        std::fs::create_dir_all(&path).map_err(|e| {
+            AppError::Internal(format!("Failed to create WAL archive directory: {}", e))
+        })?;
+        self.docker
+            .prepare_wal_archive(&format!("datify-walprep-{}", database_id), image, &path)
+            .await?;
+        Ok(path)
+    }
 
-        let plaintext = cipher
-            .decrypt(nonce, ciphertext)
-            .map_err(|e| AppError::Internal(format!("Decryption failed: {}", e)))?;
+    fn encrypt_password(&self, password: &str) -> AppResult<String> {
+        crypto::encrypt_password(&self.encryption_key, password)
+    }
 
-        String::from_utf8(plaintext)
-            .map_err(|e| AppError::Internal(format!("Invalid UTF-8 in password: {}", e)))
+    fn decrypt_password(&self, encrypted: &str) -> AppResult<String> {
+        crypto::decrypt_password(&self.encryption_key, encrypted)
     }
 
     #[allow(clippy::too_many_arguments)]
@@ -108,14 +144,16 @@ impl DatabaseService {
         is_admin: bool,
         name: &str,
         database_type: &str,
-        postgres_version: &str,
-        valkey_version: Option<&str>,
-        redis_version: Option<&str>,
+        version: Option<&str>,
+        postgres_variant: Option<PostgresVariant>,
         password: Option<&str>,
         public_exposed: Option<bool>,
         cpu_limit: f64,
         memory_limit_mb: i32,
         storage_limit_mb: i32,
+        wal_archiving: bool,
+        persistence: Option<&str>,
+        import: Option<&DatabaseImportRequest>,
     ) -> AppResult<DatabaseResponse> {
         if !is_admin && !self.project_repo.is_owner(project_id, user_id).await? {
             return Err(AppError::Forbidden);
@@ -143,25 +181,57 @@ impl DatabaseService {
             ));
         }
 
-        let is_valkey = database_type == "valkey";
-        let is_redis = database_type == "redis";
+        let engine = find_engine(database_type).ok_or_else(|| {
+            AppError::Validation(format!("Unsupported database type: {}", database_type))
+        })?;
+        let version = version.unwrap_or(engine.default_version());
+        if !engine.is_valid_version(version) {
+            return Err(AppError::Validation(format!(
+                "Invalid {} version",
+                engine.display_name()
+            )));
+        }
 
-        if is_valkey {
-            let version = valkey_version.unwrap_or("8.0");
-            if !ValkeyVersion::is_valid(version) {
-                return Err(AppError::Validation("Invalid Valkey version".to_string()));
+        if let Some(variant) = postgres_variant {
+            if engine.database_type() != "postgres" {
+                return Err(AppError::Validation(
+                    "Image variants are only available for PostgreSQL databases".to_string(),
+                ));
             }
-        } else if is_redis {
-            let version = redis_version.unwrap_or("7.4");
-            if !RedisVersion::is_valid(version) {
-                return Err(AppError::Validation("Invalid Redis version".to_string()));
+            if !variant.supports_version(version) {
+                let (min, max) = variant.versions();
+                return Err(AppError::Validation(format!(
+                    "{} images are available for PostgreSQL {} to {}",
+                    variant.display_name(),
+                    min,
+                    max
+                )));
             }
-        } else if !PostgresVersion::is_valid(postgres_version) {
+        }
+
+        if wal_archiving && !engine.supports_wal_archiving() {
             return Err(AppError::Validation(
-                "Invalid PostgreSQL version".to_string(),
+                "WAL archiving is only supported for PostgreSQL databases".to_string(),
             ));
         }
 
+        let persistence = if engine.supports_persistence_modes() {
+            let persistence = persistence.unwrap_or("aof");
+            if !KV_PERSISTENCE_MODES.contains(&persistence) {
+                return Err(AppError::Validation(format!(
+                    "Persistence must be one of: {}",
+                    KV_PERSISTENCE_MODES.join(", ")
+                )));
+            }
+            Some(persistence)
+        } else if persistence.is_some() {
+            return Err(AppError::Validation(
+                "Persistence mode is only supported for Redis or Valkey databases".to_string(),
+            ));
+        } else {
+            None
+        };
+
         if self
             .database_repo
             .find_by_name_and_project(project_id, name)
@@ -174,17 +244,31 @@ impl DatabaseService {
             )));
         }
 
+        if import.is_some() && !engine.supports_import() {
+            return Err(AppError::Validation(format!(
+                "Imports are not available for {} databases yet",
+                engine.display_name()
+            )));
+        }
+
+        let import = match import {
+            Some(request) => Some(
+                self.import_service
+                    .resolve(project_id, database_type, request)
+                    .await?,
+            ),
+            None => None,
+        };
+
         let public_exposed = public_exposed.unwrap_or(false);
 
-        let database = self
+        let mut database = self
             .database_repo
             .create(
                 project_id,
                 name,
                 database_type,
-                postgres_version,
-                valkey_version,
-                redis_version,
+                version,
                 cpu_limit,
                 memory_limit_mb,
                 storage_limit_mb,
@@ -199,7 +283,7 @@ impl DatabaseService {
             .map(|p| p.to_string())
             .unwrap_or_else(generate_password);
 
-        let internal_port = Self::internal_port_for_type(&database.database_type);
+        let internal_port = engine.internal_port() as i32;
         let port = if database.public_exposed {
             self.database_repo.get_next_available_port().await?
         } else {
@@ -212,71 +296,63 @@ impl DatabaseService {
         };
 
         let data_path = format!("{}/{}", self.data_dir, database.id);
+        self.volumes.create(&data_path).await?;
 
-        std::fs::create_dir_all(&data_path)
-            .map_err(|e| AppError::Internal(format!("Failed to create data directory: {}", e)))?;
+        if let Some(persistence) = persistence {
+            self.database_repo
+                .update_persistence(&database.id, persistence)
+                .await?;
+            database.persistence = Some(persistence.to_string());
+        }
+
+        if let Some(variant) = postgres_variant {
+            let libraries = DEFAULT_PRELOAD_LIBRARIES
+                .iter()
+                .chain(variant.preload_libraries())
+                .copied()
+                .collect::<Vec<_>>()
+                .join(",");
+            self.database_repo
+                .update_postgres_variant(&database.id, Some(variant.as_str()))
+                .await?;
+            self.database_repo
+                .update_preload_libraries(&database.id, &libraries)
+                .await?;
+            database.postgres_variant = Some(variant.as_str().to_string());
+            database.shared_preload_libraries = Some(libraries);
+        }
 
-        let container_id = if is_valkey {
-            let version = valkey_version.unwrap_or("8.0");
-            let config = ContainerConfig {
-                name: database.container_name(),
-                image: format!("valkey/valkey:{}-alpine", version),
-                env: vec![],
-                data_path,
-                cpu_limit,
-                memory_limit_mb: memory_limit_mb as i64,
-                internal_port: internal_port as u16,
-                exposed_port,
-                cmd: None,
-            };
-            self.docker
-                .create_valkey_container(config, &password)
-                .await?
-        } else if is_redis {
-            let version = redis_version.unwrap_or("7.4");
-            let config = ContainerConfig {
-                name: database.container_name(),
-                image: format!("redis:{}-alpine", version),
-                env: vec![],
-                data_path,
-                cpu_limit,
-                memory_limit_mb: memory_limit_mb as i64,
-                internal_port: internal_port as u16,
-                exposed_port,
-                cmd: None,
-            };
-            self.docker
-                .create_redis_container(config, &password)
-                .await?
+        let wal_archive_path = if wal_archiving {
+            let path = self
+                .prepare_wal_archive(&database.id, &engine.image(&database))
+                .await?;
+            self.database_repo
+                .update_wal_archiving(&database.id, true)
+                .await?;
+            Some(path)
         } else {
-            let config = ContainerConfig {
-                name: database.container_name(),
-                image: format!("postgres:{}", postgres_version),
-                env: vec![
-                    format!("POSTGRES_USER={}", database.username),
-                    "POSTGRES_DB=postgres".to_string(),
-                ],
-                data_path,
-                cpu_limit,
-                memory_limit_mb: memory_limit_mb as i64,
-                internal_port: internal_port as u16,
-                exposed_port,
-                cmd: Some(vec![
-                    "postgres".to_string(),
-                    "-c".to_string(),
-                    "shared_preload_libraries=pg_stat_statements".to_string(),
-                    "-c".to_string(),
-                    "pg_stat_statements.track=all".to_string(),
-                ]),
-            };
-            self.docker
-                .create_postgres_container(config, &password)
-                .await?
+            None
         };
 
+        let mut config = container_config(
+            engine,
+            &database,
+            database.container_name(),
+            engine.image(&database),
+            data_path,
+            exposed_port,
+        );
+        config.wal_archive_path = wal_archive_path;
+        let container_id = engine
+            .create_container(&self.docker, config, &password)
+            .await?;
+
         self.docker.start_container(&container_id).await?;
 
-        let healthy = self.docker.wait_for_healthy(&container_id, 60).await?;
+        let healthy = self
+            .docker
+            .wait_for_healthy(&container_id, engine.startup_timeout_secs())
+            .await?;
         let status = if healthy { "running" } else { "unhealthy" };
 
         let password_encrypted = self.encrypt_password(&password)?;
@@ -293,7 +369,38 @@ impl DatabaseService {
             )
             .await?;
 
-        Ok(database.to_response_with_host(Some(&password), Some(&self.host)))
+        if healthy {
+            self.initialize_database(&database, &password).await;
+        }
+
+        let mut response = database.to_response_with_host(Some(&password), Some(&self.host));
+        if let Some(source) = import {
+            let operation = self
+                .import_service
+                .start(&database, &password, source)
+                .await?;
+            response.import_operation_id = Some(operation.id);
+        }
+
+        Ok(response)
+    }
+
+    pub async fn upload_import<S, E>(
+        &self,
+        project_id: &str,
+        user_id: &str,
+        is_admin: bool,
+        body: S,
+    ) -> AppResult<ImportUploadResponse>
+    where
+        S: Stream<Item = Result<Bytes, E>> + Unpin,
+        E: std::fmt::Display,
+    {
+        if !is_admin && !self.project_repo.is_owner(project_id, user_id).await? {
+            return Err(AppError::Forbidden);
+        }
+
+        self.import_service.save_upload(project_id, body).await
     }
 
     pub async fn get_by_id(&self, id: &str) -> AppResult<Option<Database>> {
@@ -482,6 +589,55 @@ impl DatabaseService {
         public_exposed: bool,
         new_name: Option<&str>,
     ) -> AppResult<()> {
+        let port = if public_exposed {
+            self.database_repo.get_next_available_port().await?
+        } else {
+            engine_for(&database.database_type).internal_port() as i32
+        };
+        self.replace_container(database, public_exposed, new_name, port, None)
+            .await
+    }
+
+    /// Replaces the container with one built from the stored settings, keeping the name and
+    /// port. The new container is left stopped.
+    pub async fn recreate_container(&self, database: &Database) -> AppResult<()> {
+        let port = database
+            .port
+            .unwrap_or_else(|| engine_for(&database.database_type).internal_port() as i32);
+        self.replace_container(database, database.public_exposed, None, port, None)
+            .await
+    }
+
+    /// Like `recreate_container`, but with the WAL archive of `source_id` mounted read-only at
+    /// `WAL_RESTORE_MOUNT_POINT` so recovery can replay it without copying the archive.
+    pub async fn recreate_container_for_recovery(
+        &self,
+        database: &Database,
+        source_id: &str,
+    ) -> AppResult<()> {
+        let port = database
+            .port
+            .unwrap_or_else(|| engine_for(&database.database_type).internal_port() as i32);
+        let wal_restore_path = self.wal_archive_path(source_id);
+        self.replace_container(
+            database,
+            database.public_exposed,
+            None,
+            port,
+            Some(wal_restore_path),
+        )
+        .await
+    }
+
+    async fn replace_container(
+        &self,
+        database: &Database,
+        public_exposed: bool,
+        new_name: Option<&str>,
+        port: i32,
+        wal_restore_path: Option<String>,
+    ) -> AppResult<()> {
+        let engine = engine_for(&database.database_type);
         let container_name = Database::container_name_for(
             &database.database_type,
             new_name.unwrap_or(&database.name),
@@ -492,12 +648,6 @@ impl DatabaseService {
             .ok_or_else(|| AppError::Internal("No password stored for database".to_string()))?;
         let password = self.decrypt_password(password_encrypted)?;
 
-        let internal_port = Self::internal_port_for_type(&database.database_type);
-        let port = if public_exposed {
-            self.database_repo.get_next_available_port().await?
-        } else {
-            internal_port
-        };
         let exposed_port = if public_exposed {
             Some(port as u16)
         } else {
@@ -515,63 +665,24 @@ impl DatabaseService {
             }
         }
 
-        let container_result = match database.database_type.as_str() {
-            "redis" => {
-                let version = database.redis_version.as_deref().unwrap_or("7.4");
-                let config = ContainerConfig {
-                    name: container_name.clone(),
-                    image: format!("redis:{}-alpine", version),
-                    env: vec![],
-                    data_path,
-                    cpu_limit: database.cpu_limit,
-                    memory_limit_mb: database.memory_limit_mb as i64,
-                    internal_port: internal_port as u16,
-                    exposed_port,
-                    cmd: None,
-                };
-                self.docker.create_redis_container(config, &password).await
-            },
-            "valkey" => {
-                let version = database.valkey_version.as_deref().unwrap_or("8.0");
-                let config = ContainerConfig {
-                    name: container_name.clone(),
-                    image: format!("valkey/valkey:{}-alpine", version),
-                    env: vec![],
-                    data_path,
-                    cpu_limit: database.cpu_limit,
-                    memory_limit_mb: database.memory_limit_mb as i64,
-                    internal_port: internal_port as u16,
-                    exposed_port,
-                    cmd: None,
-                };
-                self.docker.create_valkey_container(config, &password).await
-            },
-            _ => {
-                let config = ContainerConfig {
-                    name: container_name.clone(),
-                    image: format!("postgres:{}", database.postgres_version),
-                    env: vec![
-                        format!("POSTGRES_USER={}", database.username),
-                        "POSTGRES_DB=postgres".to_string(),
-                    ],
-                    data_path,
-                    cpu_limit: database.cpu_limit,
-                    memory_limit_mb: database.memory_limit_mb as i64,
-                    internal_port: internal_port as u16,
-                    exposed_port,
-                    cmd: Some(vec![
-                        "postgres".to_string(),
-                        "-c".to_string(),
-                        "shared_preload_libraries=pg_stat_statements".to_string(),
-                        "-c".to_string(),
-                        "pg_stat_statements.track=all".to_string(),
-                    ]),
-                };
-                self.docker
-                    .create_postgres_container(config, &password)
-                    .await
-            },
-        };
+        let mut config = container_config(
+            engine,
+            database,
+            container_name,
+            engine.image(database),
+            data_path,
+            exposed_port,
+        );
+        if engine.supports_wal_archiving() && database.wal_archiving {
+            config.wal_archive_path = Some(
+                self.prepare_wal_archive(&database.id, &engine.image(database))
+                    .await?,
+            );
+        }
+        config.wal_restore_path = wal_restore_path;
+        let container_result = engine
+            .create_container(&self.docker, config, &password)
+            .await;
 
         let container_id = match container_result {
             Ok(id) => id,
@@ -656,7 +767,15 @@ impl DatabaseService {
             .ok_or_else(|| AppError::NotFound(format!("Database '{}' not found", id)))
     }
 
-    pub async fn delete(&self, id: &str, user_id: &str, is_admin: bool) -> AppResult<()> {
+    /// Deletes a database, handling its branches according to `children`. Returns the IDs of all
+    /// deleted databases, the database itself last.
+    pub async fn delete(
+        &self,
+        id: &str,
+        user_id: &str,
+        is_admin: bool,
+        children: ChildBranchPolicy,
+    ) -> AppResult<Vec<String>> {
         let database = self
             .database_repo
             .find_by_id(id)
@@ -672,15 +791,121 @@ impl DatabaseService {
             return Err(AppError::Forbidden);
         }
 
+        let branches = self.database_repo.find_children(id).await?;
+        let mut deleted = Vec::new();
+        if !branches.is_empty() {
+            match children {
+                ChildBranchPolicy::Refuse => {
+                    return Err(AppError::Conflict(format!(
+                        "Database '{}' has {} branch(es). Delete them first, or cascade or re-parent them",
+                        database.name,
+                        branches.len()
+                    )));
+                },
+                ChildBranchPolicy::Cascade => {
+                    // Collected parents first, so removing them in reverse never orphans a branch.
+                    let mut descendants = branches;
+                    let mut next = 0;
+                    while next < descendants.len() {
+                        let grandchildren = self
+                            .database_repo
+                            .find_children(&descendants[next].id)
+                            .await?;
+                        descendants.extend(grandchildren);
+                        next += 1;
+                    }
+                    // Nothing is removed while the database or any branch is still busy, so a
+                    // refused cascade leaves the tree whole.
+                    if self.backup_repo.has_running(&database.id).await?
+                        || self.operation_repo.has_running(&database.id).await?
+                    {
+                        return Err(AppError::Conflict(format!(
+                            "Database '{}' has a running operation or backup",
+                            database.name
+                        )));
+                    }
+                    for branch in &descendants {
+                        if self.backup_repo.has_running(&branch.id).await?
+                            || self.operation_repo.has_running(&branch.id).await?
+                        {
+                            return Err(AppError::Conflict(format!(
+                                "Branch '{}' has a running operation or backup",
+                                branch.name
+                            )));
+                        }
+                    }
+                    for branch in descendants.iter().rev() {
+                        self.remove_database(branch).await?;
+                        deleted.push(branch.id.clone());
+                    }
+                },
+                ChildBranchPolicy::Reparent => {
+                    let parent_id = database.parent_branch_id.as_deref().ok_or_else(|| {
+                        AppError::Validation(
+                            "The default branch has no parent to move its branches to. Promote a branch first"
+                                .to_string(),
+                        )
+                    })?;
+                    // A replica streams from the database being deleted and cannot follow
+                    // another parent.
+                    if let Some(replica) = branches.iter().find(|branch| branch.is_replica) {
+                        return Err(AppError::Conflict(format!(
+                            "Branch '{}' is a replica of this database. Detach it before re-parenting",
+                            replica.name
+                        )));
+                    }
+                    self.database_repo.reparent_children(id, parent_id).await?;
+                },
+            }
+        }
+
+        self.remove_database(&database).await?;
+        deleted.push(database.id);
+        Ok(deleted)
+    }
+
+    async fn remove_database(&self, database: &Database) -> AppResult<()> {
+        let id = database.id.as_str();
         if let Some(container_id) = &database.container_id {
             if self.docker.container_exists(container_id).await? {
                 let _ = self.docker.stop_container(container_id).await;
                 let _ = self.docker.remove_container(container_id, true).await;
             }
         }
+        if database.is_replica && !engine_for(&database.database_type).is_key_value() {
+            self.revoke_replication(database).await;
+        }
+
+        for snapshot in self.snapshot_repo.find_by_database_id(id).await? {
+            if let Err(e) = self.volumes.remove(&self.snapshot_path(&snapshot)).await {
+                tracing::warn!(
+                    "Failed to remove snapshot {} of database {}: {}",
+                    snapshot.id,
+                    id,
+                    e
+                );
+            }
+        }
 
         let data_path = format!("{}/{}", self.data_dir, id);
-        let _ = std::fs::remove_dir_all(&data_path);
+        if let Err(e) = self.volumes.remove(&data_path).await {
+            tracing::warn!("Failed to remove data directory of database {}: {}", id, e);
+        }
+
+        for backup in self.backup_repo.find_by_database_id(id).await? {
+            if let Err(e) = self.backup_stores.remove_file(&backup).await {
+                tracing::warn!(
+                    "Failed to remove backup {} of database {}: {}",
+                    backup.id,
+                    id,
+                    e
+                );
+            }
+        }
+        let backup_path = format!("{}/backups/{}", self.data_dir, id);
+        let _ = std::fs::remove_dir_all(&backup_path);
+
+        let _ = std::fs::remove_dir_all(self.wal_archive_path(id));
 
         self.database_repo.delete(id).await
     }
@@ -714,11 +939,93 @@ impl DatabaseService {
         self.docker.start_container(container_id).await?;
         self.database_repo.update_status(id, "running").await?;
 
+        // KV servers forget REPLICAOF on restart, PostgreSQL standbys resume on their own.
+        if database.is_replica && engine_for(&database.database_type).is_key_value() {
+            if let Err(e) = self.resume_kv_replication(&database).await {
+                tracing::warn!("Failed to resume replication of {}: {}", id, e);
+            }
+        }
+        if !database.is_replica {
+            let service = self.clone();
+            let container_id = container_id.clone();
+            tokio::spawn(async move {
+                let timeout = engine_for(&database.database_type).startup_timeout_secs();
+                match service
+                    .docker
+                    .wait_for_healthy(&container_id, timeout)
+                    .await
+                {
+                    Ok(true) => service.reinitialize(&database).await,
+                    Ok(false) => {},
+                    Err(e) => tracing::warn!("Failed to wait for database {}: {}", database.id, e),
+                }
+            });
+        }
+
         self.get_by_id_response(id)
             .await?
             .ok_or_else(|| AppError::NotFound(format!("Database '{}' not found", id)))
     }
 
+    /// Runs the engine's setup again on every running database, so databases created before a
+    /// setup step existed get it too.
+    pub async fn initialize_running(&self) -> AppResult<()> {
+        for database in self.database_repo.find_all_running().await? {
+            if !database.is_replica {
+                self.reinitialize(&database).await;
+            }
+        }
+        Ok(())
+    }
+
+    async fn reinitialize(&self, database: &Database) {
+        let password = match database
+            .password_encrypted
+            .as_ref()
+            .map(|p| self.decrypt_password(p))
+        {
+            Some(Ok(password)) => password,
+            Some(Err(e)) => {
+                tracing::warn!("Failed to initialize database {}: {}", database.id, e);
+                return;
+            },
+            None => return,
+        };
+        self.initialize_database(database, &password).await;
+    }
+
+    /// Runs the engine's setup on a database whose server is up. A failure is recorded as a
+    /// failed operation, so it shows in the database's history and not only in the logs.
+    async fn initialize_database(&self, database: &Database, password: &str) {
+        let Err(error) = engine_for(&database.database_type)
+            .initialize(&self.docker, database, password)
+            .await
+        else {
+            return;
+        };
+        tracing::warn!("Failed to initialize database {}: {}", database.id, error);
+
+        let recorded = match self
+            .operation_repo
+            .start(&database.id, INITIALIZE_OPERATION_KIND, None, None)
+            .await
+        {
+            Ok(operation) => {
+                self.operation_repo
+                    .mark_failed(&operation.id, &error.to_string())
+                    .await
+            },
+            Err(e) => Err(e),
+        };
+        if let Err(e) = recorded {
+            tracing::error!(
+                "Failed to record the failed initialization of database {}: {}",
+                database.id,
+                e
+            );
+        }
+    }
+
     pub async fn stop(
         &self,
         id: &str,
@@ -797,7 +1104,7 @@ impl DatabaseService {
             .await?
             .ok_or_else(|| AppError::NotFound(format!("Database '{}' not found", database_id)))?;
 
-        if database.database_type != "redis" && database.database_type != "valkey" {
+        if !engine_for(&database.database_type).is_key_value() {
             return Err(AppError::Validation(
                 "KV commands are only supported for Redis or Valkey databases".to_string(),
             ));
@@ -852,11 +1159,7 @@ impl DatabaseService {
             ));
         }
 
-        let cli = if database.database_type == "valkey" {
-            "valkey-cli"
-        } else {
-            "redis-cli"
-        };
+        let cli = engine_for(&database.database_type).cli_name();
 
         let mut cmd = vec![
             cli.to_string(),
@@ -897,6 +1200,115 @@ impl DatabaseService {
         })
     }
 
+    pub async fn execute_document_query(
+        &self,
+        database_id: &str,
+        user_id: &str,
+        is_admin: bool,
+        request: &ExecuteDocumentQueryRequest,
+    ) -> AppResult<DocumentQueryResult> {
+        if !self.check_access(database_id, user_id, is_admin).await? {
+            return Err(AppError::Forbidden);
+        }
+
+        let database = self
+            .database_repo
+            .find_by_id(database_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("Database '{}' not found", database_id)))?;
+
+        if !engine_for(&database.database_type).supports_document_queries() {
+            return Err(AppError::Validation(
+                "Document queries are only supported for MongoDB databases".to_string(),
+            ));
+        }
+
+        let container_id = database
+            .container_id
+            .clone()
+            .ok_or_else(|| AppError::NotFound("Database has no container".to_string()))?;
+
+        let status = self.docker.get_container_status(&container_id).await?;
+        if status != "running" {
+            return Err(AppError::Conflict(format!(
+                "Container is not running (status: {})",
+                status
+            )));
+        }
+
+        let password = database
+            .password_encrypted
+            .as_ref()
+            .and_then(|p| self.decrypt_password(p).ok())
+            .ok_or_else(|| AppError::Internal("No password stored for database".to_string()))?;
+
+        let limit = request
+            .limit
+            .unwrap_or(DEFAULT_DOCUMENT_LIMIT)
+            .clamp(1, MAX_DOCUMENT_LIMIT);
+        let timeout = request.timeout_ms.unwrap_or(5000).clamp(1000, 60000);
+        let script = document_query_script(request, limit, timeout)?;
+
+        let mut cmd = MongoContainer::shell_command();
+        cmd.extend(["--eval".to_string(), script]);
+
+        // `maxTimeMS` stops the query on the server; the extra second covers starting mongosh.
+        let start = std::time::Instant::now();
+        let output = tokio::time::timeout(
+            Duration::from_millis(timeout as u64 + 1000),
+            self.docker.run_exec(
+                &container_id,
+                cmd,
+                Some(MongoContainer::shell_env(&password)),
+            ),
+        )
+        .await
+        .map_err(|_| AppError::Validation("Query timed out".to_string()))??;
+        let execution_time_ms = start.elapsed().as_secs_f64() * 1000.0;
+
+        if output.exit_code != Some(0) {
+            let message = if output.stderr.trim().is_empty() {
+                output.stdout.trim()
+            } else {
+                output.stderr.trim()
+            };
+            return Err(AppError::Docker(format!("Query failed: {}", message)));
+        }
+
+        let mut documents: Vec<serde_json::Value> = serde_json::from_str(output.stdout.trim())
+            .map_err(|e| AppError::Internal(format!("Invalid query output: {}", e)))?;
+        let truncated = documents.len() > limit as usize;
+        documents.truncate(limit as usize);
+
+        Ok(DocumentQueryResult {
+            count: documents.len() as i64,
+            documents,
+            execution_time_ms,
+            truncated,
+        })
+    }
+
+    /// The whole branch tree the database belongs to, starting at its root.
+    pub async fn get_branch_tree(
+        &self,
+        database_id: &str,
+        user_id: &str,
+        is_admin: bool,
+    ) -> AppResult<BranchTreeNode> {
+        if !self.check_access(database_id, user_id, is_admin).await? {
+            return Err(AppError::Forbidden);
+        }
+
+        let root = self
+            .database_repo
+            .find_root_database(database_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("Database '{}' not found", database_id)))?;
+        let databases = self.database_repo.find_tree(&root.id).await?;
+
+        Ok(BranchTreeNode::build(&root, &databases))
+    }
+
     pub async fn list_branches(
         &self,
         database_id: &str,
@@ -914,14 +1326,21 @@ impl DatabaseService {
             .collect())
     }
 
+    /// Validates the request and creates the branch record, then provisions the container and
+    /// copies data in the background. The returned operation is on the new branch, so it does
+    /// not block operations on the parent.
+    #[allow(clippy::too_many_arguments)]
     pub async fn create_branch(
         &self,
         database_id: &str,
         user_id: &str,
         is_admin: bool,
         branch_name: &str,
-        include_data: bool,
-    ) -> AppResult<DatabaseResponse> {
+        data: &BranchDataOptions,
+        expires_at: Option<&str>,
+        ttl_seconds: Option<i64>,
+    ) -> AppResult<OperationResponse> {
+        let expires_at = branch_expiry(expires_at, ttl_seconds)?;
         let source = self
             .database_repo
             .find_by_id(database_id)
@@ -936,9 +1355,927 @@ impl DatabaseService {
         {
             return Err(AppError::Forbidden);
         }
+        validate_data_options(data, engine_for(&source.database_type))?;
+        if data.mode == BranchDataMode::Replica {
+            self.check_replica_source(&source).await?;
+        }
+        if data.mode != BranchDataMode::None {
+            self.check_unmasked_copy(&source).await?;
+            // Without a running parent the data can only come from a snapshot of its volume.
+            let engine = engine_for(&source.database_type);
+            let snapshottable = !data.is_partial()
+                && engine.supports_snapshots()
+                && self.volumes.supports_snapshots()
+                && source.container_status == "stopped";
+            if source.container_status != "running" && !snapshottable {
+                return Err(AppError::Validation(
+                    "The parent must be running to copy its data into a branch".to_string(),
+                ));
+            }
+        }
 
-        if branch_name.trim().is_empty() {
-            return Err(AppError::Validation(
+        let branch = self.create_branch_record(&source, branch_name).await?;
+        if let Some(expires_at) = &expires_at {
+            self.database_repo
+                .set_expires_at(&branch.id, Some(expires_at))
+                .await?;
+        }
+
+        let operation = self
+            .operation_repo
+            .start(&branch.id, BRANCH_OPERATION_KIND, None, None)
+            .await?;
+
+        let service = self.clone();
+        let running = operation.clone();
+        let data = data.clone();
+        tokio::spawn(async move {
+            let branch_id = branch.id.clone();
+            let result = service
+                .populate_branch(&source, branch, &data, &running)
+                .await;
+
+            if result.is_err() {
+                if let Err(e) = service
+                    .database_repo
+                    .update_status(&branch_id, "error")
+                    .await
+                {
+                    tracing::error!("Failed to update status of branch {}: {}", branch_id, e);
+                }
+            }
+
+            service.operation_repo.finish(&running, result).await;
+        });
+
+        Ok(operation.to_response())
+    }
+
+    async fn populate_branch(
+        &self,
+        source: &Database,
+        branch: Database,
+        data: &BranchDataOptions,
+        operation: &Operation,
+    ) -> AppResult<()> {
+        if data.mode == BranchDataMode::Replica {
+            return self.create_replica(source, branch, operation).await;
+        }
+
+        let engine = engine_for(&source.database_type);
+        let include_data = data.mode != BranchDataMode::None;
+        let snapshotted = include_data
+            && !data.is_partial()
+            && self
+                .snapshot_branch_data(source, &branch, &self.data_path(&branch.id))
+                .await;
+
+        self.record_phase(operation, "pulling_image").await;
+        self.docker.pull_image(&engine.image(source)).await?;
+        self.record_phase(operation, "starting_container").await;
+        let (branch, password) = self.provision_branch_container(source, branch).await?;
+        let copied = snapshotted || (include_data && source.container_status == "running");
+        if include_data && !copied {
+            return Err(AppError::Conflict(
+                "The parent is not running and its data could not be snapshotted".to_string(),
+            ));
+        }
+
+        if snapshotted {
+            self.adopt_snapshot(source, &branch, &password).await?;
+        } else if copied {
+            self.record_phase(operation, "copying_data").await;
+            let source_password = source
+                .password_encrypted
+                .as_ref()
+                .map(|p| self.decrypt_password(p))
+                .transpose()?
+                .ok_or_else(|| AppError::Internal("Source database has no password".to_string()))?;
+
+            tokio::time::sleep(tokio::time::Duration::from_secs(2)).await;
+
+            if engine.supports_table_filters() && data.is_partial() {
+                self.fork_partial(source, &branch, &source_password, &password, data)
+                    .await?
+            } else {
+                self.fork_with_progress(
+                    operation,
+                    source,
+                    &source_password,
+                    &branch,
+                    &password,
+                    data.mode == BranchDataMode::SchemaOnly,
+                )
+                .await?
+            }
+        }
+        if copied && engine.supports_masking() {
+            self.record_phase(operation, "masking").await;
+            self.mask_branch_data(&branch).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Forks data from `source` into `target` through the engine, recording the copied size on
+    /// `operation` as it grows and moving it to the `restoring` phase once the source is read.
+    async fn fork_with_progress(
+        &self,
+        operation: &Operation,
+        source: &Database,
+        source_password: &str,
+        target: &Database,
+        target_password: &str,
+        schema_only: bool,
+    ) -> AppResult<()> {
+        let progress = ForkProgress::default();
+        let spec = ForkSpec {
+            source,
+            target,
+            source_password,
+            target_password,
+            schema_only,
+            progress: Some(&progress),
+        };
+        let fork = engine_for(&source.database_type).fork(&self.docker, &spec);
+        tokio::pin!(fork);
+        let mut ticker = tokio::time::interval(PROGRESS_INTERVAL);
+        let mut restoring = false;
+
+        loop {
+            tokio::select! {
+                result = &mut fork => {
+                    self.record_progress(operation, progress.bytes() as i64).await;
+                    return result;
+                },
+                _ = ticker.tick() => {
+                    self.record_progress(operation, progress.bytes() as i64).await;
+                    if !restoring && progress.dumped() {
+                        restoring = true;
+                        self.record_phase(operation, "restoring").await;
+                    }
+                },
+            }
+        }
+    }
+
+    async fn record_phase(&self, operation: &Operation, phase: &str) {
+        if let Err(e) = self.operation_repo.set_phase(&operation.id, phase).await {
+            tracing::warn!(
+                "Failed to record phase of operation {}: {}",
+                operation.id,
+                e
+            );
+        }
+    }
+
+    async fn record_progress(&self, operation: &Operation, progress_bytes: i64) {
+        if let Err(e) = self
+            .operation_repo
+            .update_progress(&operation.id, progress_bytes, None)
+            .await
+        {
+            tracing::warn!(
+                "Failed to record progress of operation {}: {}",
+                operation.id,
+                e
+            );
+        }
+    }
+
+    async fn check_replica_source(&self, source: &Database) -> AppResult<()> {
+        let engine = engine_for(&source.database_type);
+        if !engine.supports_replicas() {
+            return Err(AppError::Validation(format!(
+                "Replica branches are not available for {} databases",
+                engine.display_name()
+            )));
+        }
+        if source.container_status != "running" {
+            return Err(AppError::Validation(
+                "The parent must be running to create a replica".to_string(),
+            ));
+        }
+        if engine.supports_masking() {
+            let project = self
+                .project_repo
+                .find_by_id(&source.project_id)
+                .await?
+                .ok_or_else(|| AppError::NotFound("Project not found".to_string()))?;
+            if !MaskingConfig::from_project_settings(&project.settings_json())?.is_empty() {
+                return Err(AppError::Validation(
+                    "Replicas copy data unmasked and cannot be created while the project has masking rules"
+                        .to_string(),
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Masking rules are only applied to PostgreSQL, so data of other engines with tables or
+    /// collections is not copied into branches of projects that have rules. A running
+    /// PostgreSQL source is checked for rules its column types cannot take, before anything is
+    /// copied and then dropped again.
+    pub async fn check_unmasked_copy(&self, source: &Database) -> AppResult<()> {
+        let engine = engine_for(&source.database_type);
+        if engine.is_key_value() {
+            return Ok(());
+        }
+        let project = self
+            .project_repo
+            .find_by_id(&source.project_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Project not found".to_string()))?;
+        let config = MaskingConfig::from_project_settings(&project.settings_json())?;
+        if engine.supports_masking() {
+            let check = masking_type_check(&config);
+            if let (Some(check), Some(container_id), "running") = (
+                check,
+                source.container_id.as_deref(),
+                source.container_status.as_str(),
+            ) {
+                self.run_postgres_sql(source, container_id, &source.username, &[&check])
+                    .await
+                    .map_err(|e| AppError::Validation(e.to_string()))?;
+            }
+            return Ok(());
+        }
+        if !config.is_empty() {
+            return Err(AppError::Validation(format!(
+                "Masking rules only apply to PostgreSQL, so {} data cannot be copied into branches while the project has masking rules",
+                engine.display_name()
+            )));
+        }
+        Ok(())
+    }
+
+    /// Creates a branch that keeps following its parent: a streaming standby for PostgreSQL,
+    /// which shares the parent's credentials, or a KV replica.
+    async fn create_replica(
+        &self,
+        source: &Database,
+        branch: Database,
+        operation: &Operation,
+    ) -> AppResult<()> {
+        self.database_repo.set_replica(&branch.id, true).await?;
+        self.record_phase(operation, "starting_container").await;
+        let (branch, password) = self.provision_branch_container(source, branch).await?;
+        let source_password = source
+            .password_encrypted
+            .as_ref()
+            .map(|p| self.decrypt_password(p))
+            .transpose()?
+            .ok_or_else(|| AppError::Internal("Source database has no password".to_string()))?;
+
+        self.record_phase(operation, "copying_data").await;
+        if engine_for(&branch.database_type).is_key_value() {
+            self.docker
+                .replicate_kv_database(
+                    &source.container_name(),
+                    &branch.container_name(),
+                    &source_password,
+                    &password,
+                    engine_for(&branch.database_type).cli_name(),
+                    false,
+                )
+                .await?;
+        } else {
+            self.start_postgres_standby(source, &branch).await?;
+            if let Some(encrypted) = &source.password_encrypted {
+                self.database_repo
+                    .update_password(&branch.id, encrypted)
+                    .await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Replaces the branch's freshly initialized data directory with a base backup of the
+    /// parent, configured to stream from it through a replication slot. The parent only lets
+    /// the branch's own replication role connect for replication, and caps the WAL its slots
+    /// may hold back unless a cap is already set.
+    async fn start_postgres_standby(&self, source: &Database, branch: &Database) -> AppResult<()> {
+        let source_container_id = source
+            .container_id
+            .as_deref()
+            .ok_or_else(|| AppError::Internal("Source database has no container".to_string()))?;
+        let container_id = branch
+            .container_id
+            .as_deref()
+            .ok_or_else(|| AppError::Internal("Branch has no container".to_string()))?;
+
+        let role = replication_role(&branch.id);
+        let replication_password = generate_password();
+        self.run_postgres_sql(
+            source,
+            source_container_id,
+            &source.username,
+            &[&format!(
+                "DO $$ BEGIN \
+                 IF EXISTS (SELECT 1 FROM pg_roles WHERE rolname = '{role}') THEN \
+                 ALTER ROLE \"{role}\" WITH REPLICATION LOGIN PASSWORD '{replication_password}'; \
+                 ELSE CREATE ROLE \"{role}\" WITH REPLICATION LOGIN PASSWORD '{replication_password}'; \
+                 END IF; END $$"
+            )],
+        )
+        .await?;
+
+        let output = self
+            .docker
+            .run_exec(
+                source_container_id,
+                vec![
+                    "sh".to_string(),
+                    "-c".to_string(),
+                    allow_replication_script(&role),
+                ],
+                None,
+            )
+            .await?;
+        if output.exit_code != Some(0) {
+            return Err(AppError::Docker(format!(
+                "Failed to allow replication on the parent: {}",
+                output.stderr.trim()
+            )));
+        }
+
+        let uncapped = self
+            .run_postgres_sql(
+                source,
+                source_container_id,
+                &source.username,
+                &["SELECT current_setting('max_slot_wal_keep_size', true) = '-1'"],
+            )
+            .await?;
+        let cap = (uncapped.trim() == "t").then(|| {
+            format!(
+                "ALTER SYSTEM SET max_slot_wal_keep_size = '{}MB'",
+                slot_wal_keep_mb(source.storage_limit_mb)
+            )
+        });
+        let mut statements: Vec<&str> = cap.iter().map(String::as_str).collect();
+        statements.push("SELECT pg_reload_conf()");
+        self.run_postgres_sql(source, source_container_id, &source.username, &statements)
+            .await?;
+
+        self.docker.stop_container(container_id).await?;
+
+        let image = engine_for(&source.database_type).image(source);
+        let binds = vec![format!(
+            "{}/{}:{}",
+            self.data_dir,
+            branch.id,
+            PostgresContainer::get_mount_point(&image)
+        )];
+        let script = format!(
+            r#"set -e
+mkdir -p "$PGDATA"
+find "$PGDATA" -mindepth 1 -delete
+PGPASSWORD="$REPLICATION_PASSWORD" pg_basebackup -h {} -U {} -D "$PGDATA" -X stream -R -C -S {}
+chown -R postgres:postgres "$PGDATA"
+chmod 700 "$PGDATA""#,
+            source.container_name(),
+            role,
+            replication_slot(&branch.id)
+        );
+        let output = self
+            .docker
+            .run_networked_oneoff_container(
+                &format!("datify-standby-{}", branch.id),
+                &image,
+                binds,
+                &script,
+                vec![format!("REPLICATION_PASSWORD={}", replication_password)],
+            )
+            .await?;
+        if output.exit_code != Some(0) {
+            return Err(AppError::Docker(format!(
+                "Base backup of the parent failed: {}",
+                output.stderr.trim()
+            )));
+        }
+
+        self.docker.start_container(container_id).await?;
+        if !self.docker.wait_for_healthy(container_id, 120).await? {
+            return Err(AppError::Docker(
+                "Replica did not become healthy".to_string(),
+            ));
+        }
+
+        tracing::info!(
+            "Branch {} is streaming from database {}",
+            branch.id,
+            source.id
+        );
+        Ok(())
+    }
+
+    /// Turns a replica branch into an independent, writable branch. PostgreSQL replicas get
+    /// their own password and go through the project's masking rules.
+    pub async fn detach_replica(
+        &self,
+        database_id: &str,
+        user_id: &str,
+        is_admin: bool,
+    ) -> AppResult<DatabaseResponse> {
+        let branch = self
+            .database_repo
+            .find_by_id(database_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("Database '{}' not found", database_id)))?;
+
+        if !is_admin
+            && !self
+                .project_repo
+                .is_owner(&branch.project_id, user_id)
+                .await?
+        {
+            return Err(AppError::Forbidden);
+        }
+
+        if !branch.is_replica {
+            return Err(AppError::Validation(
+                "Database is not a replica".to_string(),
+            ));
+        }
+        if branch.container_status != "running" {
+            return Err(AppError::Validation(
+                "Replica must be running to detach".to_string(),
+            ));
+        }
+        let container_id = branch
+            .container_id
+            .as_deref()
+            .ok_or_else(|| AppError::Internal("Branch has no container".to_string()))?;
+        let password = branch
+            .password_encrypted
+            .as_ref()
+            .map(|p| self.decrypt_password(p))
+            .transpose()?
+            .ok_or_else(|| AppError::Internal("Branch database has no password".to_string()))?;
+
+        if engine_for(&branch.database_type).is_key_value() {
+            self.docker
+                .promote_kv_replica(
+                    &branch.container_name(),
+                    &password,
+                    engine_for(&branch.database_type).cli_name(),
+                )
+                .await?;
+        } else {
+            self.run_postgres_sql(
+                &branch,
+                container_id,
+                &branch.username,
+                &["SELECT pg_promote(true, 60)"],
+            )
+            .await?;
+
+            let new_password = generate_password();
+            let statements = detach_standby_statements(&branch.username, &new_password);
+            let statements: Vec<&str> = statements.iter().map(String::as_str).collect();
+            self.run_postgres_sql(&branch, container_id, &branch.username, &statements)
+                .await?;
+            let encrypted = self.encrypt_password(&new_password)?;
+            self.database_repo
+                .update_password(&branch.id, &encrypted)
+                .await?;
+            self.revoke_replication(&branch).await;
+        }
+
+        self.database_repo.set_replica(&branch.id, false).await?;
+        self.database_repo.update_forked_at(&branch.id).await?;
+        let branch = self
+            .database_repo
+            .find_by_id(&branch.id)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("Database '{}' not found", database_id)))?;
+        self.mask_branch_data(&branch).await?;
+
+        self.get_by_id_response(database_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("Database '{}' not found", database_id)))
+    }
+
+    /// Drops the slot a PostgreSQL replica streamed through, so the parent stops keeping WAL
+    /// for it, along with the replica's role and its `pg_hba.conf` rule.
+    async fn revoke_replication(&self, branch: &Database) {
+        let Some(parent_id) = branch.parent_branch_id.as_deref() else {
+            return;
+        };
+        let Ok(Some(parent)) = self.database_repo.find_by_id(parent_id).await else {
+            return;
+        };
+        let Some(container_id) = parent.container_id.as_deref() else {
+            return;
+        };
+        let role = replication_role(&branch.id);
+
+        if let Err(e) = self
+            .docker
+            .run_exec(
+                container_id,
+                vec![
+                    "sh".to_string(),
+                    "-c".to_string(),
+                    revoke_replication_script(&role),
+                ],
+                None,
+            )
+            .await
+        {
+            tracing::warn!(
+                "Failed to remove the replication rule of branch {}: {}",
+                branch.id,
+                e
+            );
+        }
+
+        let statements = revoke_replication_statements(&branch.id);
+        let statements: Vec<&str> = statements.iter().map(String::as_str).collect();
+        if let Err(e) = self
+            .run_postgres_sql(&parent, container_id, &parent.username, &statements)
+            .await
+        {
+            tracing::warn!(
+                "Failed to drop replication slot of branch {}: {}",
+                branch.id,
+                e
+            );
+        }
+    }
+
+    /// Copies a filtered or sampled subset of a PostgreSQL parent into a new branch: the schema
+    /// first, then each table's sampled rows, then indexes. Rows whose foreign keys point at rows
+    /// that were not sampled are deleted before the foreign keys are added.
+    async fn fork_partial(
+        &self,
+        source: &Database,
+        branch: &Database,
+        source_password: &str,
+        password: &str,
+        data: &BranchDataOptions,
+    ) -> AppResult<()> {
+        let container_id = branch
+            .container_id
+            .as_deref()
+            .ok_or_else(|| AppError::Internal("Branch has no container".to_string()))?;
+        let source_host = source.container_name();
+        let filters = dump_filter_args(data);
+        let env = |extra: &[(&str, &str)]| {
+            let mut env = vec![
+                format!("SOURCE_PASSWORD={}", source_password),
+                format!("TARGET_PASSWORD={}", password),
+                "PGCONNECT_TIMEOUT=10".to_string(),
+            ];
+            env.extend(extra.iter().map(|(k, v)| format!("{}={}", k, v)));
+            env
+        };
+
+        let mut pre_data = vec!["--section=pre-data"];
+        pre_data.extend(filters.iter().map(String::as_str));
+        self.docker
+            .fork_database_with(
+                &source_host,
+                &branch.container_name(),
+                &source.username,
+                source_password,
+                &branch.username,
+                password,
+                &pre_data,
+                None,
+            )
+            .await?;
+
+        let relations = self
+            .psql_lines(
+                container_id,
+                None,
+                &branch.username,
+                password,
+                &[RELATIONS_QUERY],
+            )
+            .await?;
+        let relations: Vec<(&str, &str)> = relations
+            .iter()
+            .filter_map(|line| line.split_once('\t'))
+            .collect();
+        let present = |name: &str| relations.iter().any(|(_, n)| *n == name);
+
+        if data.mode == BranchDataMode::Full {
+            let script = copy_table_script(&source_host, &source.username, &branch.username);
+            for (_, table) in relations.iter().filter(|(kind, _)| *kind == "r") {
+                let copy_out = sample_copy_query(table, data);
+                let copy_in = format!("COPY {} FROM STDIN", table);
+                let output = self
+                    .docker
+                    .run_exec(
+                        container_id,
+                        vec!["sh".to_string(), "-c".to_string(), script.clone()],
+                        Some(env(&[("COPY_OUT", &copy_out), ("COPY_IN", &copy_in)])),
+                    )
+                    .await?;
+                if output.exit_code != Some(0) {
+                    return Err(AppError::Docker(format!(
+                        "Copying {} failed: {}",
+                        table,
+                        output.stderr.trim()
+                    )));
+                }
+            }
+
+            let sequences = self
+                .psql_lines(
+                    container_id,
+                    Some(&source_host),
+                    &source.username,
+                    source_password,
+                    &[SEQUENCES_QUERY],
+                )
+                .await?;
+            let setvals: Vec<&str> = sequences
+                .iter()
+                .filter_map(|line| line.split_once('\t'))
+                .filter(|(sequence, _)| present(sequence))
+                .map(|(_, setval)| setval)
+                .collect();
+            if !setvals.is_empty() {
+                self.psql_lines(container_id, None, &branch.username, password, &setvals)
+                    .await?;
+            }
+        }
+
+        let script = post_data_script(&source_host, &source.username, &branch.username, &filters);
+        let output = self
+            .docker
+            .run_exec(
+                container_id,
+                vec!["sh".to_string(), "-c".to_string(), script],
+                Some(env(&[])),
+            )
+            .await?;
+        if output.exit_code != Some(0) {
+            return Err(AppError::Docker(format!(
+                "Restoring indexes failed: {}",
+                output.stderr.trim()
+            )));
+        }
+
+        let foreign_keys = self
+            .psql_lines(
+                container_id,
+                Some(&source_host),
+                &source.username,
+                source_password,
+                &[FOREIGN_KEYS_QUERY],
+            )
+            .await?;
+        let foreign_keys: Vec<Vec<&str>> = foreign_keys
+            .iter()
+            .map(|line| line.split('\t').collect::<Vec<_>>())
+            .filter(|fields| fields.len() == 4 && present(fields[0]) && present(fields[1]))
+            .collect();
+        if foreign_keys.is_empty() {
+            return Ok(());
+        }
+
+        // Deleting orphans can orphan rows of tables further down, so repeat until none are left.
+        let deletes: Vec<&str> = foreign_keys.iter().map(|fields| fields[2]).collect();
+        for _ in 0..MAX_ORPHAN_PASSES {
+            let output = self
+                .psql_lines(container_id, None, &branch.username, password, &deletes)
+                .await?;
+            let deleted: i64 = output
+                .iter()
+                .filter_map(|line| line.strip_prefix("DELETE "))
+                .filter_map(|count| count.trim().parse::<i64>().ok())
+                .sum();
+            if deleted == 0 {
+                break;
+            }
+            tracing::debug!("Deleted {} orphaned rows in branch {}", deleted, branch.id);
+        }
+
+        let constraints: Vec<&str> = foreign_keys.iter().map(|fields| fields[3]).collect();
+        self.psql_lines(container_id, None, &branch.username, password, &constraints)
+            .await?;
+
+        tracing::info!(
+            "Copied a partial fork of database {} into branch {}",
+            source.id,
+            branch.id
+        );
+        Ok(())
+    }
+
+    /// Runs statements with psql in `container_id`, against `host` or the container's own
+    /// server, and returns the unaligned, tab-separated output lines.
+    async fn psql_lines(
+        &self,
+        container_id: &str,
+        host: Option<&str>,
+        username: &str,
+        password: &str,
+        statements: &[&str],
+    ) -> AppResult<Vec<String>> {
+        let mut cmd = vec![
+            "psql".to_string(),
+            "-h".to_string(),
+            host.unwrap_or("localhost").to_string(),
+            "-U".to_string(),
+            username.to_string(),
+            "-d".to_string(),
+            "postgres".to_string(),
+            "-At".to_string(),
+            "-F".to_string(),
+            "\t".to_string(),
+            "-v".to_string(),
+            "ON_ERROR_STOP=1".to_string(),
+        ];
+        for statement in statements {
+            cmd.push("-c".to_string());
+            cmd.push(statement.to_string());
+        }
+
+        let env = vec![
+            format!("PGPASSWORD={}", password),
+            "PGCONNECT_TIMEOUT=10".to_string(),
+        ];
+        let output = self.docker.run_exec(container_id, cmd, Some(env)).await?;
+        if output.exit_code != Some(0) {
+            return Err(AppError::Docker(format!(
+                "psql failed: {}",
+                output.stderr.trim()
+            )));
+        }
+
+        Ok(output.stdout.lines().map(str::to_string).collect())
+    }
+
+    /// Takes a copy-on-write snapshot of a PostgreSQL parent's data directory at `target_path`,
+    /// the branch's data directory or a staging path next to it. Returns false when the branch
+    /// has to be filled with a logical copy.
+    async fn snapshot_branch_data(
+        &self,
+        source: &Database,
+        branch: &Database,
+        target_path: &str,
+    ) -> bool {
+        if !self.volumes.supports_snapshots()
+            || !engine_for(&source.database_type).supports_snapshots()
+        {
+            return false;
+        }
+        let Some(container_id) = source.container_id.as_deref() else {
+            return false;
+        };
+        let running = match source.container_status.as_str() {
+            "running" => true,
+            "stopped" => false,
+            _ => return false,
+        };
+
+        let source_path = self.data_path(&source.id);
+        if let Err(e) = self
+            .take_snapshot(source, container_id, running, &source_path, target_path)
+            .await
+        {
+            tracing::warn!(
+                "Snapshot of database {} failed, copying data instead: {}",
+                source.id,
+                e
+            );
+            let _ = self.volumes.remove(target_path).await;
+            return false;
+        }
+
+        // The copied lock file belongs to the parent's server.
+        remove_pid_files(source, target_path).await;
+
+        tracing::info!(
+            "Created branch {} from a {} snapshot of database {}",
+            branch.id,
+            self.volumes.kind(),
+            source.id
+        );
+        true
+    }
+
+    /// Checkpoints first so the branch replays little WAL on startup. Snapshots that are not
+    /// atomic are taken with the parent paused, which gives the same crash-consistent state.
+    async fn take_snapshot(
+        &self,
+        source: &Database,
+        container_id: &str,
+        running: bool,
+        source_path: &str,
+        target_path: &str,
+    ) -> AppResult<()> {
+        if running {
+            self.run_postgres_sql(source, container_id, &source.username, &["CHECKPOINT"])
+                .await?;
+        }
+
+        let pause = running && !self.volumes.atomic_snapshots();
+        if pause {
+            self.docker.pause_container(container_id).await?;
+        }
+        let result = self.volumes.snapshot(source_path, target_path).await;
+        if pause {
+            if let Err(e) = self.docker.unpause_container(container_id).await {
+                tracing::error!("Failed to unpause database {}: {}", source.id, e);
+            }
+        }
+
+        result
+    }
+
+    /// A snapshot carries the parent's roles and settings: give the branch user its own
+    /// password and drop a storage-limit write block inherited from the parent.
+    async fn adopt_snapshot(
+        &self,
+        source: &Database,
+        branch: &Database,
+        password: &str,
+    ) -> AppResult<()> {
+        let container_id = branch
+            .container_id
+            .as_deref()
+            .ok_or_else(|| AppError::Internal("Branch has no container".to_string()))?;
+        let role = format!("\"{}\"", branch.username.replace('"', "\"\""));
+        let password = password.replace('\'', "''");
+        let set_password = if branch.username == source.username {
+            format!("ALTER ROLE {} WITH PASSWORD '{}'", role, password)
+        } else {
+            format!(
+                "CREATE ROLE {} WITH SUPERUSER LOGIN PASSWORD '{}'",
+                role, password
+            )
+        };
+
+        self.run_postgres_sql(
+            branch,
+            container_id,
+            &source.username,
+            &[
+                &set_password,
+                "ALTER SYSTEM RESET default_transaction_read_only",
+                "SELECT pg_reload_conf()",
+            ],
+        )
+        .await?;
+        Ok(())
+    }
+
+    async fn run_postgres_sql(
+        &self,
+        database: &Database,
+        container_id: &str,
+        username: &str,
+        statements: &[&str],
+    ) -> AppResult<String> {
+        let mut cmd = vec![
+            "psql".to_string(),
+            "-U".to_string(),
+            username.to_string(),
+            "-d".to_string(),
+            "postgres".to_string(),
+            "-v".to_string(),
+            "ON_ERROR_STOP=1".to_string(),
+            "-qtA".to_string(),
+        ];
+        for statement in statements {
+            cmd.push("-c".to_string());
+            cmd.push(statement.to_string());
+        }
+
+        let env = database
+            .password_encrypted
+            .as_ref()
+            .map(|p| self.decrypt_password(p))
+            .transpose()?
+            .map(|password| vec![format!("PGPASSWORD={}", password)]);
+
+        let output = self.docker.run_exec(container_id, cmd, env).await?;
+        if output.exit_code != Some(0) {
+            return Err(AppError::Docker(format!(
+                "psql failed on database {}: {}",
+                database.id,
+                output.stderr.trim()
+            )));
+        }
+
+        Ok(output.stdout)
+    }
+
+    pub async fn create_branch_record(
+        &self,
+        source: &Database,
+        branch_name: &str,
+    ) -> AppResult<Database> {
+        if branch_name.trim().is_empty() {
+            return Err(AppError::Validation(
                 "Branch name cannot be empty".to_string(),
             ));
         }
@@ -959,7 +2296,7 @@ impl DatabaseService {
             ));
         }
 
-        let branches = self.database_repo.find_branches(database_id).await?;
+        let branches = self.database_repo.find_branches(&source.id).await?;
         if branches.iter().any(|b| b.branch_name == branch_name) {
             return Err(AppError::AlreadyExists(format!(
                 "Branch '{}' already exists",
@@ -981,27 +2318,53 @@ impl DatabaseService {
             )));
         }
 
-        let branch = self
+        let mut branch = self
             .database_repo
             .create(
                 &source.project_id,
                 &db_name,
                 &source.database_type,
-                &source.postgres_version,
-                source.valkey_version.as_deref(),
-                source.redis_version.as_deref(),
+                &source.version,
                 source.cpu_limit,
                 source.memory_limit_mb,
                 source.storage_limit_mb,
                 false,
                 branch_name,
                 false,
-                Some(database_id),
+                Some(&source.id),
             )
             .await?;
 
+        if let Some(persistence) = &source.persistence {
+            self.database_repo
+                .update_persistence(&branch.id, persistence)
+                .await?;
+            branch.persistence = Some(persistence.clone());
+        }
+        if source.postgres_variant.is_some() {
+            self.database_repo
+                .update_postgres_variant(&branch.id, source.postgres_variant.as_deref())
+                .await?;
+            branch.postgres_variant = source.postgres_variant.clone();
+        }
+        if let Some(libraries) = &source.shared_preload_libraries {
+            self.database_repo
+                .update_preload_libraries(&branch.id, libraries)
+                .await?;
+            branch.shared_preload_libraries = Some(libraries.clone());
+        }
+
+        Ok(branch)
+    }
+
+    pub async fn provision_branch_container(
+        &self,
+        source: &Database,
+        branch: Database,
+    ) -> AppResult<(Database, String)> {
         let password = generate_password();
-        let internal_port = Self::internal_port_for_type(&branch.database_type);
+        let engine = engine_for(&source.database_type);
+        let internal_port = engine.internal_port() as i32;
         let port = if branch.public_exposed {
             self.database_repo.get_next_available_port().await?
         } else {
@@ -1012,156 +2375,561 @@ impl DatabaseService {
         } else {
             None
         };
-        let container_name = branch.container_name();
         let data_path = format!("{}/{}", self.data_dir, branch.id);
+        self.volumes.create(&data_path).await?;
+
+        let config = container_config(
+            engine,
+            &branch,
+            branch.container_name(),
+            engine.image(source),
+            data_path,
+            exposed_port,
+        );
+        let container_id = engine
+            .create_container(&self.docker, config, &password)
+            .await?;
+        self.docker.start_container(&container_id).await?;
 
-        std::fs::create_dir_all(&data_path)
-            .map_err(|e| AppError::Internal(format!("Failed to create data directory: {}", e)))?;
+        let healthy = self
+            .docker
+            .wait_for_healthy(&container_id, engine.startup_timeout_secs())
+            .await?;
+        if !healthy {
+            return Err(AppError::Internal(
+                "Branch container failed to start".to_string(),
+            ));
+        }
 
-        let container_id = match source.database_type.as_str() {
-            "redis" => {
-                let version = source.redis_version.as_deref().unwrap_or("7.4");
-                let config = ContainerConfig {
-                    name: container_name.clone(),
-                    image: format!("redis:{}-alpine", version),
-                    env: vec![],
-                    data_path,
-                    cpu_limit: source.cpu_limit,
-                    memory_limit_mb: source.memory_limit_mb as i64,
-                    internal_port: internal_port as u16,
-                    exposed_port,
-                    cmd: None,
-                };
-                self.docker
-                    .create_redis_container(config, &password)
-                    .await?
-            },
-            "valkey" => {
-                let version = source.valkey_version.as_deref().unwrap_or("8.0");
-                let config = ContainerConfig {
-                    name: container_name.clone(),
-                    image: format!("valkey/valkey:{}-alpine", version),
-                    env: vec![],
-                    data_path,
-                    cpu_limit: source.cpu_limit,
-                    memory_limit_mb: source.memory_limit_mb as i64,
-                    internal_port: internal_port as u16,
-                    exposed_port,
-                    cmd: None,
-                };
-                self.docker
-                    .create_valkey_container(config, &password)
-                    .await?
-            },
-            _ => {
-                let config = ContainerConfig {
-                    name: container_name.clone(),
-                    image: format!("postgres:{}", source.postgres_version),
-                    env: vec![
-                        format!("POSTGRES_USER={}", branch.username),
-                        "POSTGRES_DB=postgres".to_string(),
-                    ],
-                    data_path,
-                    cpu_limit: source.cpu_limit,
-                    memory_limit_mb: source.memory_limit_mb as i64,
-                    internal_port: internal_port as u16,
-                    exposed_port,
-                    cmd: Some(vec![
-                        "postgres".to_string(),
-                        "-c".to_string(),
-                        "shared_preload_libraries=pg_stat_statements".to_string(),
-                        "-c".to_string(),
-                        "pg_stat_statements.track=all".to_string(),
-                    ]),
-                };
-                self.docker
-                    .create_postgres_container(config, &password)
-                    .await?
+        let password_encrypted = self.encrypt_password(&password)?;
+
+        let branch = self
+            .database_repo
+            .update_container(
+                &branch.id,
+                &container_id,
+                "running",
+                &self.host,
+                port,
+                &password_encrypted,
+            )
+            .await?;
+
+        Ok((branch, password))
+    }
+
+    async fn resume_kv_replication(&self, database: &Database) -> AppResult<()> {
+        let parent_id = database
+            .parent_branch_id
+            .as_deref()
+            .ok_or_else(|| AppError::Internal("Replica has no parent".to_string()))?;
+        let parent = self
+            .database_repo
+            .find_by_id(parent_id)
+            .await?
+            .ok_or_else(|| {
+                AppError::NotFound(format!("Parent database '{}' not found", parent_id))
+            })?;
+        let decrypt = |db: &Database| {
+            db.password_encrypted
+                .as_ref()
+                .map(|p| self.decrypt_password(p))
+                .transpose()?
+                .ok_or_else(|| AppError::Internal(format!("Database {} has no password", db.id)))
+        };
+        let container_id = database
+            .container_id
+            .as_deref()
+            .ok_or_else(|| AppError::Internal("Replica has no container".to_string()))?;
+        self.docker.wait_for_healthy(container_id, 60).await?;
+
+        self.docker
+            .replicate_kv_database(
+                &parent.container_name(),
+                &database.container_name(),
+                &decrypt(&parent)?,
+                &decrypt(database)?,
+                engine_for(&database.database_type).cli_name(),
+                false,
+            )
+            .await
+    }
+
+    pub async fn sync_from_parent(
+        &self,
+        database_id: &str,
+        user_id: &str,
+        is_admin: bool,
+    ) -> AppResult<OperationResponse> {
+        let branch = self
+            .database_repo
+            .find_by_id(database_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("Database '{}' not found", database_id)))?;
+
+        if !is_admin
+            && !self
+                .project_repo
+                .is_owner(&branch.project_id, user_id)
+                .await?
+        {
+            return Err(AppError::Forbidden);
+        }
+
+        let parent_id = branch.parent_branch_id.as_ref().ok_or_else(|| {
+            AppError::Validation("Cannot sync root database, it has no parent".to_string())
+        })?;
+        if branch.is_replica {
+            return Err(AppError::Validation(
+                "Replicas follow their parent already, detach the replica to sync it manually"
+                    .to_string(),
+            ));
+        }
+
+        let parent = self
+            .database_repo
+            .find_by_id(parent_id)
+            .await?
+            .ok_or_else(|| {
+                AppError::NotFound(format!("Parent database '{}' not found", parent_id))
+            })?;
+
+        if branch.container_status != "running" {
+            return Err(AppError::Validation(
+                "Branch must be running to sync".to_string(),
+            ));
+        }
+
+        if parent.container_status != "running" {
+            return Err(AppError::Validation(
+                "Parent branch must be running to sync".to_string(),
+            ));
+        }
+
+        if self.operation_repo.has_running(&branch.id).await? {
+            return Err(AppError::Conflict(
+                "Another operation is running for this branch".to_string(),
+            ));
+        }
+        self.check_unmasked_copy(&parent).await?;
+
+        let operation = self
+            .operation_repo
+            .start(&branch.id, SYNC_OPERATION_KIND, None, None)
+            .await?;
+
+        let service = self.clone();
+        let running = operation.clone();
+        tokio::spawn(async move {
+            let result = service.copy_from_parent(&parent, &branch, &running).await;
+            service.operation_repo.finish(&running, result).await;
+        });
+
+        Ok(operation.to_response())
+    }
+
+    async fn copy_from_parent(
+        &self,
+        parent: &Database,
+        branch: &Database,
+        operation: &Operation,
+    ) -> AppResult<()> {
+        let parent_password = parent
+            .password_encrypted
+            .as_ref()
+            .map(|p| self.decrypt_password(p))
+            .transpose()?
+            .ok_or_else(|| AppError::Internal("Parent database has no password".to_string()))?;
+
+        let branch_password = branch
+            .password_encrypted
+            .as_ref()
+            .map(|p| self.decrypt_password(p))
+            .transpose()?
+            .ok_or_else(|| AppError::Internal("Branch database has no password".to_string()))?;
+
+        self.record_phase(operation, "copying_data").await;
+        self.fork_with_progress(
+            operation,
+            parent,
+            &parent_password,
+            branch,
+            &branch_password,
+            false,
+        )
+        .await?;
+        if engine_for(&branch.database_type).supports_masking() {
+            self.record_phase(operation, "masking").await;
+            self.mask_branch_data(branch).await?;
+        }
+
+        self.database_repo.update_forked_at(&branch.id).await
+    }
+
+    /// Sets a new expiry time on a branch, which is deleted automatically once it passes.
+    pub async fn extend_branch(
+        &self,
+        database_id: &str,
+        user_id: &str,
+        is_admin: bool,
+        expires_at: Option<&str>,
+        ttl_seconds: Option<i64>,
+    ) -> AppResult<DatabaseResponse> {
+        let branch = self
+            .database_repo
+            .find_by_id(database_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("Database '{}' not found", database_id)))?;
+
+        if !is_admin
+            && !self
+                .project_repo
+                .is_owner(&branch.project_id, user_id)
+                .await?
+        {
+            return Err(AppError::Forbidden);
+        }
+
+        if branch.parent_branch_id.is_none() {
+            return Err(AppError::Validation(
+                "Only branches can expire, not the default branch".to_string(),
+            ));
+        }
+
+        let expires_at = branch_expiry(expires_at, ttl_seconds)?.ok_or_else(|| {
+            AppError::Validation("Either expires_at or ttl_seconds is required".to_string())
+        })?;
+        self.database_repo
+            .set_expires_at(database_id, Some(&expires_at))
+            .await?;
+
+        self.get_by_id_response(database_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("Database '{}' not found", database_id)))
+    }
+
+    /// Replaces a branch's contents with its parent's current state. Full resets of PostgreSQL
+    /// branches swap in a fresh snapshot of the parent's data directory when the storage backend
+    /// supports it; everything else drops the branch's schemas and copies the parent logically.
+    pub async fn reset_from_parent(
+        &self,
+        branch: &Database,
+        parent: &Database,
+        schema_only: bool,
+    ) -> AppResult<()> {
+        let container_id = branch
+            .container_id
+            .as_deref()
+            .ok_or_else(|| AppError::Internal("Branch has no container".to_string()))?;
+        let password = branch
+            .password_encrypted
+            .as_ref()
+            .map(|p| self.decrypt_password(p))
+            .transpose()?
+            .ok_or_else(|| AppError::Internal("Branch database has no password".to_string()))?;
+
+        let engine = engine_for(&branch.database_type);
+        if !schema_only && engine.supports_snapshots() && self.volumes.supports_snapshots() {
+            // The branch keeps its data until the parent's snapshot is taken.
+            let staging_path = format!("{}-reset", self.data_path(&branch.id));
+            let _ = self.volumes.remove(&staging_path).await;
+            if self
+                .snapshot_branch_data(parent, branch, &staging_path)
+                .await
+            {
+                self.swap_data_directory(branch, container_id, &staging_path)
+                    .await?;
+                return self.adopt_snapshot(parent, branch, &password).await;
+            }
+        }
+
+        if parent.container_status != "running" {
+            return Err(AppError::Validation(
+                "Parent branch must be running to copy its data".to_string(),
+            ));
+        }
+
+        let parent_password = parent
+            .password_encrypted
+            .as_ref()
+            .map(|p| self.decrypt_password(p))
+            .transpose()?
+            .ok_or_else(|| AppError::Internal("Parent database has no password".to_string()))?;
+        engine.clear_data(&self.docker, branch, &password).await?;
+        engine
+            .fork(
+                &self.docker,
+                &ForkSpec {
+                    source: parent,
+                    target: branch,
+                    source_password: &parent_password,
+                    target_password: &password,
+                    schema_only,
+                    progress: None,
+                },
+            )
+            .await
+    }
+
+    /// Takes a named copy-on-write snapshot of a PostgreSQL database's data directory.
+    pub async fn create_snapshot(&self, database: &Database, name: &str) -> AppResult<Snapshot> {
+        if name.trim().is_empty()
+            || name.len() > 63
+            || !name
+                .chars()
+                .all(|c| c.is_alphanumeric() || c == '_' || c == '-')
+        {
+            return Err(AppError::Validation(
+                "Snapshot names are 1 to 63 letters, numbers, underscores and hyphens".to_string(),
+            ));
+        }
+        let engine = engine_for(&database.database_type);
+        if !engine.supports_snapshots() {
+            return Err(AppError::Validation(format!(
+                "Named snapshots are not available for {} databases",
+                engine.display_name()
+            )));
+        }
+        if !self.volumes.supports_snapshots() {
+            return Err(AppError::Validation(format!(
+                "Named snapshots need a storage backend with snapshots, not {}",
+                self.volumes.kind()
+            )));
+        }
+        let running = match database.container_status.as_str() {
+            "running" => true,
+            "stopped" => false,
+            status => {
+                return Err(AppError::Conflict(format!("Database is {}", status)));
             },
         };
-        self.docker.start_container(&container_id).await?;
-
-        let healthy = self.docker.wait_for_healthy(&container_id, 60).await?;
-        if !healthy {
-            return Err(AppError::Internal(
-                "Branch container failed to start".to_string(),
+        let container_id = database
+            .container_id
+            .as_deref()
+            .ok_or_else(|| AppError::Internal("Database has no container".to_string()))?;
+        if self.operation_repo.has_running(&database.id).await? {
+            return Err(AppError::Conflict(
+                "Another operation is running for this database".to_string(),
             ));
         }
+        if self
+            .snapshot_repo
+            .find_by_name(&database.id, name)
+            .await?
+            .is_some()
+        {
+            return Err(AppError::AlreadyExists(format!(
+                "Snapshot '{}' already exists",
+                name
+            )));
+        }
 
-        let password_encrypted = self.encrypt_password(&password)?;
-
-        let branch = self
-            .database_repo
-            .update_container(
-                &branch.id,
-                &container_id,
-                "running",
-                &self.host,
-                port,
-                &password_encrypted,
+        let snapshot = self.snapshot_repo.create(&database.id, name).await?;
+        let snapshot_path = self.snapshot_path(&snapshot);
+        if let Err(e) = self
+            .take_snapshot(
+                database,
+                container_id,
+                running,
+                &self.data_path(&database.id),
+                &snapshot_path,
             )
+            .await
+        {
+            let _ = self.volumes.remove(&snapshot_path).await;
+            let _ = self.snapshot_repo.delete(&snapshot.id).await;
+            return Err(e);
+        }
+        remove_pid_files(database, &snapshot_path).await;
+
+        Ok(snapshot)
+    }
+
+    pub async fn list_snapshots(&self, database_id: &str) -> AppResult<Vec<Snapshot>> {
+        self.snapshot_repo.find_by_database_id(database_id).await
+    }
+
+    pub async fn find_snapshot(&self, database_id: &str, name: &str) -> AppResult<Snapshot> {
+        self.snapshot_repo
+            .find_by_name(database_id, name)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("Snapshot '{}' not found", name)))
+    }
+
+    pub async fn delete_snapshot(&self, snapshot: &Snapshot) -> AppResult<()> {
+        self.volumes.remove(&self.snapshot_path(snapshot)).await?;
+        self.snapshot_repo.delete(&snapshot.id).await
+    }
+
+    /// Returns a database to one of its named snapshots. The snapshot itself stays, so the
+    /// database can be reset to it again.
+    pub async fn reset_to_snapshot(
+        &self,
+        database: &Database,
+        snapshot: &Snapshot,
+    ) -> AppResult<()> {
+        let container_id = database
+            .container_id
+            .as_deref()
+            .ok_or_else(|| AppError::Internal("Database has no container".to_string()))?;
+        let password = database
+            .password_encrypted
+            .as_ref()
+            .map(|p| self.decrypt_password(p))
+            .transpose()?
+            .ok_or_else(|| AppError::Internal("Database has no password".to_string()))?;
+
+        let staging_path = format!("{}-reset", self.data_path(&database.id));
+        let _ = self.volumes.remove(&staging_path).await;
+        self.volumes
+            .snapshot(&self.snapshot_path(snapshot), &staging_path)
+            .await?;
+        self.swap_data_directory(database, container_id, &staging_path)
             .await?;
 
-        if include_data && source.container_status == "running" {
-            let source_password = source
-                .password_encrypted
-                .as_ref()
-                .map(|p| self.decrypt_password(p))
-                .transpose()?
-                .ok_or_else(|| AppError::Internal("Source database has no password".to_string()))?;
+        // The password may have changed since the snapshot was taken.
+        self.adopt_snapshot(database, database, &password).await
+    }
 
-            let source_container = source.container_name();
+    /// Stops the database, puts `staging_path` in place of its data directory and starts it
+    /// again. The old directory is only removed once the new one is in place.
+    async fn swap_data_directory(
+        &self,
+        database: &Database,
+        container_id: &str,
+        staging_path: &str,
+    ) -> AppResult<()> {
+        let data_path = self.data_path(&database.id);
+        let previous_path = format!("{}-previous", data_path);
 
-            tokio::time::sleep(tokio::time::Duration::from_secs(2)).await;
+        let _ = self.docker.stop_container(container_id).await;
+        let _ = self.volumes.remove(&previous_path).await;
+        self.volumes.rename(&data_path, &previous_path).await?;
+        if let Err(e) = self.volumes.rename(staging_path, &data_path).await {
+            let _ = self.volumes.rename(&previous_path, &data_path).await;
+            let _ = self.volumes.remove(staging_path).await;
+            let _ = self.docker.start_container(container_id).await;
+            return Err(e);
+        }
+        let _ = self.volumes.remove(&previous_path).await;
 
-            let fork_result = match source.database_type.as_str() {
-                "redis" => {
-                    self.docker
-                        .fork_redis_database(
-                            &source_container,
-                            &container_name,
-                            &source_password,
-                            &password,
-                        )
-                        .await
-                },
-                "valkey" => {
-                    self.docker
-                        .fork_valkey_database(
-                            &source_container,
-                            &container_name,
-                            &source_password,
-                            &password,
-                        )
-                        .await
-                },
-                _ => {
-                    self.docker
-                        .fork_database(
-                            &source_container,
-                            &container_name,
-                            &source.username,
-                            &source_password,
-                            &branch.username,
-                            &password,
-                        )
-                        .await
-                },
-            };
+        self.docker.start_container(container_id).await?;
+        if !self.docker.wait_for_healthy(container_id, 60).await? {
+            return Err(AppError::Docker(
+                "Database did not become healthy after its data was replaced".to_string(),
+            ));
+        }
+        self.database_repo
+            .update_status(&database.id, "running")
+            .await
+    }
 
-            if let Err(e) = fork_result {
-                tracing::warn!("Fork data failed (branch still created): {}", e);
+    /// Applies the project's masking rules to data just copied into a PostgreSQL branch and
+    /// records the rule set on it. If masking fails the copied data is dropped, so unmasked data
+    /// never stays behind in a branch.
+    pub async fn mask_branch_data(&self, branch: &Database) -> AppResult<()> {
+        if !engine_for(&branch.database_type).supports_masking() {
+            return Ok(());
+        }
+
+        let project = self
+            .project_repo
+            .find_by_id(&branch.project_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Project not found".to_string()))?;
+        let config = MaskingConfig::from_project_settings(&project.settings_json())?;
+        if config.is_empty() {
+            return self.database_repo.set_masking(&branch.id, None, None).await;
+        }
+
+        let container_id = branch
+            .container_id
+            .as_deref()
+            .ok_or_else(|| AppError::Internal("Branch has no container".to_string()))?;
+        if let Err(e) = self.apply_masking(branch, container_id, &config).await {
+            tracing::error!("Masking branch {} failed: {}", branch.id, e);
+            if let Err(e) = self.clear_data(branch).await {
+                tracing::error!(
+                    "Failed to drop unmasked data of branch {}: {}",
+                    branch.id,
+                    e
+                );
             }
+            self.database_repo
+                .set_masking(&branch.id, None, None)
+                .await?;
+            return Err(AppError::Internal(format!(
+                "Masking the branch's data failed, the copied data was dropped: {}",
+                e
+            )));
         }
 
-        Ok(branch.to_response_with_host(Some(&password), Some(&self.host)))
+        let rules = serde_json::to_string(&config.rules).unwrap_or_default();
+        self.database_repo
+            .set_masking(&branch.id, Some(&config.fingerprint()), Some(&rules))
+            .await?;
+        tracing::info!(
+            "Applied masking rule set {} to branch {}",
+            config.fingerprint(),
+            branch.id
+        );
+        Ok(())
     }
 
-    pub async fn sync_from_parent(
+    /// Checks column types, masks in one transaction and then rewrites the masked tables, since
+    /// an `UPDATE` leaves the original rows behind as dead tuples.
+    async fn apply_masking(
+        &self,
+        branch: &Database,
+        container_id: &str,
+        config: &MaskingConfig,
+    ) -> AppResult<()> {
+        let mut salt = [0u8; 16];
+        rand::thread_rng().fill_bytes(&mut salt);
+        let statements = masking_statements(config, &hex::encode(salt));
+        let type_check = masking_type_check(config);
+
+        let mut script: Vec<&str> = type_check.iter().map(String::as_str).collect();
+        script.push("BEGIN");
+        script.extend(statements.iter().map(String::as_str));
+        script.push("COMMIT");
+        self.run_postgres_sql(branch, container_id, &branch.username, &script)
+            .await?;
+
+        let Some(query) = vacuum_statements_query(config) else {
+            return Ok(());
+        };
+        let vacuums = self
+            .run_postgres_sql(branch, container_id, &branch.username, &[&query])
+            .await?;
+        let mut script: Vec<&str> = vacuums.lines().filter(|l| !l.trim().is_empty()).collect();
+        script.push("CHECKPOINT");
+        self.run_postgres_sql(branch, container_id, &branch.username, &script)
+            .await?;
+        Ok(())
+    }
+
+    /// Empties a database before a logical copy is restored into it.
+    pub async fn clear_data(&self, database: &Database) -> AppResult<()> {
+        let password = database
+            .password_encrypted
+            .as_ref()
+            .map(|p| self.decrypt_password(p))
+            .transpose()?
+            .ok_or_else(|| AppError::Internal("Database has no password".to_string()))?;
+        engine_for(&database.database_type)
+            .clear_data(&self.docker, database, &password)
+            .await
+    }
+
+    /// Makes a branch the project's default database. With `keep_endpoint` the promoted branch
+    /// also takes over the previous default's name, port and password.
+    pub async fn promote_branch(
         &self,
         database_id: &str,
         user_id: &str,
         is_admin: bool,
+        keep_endpoint: bool,
     ) -> AppResult<DatabaseResponse> {
         let branch = self
             .database_repo
@@ -1178,87 +2946,192 @@ impl DatabaseService {
             return Err(AppError::Forbidden);
         }
 
-        let parent_id = branch.parent_branch_id.as_ref().ok_or_else(|| {
-            AppError::Validation("Cannot sync root database, it has no parent".to_string())
-        })?;
+        if branch.parent_branch_id.is_none() {
+            return Err(AppError::Validation(
+                "Database is already the default branch".to_string(),
+            ));
+        }
+        if branch.is_replica {
+            return Err(AppError::Validation(
+                "Detach the replica before promoting it".to_string(),
+            ));
+        }
 
-        let parent = self
+        let root = self
             .database_repo
-            .find_by_id(parent_id)
+            .find_root_database(database_id)
             .await?
-            .ok_or_else(|| {
-                AppError::NotFound(format!("Parent database '{}' not found", parent_id))
-            })?;
+            .ok_or_else(|| AppError::NotFound("Default branch not found".to_string()))?;
 
-        if branch.container_status != "running" {
-            return Err(AppError::Validation(
-                "Branch must be running to sync".to_string(),
-            ));
+        if keep_endpoint {
+            self.move_endpoint(&root, &branch).await?;
         }
 
-        if parent.container_status != "running" {
+        self.database_repo
+            .promote_branch(&branch.id, &root.id)
+            .await?;
+
+        self.get_by_id_response(database_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("Database '{}' not found", database_id)))
+    }
+
+    /// Swaps name, port and password between the two databases and recreates both containers,
+    /// so the container reachable under `from`'s hostname and port is `to`. A failure part way
+    /// puts both databases back on their own endpoint.
+    async fn move_endpoint(&self, from: &Database, to: &Database) -> AppResult<()> {
+        if from.container_status != "running" || to.container_status != "running" {
             return Err(AppError::Validation(
-                "Parent branch must be running to sync".to_string(),
+                "Both databases must be running to move the connection endpoint".to_string(),
             ));
         }
+        for database in [from, to] {
+            if self.operation_repo.has_running(&database.id).await? {
+                return Err(AppError::Conflict(format!(
+                    "An operation is running for database '{}'",
+                    database.name
+                )));
+            }
+        }
 
-        let parent_password = parent
-            .password_encrypted
-            .as_ref()
-            .map(|p| self.decrypt_password(p))
-            .transpose()?
-            .ok_or_else(|| AppError::Internal("Parent database has no password".to_string()))?;
+        // PostgreSQL keeps role passwords in the data directory, so they are swapped while the
+        // containers still run. Key-value stores take theirs from the new container.
+        self.swap_role_passwords(from, to).await?;
+
+        if let Err(e) = self.swap_endpoint_containers(from, to).await {
+            tracing::error!(
+                "Moving the endpoint of database {} to {} failed, reverting: {}",
+                from.id,
+                to.id,
+                e
+            );
+            if let Err(revert_error) = self.revert_endpoint_move(from, to).await {
+                tracing::error!(
+                    "Failed to revert the endpoint move of database {}: {}",
+                    from.id,
+                    revert_error
+                );
+            }
+            return Err(e);
+        }
 
-        let branch_password = branch
-            .password_encrypted
-            .as_ref()
-            .map(|p| self.decrypt_password(p))
-            .transpose()?
-            .ok_or_else(|| AppError::Internal("Branch database has no password".to_string()))?;
+        Ok(())
+    }
 
-        let parent_container = parent.container_name();
-        let branch_container = branch.container_name();
+    /// Gives each role the other database's password. Undoes the first change if the second
+    /// one fails.
+    async fn swap_role_passwords(&self, from: &Database, to: &Database) -> AppResult<()> {
+        self.set_role_password(from, &to.password_encrypted).await?;
+        if let Err(e) = self.set_role_password(to, &from.password_encrypted).await {
+            let _ = self.set_role_password(from, &from.password_encrypted).await;
+            return Err(e);
+        }
+        Ok(())
+    }
 
-        match branch.database_type.as_str() {
-            "redis" => {
-                self.docker
-                    .fork_redis_database(
-                        &parent_container,
-                        &branch_container,
-                        &parent_password,
-                        &branch_password,
-                    )
-                    .await?
-            },
-            "valkey" => {
-                self.docker
-                    .fork_valkey_database(
-                        &parent_container,
-                        &branch_container,
-                        &parent_password,
-                        &branch_password,
-                    )
-                    .await?
-            },
-            _ => {
-                self.docker
-                    .fork_database(
-                        &parent_container,
-                        &branch_container,
-                        &parent.username,
-                        &parent_password,
-                        &branch.username,
-                        &branch_password,
-                    )
-                    .await?
-            },
+    async fn set_role_password(
+        &self,
+        database: &Database,
+        password_encrypted: &Option<String>,
+    ) -> AppResult<()> {
+        let decrypt = |encrypted: &Option<String>| {
+            encrypted
+                .as_ref()
+                .map(|p| self.decrypt_password(p))
+                .transpose()?
+                .ok_or_else(|| AppError::Internal("Database has no password".to_string()))
+        };
+        let current = decrypt(&database.password_encrypted)?;
+        let password = decrypt(password_encrypted)?;
+        engine_for(&database.database_type)
+            .set_user_password(&self.docker, database, &current, &password)
+            .await
+    }
+
+    async fn swap_endpoint_containers(&self, from: &Database, to: &Database) -> AppResult<()> {
+        // Both containers hold the name and port the other one needs, so they go first.
+        for database in [from, to] {
+            if let Some(container_id) = &database.container_id {
+                let _ = self.docker.stop_container(container_id).await;
+                self.docker.remove_container(container_id, true).await?;
+            }
         }
 
-        self.database_repo.update_forked_at(database_id).await?;
+        self.database_repo.swap_endpoints(from, to).await?;
 
-        self.get_by_id_response(database_id)
+        for id in [&to.id, &from.id] {
+            self.restart_on_endpoint(id).await?;
+        }
+        Ok(())
+    }
+
+    async fn revert_endpoint_move(&self, from: &Database, to: &Database) -> AppResult<()> {
+        let current_from = self
+            .database_repo
+            .find_by_id(&from.id)
             .await?
-            .ok_or_else(|| AppError::NotFound(format!("Database '{}' not found", database_id)))
+            .ok_or_else(|| AppError::NotFound(format!("Database '{}' not found", from.id)))?;
+        let current_to = self
+            .database_repo
+            .find_by_id(&to.id)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("Database '{}' not found", to.id)))?;
+
+        for database in [&current_from, &current_to] {
+            if let Some(container_id) = &database.container_id {
+                if self.docker.container_exists(container_id).await? {
+                    let _ = self.docker.stop_container(container_id).await;
+                    self.docker.remove_container(container_id, true).await?;
+                }
+            }
+        }
+
+        if current_from.name != from.name {
+            self.database_repo
+                .swap_endpoints(&current_from, &current_to)
+                .await?;
+        }
+
+        for database in [from, to] {
+            self.restart_on_endpoint(&database.id).await?;
+        }
+
+        // The data directories still hold the swapped role passwords.
+        for database in [from, to] {
+            let current = self
+                .database_repo
+                .find_by_id(&database.id)
+                .await?
+                .ok_or_else(|| {
+                    AppError::NotFound(format!("Database '{}' not found", database.id))
+                })?;
+            self.set_role_password(&current, &current.password_encrypted)
+                .await?;
+        }
+        Ok(())
+    }
+
+    /// Recreates the container from the stored endpoint and waits for it to come up.
+    async fn restart_on_endpoint(&self, id: &str) -> AppResult<()> {
+        let database = self
+            .database_repo
+            .find_by_id(id)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("Database '{}' not found", id)))?;
+        self.recreate_container(&database).await?;
+
+        let container_id = self
+            .database_repo
+            .find_by_id(id)
+            .await?
+            .and_then(|database| database.container_id)
+            .ok_or_else(|| AppError::Internal("Database has no container".to_string()))?;
+        self.docker.start_container(&container_id).await?;
+
+        let healthy = self.docker.wait_for_healthy(&container_id, 60).await?;
+        let status = if healthy { "running" } else { "unhealthy" };
+        self.database_repo.update_status(id, status).await?;
+        Ok(())
     }
 
     pub async fn get_config(
@@ -1277,14 +3150,11 @@ impl DatabaseService {
             .await?
             .ok_or_else(|| AppError::NotFound(format!("Database '{}' not found", database_id)))?;
 
-        let mut warnings = Vec::new();
-        let mut requires_restart = false;
-
         let container_id = database
             .container_id
-            .clone()
+            .as_ref()
             .ok_or_else(|| AppError::NotFound("Database has no container".to_string()))?;
-        let status = self.docker.get_container_status(&container_id).await?;
+        let status = self.docker.get_container_status(container_id).await?;
         if status != "running" {
             return Err(AppError::Conflict(format!(
                 "Container is not running (status: {})",
@@ -1292,48 +3162,16 @@ impl DatabaseService {
             )));
         }
 
-        let (format, source, content) = match database.database_type.as_str() {
-            "postgres" => {
-                requires_restart = true;
-                let password = database
-                    .password_encrypted
-                    .as_ref()
-                    .and_then(|p| self.decrypt_password(p).ok())
-                    .ok_or_else(|| {
-                        AppError::Internal("No password stored for database".to_string())
-                    })?;
-                let config_path = self
-                    .get_postgres_config_path(&container_id, &database.username, &password)
-                    .await?;
-                let content = self
-                    .read_file_from_container(&container_id, &config_path)
-                    .await?;
-                (ConfigFormat::File, ConfigSource::File, content)
-            },
-            "redis" | "valkey" => {
-                let password = database
-                    .password_encrypted
-                    .as_ref()
-                    .and_then(|p| self.decrypt_password(p).ok())
-                    .ok_or_else(|| {
-                        AppError::Internal("No password stored for database".to_string())
-                    })?;
-                let content = self
-                    .fetch_kv_config_from_container(
-                        &container_id,
-                        database.database_type.as_str(),
-                        &password,
-                        &mut warnings,
-                    )
-                    .await?;
-                (ConfigFormat::Kv, ConfigSource::Runtime, content)
-            },
-            _ => {
-                return Err(AppError::Validation(
-                    "Unsupported database type for config".to_string(),
-                ));
-            },
-        };
+        let engine = engine_for(&database.database_type);
+        let password = self.config_password(&database)?;
+        let mut warnings = Vec::new();
+        let EngineConfig {
+            format,
+            source,
+            content,
+        } = engine
+            .read_config(&self.docker, &database, &password, &mut warnings)
+            .await?;
 
         Ok(DatabaseConfigResponse {
             database_id: database.id,
@@ -1342,7 +3180,7 @@ impl DatabaseService {
             source,
             content,
             warnings,
-            requires_restart,
+            requires_restart: engine.config_requires_restart(),
         })
     }
 
@@ -1363,9 +3201,6 @@ impl DatabaseService {
             .await?
             .ok_or_else(|| AppError::NotFound(format!("Database '{}' not found", database_id)))?;
 
-        let mut warnings = Vec::new();
-        let mut requires_restart = false;
-
         let container_id = database
             .container_id
             .as_ref()
@@ -1378,418 +3213,159 @@ impl DatabaseService {
             )));
         }
 
-        let applied = match database.database_type.as_str() {
-            "postgres" => {
-                requires_restart = true;
-                let password = database
-                    .password_encrypted
-                    .as_ref()
-                    .and_then(|p| self.decrypt_password(p).ok())
-                    .ok_or_else(|| {
-                        AppError::Internal("No password stored for database".to_string())
-                    })?;
-                let config_path = self
-                    .get_postgres_config_path(container_id, &database.username, &password)
-                    .await?;
-                self.write_file_in_container(container_id, &config_path, content)
-                    .await?;
-                let output = self
-                    .reload_postgres_config(container_id, &database.username, &password)
-                    .await?;
-                if !output {
-                    warnings.push("Config saved, but reload did not report success.".to_string());
-                }
-                output
-            },
-            "redis" | "valkey" => {
-                let password = database
-                    .password_encrypted
-                    .as_ref()
-                    .and_then(|p| self.decrypt_password(p).ok())
-                    .ok_or_else(|| {
-                        AppError::Internal("No password stored for database".to_string())
-                    })?;
-                self.apply_kv_config(
-                    container_id,
-                    database.database_type.as_str(),
-                    &password,
-                    content,
-                    &mut warnings,
-                )
-                .await?;
-                let rewritten = self
-                    .rewrite_kv_config(container_id, database.database_type.as_str(), &password)
-                    .await?;
-                if !rewritten {
-                    warnings.push(
-                        "CONFIG REWRITE did not succeed. Changes may not persist after restart."
-                            .to_string(),
-                    );
-                }
-                true
-            },
-            _ => {
-                return Err(AppError::Validation(
-                    "Unsupported database type for config".to_string(),
-                ));
-            },
-        };
+        let engine = engine_for(&database.database_type);
+        let password = self.config_password(&database)?;
+        let mut warnings = Vec::new();
+        let applied = engine
+            .write_config(&self.docker, &database, &password, content, &mut warnings)
+            .await?;
 
         Ok(UpdateDatabaseConfigResponse {
             database_id: database.id,
             database_type: database.database_type,
             applied,
             warnings,
-            requires_restart,
+            requires_restart: engine.config_requires_restart(),
         })
     }
 
-    async fn reload_postgres_config(
-        &self,
-        container_id: &str,
-        username: &str,
-        password: &str,
-    ) -> AppResult<bool> {
-        let output = self
-            .docker
-            .run_exec(
-                container_id,
-                vec![
-                    "psql".to_string(),
-                    "-U".to_string(),
-                    username.to_string(),
-                    "-d".to_string(),
-                    "postgres".to_string(),
-                    "-t".to_string(),
-                    "-A".to_string(),
-                    "-c".to_string(),
-                    "SELECT pg_reload_conf();".to_string(),
-                ],
-                Some(vec![format!("PGPASSWORD={}", password)]),
-            )
-            .await?;
-
-        if let Some(code) = output.exit_code {
-            if code != 0 {
-                return Err(AppError::Docker(format!(
-                    "Failed to reload PostgreSQL config: {}",
-                    output.stderr.trim()
-                )));
-            }
-        }
-
-        Ok(output.stdout.trim() == "t")
-    }
-
-    async fn read_file_from_container(&self, container_id: &str, path: &str) -> AppResult<String> {
-        let output = self
-            .docker
-            .run_exec(
-                container_id,
-                vec!["cat".to_string(), path.to_string()],
-                None,
-            )
-            .await?;
-
-        if let Some(code) = output.exit_code {
-            if code != 0 {
-                return Err(AppError::Docker(format!(
-                    "Failed to read config file: {}",
-                    output.stderr.trim()
-                )));
-            }
-        }
-
-        Ok(output.stdout)
-    }
-
-    async fn write_file_in_container(
-        &self,
-        container_id: &str,
-        path: &str,
-        content: &str,
-    ) -> AppResult<()> {
-        let encoded = general_purpose::STANDARD.encode(content);
-        let encoded_escaped = Self::shell_escape_single(&encoded);
-        let path_escaped = Self::shell_escape_single(path);
-        let command = format!(
-            "printf '%s' '{}' | base64 -d > '{}'",
-            encoded_escaped, path_escaped
-        );
-
-        let output = self
-            .docker
-            .run_exec(
-                container_id,
-                vec!["sh".to_string(), "-c".to_string(), command],
-                None,
-            )
-            .await?;
-
-        if let Some(code) = output.exit_code {
-            if code != 0 {
-                return Err(AppError::Docker(format!(
-                    "Failed to write config file: {}",
-                    output.stderr.trim()
-                )));
-            }
-        }
-
-        Ok(())
+    fn config_password(&self, database: &Database) -> AppResult<String> {
+        database
+            .password_encrypted
+            .as_ref()
+            .and_then(|p| self.decrypt_password(p).ok())
+            .ok_or_else(|| AppError::Internal("No password stored for database".to_string()))
     }
+}
 
-    async fn get_postgres_config_path(
-        &self,
-        container_id: &str,
-        username: &str,
-        password: &str,
-    ) -> AppResult<String> {
-        let output = self
-            .docker
-            .run_exec(
-                container_id,
-                vec![
-                    "psql".to_string(),
-                    "-U".to_string(),
-                    username.to_string(),
-                    "-d".to_string(),
-                    "postgres".to_string(),
-                    "-t".to_string(),
-                    "-A".to_string(),
-                    "-c".to_string(),
-                    "SHOW config_file;".to_string(),
-                ],
-                Some(vec![format!("PGPASSWORD={}", password)]),
-            )
-            .await?;
-
-        if let Some(code) = output.exit_code {
-            if code != 0 {
-                return Err(AppError::Docker(format!(
-                    "Failed to read PostgreSQL config path: {}",
-                    output.stderr.trim()
-                )));
-            }
-        }
-
-        let path = output.stdout.trim().to_string();
-        if path.is_empty() {
-            return Err(AppError::Docker(
-                "PostgreSQL returned empty config path".to_string(),
-            ));
-        }
-
-        Ok(path)
+/// Resolves an absolute expiry or a TTL in seconds to an RFC 3339 time in the future.
+fn branch_expiry(expires_at: Option<&str>, ttl_seconds: Option<i64>) -> AppResult<Option<String>> {
+    let now = Utc::now();
+    let expires_at = match (expires_at, ttl_seconds) {
+        (None, None) => return Ok(None),
+        (Some(_), Some(_)) => {
+            return Err(AppError::Validation(
+                "Specify either expires_at or ttl_seconds, not both".to_string(),
+            ))
+        },
+        (Some(at), None) => DateTime::parse_from_rfc3339(at)
+            .map_err(|_| {
+                AppError::Validation("expires_at must be an RFC 3339 timestamp".to_string())
+            })?
+            .with_timezone(&Utc),
+        (None, Some(ttl)) => TimeDelta::try_seconds(ttl)
+            .filter(|_| ttl > 0)
+            .and_then(|ttl| now.checked_add_signed(ttl))
+            .ok_or_else(|| {
+                AppError::Validation("ttl_seconds must be a positive number of seconds".to_string())
+            })?,
+    };
+
+    if expires_at <= now {
+        return Err(AppError::Validation(
+            "Expiry time must be in the future".to_string(),
+        ));
     }
 
-    async fn rewrite_kv_config(
-        &self,
-        container_id: &str,
-        database_type: &str,
-        password: &str,
-    ) -> AppResult<bool> {
-        let cli = if database_type == "valkey" {
-            "valkey-cli"
-        } else {
-            "redis-cli"
-        };
-
-        let output = self
-            .docker
-            .run_exec(
-                container_id,
-                vec![
-                    cli.to_string(),
-                    "-a".to_string(),
-                    password.to_string(),
-                    "CONFIG".to_string(),
-                    "REWRITE".to_string(),
-                ],
-                None,
-            )
-            .await?;
-
-        if let Some(code) = output.exit_code {
-            if code != 0 {
-                return Ok(false);
-            }
-        }
+    Ok(Some(expires_at.to_rfc3339()))
+}
 
-        Ok(output.stdout.trim().eq_ignore_ascii_case("ok"))
+/// Removes a lock file copied along with a running server's data directory.
+async fn remove_pid_files(source: &Database, data_path: &str) {
+    for pid_file in [
+        format!("{}/postmaster.pid", data_path),
+        format!("{}/{}/docker/postmaster.pid", data_path, source.version),
+    ] {
+        let _ = tokio::fs::remove_file(pid_file).await;
     }
+}
 
-    async fn fetch_kv_config_from_container(
-        &self,
-        container_id: &str,
-        database_type: &str,
-        password: &str,
-        warnings: &mut Vec<String>,
-    ) -> AppResult<String> {
-        let cli = if database_type == "valkey" {
-            "valkey-cli"
-        } else {
-            "redis-cli"
-        };
-
-        let output = self
-            .docker
-            .run_exec(
-                container_id,
-                vec![
-                    cli.to_string(),
-                    "-a".to_string(),
-                    password.to_string(),
-                    "--raw".to_string(),
-                    "CONFIG".to_string(),
-                    "GET".to_string(),
-                    "*".to_string(),
-                ],
-                None,
-            )
-            .await?;
-
-        if let Some(code) = output.exit_code {
-            if code != 0 {
-                return Err(AppError::Docker(format!(
-                    "Failed to fetch config: {}",
-                    output.stderr.trim()
-                )));
-            }
-        }
-
-        let lines: Vec<&str> = output.stdout.lines().collect();
-        let mut content = String::new();
-        let mut skipped = Vec::new();
-
-        for pair in lines.chunks(2) {
-            if pair.len() < 2 {
-                continue;
-            }
-            let key = pair[0].trim();
-            let value = pair[1].trim();
-            if key.is_empty() {
-                continue;
-            }
-            if KV_SENSITIVE_KEYS.contains(&key) {
-                skipped.push(key.to_string());
-                continue;
-            }
-            content.push_str(key);
-            if !value.is_empty() {
-                content.push(' ');
-                content.push_str(value);
-            }
-            content.push('\n');
-        }
-
-        if !skipped.is_empty() {
-            warnings.push(format!("Sensitive keys hidden: {}", skipped.join(", ")));
-        }
-
-        Ok(content)
+fn container_config(
+    engine: &dyn Engine,
+    database: &Database,
+    name: String,
+    image: String,
+    data_path: String,
+    exposed_port: Option<u16>,
+) -> ContainerConfig {
+    ContainerConfig {
+        name,
+        image,
+        env: engine.container_env(database),
+        data_path,
+        cpu_limit: database.cpu_limit,
+        memory_limit_mb: database.memory_limit_mb as i64,
+        internal_port: engine.internal_port(),
+        exposed_port,
+        cmd: engine.container_cmd(database),
+        wal_archive_path: None,
+        wal_restore_path: None,
     }
+}
 
-    async fn apply_kv_config(
-        &self,
-        container_id: &str,
-        database_type: &str,
-        password: &str,
-        content: &str,
-        warnings: &mut Vec<String>,
-    ) -> AppResult<()> {
-        let entries = Self::parse_kv_config(content)?;
-        let cli = if database_type == "valkey" {
-            "valkey-cli"
-        } else {
-            "redis-cli"
-        };
-
-        let mut skipped = Vec::new();
-
-        for entry in entries {
-            if KV_SENSITIVE_KEYS.contains(&entry.key.as_str()) {
-                skipped.push(entry.key);
-                continue;
-            }
-
-            if entry.values.is_empty() {
-                return Err(AppError::Validation(format!(
-                    "Config key '{}' must have a value",
-                    entry.key
-                )));
-            }
-
-            let mut cmd = vec![
-                cli.to_string(),
-                "-a".to_string(),
-                password.to_string(),
-                "CONFIG".to_string(),
-                "SET".to_string(),
-                entry.key.clone(),
-            ];
-            cmd.extend(entry.values.clone());
-
-            let output = self.docker.run_exec(container_id, cmd, None).await?;
-            if let Some(code) = output.exit_code {
-                if code != 0 {
-                    return Err(AppError::Docker(format!(
-                        "Failed to apply config '{}': {}",
-                        entry.key,
-                        output.stderr.trim()
-                    )));
-                }
-            }
-            let response = output.stdout.trim();
-            if !response.eq_ignore_ascii_case("ok") {
-                return Err(AppError::Docker(format!(
-                    "Failed to apply config '{}': {}",
-                    entry.key, response
-                )));
-            }
-        }
+/// Name of the replication slot a PostgreSQL replica branch streams through.
+fn replication_slot(branch_id: &str) -> String {
+    format!("datify_{}", branch_id.replace('-', "_"))
+}
 
-        if !skipped.is_empty() {
-            warnings.push(format!("Ignored sensitive keys: {}", skipped.join(", ")));
-        }
+/// Name of the role a PostgreSQL replica branch connects to its parent with.
+fn replication_role(branch_id: &str) -> String {
+    format!("datify_replica_{}", branch_id.replace('-', "_"))
+}
 
-        Ok(())
-    }
+fn replication_hba_rule(role: &str) -> String {
+    format!("host replication {} all scram-sha-256", role)
+}
 
-    fn parse_kv_config(content: &str) -> AppResult<Vec<KvConfigEntry>> {
-        let mut entries = Vec::new();
+fn allow_replication_script(role: &str) -> String {
+    let rule = replication_hba_rule(role);
+    format!(r#"grep -qx '{rule}' "$PGDATA/pg_hba.conf" || echo '{rule}' >> "$PGDATA/pg_hba.conf""#)
+}
 
-        for (idx, line) in content.lines().enumerate() {
-            let trimmed = line.trim();
-            if trimmed.is_empty() || trimmed.starts_with('#') || trimmed.starts_with(';') {
-                continue;
-            }
-            let parts: Vec<&str> = trimmed.split_whitespace().collect();
-            if parts.is_empty() {
-                continue;
-            }
-            if parts.len() < 2 {
-                return Err(AppError::Validation(format!(
-                    "Invalid config at line {}: missing value",
-                    idx + 1
-                )));
-            }
-            let key = parts[0].to_string();
-            let values = parts[1..].iter().map(|v| v.to_string()).collect();
-            entries.push(KvConfigEntry { key, values });
-        }
+fn revoke_replication_script(role: &str) -> String {
+    format!(
+        r#"sed -i '/^{}$/d' "$PGDATA/pg_hba.conf""#,
+        replication_hba_rule(role)
+    )
+}
 
-        Ok(entries)
-    }
+/// Run on the parent once a replica stops streaming from it: drops the replica's slot, so the
+/// parent no longer keeps WAL for it, and its replication role.
+fn revoke_replication_statements(branch_id: &str) -> Vec<String> {
+    vec![
+        format!(
+            "SELECT pg_drop_replication_slot(slot_name) FROM pg_replication_slots \
+             WHERE slot_name = '{}' AND NOT active",
+            replication_slot(branch_id)
+        ),
+        format!("DROP ROLE IF EXISTS \"{}\"", replication_role(branch_id)),
+        "SELECT pg_reload_conf()".to_string(),
+    ]
+}
 
-    fn shell_escape_single(value: &str) -> String {
-        value.replace('\'', "'\"'\"'")
-    }
+/// Run on a promoted standby: gives its owner a password of its own and forgets the parent it
+/// streamed from.
+fn detach_standby_statements(username: &str, password: &str) -> Vec<String> {
+    vec![
+        format!(
+            "ALTER ROLE \"{}\" WITH PASSWORD '{}'",
+            username.replace('"', "\"\""),
+            password
+        ),
+        "ALTER SYSTEM RESET primary_conninfo".to_string(),
+        "ALTER SYSTEM RESET primary_slot_name".to_string(),
+        "SELECT pg_reload_conf()".to_string(),
+    ]
 }
 
-#[derive(Debug, Clone)]
-struct KvConfigEntry {
-    key: String,
-    values: Vec<String>,
+/// WAL the parent may keep for replica slots before dropping a lagging replica: a quarter of
+/// its storage limit, so a stalled replica cannot fill the parent's disk.
+fn slot_wal_keep_mb(storage_limit_mb: i32) -> i32 {
+    if storage_limit_mb <= 0 {
+        4096
+    } else {
+        (storage_limit_mb / 4).max(64)
+    }
 }
 
 fn generate_password() -> String {
@@ -1803,3 +3379,112 @@ fn generate_password() -> String {
         })
         .collect()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_branch_expiry() {
+        assert_eq!(branch_expiry(None, None).unwrap(), None);
+
+        let expires_at = branch_expiry(None, Some(3600)).unwrap().unwrap();
+        let remaining = DateTime::parse_from_rfc3339(&expires_at)
+            .unwrap()
+            .with_timezone(&Utc)
+            - Utc::now();
+        assert!(remaining > TimeDelta::minutes(59) && remaining <= TimeDelta::hours(1));
+
+        assert_eq!(
+            branch_expiry(Some("2999-01-01T00:00:00+02:00"), None).unwrap(),
+            Some("2998-12-31T22:00:00+00:00".to_string())
+        );
+
+        assert!(branch_expiry(Some("2000-01-01T00:00:00Z"), None).is_err());
+        assert!(branch_expiry(Some("tomorrow"), None).is_err());
+        assert!(branch_expiry(None, Some(0)).is_err());
+        assert!(branch_expiry(None, Some(i64::MAX)).is_err());
+        assert!(branch_expiry(Some("2999-01-01T00:00:00Z"), Some(60)).is_err());
+    }
+
+    #[test]
+    fn test_replication_access() {
+        let branch_id = "0b9c-41aa";
+        assert_eq!(replication_slot(branch_id), "datify_0b9c_41aa");
+        assert_eq!(replication_role(branch_id), "datify_replica_0b9c_41aa");
+
+        let role = replication_role(branch_id);
+        assert!(allow_replication_script(&role)
+            .contains("grep -qx 'host replication datify_replica_0b9c_41aa all scram-sha-256'"));
+        assert!(revoke_replication_script(&role).starts_with(
+            "sed -i '/^host replication datify_replica_0b9c_41aa all scram-sha-256$/d'"
+        ));
+
+        assert_eq!(slot_wal_keep_mb(1024), 256);
+        assert_eq!(slot_wal_keep_mb(100), 64);
+        assert_eq!(slot_wal_keep_mb(0), 4096);
+    }
+
+    #[tokio::test]
+    async fn test_detach_replica() {
+        let repo = DatabaseRepository::new(crate::repositories::test_pool().await);
+        let parent = repo
+            .create(
+                "project-1",
+                "main",
+                "postgres",
+                "17",
+                1.0,
+                512,
+                1024,
+                false,
+                "main",
+                true,
+                None,
+            )
+            .await
+            .unwrap();
+        let replica = repo
+            .create(
+                "project-1",
+                "follower",
+                "postgres",
+                "17",
+                1.0,
+                512,
+                1024,
+                false,
+                "main",
+                false,
+                Some(&parent.id),
+            )
+            .await
+            .unwrap();
+        repo.set_replica(&replica.id, true).await.unwrap();
+
+        // The parent forgets the replica's slot and role
+        let revoke = revoke_replication_statements(&replica.id);
+        let slot = replication_slot(&replica.id);
+        assert!(revoke[0].starts_with("SELECT pg_drop_replication_slot(slot_name)"));
+        assert!(revoke[0].contains(&format!("WHERE slot_name = '{}'", slot)));
+        assert_eq!(
+            revoke[1],
+            format!("DROP ROLE IF EXISTS \"{}\"", replication_role(&replica.id))
+        );
+        assert_eq!(revoke.last().unwrap(), "SELECT pg_reload_conf()");
+
+        // The promoted standby stops following the parent and gets its own password
+        let detach = detach_standby_statements("ma\"in", "secret");
+        assert_eq!(detach[0], "ALTER ROLE \"ma\"\"in\" WITH PASSWORD 'secret'");
+        assert!(detach.contains(&"ALTER SYSTEM RESET primary_conninfo".to_string()));
+        assert!(detach.contains(&"ALTER SYSTEM RESET primary_slot_name".to_string()));
+
+        repo.set_replica(&replica.id, false).await.unwrap();
+        let detached = repo.find_by_id(&replica.id).await.unwrap().unwrap();
+        assert!(!detached.is_replica);
+        assert_eq!(
+            detached.parent_branch_id.as_deref(),
+            Some(parent.id.as_str())
+        );
+    }
+}
@@ -0,0 +1,139 @@
+use serde_json::{json, Value};
+
+use crate::domain::models::{
+    DocumentOperation, ExecuteDocumentQueryRequest, MONGO_DEFAULT_DATABASE,
+};
+use crate::error::{AppError, AppResult};
+use crate::infrastructure::docker::containers::MONGO_SYSTEM_DATABASES;
+
+const MAX_DOCUMENT_QUERY_LEN: usize = 16_384;
+pub(super) const DEFAULT_DOCUMENT_LIMIT: i32 = 100;
+pub(super) const MAX_DOCUMENT_LIMIT: i32 = 1000;
+
+/// Stages that write their results to a collection.
+const WRITE_STAGES: &[&str] = &["$out", "$merge"];
+
+/// Builds the `mongosh` script for a console query. The request travels as an Extended JSON
+/// string literal, so none of it is evaluated as code; one more document than `limit` is
+/// fetched to tell whether the result was truncated.
+pub(super) fn document_query_script(
+    request: &ExecuteDocumentQueryRequest,
+    limit: i32,
+    timeout_ms: i32,
+) -> AppResult<String> {
+    let database = request
+        .database
+        .as_deref()
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .unwrap_or(MONGO_DEFAULT_DATABASE);
+    if MONGO_SYSTEM_DATABASES.contains(&database) {
+        return Err(AppError::Validation(format!(
+            "The {} database cannot be queried",
+            database
+        )));
+    }
+
+    let collection = request.collection.trim();
+    if collection.is_empty() || collection.contains('\0') || collection.starts_with("system.") {
+        return Err(AppError::Validation("Invalid collection name".to_string()));
+    }
+
+    for (name, value) in [
+        ("filter", &request.filter),
+        ("projection", &request.projection),
+        ("sort", &request.sort),
+    ] {
+        if value.as_ref().is_some_and(|v| !v.is_object()) {
+            return Err(AppError::Validation(format!("{} must be an object", name)));
+        }
+    }
+
+    if request.operation == DocumentOperation::Aggregate {
+        for stage in &request.pipeline {
+            let stage = stage.as_object().ok_or_else(|| {
+                AppError::Validation("Pipeline stages must be objects".to_string())
+            })?;
+            if let Some(write) = WRITE_STAGES.iter().find(|s| stage.contains_key(**s)) {
+                return Err(AppError::Validation(format!(
+                    "The {} stage is not allowed in the console",
+                    write
+                )));
+            }
+        }
+    }
+
+    let payload = json!({
+        "database": database,
+        "collection": collection,
+        "aggregate": request.operation == DocumentOperation::Aggregate,
+        "filter": request.filter.clone().unwrap_or_else(|| json!({})),
+        "projection": request.projection.clone().unwrap_or_else(|| json!({})),
+        "sort": request.sort.clone().unwrap_or_else(|| json!({})),
+        "pipeline": Value::Array(request.pipeline.clone()),
+        "limit": limit + 1,
+        "timeout_ms": timeout_ms,
+    })
+    .to_string();
+    if payload.len() > MAX_DOCUMENT_QUERY_LEN {
+        return Err(AppError::Validation("Query is too large".to_string()));
+    }
+
+    let literal = serde_json::to_string(&payload)
+        .map_err(|e| AppError::Internal(format!("Failed to encode query: {}", e)))?;
+
+    Ok(format!(
+        "const q = EJSON.parse({});
+const coll = db.getSiblingDB(q.database).getCollection(q.collection);
+const docs = q.aggregate
+  ? coll.aggregate(q.pipeline.concat([{{ $limit: q.limit }}]), {{ maxTimeMS: q.timeout_ms }}).toArray()
+  : coll.find(q.filter, q.projection).sort(q.sort).limit(q.limit).maxTimeMS(q.timeout_ms).toArray();
+print(EJSON.stringify(docs, {{ relaxed: true }}));",
+        literal
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(operation: DocumentOperation) -> ExecuteDocumentQueryRequest {
+        ExecuteDocumentQueryRequest {
+            database: None,
+            collection: "orders".to_string(),
+            operation,
+            filter: Some(json!({ "status": "open\"); db.dropDatabase(); (\"" })),
+            projection: None,
+            sort: None,
+            pipeline: vec![],
+            limit: None,
+            timeout_ms: None,
+        }
+    }
+
+    #[test]
+    fn test_document_query_script() {
+        let script = document_query_script(&request(DocumentOperation::Find), 10, 5000).unwrap();
+        let literal = script
+            .strip_prefix("const q = EJSON.parse(")
+            .and_then(|rest| rest.split_once(");\n"))
+            .map(|(literal, _)| literal)
+            .unwrap();
+        let payload: Value =
+            serde_json::from_str(&serde_json::from_str::<String>(literal).unwrap()).unwrap();
+        assert_eq!(payload["database"], "app");
+        assert_eq!(payload["limit"], 11);
+        assert_eq!(
+            payload["filter"]["status"],
+            "open\"); db.dropDatabase(); (\""
+        );
+
+        let mut merge = request(DocumentOperation::Aggregate);
+        merge.pipeline = vec![json!({ "$match": {} }), json!({ "$merge": "copy" })];
+        assert!(document_query_script(&merge, 10, 5000).is_err());
+
+        let mut admin = request(DocumentOperation::Find);
+        admin.database = Some("admin".to_string());
+        assert!(document_query_script(&admin, 10, 5000).is_err());
+    }
+}
@@ -0,0 +1,689 @@
+use std::pin::Pin;
+use std::sync::Arc;
+
+use async_zip::base::write::ZipFileWriter;
+use async_zip::{Compression, ZipEntryBuilder};
+use bytes::Bytes;
+use futures::{AsyncWriteExt, Stream, StreamExt};
+use serde_json::{json, Map, Value};
+use tokio_util::io::ReaderStream;
+
+use super::common::{owned_database, psql_command};
+use crate::domain::engines::engine_for;
+use crate::domain::models::{Database, ExportQuery};
+use crate::error::{AppError, AppResult};
+use crate::infrastructure::docker::containers::{ContainerProvider, RedisContainer};
+use crate::infrastructure::docker::{DockerManager, ExecStream};
+use crate::repositories::{DatabaseRepository, ProjectRepository};
+use crate::utils::crypto;
+
+const SNAPSHOT_TIMEOUT_SECONDS: u64 = 600;
+const KV_SCAN_COUNT: &str = "500";
+const ZIP_BUFFER_SIZE: usize = 64 * 1024;
+const FIELD_SEPARATOR: char = '\u{1f}';
+
+/// Scans one batch of keys and returns `[cursor, [[key, type, pttl, value], ...]]` as JSON.
+const KV_DUMP_SCRIPT: &str = r#"
+local scan = redis.call('SCAN', ARGV[1], 'COUNT', ARGV[2])
+local entries = {}
+for _, key in ipairs(scan[2]) do
+  local kind = redis.call('TYPE', key).ok
+  local value
+  if kind == 'string' then value = redis.call('GET', key)
+  elseif kind == 'list' then value = redis.call('LRANGE', key, 0, -1)
+  elseif kind == 'set' then value = redis.call('SMEMBERS', key)
+  elseif kind == 'zset' then value = redis.call('ZRANGE', key, 0, -1, 'WITHSCORES')
+  elseif kind == 'hash' then value = redis.call('HGETALL', key)
+  elseif kind == 'stream' then value = redis.call('XRANGE', key, '-', '+')
+  end
+  if value ~= nil then
+    table.insert(entries, {key, kind, redis.call('PTTL', key), value})
+  end
+end
+return cjson.encode({scan[1], entries})
+"#;
+
+pub type ExportStream = Pin<Box<dyn Stream<Item = AppResult<Bytes>> + Send>>;
+
+pub struct DatabaseExport {
+    pub file_name: String,
+    pub content_type: &'static str,
+    pub stream: ExportStream,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ExportFormat {
+    Sql,
+    Custom,
+    Csv,
+    Rdb,
+    Json,
+}
+
+impl ExportFormat {
+    fn parse(database: &Database, format: Option<&str>) -> AppResult<Self> {
+        let engine = engine_for(&database.database_type);
+        if !engine.supports_exports() {
+            return Err(AppError::Validation(format!(
+                "Exports are not available for {} databases yet",
+                engine.display_name()
+            )));
+        }
+        let kv = engine_for(&database.database_type).is_key_value();
+        match (format, kv) {
+            (None, false) | (Some("sql"), false) => Ok(Self::Sql),
+            (Some("custom"), false) => Ok(Self::Custom),
+            (Some("csv"), false) => Ok(Self::Csv),
+            (None, true) | (Some("rdb"), true) => Ok(Self::Rdb),
+            (Some("json"), true) => Ok(Self::Json),
+            (Some(other), _) => Err(AppError::Validation(format!(
+                "Unsupported export format '{}' for {} databases",
+                other, database.database_type
+            ))),
+        }
+    }
+
+    fn extension(self) -> &'static str {
+        match self {
+            Self::Sql => "sql",
+            Self::Custom => "dump",
+            Self::Csv => "zip",
+            Self::Rdb => "rdb",
+            Self::Json => "json",
+        }
+    }
+
+    fn content_type(self) -> &'static str {
+        match self {
+            Self::Sql => "application/sql",
+            Self::Csv => "application/zip",
+            Self::Json => "application/json",
+            Self::Custom | Self::Rdb => "application/octet-stream",
+        }
+    }
+}
+
+struct TableFilter {
+    include: Vec<String>,
+    exclude: Vec<String>,
+}
+
+impl TableFilter {
+    fn parse(query: &ExportQuery) -> AppResult<Self> {
+        Ok(Self {
+            include: parse_patterns(query.tables.as_deref())?,
+            exclude: parse_patterns(query.exclude_tables.as_deref())?,
+        })
+    }
+
+    fn is_empty(&self) -> bool {
+        self.include.is_empty() && self.exclude.is_empty()
+    }
+
+    fn matches(&self, schema: &str, table: &str) -> bool {
+        let qualified = format!("{}.{}", schema, table);
+        let matches_any = |patterns: &[String]| {
+            patterns
+                .iter()
+                .any(|p| glob_match(p, table) || glob_match(p, &qualified))
+        };
+        (self.include.is_empty() || matches_any(&self.include)) && !matches_any(&self.exclude)
+    }
+}
+
+struct ExportTable {
+    schema: String,
+    name: String,
+    qualified: String,
+}
+
+impl ExportTable {
+    /// A `pg_dump` pattern that matches only this table: quoted names are taken literally.
+    fn dump_pattern(&self) -> String {
+        format!(
+            "\"{}\".\"{}\"",
+            self.schema.replace('"', "\"\""),
+            self.name.replace('"', "\"\"")
+        )
+    }
+}
+
+#[derive(Clone)]
+pub struct ExportService {
+    database_repo: DatabaseRepository,
+    project_repo: ProjectRepository,
+    docker: Arc<DockerManager>,
+    encryption_key: [u8; 32],
+}
+
+impl ExportService {
+    pub fn new(
+        database_repo: DatabaseRepository,
+        project_repo: ProjectRepository,
+        docker: Arc<DockerManager>,
+        encryption_key_hex: &str,
+    ) -> Self {
+        let encryption_key = crypto::parse_encryption_key(encryption_key_hex);
+
+        Self {
+            database_repo,
+            project_repo,
+            docker,
+            encryption_key,
+        }
+    }
+
+    fn decrypt_password(&self, encrypted: &str) -> AppResult<String> {
+        crypto::decrypt_password(&self.encryption_key, encrypted)
+    }
+
+    /// Starts the export and waits for its first chunk, so a dump that fails right away is
+    /// reported as an error response instead of a truncated download.
+    pub async fn export(
+        &self,
+        database_id: &str,
+        user_id: &str,
+        is_admin: bool,
+        query: &ExportQuery,
+    ) -> AppResult<DatabaseExport> {
+        let database = owned_database(
+            &self.database_repo,
+            &self.project_repo,
+            database_id,
+            user_id,
+            is_admin,
+        )
+        .await?;
+
+        let format = ExportFormat::parse(&database, query.format.as_deref())?;
+        let filter = TableFilter::parse(query)?;
+        if query.schema_only && query.data_only {
+            return Err(AppError::Validation(
+                "schema_only and data_only cannot be combined".to_string(),
+            ));
+        }
+        if engine_for(&database.database_type).is_key_value()
+            && (query.schema_only || query.data_only || !filter.is_empty())
+        {
+            return Err(AppError::Validation(
+                "Schema and table options only apply to PostgreSQL exports".to_string(),
+            ));
+        }
+        if format == ExportFormat::Csv && query.schema_only {
+            return Err(AppError::Validation(
+                "CSV exports always contain data".to_string(),
+            ));
+        }
+
+        if database.container_status != "running" {
+            return Err(AppError::Validation(
+                "Database must be running to export".to_string(),
+            ));
+        }
+        let container_id = database
+            .container_id
+            .clone()
+            .ok_or_else(|| AppError::Internal("Database has no container".to_string()))?;
+        let password = self.decrypt_password(
+            database
+                .password_encrypted
+                .as_ref()
+                .ok_or_else(|| AppError::Internal("Database password not found".to_string()))?,
+        )?;
+
+        let stream = match format {
+            ExportFormat::Sql | ExportFormat::Custom => {
+                let tables = self
+                    .dump_table_args(&container_id, &database.username, &password, &filter)
+                    .await?;
+                let cmd = pg_dump_command(&database.username, format, query, tables);
+                let exec = self
+                    .docker
+                    .stream_exec(&container_id, cmd, Some(pg_env(&password)))
+                    .await?;
+                exec_stdout(self.docker.clone(), exec, "pg_dump")
+            },
+            ExportFormat::Csv => {
+                self.csv_archive(&database, container_id, password, &filter)
+                    .await?
+            },
+            ExportFormat::Rdb => {
+                self.docker
+                    .kv_bgsave(
+                        &container_id,
+                        engine_for(&database.database_type).cli_name(),
+                        &password,
+                        SNAPSHOT_TIMEOUT_SECONDS,
+                    )
+                    .await?;
+                let cmd = vec![
+                    "cat".to_string(),
+                    format!("{}/dump.rdb", RedisContainer::data_mount_point()),
+                ];
+                let exec = self.docker.stream_exec(&container_id, cmd, None).await?;
+                exec_stdout(self.docker.clone(), exec, "cat")
+            },
+            ExportFormat::Json => self.kv_json(&database, container_id, password),
+        };
+
+        let mut stream = stream;
+        let stream: ExportStream = match stream.next().await {
+            Some(Err(e)) => return Err(e),
+            Some(Ok(first)) => Box::pin(futures::stream::once(async { Ok(first) }).chain(stream)),
+            None => Box::pin(futures::stream::empty()),
+        };
+
+        let timestamp = chrono::Utc::now().format("%Y%m%d%H%M%S");
+        Ok(DatabaseExport {
+            file_name: format!("{}-{}.{}", database.name, timestamp, format.extension()),
+            content_type: format.content_type(),
+            stream,
+        })
+    }
+
+    /// Lists user tables with their schema, name and quoted qualified name.
+    async fn list_tables(
+        &self,
+        container_id: &str,
+        username: &str,
+        password: &str,
+    ) -> AppResult<Vec<ExportTable>> {
+        let list_cmd = psql_rows_command(
+            username,
+            "SELECT schemaname, tablename, format('%I.%I', schemaname, tablename) \
+             FROM pg_tables WHERE schemaname NOT IN ('pg_catalog', 'information_schema') \
+             ORDER BY 1, 2",
+        );
+        let output = self
+            .docker
+            .run_exec(container_id, list_cmd, Some(pg_env(password)))
+            .await?;
+        if output.exit_code != Some(0) {
+            return Err(AppError::Docker(format!(
+                "Failed to list tables: {}",
+                output.stderr.trim()
+            )));
+        }
+
+        Ok(output
+            .stdout
+            .lines()
+            .filter_map(|line| {
+                let mut fields = line.split(FIELD_SEPARATOR);
+                Some(ExportTable {
+                    schema: fields.next()?.to_string(),
+                    name: fields.next()?.to_string(),
+                    qualified: fields.next()?.to_string(),
+                })
+            })
+            .collect())
+    }
+
+    /// Resolves the filter against the database's tables so `pg_dump` gets exact names and
+    /// selects the same tables as the CSV export.
+    async fn dump_table_args(
+        &self,
+        container_id: &str,
+        username: &str,
+        password: &str,
+        filter: &TableFilter,
+    ) -> AppResult<Vec<String>> {
+        if filter.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let tables = self.list_tables(container_id, username, password).await?;
+        if filter.include.is_empty() {
+            return Ok(tables
+                .iter()
+                .filter(|t| !filter.matches(&t.schema, &t.name))
+                .map(|t| format!("--exclude-table={}", t.dump_pattern()))
+                .collect());
+        }
+
+        let args: Vec<String> = tables
+            .iter()
+            .filter(|t| filter.matches(&t.schema, &t.name))
+            .map(|t| format!("--table={}", t.dump_pattern()))
+            .collect();
+        if args.is_empty() {
+            return Err(AppError::Validation(
+                "No tables match the export filter".to_string(),
+            ));
+        }
+        Ok(args)
+    }
+
+    /// Writes one CSV per table into a ZIP archive that is streamed while it is being built.
+    async fn csv_archive(
+        &self,
+        database: &Database,
+        container_id: String,
+        password: String,
+        filter: &TableFilter,
+    ) -> AppResult<ExportStream> {
+        let tables: Vec<(String, String)> = self
+            .list_tables(&container_id, &database.username, &password)
+            .await?
+            .into_iter()
+            .filter(|t| filter.matches(&t.schema, &t.name))
+            .map(|t| (format!("{}.{}.csv", t.schema, t.name), t.qualified))
+            .collect();
+        if tables.is_empty() {
+            return Err(AppError::Validation(
+                "No tables match the export filter".to_string(),
+            ));
+        }
+
+        let (writer, reader) = tokio::io::duplex(ZIP_BUFFER_SIZE);
+        let docker = self.docker.clone();
+        let username = database.username.clone();
+
+        let task = tokio::spawn(async move {
+            let mut zip = ZipFileWriter::with_tokio(writer);
+            for (entry_name, qualified) in tables {
+                let copy = format!("COPY {} TO STDOUT WITH (FORMAT csv, HEADER)", qualified);
+                let exec = docker
+                    .stream_exec(
+                        &container_id,
+                        psql_rows_command(&username, &copy),
+                        Some(pg_env(&password)),
+                    )
+                    .await?;
+                let mut rows = exec_stdout(docker.clone(), exec, "psql");
+
+                let mut entry = zip
+                    .write_entry_stream(ZipEntryBuilder::new(
+                        entry_name.into(),
+                        Compression::Deflate,
+                    ))
+                    .await
+                    .map_err(zip_error)?;
+                while let Some(chunk) = rows.next().await {
+                    entry.write_all(&chunk?).await.map_err(|e| {
+                        AppError::Internal(format!("Failed to write export archive: {}", e))
+                    })?;
+                }
+                entry.close().await.map_err(zip_error)?;
+            }
+            zip.close().await.map_err(zip_error)?;
+            Ok::<_, AppError>(())
+        });
+
+        let body = ReaderStream::new(reader).map(|chunk| {
+            chunk.map_err(|e| AppError::Internal(format!("Failed to read export archive: {}", e)))
+        });
+        let finished = futures::stream::once(async move {
+            match task.await {
+                Ok(result) => result,
+                Err(e) => Err(AppError::Internal(format!("Export task failed: {}", e))),
+            }
+        })
+        .filter_map(|result| futures::future::ready(result.err().map(Err)));
+
+        Ok(Box::pin(body.chain(finished)))
+    }
+
+    /// Streams every key as a JSON array, one SCAN batch at a time.
+    fn kv_json(&self, database: &Database, container_id: String, password: String) -> ExportStream {
+        let docker = self.docker.clone();
+        let cli = engine_for(&database.database_type).cli_name();
+
+        let batches = futures::stream::unfold(Some((String::from("0"), true)), move |state| {
+            let docker = docker.clone();
+            let container_id = container_id.clone();
+            let password = password.clone();
+            async move {
+                let (cursor, mut first) = state?;
+                let reply = docker
+                    .kv_command(
+                        &container_id,
+                        cli,
+                        &password,
+                        &["EVAL", KV_DUMP_SCRIPT, "0", &cursor, KV_SCAN_COUNT],
+                    )
+                    .await;
+                let (next_cursor, entries) = match reply.and_then(|r| parse_kv_batch(&r)) {
+                    Ok(batch) => batch,
+                    Err(e) => return Some((Err(e), None)),
+                };
+
+                let mut chunk = String::new();
+                for entry in entries {
+                    if !first {
+                        chunk.push(',');
+                    }
+                    chunk.push_str(&entry.to_string());
+                    first = false;
+                }
+
+                let finished = next_cursor == "0";
+                let next = (!finished).then_some((next_cursor, first));
+                if finished {
+                    chunk.push(']');
+                }
+                Some((Ok(chunk), next))
+            }
+        });
+
+        let body = futures::stream::once(async { Ok(String::from("[")) })
+            .chain(batches)
+            .filter(|chunk| futures::future::ready(!matches!(chunk, Ok(c) if c.is_empty())))
+            .map(|chunk| chunk.map(Bytes::from));
+        Box::pin(body)
+    }
+}
+
+/// Forwards the exec's stdout and fails the stream at the end if the command exited non-zero.
+fn exec_stdout(docker: Arc<DockerManager>, exec: ExecStream, name: &'static str) -> ExportStream {
+    let (stdout, exit) = exec.split();
+    let exit = futures::stream::once(async move { exit.check(&docker, name).await })
+        .filter_map(|result| futures::future::ready(result.err().map(Err)));
+
+    Box::pin(stdout.chain(exit))
+}
+
+fn pg_env(password: &str) -> Vec<String> {
+    vec![format!("PGPASSWORD={}", password)]
+}
+
+fn pg_dump_command(
+    username: &str,
+    format: ExportFormat,
+    query: &ExportQuery,
+    tables: Vec<String>,
+) -> Vec<String> {
+    let mut cmd = vec![
+        "pg_dump".to_string(),
+        "-U".to_string(),
+        username.to_string(),
+        "-d".to_string(),
+        "postgres".to_string(),
+        if format == ExportFormat::Custom {
+            "-Fc".to_string()
+        } else {
+            "-Fp".to_string()
+        },
+        "--no-owner".to_string(),
+        "--no-privileges".to_string(),
+    ];
+    if query.schema_only {
+        cmd.push("--schema-only".to_string());
+    }
+    if query.data_only {
+        cmd.push("--data-only".to_string());
+    }
+    cmd.extend(tables);
+    cmd
+}
+
+/// `psql` printing rows with their fields separated by `FIELD_SEPARATOR`.
+fn psql_rows_command(username: &str, sql: &str) -> Vec<String> {
+    let mut cmd = psql_command(username, "postgres", &[sql]);
+    cmd.push("-F".to_string());
+    cmd.push(FIELD_SEPARATOR.to_string());
+    cmd
+}
+
+fn parse_patterns(value: Option<&str>) -> AppResult<Vec<String>> {
+    let Some(value) = value else {
+        return Ok(Vec::new());
+    };
+
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|p| !p.is_empty())
+        .map(|p| {
+            if p.chars().any(char::is_control) {
+                return Err(AppError::Validation(format!(
+                    "Invalid table pattern '{}'",
+                    p.escape_debug()
+                )));
+            }
+            Ok(p.to_string())
+        })
+        .collect()
+}
+
+fn glob_match(pattern: &str, text: &str) -> bool {
+    match pattern.split_once('*') {
+        None => pattern == text,
+        Some((prefix, rest)) => {
+            let Some(text) = text.strip_prefix(prefix) else {
+                return false;
+            };
+            (0..=text.len())
+                .filter(|&i| text.is_char_boundary(i))
+                .any(|i| glob_match(rest, &text[i..]))
+        },
+    }
+}
+
+fn parse_kv_batch(reply: &str) -> AppResult<(String, Vec<Value>)> {
+    let invalid = || AppError::Internal("Unexpected reply from key dump script".to_string());
+
+    let parsed: Value = serde_json::from_str(reply.trim()).map_err(|_| invalid())?;
+    let cursor = parsed
+        .get(0)
+        .and_then(Value::as_str)
+        .ok_or_else(invalid)?
+        .to_string();
+
+    // cjson encodes an empty Lua table as an object.
+    let raw_entries = match parsed.get(1) {
+        Some(Value::Array(entries)) => entries.clone(),
+        Some(Value::Object(map)) if map.is_empty() => Vec::new(),
+        _ => return Err(invalid()),
+    };
+
+    let entries = raw_entries
+        .into_iter()
+        .map(|entry| {
+            let [key, kind, ttl, value] =
+                <[Value; 4]>::try_from(entry.as_array().cloned()?).ok()?;
+            let kind = kind.as_str()?.to_string();
+            let ttl_ms = ttl.as_i64().filter(|ttl| *ttl >= 0);
+            Some(json!({
+                "key": key,
+                "type": kind,
+                "ttl_ms": ttl_ms,
+                "value": normalize_kv_value(&kind, value),
+            }))
+        })
+        .collect::<Option<Vec<_>>>()
+        .ok_or_else(invalid)?;
+
+    Ok((cursor, entries))
+}
+
+fn normalize_kv_value(kind: &str, value: Value) -> Value {
+    let Value::Array(items) = value else {
+        return value;
+    };
+
+    match kind {
+        "hash" => Value::Object(
+            items
+                .chunks(2)
+                .filter_map(|pair| match pair {
+                    [Value::String(field), value] => Some((field.clone(), value.clone())),
+                    _ => None,
+                })
+                .collect::<Map<_, _>>(),
+        ),
+        "zset" => Value::Array(
+            items
+                .chunks(2)
+                .filter_map(|pair| match pair {
+                    [member, Value::String(score)] => Some(json!({
+                        "member": member,
+                        "score": score.parse::<f64>().ok(),
+                    })),
+                    _ => None,
+                })
+                .collect(),
+        ),
+        _ => Value::Array(items),
+    }
+}
+
+fn zip_error(e: async_zip::error::ZipError) -> AppError {
+    AppError::Internal(format!("Failed to write export archive: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_table_filter() {
+        let query = ExportQuery {
+            tables: Some("public.user*, audit".to_string()),
+            exclude_tables: Some("*_archive".to_string()),
+            ..Default::default()
+        };
+        let filter = TableFilter::parse(&query).unwrap();
+
+        assert!(filter.matches("public", "users"));
+        assert!(filter.matches("reporting", "audit"));
+        assert!(!filter.matches("public", "users_archive"));
+        assert!(!filter.matches("private", "users"));
+        assert!(glob_match("*", ""));
+        assert!(!glob_match("a*c", "abd"));
+
+        let table = ExportTable {
+            schema: "public".to_string(),
+            name: "odd\"name*".to_string(),
+            qualified: String::new(),
+        };
+        assert_eq!(table.dump_pattern(), r#""public"."odd""name*""#);
+
+        let invalid = ExportQuery {
+            tables: Some("users\n--".to_string()),
+            ..Default::default()
+        };
+        assert!(TableFilter::parse(&invalid).is_err());
+    }
+
+    #[test]
+    fn test_parse_kv_batch() {
+        let reply = r#"["17",[["h","hash",-1,["a","1","b","2"]],["z","zset",1500,["m","2.5"]],["s","string",-1,"v"]]]"#;
+        let (cursor, entries) = parse_kv_batch(reply).unwrap();
+
+        assert_eq!(cursor, "17");
+        assert_eq!(
+            entries[0],
+            json!({"key": "h", "type": "hash", "ttl_ms": null, "value": {"a": "1", "b": "2"}})
+        );
+        assert_eq!(entries[1]["value"], json!([{"member": "m", "score": 2.5}]));
+        assert_eq!(entries[1]["ttl_ms"], json!(1500));
+        assert_eq!(entries[2]["value"], json!("v"));
+
+        assert_eq!(
+            parse_kv_batch(r#"["0",{}]"#).unwrap(),
+            ("0".to_string(), Vec::new())
+        );
+        assert!(parse_kv_batch("ERR").is_err());
+    }
+}
@@ -0,0 +1,490 @@
+use std::sync::Arc;
+
+use tokio_postgres::{Client, NoTls};
+
+use super::common::{owned_database, quote_ident};
+use super::DatabaseService;
+use crate::domain::engines::engine_for;
+use crate::domain::models::{
+    Database, ExtensionInfo, ExtensionsResponse, InstallExtensionRequest, Operation,
+    OperationResponse, PostgresVariant, DEFAULT_PRELOAD_LIBRARIES, PRELOAD_EXTENSIONS,
+};
+use crate::error::{AppError, AppResult};
+use crate::infrastructure::docker::DockerManager;
+use crate::repositories::{DatabaseRepository, OperationRepository, ProjectRepository};
+use crate::utils::crypto;
+
+const RESTART_TIMEOUT_SECONDS: u64 = 120;
+
+pub const PRELOAD_LIBRARIES_OPERATION_KIND: &str = "preload_libraries";
+
+const EXTENSIONS_QUERY: &str = "SELECT name, default_version, installed_version, comment \
+     FROM pg_available_extensions ORDER BY name";
+
+/// Lists, installs and removes PostgreSQL extensions and manages the libraries the server
+/// preloads, which only take effect after a restart.
+#[derive(Clone)]
+pub struct ExtensionService {
+    database_repo: DatabaseRepository,
+    project_repo: ProjectRepository,
+    operation_repo: OperationRepository,
+    database_service: Arc<DatabaseService>,
+    docker: Arc<DockerManager>,
+    encryption_key: [u8; 32],
+}
+
+impl ExtensionService {
+    pub fn new(
+        database_repo: DatabaseRepository,
+        project_repo: ProjectRepository,
+        operation_repo: OperationRepository,
+        database_service: Arc<DatabaseService>,
+        docker: Arc<DockerManager>,
+        encryption_key_hex: &str,
+    ) -> Self {
+        let encryption_key = crypto::parse_encryption_key(encryption_key_hex);
+
+        Self {
+            database_repo,
+            project_repo,
+            operation_repo,
+            database_service,
+            docker,
+            encryption_key,
+        }
+    }
+
+    fn decrypt_password(&self, encrypted: &str) -> AppResult<String> {
+        crypto::decrypt_password(&self.encryption_key, encrypted)
+    }
+
+    async fn get_database(
+        &self,
+        database_id: &str,
+        user_id: &str,
+        is_admin: bool,
+    ) -> AppResult<Database> {
+        let database = owned_database(
+            &self.database_repo,
+            &self.project_repo,
+            database_id,
+            user_id,
+            is_admin,
+        )
+        .await?;
+
+        let engine = engine_for(&database.database_type);
+        if !engine.supports_extensions() {
+            return Err(AppError::Validation(format!(
+                "Extensions are not available for {} databases",
+                engine.display_name()
+            )));
+        }
+
+        Ok(database)
+    }
+
+    async fn connect(&self, database: &Database) -> AppResult<Client> {
+        if database.container_status != "running" {
+            return Err(AppError::Conflict("Database is not running".to_string()));
+        }
+        if database.is_replica {
+            return Err(AppError::Conflict(
+                "Replicas are read-only. Manage extensions on the parent or detach the replica"
+                    .to_string(),
+            ));
+        }
+
+        let password = database
+            .password_encrypted
+            .as_ref()
+            .ok_or_else(|| AppError::Internal("Database password not found".to_string()))
+            .and_then(|p| self.decrypt_password(p))?;
+
+        let container_name = database.container_name();
+        let connection_string = format!(
+            "host={} port={} user={} password={} dbname=postgres connect_timeout=10",
+            container_name,
+            engine_for(&database.database_type).internal_port(),
+            database.username,
+            password
+        );
+
+        let (client, connection) = tokio_postgres::connect(&connection_string, NoTls)
+            .await
+            .map_err(|e| {
+                tracing::error!("PostgreSQL connection failed to {}: {}", container_name, e);
+                AppError::Internal(format!("Failed to connect to database: {}", e))
+            })?;
+
+        tokio::spawn(async move {
+            if let Err(e) = connection.await {
+                tracing::error!("Database connection error: {}", e);
+            }
+        });
+
+        Ok(client)
+    }
+
+    async fn available_extensions(&self, client: &Client) -> AppResult<Vec<ExtensionInfo>> {
+        let rows = client
+            .query(EXTENSIONS_QUERY, &[])
+            .await
+            .map_err(|e| AppError::Internal(format!("Failed to list extensions: {}", e)))?;
+
+        Ok(rows
+            .iter()
+            .map(|row| {
+                let name: String = row.get(0);
+                ExtensionInfo {
+                    requires_preload: PRELOAD_EXTENSIONS.contains(&name.as_str()),
+                    name,
+                    default_version: row.get(1),
+                    installed_version: row.get(2),
+                    comment: row.get(3),
+                }
+            })
+            .collect())
+    }
+
+    pub async fn list(
+        &self,
+        database_id: &str,
+        user_id: &str,
+        is_admin: bool,
+    ) -> AppResult<ExtensionsResponse> {
+        let database = self.get_database(database_id, user_id, is_admin).await?;
+        let client = self.connect(&database).await?;
+        let extensions = self.available_extensions(&client).await?;
+
+        Ok(ExtensionsResponse {
+            variant: database.variant(),
+            shared_preload_libraries: database.preload_libraries(),
+            database_id: database.id,
+            extensions,
+        })
+    }
+
+    pub async fn install(
+        &self,
+        database_id: &str,
+        user_id: &str,
+        is_admin: bool,
+        request: &InstallExtensionRequest,
+    ) -> AppResult<ExtensionInfo> {
+        let database = self.get_database(database_id, user_id, is_admin).await?;
+        if let Some(version) = &request.version {
+            if version.is_empty()
+                || !version
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_'))
+            {
+                return Err(AppError::Validation(
+                    "Invalid extension version".to_string(),
+                ));
+            }
+        }
+
+        let client = self.connect(&database).await?;
+        let extension = self
+            .available_extensions(&client)
+            .await?
+            .into_iter()
+            .find(|extension| extension.name == request.name)
+            .ok_or_else(|| missing_extension(&request.name))?;
+
+        if extension.installed_version.is_some() {
+            return Err(AppError::Conflict(format!(
+                "Extension '{}' is already installed",
+                extension.name
+            )));
+        }
+        if extension.requires_preload
+            && !database
+                .preload_libraries()
+                .iter()
+                .any(|library| library == &extension.name)
+        {
+            return Err(AppError::Validation(format!(
+                "Extension '{}' must be added to shared_preload_libraries first",
+                extension.name
+            )));
+        }
+
+        let mut statement = format!("CREATE EXTENSION {}", quote_ident(&extension.name));
+        if let Some(schema) = &request.schema {
+            statement.push_str(&format!(" SCHEMA {}", quote_ident(schema)));
+        }
+        if let Some(version) = &request.version {
+            statement.push_str(&format!(" VERSION '{}'", version));
+        }
+        if request.cascade {
+            statement.push_str(" CASCADE");
+        }
+
+        client.batch_execute(&statement).await.map_err(|e| {
+            AppError::Validation(format!(
+                "Failed to install extension '{}': {}",
+                extension.name,
+                db_error_message(&e)
+            ))
+        })?;
+
+        self.available_extensions(&client)
+            .await?
+            .into_iter()
+            .find(|installed| installed.name == extension.name)
+            .ok_or_else(|| missing_extension(&extension.name))
+    }
+
+    pub async fn remove(
+        &self,
+        database_id: &str,
+        user_id: &str,
+        is_admin: bool,
+        name: &str,
+        cascade: bool,
+    ) -> AppResult<()> {
+        let database = self.get_database(database_id, user_id, is_admin).await?;
+        if DEFAULT_PRELOAD_LIBRARIES.contains(&name) {
+            return Err(AppError::Validation(format!(
+                "Extension '{}' is used for query metrics and cannot be removed",
+                name
+            )));
+        }
+
+        let client = self.connect(&database).await?;
+        let installed = self
+            .available_extensions(&client)
+            .await?
+            .into_iter()
+            .any(|extension| extension.name == name && extension.installed_version.is_some());
+        if !installed {
+            return Err(AppError::NotFound(format!(
+                "Extension '{}' is not installed",
+                name
+            )));
+        }
+
+        let mut statement = format!("DROP EXTENSION {}", quote_ident(name));
+        if cascade {
+            statement.push_str(" CASCADE");
+        }
+
+        client.batch_execute(&statement).await.map_err(|e| {
+            AppError::Conflict(format!(
+                "Failed to remove extension '{}': {}",
+                name,
+                db_error_message(&e)
+            ))
+        })
+    }
+
+    /// Stores the libraries and recreates the container with them. A running database is
+    /// restarted, and if it does not come back healthy the previous libraries are restored.
+    /// Changes the libraries the server preloads in the background, recreating the container
+    /// and restarting a running database. A database that does not come up with the new
+    /// libraries gets the previous ones back.
+    pub async fn update_preload_libraries(
+        &self,
+        database_id: &str,
+        user_id: &str,
+        is_admin: bool,
+        libraries: &[String],
+    ) -> AppResult<OperationResponse> {
+        let database = self.get_database(database_id, user_id, is_admin).await?;
+        let libraries = normalize_libraries(libraries)?;
+
+        if !matches!(database.container_status.as_str(), "running" | "stopped") {
+            return Err(AppError::Conflict(format!(
+                "Database is {}",
+                database.container_status
+            )));
+        }
+        if self.operation_repo.has_running(&database.id).await? {
+            return Err(AppError::Conflict(
+                "Another operation is running for this database".to_string(),
+            ));
+        }
+
+        let operation = self
+            .operation_repo
+            .start(&database.id, PRELOAD_LIBRARIES_OPERATION_KIND, None, None)
+            .await?;
+        if libraries == database.preload_libraries() {
+            self.operation_repo.mark_completed(&operation.id).await?;
+            return Ok(operation.to_response());
+        }
+        self.database_repo
+            .update_status(&database.id, "updating")
+            .await?;
+
+        let service = self.clone();
+        let running = operation.clone();
+        tokio::spawn(async move {
+            service
+                .execute_preload_libraries(database, libraries.join(","), running)
+                .await;
+        });
+
+        Ok(operation.to_response())
+    }
+
+    async fn execute_preload_libraries(
+        &self,
+        database: Database,
+        libraries: String,
+        operation: Operation,
+    ) {
+        // Read before the status became `updating`.
+        let was_running = database.container_status == "running";
+        let previous = database.preload_libraries().join(",");
+
+        let result = match self
+            .apply_preload_libraries(&database, &libraries, was_running)
+            .await
+        {
+            Ok(()) => Ok(()),
+            Err(e) => {
+                tracing::warn!(
+                    "Restoring shared_preload_libraries of {} after failed change: {}",
+                    database.id,
+                    e
+                );
+                if let Err(restore) = self
+                    .apply_preload_libraries(&database, &previous, was_running)
+                    .await
+                {
+                    tracing::error!(
+                        "Failed to restore shared_preload_libraries of {}: {}",
+                        database.id,
+                        restore
+                    );
+                }
+                Err(AppError::Internal(format!(
+                    "The database did not start with the new libraries, the previous ones were restored: {}",
+                    e
+                )))
+            },
+        };
+
+        let status = if was_running { "running" } else { "stopped" };
+        if let Err(e) = self.database_repo.update_status(&database.id, status).await {
+            tracing::error!("Failed to update status of database {}: {}", database.id, e);
+        }
+        self.operation_repo.finish(&operation, result).await;
+    }
+
+    async fn apply_preload_libraries(
+        &self,
+        database: &Database,
+        libraries: &str,
+        start: bool,
+    ) -> AppResult<()> {
+        self.database_repo
+            .update_preload_libraries(&database.id, libraries)
+            .await?;
+        let updated = Database {
+            shared_preload_libraries: Some(libraries.to_string()),
+            ..database.clone()
+        };
+        self.database_service.recreate_container(&updated).await?;
+        if !start {
+            return Ok(());
+        }
+
+        let container_id = self
+            .database_repo
+            .find_by_id(&database.id)
+            .await?
+            .and_then(|database| database.container_id)
+            .ok_or_else(|| AppError::Internal("Database has no container".to_string()))?;
+        self.docker.start_container(&container_id).await?;
+        if !self
+            .docker
+            .wait_for_healthy(&container_id, RESTART_TIMEOUT_SECONDS)
+            .await?
+        {
+            return Err(AppError::Docker(
+                "Database did not become healthy after the restart".to_string(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+fn missing_extension(name: &str) -> AppError {
+    match PostgresVariant::providing(name) {
+        Some(variant) => AppError::NotFound(format!(
+            "Extension '{}' is not available in this image. Create the database on the {} variant to use it",
+            name,
+            variant.display_name()
+        )),
+        None => AppError::NotFound(format!(
+            "Extension '{}' is not available in this image",
+            name
+        )),
+    }
+}
+
+/// Deduplicates the libraries and keeps the defaults first, since metrics rely on them.
+fn normalize_libraries(libraries: &[String]) -> AppResult<Vec<String>> {
+    let mut normalized: Vec<String> = DEFAULT_PRELOAD_LIBRARIES
+        .iter()
+        .map(|library| library.to_string())
+        .collect();
+
+    for library in libraries {
+        let library = library.trim();
+        if library.is_empty()
+            || library.len() > 63
+            || !library
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+        {
+            return Err(AppError::Validation(format!(
+                "Invalid library name '{}'",
+                library
+            )));
+        }
+        if !normalized.iter().any(|existing| existing == library) {
+            normalized.push(library.to_string());
+        }
+    }
+
+    Ok(normalized)
+}
+
+fn db_error_message(error: &tokio_postgres::Error) -> String {
+    error
+        .as_db_error()
+        .map(|db| db.message().to_string())
+        .unwrap_or_else(|| error.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_libraries() {
+        let libraries = normalize_libraries(&[
+            "timescaledb".to_string(),
+            " pg_cron ".to_string(),
+            "pg_stat_statements".to_string(),
+            "timescaledb".to_string(),
+        ])
+        .unwrap();
+        assert_eq!(libraries, ["pg_stat_statements", "timescaledb", "pg_cron"]);
+
+        assert_eq!(normalize_libraries(&[]).unwrap(), ["pg_stat_statements"]);
+        assert!(normalize_libraries(&["a,b".to_string()]).is_err());
+        assert!(normalize_libraries(&["x' ; DROP".to_string()]).is_err());
+    }
+
+    #[test]
+    fn test_quote_ident() {
+        assert_eq!(quote_ident("uuid-ossp"), "\"uuid-ossp\"");
+        assert_eq!(quote_ident("a\"b"), "\"a\"\"b\"");
+    }
+}
@@ -0,0 +1,184 @@
+use crate::domain::models::{FakeFormat, MaskingConfig, MaskingRule, MaskingStrategy};
+
+/// One statement per rule that changes data. Each only runs if its column exists, so a project's
+/// rules can cover tables that some of its databases do not have. `salt` must be hex.
+pub(super) fn masking_statements(config: &MaskingConfig, salt: &str) -> Vec<String> {
+    config
+        .rules
+        .iter()
+        .filter_map(|rule| {
+            let update = update_statement(rule, salt)?;
+            Some(format!(
+                "DO $mask$ BEGIN IF EXISTS (SELECT 1 FROM information_schema.columns \
+                 WHERE table_schema = {} AND table_name = {} AND column_name = {}) \
+                 THEN EXECUTE $stmt${}$stmt$; END IF; END $mask$",
+                literal(&rule.schema),
+                literal(&rule.table),
+                literal(&rule.column),
+                update
+            ))
+        })
+        .collect()
+}
+
+/// A block that fails, before anything is changed, when a hash or fake rule targets a column
+/// that cannot hold the generated text. `None` when no rule generates text.
+pub(super) fn masking_type_check(config: &MaskingConfig) -> Option<String> {
+    let targets: Vec<String> = config
+        .rules
+        .iter()
+        .filter_map(|rule| {
+            Some(format!(
+                "({}, {}, {}, {})",
+                literal(&rule.schema),
+                literal(&rule.table),
+                literal(&rule.column),
+                generated_length(rule)?
+            ))
+        })
+        .collect();
+    if targets.is_empty() {
+        return None;
+    }
+
+    Some(format!(
+        "DO $check$ DECLARE invalid text; BEGIN \
+         SELECT string_agg(format('%I.%I.%I (%s)', c.table_schema, c.table_name, c.column_name, c.data_type), ', ') \
+         INTO invalid FROM information_schema.columns c \
+         JOIN (VALUES {}) AS r(schema_name, table_name, column_name, length) \
+         ON c.table_schema = r.schema_name AND c.table_name = r.table_name AND c.column_name = r.column_name \
+         WHERE c.udt_name NOT IN ('text', 'varchar', 'bpchar', 'citext') OR c.character_maximum_length < r.length; \
+         IF invalid IS NOT NULL THEN \
+         RAISE EXCEPTION 'Masking rules that hash or fake values need text columns long enough for them: %', invalid; \
+         END IF; END $check$",
+        targets.join(", ")
+    ))
+}
+
+/// A query listing a `VACUUM FULL` statement for every existing table that rules change, which
+/// rewrites it without the dead row versions that still hold the original values.
+pub(super) fn vacuum_statements_query(config: &MaskingConfig) -> Option<String> {
+    let mut tables: Vec<String> = config
+        .rules
+        .iter()
+        .filter(|rule| rule.strategy != MaskingStrategy::Keep)
+        .map(|rule| format!("({}, {})", literal(&rule.schema), literal(&rule.table)))
+        .collect();
+    tables.sort();
+    tables.dedup();
+    if tables.is_empty() {
+        return None;
+    }
+
+    Some(format!(
+        "SELECT format('VACUUM FULL %I.%I', table_schema, table_name) \
+         FROM information_schema.tables WHERE (table_schema, table_name) IN ({})",
+        tables.join(", ")
+    ))
+}
+
+/// The length of the text a rule writes, for rules that write text.
+fn generated_length(rule: &MaskingRule) -> Option<i32> {
+    match rule.strategy {
+        MaskingStrategy::Keep | MaskingStrategy::Nullify | MaskingStrategy::Shuffle => None,
+        MaskingStrategy::Hash => Some(64),
+        MaskingStrategy::Fake => Some(match rule.fake.unwrap_or_default() {
+            FakeFormat::Email => 29,
+            FakeFormat::Phone => 12,
+            FakeFormat::Name => 15,
+            FakeFormat::Text => 16,
+        }),
+    }
+}
+
+fn update_statement(rule: &MaskingRule, salt: &str) -> Option<String> {
+    let table = format!("{}.{}", ident(&rule.schema), ident(&rule.table));
+    let column = ident(&rule.column);
+    let digest = format!(
+        "encode(sha256(convert_to('{}' || {}::text, 'UTF8')), 'hex')",
+        salt, column
+    );
+
+    let value = match rule.strategy {
+        MaskingStrategy::Keep => return None,
+        MaskingStrategy::Nullify => {
+            return Some(format!("UPDATE {} SET {} = NULL", table, column));
+        },
+        MaskingStrategy::Shuffle => {
+            return Some(format!(
+                "WITH shuffled AS (SELECT ctid AS row_id, row_number() OVER (ORDER BY random()) AS n FROM {table}), \
+                 original AS (SELECT {column} AS value, row_number() OVER () AS n FROM {table}) \
+                 UPDATE {table} AS masked SET {column} = original.value \
+                 FROM shuffled JOIN original USING (n) WHERE masked.ctid = shuffled.row_id"
+            ));
+        },
+        MaskingStrategy::Hash => digest,
+        MaskingStrategy::Fake => match rule.fake.unwrap_or_default() {
+            FakeFormat::Email => format!("'user_' || left({}, 12) || '@example.com'", digest),
+            FakeFormat::Phone => format!(
+                "'+1555' || translate(left({}, 7), 'abcdef', '012345')",
+                digest
+            ),
+            FakeFormat::Name => format!("'Person ' || upper(left({}, 8))", digest),
+            FakeFormat::Text => format!("left({}, 16)", digest),
+        },
+    };
+
+    Some(format!(
+        "UPDATE {} SET {} = {} WHERE {} IS NOT NULL",
+        table, column, value, column
+    ))
+}
+
+fn ident(name: &str) -> String {
+    format!("\"{}\"", name.replace('"', "\"\""))
+}
+
+fn literal(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "''"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_masking_statements() {
+        let settings = serde_json::json!({
+            "masking": {
+                "rules": [
+                    { "table": "users", "column": "email", "strategy": "fake", "fake": "email" },
+                    { "table": "users", "column": "id", "strategy": "keep" },
+                    { "schema": "crm", "table": "o'brien", "column": "phone", "strategy": "nullify" },
+                    { "table": "users", "column": "city", "strategy": "shuffle" }
+                ]
+            }
+        });
+        let config = MaskingConfig::from_project_settings(&settings).unwrap();
+        let statements = masking_statements(&config, "ab12");
+
+        assert_eq!(statements.len(), 3);
+        assert!(statements[0].contains("table_schema = 'public' AND table_name = 'users'"));
+        assert!(statements[0].contains(
+            "UPDATE \"public\".\"users\" SET \"email\" = 'user_' || left(encode(sha256(convert_to('ab12' || \"email\"::text"
+        ));
+        assert!(statements[1].contains("table_name = 'o''brien'"));
+        assert!(statements[1].contains("UPDATE \"crm\".\"o'brien\" SET \"phone\" = NULL"));
+        assert!(statements[2].contains("ORDER BY random()"));
+        assert_eq!(config.fingerprint().len(), 12);
+
+        let check = masking_type_check(&config).unwrap();
+        assert!(check.contains("VALUES ('public', 'users', 'email', 29))"));
+        let vacuum = vacuum_statements_query(&config).unwrap();
+        assert!(vacuum.contains("IN (('crm', 'o''brien'), ('public', 'users'))"));
+
+        let invalid = serde_json::json!({
+            "masking": { "rules": [{ "table": "a$b", "column": "c", "strategy": "hash" }] }
+        });
+        assert!(MaskingConfig::from_project_settings(&invalid).is_err());
+        let misplaced = serde_json::json!({
+            "masking": { "rules": [{ "table": "t", "column": "c", "strategy": "hash", "fake": "email" }] }
+        });
+        assert!(MaskingConfig::from_project_settings(&misplaced).is_err());
+    }
+}
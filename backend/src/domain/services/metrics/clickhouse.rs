@@ -0,0 +1,201 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use chrono::Utc;
+
+use super::super::sql_clickhouse::{int, text, ClickhouseHttp, USER_DATABASES};
+use super::MetricsCollector;
+use crate::domain::models::{
+    ConnectionMetrics, Database, DatabaseMetrics, QueryMetrics, ResourceMetrics, RowMetrics,
+    StorageMetrics, TableMetrics, UnifiedMetrics,
+};
+use crate::error::{AppError, AppResult};
+use crate::infrastructure::docker::ContainerStats;
+use crate::utils::crypto;
+
+/// Collects ClickHouse metrics over its HTTP interface. `system.events` counts since the server
+/// started and `system.metrics` holds current values.
+pub struct ClickhouseMetricsCollector {
+    encryption_key: Arc<[u8; 32]>,
+}
+
+impl ClickhouseMetricsCollector {
+    pub fn new(encryption_key: [u8; 32]) -> Self {
+        Self {
+            encryption_key: Arc::new(encryption_key),
+        }
+    }
+
+    fn decrypt_password(&self, encrypted: &str) -> AppResult<String> {
+        crypto::decrypt_password(&self.encryption_key, encrypted)
+    }
+
+    fn client(&self, database: &Database) -> AppResult<ClickhouseHttp> {
+        let encrypted = database
+            .password_encrypted
+            .as_ref()
+            .ok_or_else(|| AppError::Internal("Database password not found".to_string()))?;
+
+        let password = self.decrypt_password(encrypted)?;
+        ClickhouseHttp::new(database, &password)
+    }
+
+    /// Reads name/value pairs of `system.events` or `system.metrics`.
+    async fn counters(http: &ClickhouseHttp, sql: &str) -> HashMap<String, i64> {
+        match http.fetch(sql).await {
+            Ok(result) => result
+                .data
+                .iter()
+                .filter_map(|row| Some((text(row, 0)?, int(row, 1))))
+                .collect(),
+            Err(e) => {
+                tracing::warn!("Failed to read ClickHouse counters: {}", e);
+                HashMap::new()
+            },
+        }
+    }
+
+    async fn collect_clickhouse_metrics(&self, http: &ClickhouseHttp) -> ClickhouseMetrics {
+        let mut metrics = ClickhouseMetrics::default();
+
+        let events = Self::counters(
+            http,
+            "SELECT event, toInt64(value) FROM system.events \
+             WHERE event IN ('Query', 'QueryTimeMicroseconds', 'SelectedRows', 'InsertedRows')",
+        )
+        .await;
+        let current = Self::counters(
+            http,
+            "SELECT metric, toInt64(value) FROM system.metrics \
+             WHERE metric IN ('Query', 'TCPConnection', 'HTTPConnection', 'MySQLConnection', \
+                              'PostgreSQLConnection')",
+        )
+        .await;
+        let event = |name: &str| events.get(name).copied().unwrap_or(0);
+        let metric = |name: &str| current.get(name).copied().unwrap_or(0);
+
+        metrics.queries.total_queries = event("Query");
+        if metrics.queries.total_queries > 0 {
+            metrics.queries.avg_latency_ms = event("QueryTimeMicroseconds") as f64
+                / metrics.queries.total_queries as f64
+                / 1000.0;
+        }
+        metrics.rows.rows_read = event("SelectedRows");
+        metrics.rows.rows_written = event("InsertedRows");
+
+        if let Ok(result) = http
+            .fetch(
+                "SELECT toInt64(uptime()), toInt64OrZero((SELECT value FROM system.server_settings \
+                 WHERE name = 'max_connections'))",
+            )
+            .await
+        {
+            if let Some(row) = result.data.first() {
+                let uptime = int(row, 0);
+                if uptime > 0 {
+                    metrics.queries.queries_per_sec =
+                        metrics.queries.total_queries as f64 / uptime as f64;
+                }
+                metrics.connections.max_connections = int(row, 1) as i32;
+            }
+        }
+
+        let connections = &mut metrics.connections;
+        let total = (metric("TCPConnection")
+            + metric("HTTPConnection")
+            + metric("MySQLConnection")
+            + metric("PostgreSQLConnection")) as i32;
+        connections.active_connections = metric("Query") as i32;
+        connections.idle_connections = (total - connections.active_connections).max(0);
+        connections.connection_percent = if connections.max_connections > 0 {
+            (total as f64 / connections.max_connections as f64) * 100.0
+        } else {
+            0.0
+        };
+
+        if let Ok(result) = http
+            .fetch(&format!(
+                "SELECT toInt64(count()), toInt64(sum(ifNull(total_rows, 0))), \
+                        toInt64(max(ifNull(total_bytes, 0))), toInt64(sum(ifNull(total_bytes, 0))) \
+                 FROM system.tables \
+                 WHERE {} AND NOT is_temporary AND engine NOT LIKE '%View'",
+                USER_DATABASES
+            ))
+            .await
+        {
+            if let Some(row) = result.data.first() {
+                metrics.tables.total_tables = int(row, 0);
+                metrics.rows.total_rows = int(row, 1);
+                metrics.tables.largest_table_bytes = int(row, 2);
+                metrics.database_size_bytes = int(row, 3);
+            }
+        }
+
+        if let Ok(result) = http
+            .fetch(&format!(
+                "SELECT toInt64(count()) FROM system.data_skipping_indices WHERE {}",
+                USER_DATABASES
+            ))
+            .await
+        {
+            metrics.tables.total_indexes = result.data.first().map(|row| int(row, 0)).unwrap_or(0);
+        }
+
+        metrics
+    }
+
+    pub async fn collect_metrics(
+        &self,
+        database: &Database,
+        docker_stats: &ContainerStats,
+    ) -> AppResult<UnifiedMetrics> {
+        let clickhouse_metrics = match self.client(database) {
+            Ok(http) => self.collect_clickhouse_metrics(&http).await,
+            Err(e) => {
+                tracing::warn!("Failed to create ClickHouse client for metrics: {}", e);
+                ClickhouseMetrics::default()
+            },
+        };
+
+        Ok(UnifiedMetrics::Clickhouse(DatabaseMetrics {
+            timestamp: Utc::now().to_rfc3339(),
+            queries: clickhouse_metrics.queries,
+            rows: clickhouse_metrics.rows,
+            tables: clickhouse_metrics.tables,
+            storage: StorageMetrics::new(
+                clickhouse_metrics.database_size_bytes,
+                database.storage_used_bytes,
+                database.storage_limit_mb,
+            ),
+            connections: clickhouse_metrics.connections,
+            standby: None,
+            resources: ResourceMetrics {
+                cpu_percent: docker_stats.cpu_percent,
+                memory_used_bytes: docker_stats.memory_used_bytes,
+                memory_limit_bytes: docker_stats.memory_limit_bytes,
+                memory_percent: docker_stats.memory_percent,
+            },
+        }))
+    }
+}
+
+#[derive(Default)]
+struct ClickhouseMetrics {
+    queries: QueryMetrics,
+    rows: RowMetrics,
+    tables: TableMetrics,
+    connections: ConnectionMetrics,
+    database_size_bytes: i64,
+}
+
+#[async_trait]
+impl MetricsCollector for ClickhouseMetricsCollector {
+    async fn collect_metrics(
+        &self,
+        database: &Database,
+        docker_stats: &ContainerStats,
+    ) -> AppResult<UnifiedMetrics> {
+        ClickhouseMetricsCollector::collect_metrics(self, database, docker_stats).await
+    }
+}
@@ -1,7 +1,40 @@
+mod clickhouse;
+mod mongo;
+mod mysql;
 mod postgres;
 mod redis;
 mod service;
 
+use async_trait::async_trait;
+
+pub use clickhouse::ClickhouseMetricsCollector;
+pub use mongo::MongoMetricsCollector;
+pub use mysql::MysqlMetricsCollector;
 pub use postgres::PostgresMetricsCollector;
 pub use redis::RedisMetricsCollector;
 pub use service::MetricsService;
+
+use crate::domain::models::{Database, QueryLogEntry, UnifiedMetrics};
+use crate::error::AppResult;
+use crate::infrastructure::docker::ContainerStats;
+
+/// Reads the metrics of one engine. Each engine builds its own collector.
+#[async_trait]
+pub trait MetricsCollector: Send + Sync {
+    async fn collect_metrics(
+        &self,
+        database: &Database,
+        docker_stats: &ContainerStats,
+    ) -> AppResult<UnifiedMetrics>;
+
+    /// Per-statement statistics and the number of statements tracked, for engines that keep
+    /// them.
+    async fn query_logs(
+        &self,
+        _database: &Database,
+        _limit: i32,
+        _sort_by: &str,
+    ) -> AppResult<Option<(Vec<QueryLogEntry>, i64)>> {
+        Ok(None)
+    }
+}
@@ -0,0 +1,231 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use chrono::Utc;
+use serde_json::Value;
+
+use super::MetricsCollector;
+use crate::domain::models::{
+    CollectionMetrics, ConnectionMetrics, Database, DocumentCounterMetrics, DocumentMemoryMetrics,
+    DocumentMetrics, OperationCounterMetrics, ResourceMetrics, StorageMetrics, UnifiedMetrics,
+};
+use crate::error::{AppError, AppResult};
+use crate::infrastructure::docker::containers::MongoContainer;
+use crate::infrastructure::docker::{ContainerStats, DockerManager};
+use crate::utils::crypto;
+
+/// Gathers `serverStatus` together with per-database stats of the user databases.
+const SERVER_STATUS_SCRIPT: &str = r#"
+const s = db.adminCommand({ serverStatus: 1 });
+const out = { uptime: s.uptime, opcounters: s.opcounters, opLatencies: s.opLatencies,
+  connections: s.connections, mem: s.mem, document: s.metrics.document,
+  cache: s.wiredTiger ? s.wiredTiger.cache : {}, collections: 0, objects: 0, indexes: 0, size: 0 };
+db.adminCommand({ listDatabases: 1 }).databases
+  .filter(d => !['admin', 'config', 'local'].includes(d.name))
+  .forEach(d => {
+    const st = db.getSiblingDB(d.name).stats();
+    out.collections += st.collections; out.objects += st.objects; out.indexes += st.indexes;
+    out.size += st.storageSize + st.indexSize;
+  });
+print(EJSON.stringify(out, { relaxed: true }));
+"#;
+
+pub struct MongoMetricsCollector {
+    encryption_key: Arc<[u8; 32]>,
+    docker: Arc<DockerManager>,
+}
+
+impl MongoMetricsCollector {
+    pub fn new(encryption_key: [u8; 32], docker: Arc<DockerManager>) -> Self {
+        Self {
+            encryption_key: Arc::new(encryption_key),
+            docker,
+        }
+    }
+
+    fn decrypt_password(&self, encrypted: &str) -> AppResult<String> {
+        crypto::decrypt_password(&self.encryption_key, encrypted)
+    }
+
+    async fn server_status(&self, database: &Database) -> AppResult<Value> {
+        let container_id = database
+            .container_id
+            .as_ref()
+            .ok_or_else(|| AppError::Internal("Database has no container".to_string()))?;
+        let encrypted = database
+            .password_encrypted
+            .as_ref()
+            .ok_or_else(|| AppError::Internal("Database password not found".to_string()))?;
+        let password = self.decrypt_password(encrypted)?;
+
+        let mut cmd = MongoContainer::shell_command();
+        cmd.push("--eval".to_string());
+        cmd.push(SERVER_STATUS_SCRIPT.to_string());
+
+        let output = tokio::time::timeout(
+            Duration::from_secs(10),
+            self.docker.run_exec(
+                container_id,
+                cmd,
+                Some(MongoContainer::shell_env(&password)),
+            ),
+        )
+        .await
+        .map_err(|_| AppError::Internal("serverStatus timed out".to_string()))??;
+
+        if output.exit_code != Some(0) {
+            return Err(AppError::Internal(format!(
+                "serverStatus failed: {}",
+                output.stderr.trim()
+            )));
+        }
+
+        serde_json::from_str(output.stdout.trim())
+            .map_err(|e| AppError::Internal(format!("Invalid serverStatus output: {}", e)))
+    }
+
+    fn parse_status(status: &Value) -> MongoMetrics {
+        let int = |pointer: &str| {
+            status
+                .pointer(pointer)
+                .and_then(Value::as_f64)
+                .unwrap_or(0.0) as i64
+        };
+
+        let operations = OperationCounterMetrics {
+            inserts: int("/opcounters/insert"),
+            queries: int("/opcounters/query"),
+            updates: int("/opcounters/update"),
+            deletes: int("/opcounters/delete"),
+            getmores: int("/opcounters/getmore"),
+            commands: int("/opcounters/command"),
+            ..Default::default()
+        };
+        let total_operations = operations.inserts
+            + operations.queries
+            + operations.updates
+            + operations.deletes
+            + operations.getmores
+            + operations.commands;
+        let uptime = int("/uptime");
+
+        // opLatencies are cumulative microseconds per operation class
+        let latency_micros = ["reads", "writes", "commands"]
+            .iter()
+            .map(|class| int(&format!("/opLatencies/{}/latency", class)))
+            .sum::<i64>();
+        let latency_ops = ["reads", "writes", "commands"]
+            .iter()
+            .map(|class| int(&format!("/opLatencies/{}/ops", class)))
+            .sum::<i64>();
+
+        let current = int("/connections/current") as i32;
+        let available = int("/connections/available") as i32;
+        let active = int("/connections/active") as i32;
+        let max_connections = current + available;
+
+        MongoMetrics {
+            operations: OperationCounterMetrics {
+                total_operations,
+                ops_per_sec: if uptime > 0 {
+                    total_operations as f64 / uptime as f64
+                } else {
+                    0.0
+                },
+                avg_latency_ms: if latency_ops > 0 {
+                    latency_micros as f64 / latency_ops as f64 / 1000.0
+                } else {
+                    0.0
+                },
+                ..operations
+            },
+            documents: DocumentCounterMetrics {
+                returned: int("/document/returned"),
+                inserted: int("/document/inserted"),
+                updated: int("/document/updated"),
+                deleted: int("/document/deleted"),
+            },
+            collections: CollectionMetrics {
+                total_collections: int("/collections"),
+                total_documents: int("/objects"),
+                total_indexes: int("/indexes"),
+            },
+            connections: ConnectionMetrics {
+                active_connections: active,
+                idle_connections: (current - active).max(0),
+                max_connections,
+                connection_percent: if max_connections > 0 {
+                    current as f64 / max_connections as f64 * 100.0
+                } else {
+                    0.0
+                },
+            },
+            memory: DocumentMemoryMetrics {
+                resident_bytes: int("/mem/resident") * 1024 * 1024,
+                virtual_bytes: int("/mem/virtual") * 1024 * 1024,
+                cache_used_bytes: int("/cache/bytes currently in the cache"),
+                cache_max_bytes: int("/cache/maximum bytes configured"),
+            },
+            size_bytes: int("/size"),
+        }
+    }
+
+    pub async fn collect_metrics(
+        &self,
+        database: &Database,
+        docker_stats: &ContainerStats,
+    ) -> AppResult<UnifiedMetrics> {
+        let mongo_metrics = match self.server_status(database).await {
+            Ok(status) => Self::parse_status(&status),
+            Err(e) => {
+                tracing::warn!("Failed to collect MongoDB metrics: {}", e);
+                MongoMetrics::default()
+            },
+        };
+
+        let timestamp = Utc::now().to_rfc3339();
+        let storage = StorageMetrics::new(
+            mongo_metrics.size_bytes,
+            database.storage_used_bytes,
+            database.storage_limit_mb,
+        );
+
+        Ok(UnifiedMetrics::Mongodb(DocumentMetrics {
+            timestamp,
+            operations: mongo_metrics.operations,
+            documents: mongo_metrics.documents,
+            collections: mongo_metrics.collections,
+            connections: mongo_metrics.connections,
+            memory: mongo_metrics.memory,
+            storage,
+            resources: ResourceMetrics {
+                cpu_percent: docker_stats.cpu_percent,
+                memory_used_bytes: docker_stats.memory_used_bytes,
+                memory_limit_bytes: docker_stats.memory_limit_bytes,
+                memory_percent: docker_stats.memory_percent,
+            },
+        }))
+    }
+}
+
+#[derive(Default)]
+struct MongoMetrics {
+    operations: OperationCounterMetrics,
+    documents: DocumentCounterMetrics,
+    collections: CollectionMetrics,
+    connections: ConnectionMetrics,
+    memory: DocumentMemoryMetrics,
+    size_bytes: i64,
+}
+
+#[async_trait]
+impl MetricsCollector for MongoMetricsCollector {
+    async fn collect_metrics(
+        &self,
+        database: &Database,
+        docker_stats: &ContainerStats,
+    ) -> AppResult<UnifiedMetrics> {
+        MongoMetricsCollector::collect_metrics(self, database, docker_stats).await
+    }
+}
@@ -0,0 +1,216 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use chrono::Utc;
+use sqlx::mysql::MySqlConnection;
+use sqlx::Row;
+
+use super::super::sql_mysql;
+use super::MetricsCollector;
+use crate::domain::models::{
+    ConnectionMetrics, Database, DatabaseMetrics, QueryMetrics, ResourceMetrics, RowMetrics,
+    StorageMetrics, TableMetrics, UnifiedMetrics,
+};
+use crate::error::{AppError, AppResult};
+use crate::infrastructure::docker::ContainerStats;
+use crate::utils::crypto;
+
+const USER_SCHEMAS: &str =
+    "table_schema NOT IN ('mysql', 'information_schema', 'performance_schema', 'sys')";
+
+/// Collects MySQL and MariaDB metrics from `performance_schema`, which the containers start with.
+pub struct MysqlMetricsCollector {
+    encryption_key: Arc<[u8; 32]>,
+    is_mariadb: bool,
+}
+
+impl MysqlMetricsCollector {
+    pub fn new(encryption_key: [u8; 32], is_mariadb: bool) -> Self {
+        Self {
+            encryption_key: Arc::new(encryption_key),
+            is_mariadb,
+        }
+    }
+
+    fn decrypt_password(&self, encrypted: &str) -> AppResult<String> {
+        crypto::decrypt_password(&self.encryption_key, encrypted)
+    }
+
+    async fn connect(&self, database: &Database) -> AppResult<MySqlConnection> {
+        let encrypted = database
+            .password_encrypted
+            .as_ref()
+            .ok_or_else(|| AppError::Internal("Database password not found".to_string()))?;
+
+        let password = self.decrypt_password(encrypted)?;
+        sql_mysql::connect(database, &password).await
+    }
+
+    async fn collect_mysql_metrics(&self, conn: &mut MySqlConnection) -> MysqlMetrics {
+        let mut metrics = MysqlMetrics::default();
+
+        // Timers are in picoseconds.
+        if let Ok(row) = sqlx::query(
+            r#"
+            SELECT
+                CAST(COALESCE(SUM(COUNT_STAR), 0) AS SIGNED),
+                CAST(COALESCE(SUM(SUM_TIMER_WAIT) / NULLIF(SUM(COUNT_STAR), 0), 0) / 1e9 AS DOUBLE),
+                CAST(COALESCE(MAX(MAX_TIMER_WAIT), 0) / 1e9 AS DOUBLE),
+                CAST(COALESCE(SUM(SUM_ROWS_EXAMINED), 0) AS SIGNED),
+                CAST(COALESCE(SUM(SUM_ROWS_AFFECTED), 0) AS SIGNED)
+            FROM performance_schema.events_statements_summary_global_by_event_name
+            WHERE COUNT_STAR > 0
+            "#,
+        )
+        .fetch_one(&mut *conn)
+        .await
+        {
+            metrics.queries.total_queries = row.try_get(0).unwrap_or(0);
+            metrics.queries.avg_latency_ms = row.try_get(1).unwrap_or(0.0);
+            metrics.queries.max_latency_ms = row.try_get(2).unwrap_or(0.0);
+            metrics.rows.rows_read = row.try_get(3).unwrap_or(0);
+            metrics.rows.rows_written = row.try_get(4).unwrap_or(0);
+        }
+
+        if let Ok(rows) =
+            sqlx::query("SHOW GLOBAL STATUS WHERE Variable_name IN ('Questions', 'Uptime')")
+                .fetch_all(&mut *conn)
+                .await
+        {
+            let status = |name: &str| {
+                rows.iter()
+                    .find(|row| {
+                        row.try_get_unchecked::<String, _>(0)
+                            .is_ok_and(|n| n == name)
+                    })
+                    .and_then(|row| row.try_get_unchecked::<String, _>(1).ok())
+                    .and_then(|value| value.parse::<f64>().ok())
+                    .unwrap_or(0.0)
+            };
+            let uptime = status("Uptime");
+            if uptime > 0.0 {
+                metrics.queries.queries_per_sec = status("Questions") / uptime;
+            }
+        }
+
+        if let Ok(row) = sqlx::query(&format!(
+            r#"
+            SELECT
+                CAST(COUNT(*) AS SIGNED),
+                CAST(COALESCE(SUM(table_rows), 0) AS SIGNED),
+                CAST(COALESCE(MAX(data_length + index_length), 0) AS SIGNED),
+                CAST(COALESCE(SUM(data_length + index_length), 0) AS SIGNED)
+            FROM information_schema.tables
+            WHERE table_type = 'BASE TABLE' AND {}
+            "#,
+            USER_SCHEMAS
+        ))
+        .fetch_one(&mut *conn)
+        .await
+        {
+            metrics.tables.total_tables = row.try_get(0).unwrap_or(0);
+            metrics.rows.total_rows = row.try_get(1).unwrap_or(0);
+            metrics.tables.largest_table_bytes = row.try_get(2).unwrap_or(0);
+            metrics.database_size_bytes = row.try_get(3).unwrap_or(0);
+        }
+
+        if let Ok(row) = sqlx::query(&format!(
+            "SELECT CAST(COUNT(DISTINCT table_schema, table_name, index_name) AS SIGNED) \
+             FROM information_schema.statistics WHERE {}",
+            USER_SCHEMAS
+        ))
+        .fetch_one(&mut *conn)
+        .await
+        {
+            metrics.tables.total_indexes = row.try_get(0).unwrap_or(0);
+        }
+
+        if let Ok(row) = sqlx::query(
+            r#"
+            SELECT
+                CAST(COALESCE(SUM(PROCESSLIST_COMMAND <> 'Sleep'), 0) AS SIGNED),
+                CAST(COALESCE(SUM(PROCESSLIST_COMMAND = 'Sleep'), 0) AS SIGNED),
+                CAST(@@max_connections AS SIGNED)
+            FROM performance_schema.threads
+            WHERE TYPE = 'FOREGROUND' AND PROCESSLIST_ID IS NOT NULL
+              AND PROCESSLIST_COMMAND <> 'Daemon'
+            "#,
+        )
+        .fetch_one(&mut *conn)
+        .await
+        {
+            let connections = &mut metrics.connections;
+            connections.active_connections = row.try_get::<i64, _>(0).unwrap_or(0) as i32;
+            connections.idle_connections = row.try_get::<i64, _>(1).unwrap_or(0) as i32;
+            connections.max_connections = row.try_get::<i64, _>(2).unwrap_or(0) as i32;
+            let total = connections.active_connections + connections.idle_connections;
+            connections.connection_percent = if connections.max_connections > 0 {
+                (total as f64 / connections.max_connections as f64) * 100.0
+            } else {
+                0.0
+            };
+        }
+
+        metrics
+    }
+
+    pub async fn collect_metrics(
+        &self,
+        database: &Database,
+        docker_stats: &ContainerStats,
+    ) -> AppResult<UnifiedMetrics> {
+        let mysql_metrics = match self.connect(database).await {
+            Ok(mut conn) => self.collect_mysql_metrics(&mut conn).await,
+            Err(e) => {
+                tracing::warn!("Failed to get connection for metrics: {}", e);
+                MysqlMetrics::default()
+            },
+        };
+
+        let metrics = DatabaseMetrics {
+            timestamp: Utc::now().to_rfc3339(),
+            queries: mysql_metrics.queries,
+            rows: mysql_metrics.rows,
+            tables: mysql_metrics.tables,
+            storage: StorageMetrics::new(
+                mysql_metrics.database_size_bytes,
+                database.storage_used_bytes,
+                database.storage_limit_mb,
+            ),
+            connections: mysql_metrics.connections,
+            standby: None,
+            resources: ResourceMetrics {
+                cpu_percent: docker_stats.cpu_percent,
+                memory_used_bytes: docker_stats.memory_used_bytes,
+                memory_limit_bytes: docker_stats.memory_limit_bytes,
+                memory_percent: docker_stats.memory_percent,
+            },
+        };
+
+        if self.is_mariadb {
+            Ok(UnifiedMetrics::Mariadb(metrics))
+        } else {
+            Ok(UnifiedMetrics::Mysql(metrics))
+        }
+    }
+}
+
+#[derive(Default)]
+struct MysqlMetrics {
+    queries: QueryMetrics,
+    rows: RowMetrics,
+    tables: TableMetrics,
+    connections: ConnectionMetrics,
+    database_size_bytes: i64,
+}
+
+#[async_trait]
+impl MetricsCollector for MysqlMetricsCollector {
+    async fn collect_metrics(
+        &self,
+        database: &Database,
+        docker_stats: &ContainerStats,
+    ) -> AppResult<UnifiedMetrics> {
+        MysqlMetricsCollector::collect_metrics(self, database, docker_stats).await
+    }
+}
@@ -2,21 +2,20 @@ use std::collections::HashMap;
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 
-use aes_gcm::{
-    aead::{Aead, KeyInit},
-    Aes256Gcm, Nonce,
-};
+use async_trait::async_trait;
 use chrono::Utc;
 use deadpool_postgres::{ManagerConfig, Pool, RecyclingMethod};
 use tokio::sync::RwLock;
 use tokio_postgres::NoTls;
 
+use super::MetricsCollector;
 use crate::domain::models::{
-    ConnectionMetrics, Database, DatabaseMetrics, QueryMetrics, ResourceMetrics, RowMetrics,
-    StorageMetrics, TableMetrics, UnifiedMetrics,
+    ConnectionMetrics, Database, DatabaseMetrics, QueryLogEntry, QueryMetrics, ResourceMetrics,
+    RowMetrics, StandbyMetrics, StorageMetrics, TableMetrics, UnifiedMetrics,
 };
 use crate::error::{AppError, AppResult};
 use crate::infrastructure::docker::ContainerStats;
+use crate::utils::crypto;
 
 const POOL_MAX_SIZE: usize = 2;
 const POOL_TIMEOUT_SECS: u64 = 10;
@@ -49,25 +48,7 @@ impl PostgresMetricsCollector {
     }
 
     fn decrypt_password(&self, encrypted: &str) -> AppResult<String> {
-        let data = hex::decode(encrypted)
-            .map_err(|e| AppError::Internal(format!("Invalid encrypted data: {}", e)))?;
-
-        if data.len() < 12 {
-            return Err(AppError::Internal("Encrypted data too short".to_string()));
-        }
-
-        let (nonce_bytes, ciphertext) = data.split_at(12);
-        let nonce = Nonce::from_slice(nonce_bytes);
-
-        let cipher = Aes256Gcm::new_from_slice(&*self.encryption_key)
-            .map_err(|e| AppError::Internal(format!("Decryption init failed: {}", e)))?;
-
-        let plaintext = cipher
-            .decrypt(nonce, ciphertext)
-            .map_err(|e| AppError::Internal(format!("Decryption failed: {}", e)))?;
-
-        String::from_utf8(plaintext)
-            .map_err(|e| AppError::Internal(format!("Invalid UTF-8 in password: {}", e)))
+        crypto::decrypt_password(&self.encryption_key, encrypted)
     }
 
     async fn get_pool(&self, database: &Database) -> AppResult<Pool> {
@@ -168,32 +149,17 @@ impl PostgresMetricsCollector {
         }
     }
 
-    async fn collect_pg_metrics(&self, client: &deadpool_postgres::Client) -> PgMetrics {
-        let mut metrics = PgMetrics::default();
-
-        if let Ok(row) = client
-            .query_one("SELECT pg_database_size(current_database())", &[])
-            .await
-        {
-            metrics.database_size_bytes = row.get::<_, i64>(0);
-        }
-
-        let extension_exists = client
-            .query_one(
-                "SELECT 1 FROM pg_extension WHERE extname = 'pg_stat_statements'",
-                &[],
-            )
-            .await
-            .is_ok();
-
-        if !extension_exists {
-            let _ = client
-                .execute("CREATE EXTENSION IF NOT EXISTS pg_stat_statements", &[])
-                .await;
-        }
-
-        let version = self.detect_pg_stat_statements_version(client).await;
-        let use_modern = Self::use_modern_columns(version.as_deref());
+    async fn collect_query_stats(
+        &self,
+        client: &deadpool_postgres::Client,
+        queries: &mut QueryMetrics,
+    ) {
+        // Databases created before pg_stat_statements was installed, or that dropped it, have
+        // no query statistics.
+        let Some(version) = self.detect_pg_stat_statements_version(client).await else {
+            return;
+        };
+        let use_modern = Self::use_modern_columns(Some(&version));
 
         let (mean_time_col, max_time_col) = if use_modern {
             ("mean_exec_time", "max_exec_time")
@@ -221,11 +187,24 @@ impl PostgresMetricsCollector {
 
         if let Ok(rows) = client.query(&query, &[]).await {
             if let Some(row) = rows.first() {
-                metrics.queries.total_queries = row.get::<_, i64>(0);
-                metrics.queries.avg_latency_ms = row.get::<_, f64>(1);
-                metrics.queries.max_latency_ms = row.get::<_, f64>(2);
+                queries.total_queries = row.get::<_, i64>(0);
+                queries.avg_latency_ms = row.get::<_, f64>(1);
+                queries.max_latency_ms = row.get::<_, f64>(2);
             }
         }
+    }
+
+    async fn collect_pg_metrics(&self, client: &deadpool_postgres::Client) -> PgMetrics {
+        let mut metrics = PgMetrics::default();
+
+        if let Ok(row) = client
+            .query_one("SELECT pg_database_size(current_database())", &[])
+            .await
+        {
+            metrics.database_size_bytes = row.get::<_, i64>(0);
+        }
+
+        self.collect_query_stats(client, &mut metrics.queries).await;
 
         if let Ok(rows) = client
             .query(
@@ -301,6 +280,28 @@ impl PostgresMetricsCollector {
             }
         }
 
+        if let Ok(row) = client
+            .query_one(
+                r#"
+                SELECT
+                    (SELECT status FROM pg_stat_wal_receiver),
+                    CASE WHEN pg_last_wal_receive_lsn() = pg_last_wal_replay_lsn() THEN 0
+                        ELSE COALESCE(EXTRACT(EPOCH FROM now() - pg_last_xact_replay_timestamp()), 0)
+                    END::float8,
+                    COALESCE(pg_wal_lsn_diff(pg_last_wal_receive_lsn(), pg_last_wal_replay_lsn()), 0)::bigint
+                WHERE pg_is_in_recovery()
+                "#,
+                &[],
+            )
+            .await
+        {
+            metrics.standby = Some(StandbyMetrics {
+                receiver_status: row.get::<_, Option<String>>(0),
+                lag_seconds: row.get::<_, f64>(1),
+                lag_bytes: row.get::<_, i64>(2),
+            });
+        }
+
         metrics
     }
 
@@ -324,19 +325,13 @@ impl PostgresMetricsCollector {
             queries: pg_metrics.queries,
             rows: pg_metrics.rows,
             tables: pg_metrics.tables,
-            storage: StorageMetrics {
-                database_size_bytes: pg_metrics.database_size_bytes,
-                container_storage_bytes: docker_stats.memory_used_bytes,
-                storage_limit_bytes: (database.storage_limit_mb as i64) * 1024 * 1024,
-                storage_percent: if database.storage_limit_mb > 0 {
-                    (pg_metrics.database_size_bytes as f64)
-                        / ((database.storage_limit_mb as i64 * 1024 * 1024) as f64)
-                        * 100.0
-                } else {
-                    0.0
-                },
-            },
+            storage: StorageMetrics::new(
+                pg_metrics.database_size_bytes,
+                database.storage_used_bytes,
+                database.storage_limit_mb,
+            ),
             connections: pg_metrics.connections,
+            standby: pg_metrics.standby,
             resources: ResourceMetrics {
                 cpu_percent: docker_stats.cpu_percent,
                 memory_used_bytes: docker_stats.memory_used_bytes,
@@ -351,26 +346,13 @@ impl PostgresMetricsCollector {
         database: &Database,
         limit: i32,
         sort_by: &str,
-    ) -> AppResult<Vec<crate::domain::models::QueryLogEntry>> {
+    ) -> AppResult<Vec<QueryLogEntry>> {
         let client = self.get_client(database).await?;
 
-        let extension_exists = client
-            .query_one(
-                "SELECT 1 FROM pg_extension WHERE extname = 'pg_stat_statements'",
-                &[],
-            )
-            .await
-            .is_ok();
-
-        if !extension_exists {
-            let _ = client
-                .execute("CREATE EXTENSION IF NOT EXISTS pg_stat_statements", &[])
-                .await;
+        let Some(version) = self.detect_pg_stat_statements_version(&client).await else {
             return Ok(vec![]);
-        }
-
-        let version = self.detect_pg_stat_statements_version(&client).await;
-        let use_modern = Self::use_modern_columns(version.as_deref());
+        };
+        let use_modern = Self::use_modern_columns(Some(&version));
 
         let (total_time_col, mean_time_col, min_time_col, max_time_col) = if use_modern {
             (
@@ -421,9 +403,9 @@ impl PostgresMetricsCollector {
                 AppError::Internal(format!("Failed to query pg_stat_statements: {}", e))
             })?;
 
-        let entries: Vec<crate::domain::models::QueryLogEntry> = rows
+        let entries: Vec<QueryLogEntry> = rows
             .iter()
-            .map(|row| crate::domain::models::QueryLogEntry {
+            .map(|row| QueryLogEntry {
                 query: truncate_query(row.get::<_, &str>("query")),
                 calls: row.get::<_, i64>("calls"),
                 total_time_ms: row.get::<_, f64>("total_time_ms"),
@@ -441,6 +423,14 @@ impl PostgresMetricsCollector {
     pub async fn count_query_logs(&self, database: &Database) -> AppResult<i64> {
         let client = self.get_client(database).await?;
 
+        if self
+            .detect_pg_stat_statements_version(&client)
+            .await
+            .is_none()
+        {
+            return Ok(0);
+        }
+
         let count_row = client
             .query_one(
                 r#"
@@ -469,6 +459,7 @@ struct PgMetrics {
     tables: TableMetrics,
     connections: ConnectionMetrics,
     database_size_bytes: i64,
+    standby: Option<StandbyMetrics>,
 }
 
 fn truncate_query(query: &str) -> String {
@@ -479,3 +470,25 @@ fn truncate_query(query: &str) -> String {
         query.to_string()
     }
 }
+
+#[async_trait]
+impl MetricsCollector for PostgresMetricsCollector {
+    async fn collect_metrics(
+        &self,
+        database: &Database,
+        docker_stats: &ContainerStats,
+    ) -> AppResult<UnifiedMetrics> {
+        PostgresMetricsCollector::collect_metrics(self, database, docker_stats).await
+    }
+
+    async fn query_logs(
+        &self,
+        database: &Database,
+        limit: i32,
+        sort_by: &str,
+    ) -> AppResult<Option<(Vec<QueryLogEntry>, i64)>> {
+        let entries = self.get_query_logs(database, limit, sort_by).await?;
+        let total_queries = self.count_query_logs(database).await?;
+        Ok(Some((entries, total_queries)))
+    }
+}
@@ -1,20 +1,19 @@
 use std::collections::HashMap;
 use std::sync::Arc;
 
-use aes_gcm::{
-    aead::{Aead, KeyInit},
-    Aes256Gcm, Nonce,
-};
+use async_trait::async_trait;
 use chrono::Utc;
 use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
 use tokio::net::TcpStream;
 
+use super::MetricsCollector;
 use crate::domain::models::{
     ClientMetrics, CommandMetrics, Database, KeyMetrics, KeyValueMetrics, MemoryMetrics,
-    ReplicationMetrics, ResourceMetrics, UnifiedMetrics,
+    ReplicationMetrics, ResourceMetrics, StorageMetrics, UnifiedMetrics,
 };
 use crate::error::{AppError, AppResult};
 use crate::infrastructure::docker::ContainerStats;
+use crate::utils::crypto;
 
 pub struct RedisMetricsCollector {
     encryption_key: Arc<[u8; 32]>,
@@ -30,38 +29,19 @@ impl RedisMetricsCollector {
     }
 
     fn decrypt_password(&self, encrypted: &str) -> AppResult<String> {
-        let data = hex::decode(encrypted)
-            .map_err(|e| AppError::Internal(format!("Invalid encrypted data: {}", e)))?;
-
-        if data.len() < 12 {
-            return Err(AppError::Internal("Encrypted data too short".to_string()));
-        }
-
-        let (nonce_bytes, ciphertext) = data.split_at(12);
-        let nonce = Nonce::from_slice(nonce_bytes);
-
-        let cipher = Aes256Gcm::new_from_slice(&*self.encryption_key)
-            .map_err(|e| AppError::Internal(format!("Decryption init failed: {}", e)))?;
-
-        let plaintext = cipher
-            .decrypt(nonce, ciphertext)
-            .map_err(|e| AppError::Internal(format!("Decryption failed: {}", e)))?;
-
-        String::from_utf8(plaintext)
-            .map_err(|e| AppError::Internal(format!("Invalid UTF-8 in password: {}", e)))
+        crypto::decrypt_password(&self.encryption_key, encrypted)
     }
 
     async fn connect(&self, database: &Database) -> AppResult<TcpStream> {
-        let container_name = database.container_name();
-        let addr = format!("{}:6379", container_name);
+        Self::connect_to(&format!("{}:6379", database.container_name())).await
+    }
 
-        let stream = tokio::time::timeout(
-            std::time::Duration::from_secs(10),
-            TcpStream::connect(&addr),
-        )
-        .await
-        .map_err(|_| AppError::Internal("Connection timeout".to_string()))?
-        .map_err(|e| AppError::Internal(format!("Failed to connect to Redis: {}", e)))?;
+    async fn connect_to(addr: &str) -> AppResult<TcpStream> {
+        let stream =
+            tokio::time::timeout(std::time::Duration::from_secs(10), TcpStream::connect(addr))
+                .await
+                .map_err(|_| AppError::Internal("Connection timeout".to_string()))?
+                .map_err(|e| AppError::Internal(format!("Failed to connect to Redis: {}", e)))?;
 
         Ok(stream)
     }
@@ -94,21 +74,74 @@ impl RedisMetricsCollector {
         match line.chars().next() {
             Some('+') => Ok(line[1..].to_string()),
             Some('-') => Err(AppError::Internal(format!("Redis error: {}", &line[1..]))),
-            Some('$') => {
-                let len: i64 = line[1..].parse().unwrap_or(-1);
-                if len < 0 {
-                    return Ok(String::new());
+            Some('$') => Self::read_bulk(&mut reader, line).await,
+            // Arrays of bulk strings, one element per line
+            Some('*') => {
+                let count: i64 = line[1..].parse().unwrap_or(-1);
+                let mut elements = Vec::new();
+                for _ in 0..count.max(0) {
+                    let mut header = String::new();
+                    reader.read_line(&mut header).await.map_err(|e| {
+                        AppError::Internal(format!("Failed to read response: {}", e))
+                    })?;
+                    elements.push(Self::read_bulk(&mut reader, header.trim()).await?);
                 }
-                let mut buf = vec![0u8; len as usize + 2];
-                tokio::io::AsyncReadExt::read_exact(&mut reader, &mut buf)
-                    .await
-                    .map_err(|e| AppError::Internal(format!("Failed to read bulk: {}", e)))?;
-                Ok(String::from_utf8_lossy(&buf[..len as usize]).to_string())
+                Ok(elements.join("\n"))
             },
             _ => Ok(line.to_string()),
         }
     }
 
+    async fn read_bulk(reader: &mut BufReader<&mut TcpStream>, header: &str) -> AppResult<String> {
+        let len: i64 = header
+            .strip_prefix('$')
+            .and_then(|len| len.parse().ok())
+            .unwrap_or(-1);
+        if len < 0 {
+            return Ok(String::new());
+        }
+        let mut buf = vec![0u8; len as usize + 2];
+        tokio::io::AsyncReadExt::read_exact(reader, &mut buf)
+            .await
+            .map_err(|e| AppError::Internal(format!("Failed to read bulk: {}", e)))?;
+        Ok(String::from_utf8_lossy(&buf[..len as usize]).to_string())
+    }
+
+    /// Bytes of the master's replication stream a replica has not processed yet. The replica's
+    /// own `master_repl_offset` only mirrors what it received, so the master is asked for its
+    /// offset, authenticated with the replica's `masterauth`.
+    async fn replica_lag_bytes(
+        replica: &mut TcpStream,
+        info: &HashMap<String, String>,
+    ) -> AppResult<i64> {
+        let missing = |field: &str| AppError::Internal(format!("INFO has no {}", field));
+        let host = info
+            .get("master_host")
+            .ok_or_else(|| missing("master_host"))?;
+        let port = info
+            .get("master_port")
+            .ok_or_else(|| missing("master_port"))?;
+        let replica_offset: i64 = info
+            .get("slave_repl_offset")
+            .and_then(|v| v.parse().ok())
+            .ok_or_else(|| missing("slave_repl_offset"))?;
+
+        let masterauth = Self::send_command(replica, &["CONFIG", "GET", "masterauth"]).await?;
+        let mut master = Self::connect_to(&format!("{}:{}", host, port)).await?;
+        if let Some((_, password)) = masterauth.split_once('\n') {
+            if !password.is_empty() {
+                Self::send_command(&mut master, &["AUTH", password]).await?;
+            }
+        }
+        let master_info = Self::send_command(&mut master, &["INFO", "replication"]).await?;
+        let master_offset: i64 = Self::parse_info(&master_info)
+            .get("master_repl_offset")
+            .and_then(|v| v.parse().ok())
+            .ok_or_else(|| missing("master_repl_offset"))?;
+
+        Ok((master_offset - replica_offset).max(0))
+    }
+
     fn parse_info(info: &str) -> HashMap<String, String> {
         let mut map = HashMap::new();
         for line in info.lines() {
@@ -234,6 +267,19 @@ impl RedisMetricsCollector {
             .and_then(|v| v.parse().ok())
             .unwrap_or(0);
 
+        let master_link_status = parsed.get("master_link_status").cloned();
+        let lag_bytes = if role == "slave" {
+            match Self::replica_lag_bytes(&mut stream, &parsed).await {
+                Ok(lag) => Some(lag),
+                Err(e) => {
+                    tracing::warn!("Failed to measure replication lag: {}", e);
+                    None
+                },
+            }
+        } else {
+            None
+        };
+
         Ok(KvMetrics {
             keys: KeyMetrics {
                 total_keys,
@@ -263,6 +309,8 @@ impl RedisMetricsCollector {
             replication: ReplicationMetrics {
                 role,
                 connected_slaves,
+                master_link_status,
+                lag_bytes,
             },
         })
     }
@@ -281,6 +329,11 @@ impl RedisMetricsCollector {
         };
 
         let timestamp = Utc::now().to_rfc3339();
+        let storage = StorageMetrics::new(
+            kv_metrics.memory.used_memory,
+            database.storage_used_bytes,
+            database.storage_limit_mb,
+        );
 
         let metrics = KeyValueMetrics {
             timestamp,
@@ -289,6 +342,7 @@ impl RedisMetricsCollector {
             memory: kv_metrics.memory,
             clients: kv_metrics.clients,
             replication: kv_metrics.replication,
+            storage,
             resources: ResourceMetrics {
                 cpu_percent: docker_stats.cpu_percent,
                 memory_used_bytes: docker_stats.memory_used_bytes,
@@ -313,3 +367,14 @@ struct KvMetrics {
     clients: ClientMetrics,
     replication: ReplicationMetrics,
 }
+
+#[async_trait]
+impl MetricsCollector for RedisMetricsCollector {
+    async fn collect_metrics(
+        &self,
+        database: &Database,
+        docker_stats: &ContainerStats,
+    ) -> AppResult<UnifiedMetrics> {
+        RedisMetricsCollector::collect_metrics(self, database, docker_stats).await
+    }
+}
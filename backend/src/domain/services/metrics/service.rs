@@ -1,15 +1,17 @@
+use std::collections::HashMap;
 use std::sync::Arc;
 
 use chrono::Utc;
 
-use super::postgres::PostgresMetricsCollector;
-use super::redis::RedisMetricsCollector;
+use super::MetricsCollector;
+use crate::domain::engines::{all_engines, engine_for};
 use crate::domain::models::{
     Database, MetricsHistory, QueryLogsResponse, TimeRange, UnifiedMetrics, UnifiedMetricsResponse,
 };
 use crate::error::{AppError, AppResult};
 use crate::infrastructure::docker::DockerManager;
 use crate::repositories::{DatabaseRepository, MetricsRepository, ProjectRepository};
+use crate::utils::crypto;
 
 #[derive(Clone)]
 pub struct MetricsService {
@@ -17,9 +19,7 @@ pub struct MetricsService {
     project_repo: ProjectRepository,
     metrics_repo: MetricsRepository,
     docker: Arc<DockerManager>,
-    postgres_collector: Arc<PostgresMetricsCollector>,
-    redis_collector: Arc<RedisMetricsCollector>,
-    valkey_collector: Arc<RedisMetricsCollector>,
+    collectors: HashMap<&'static str, Arc<dyn MetricsCollector>>,
 }
 
 impl MetricsService {
@@ -30,22 +30,34 @@ impl MetricsService {
         docker: Arc<DockerManager>,
         encryption_key_hex: &str,
     ) -> Self {
-        let encryption_key: [u8; 32] = hex::decode(encryption_key_hex)
-            .expect("Invalid encryption key hex")
-            .try_into()
-            .expect("Encryption key must be 32 bytes");
+        let encryption_key = crypto::parse_encryption_key(encryption_key_hex);
+
+        let collectors = all_engines()
+            .iter()
+            .map(|engine| {
+                (
+                    engine.database_type(),
+                    engine.metrics_collector(encryption_key, &docker),
+                )
+            })
+            .collect();
 
         Self {
             database_repo,
             project_repo,
             metrics_repo,
             docker,
-            postgres_collector: Arc::new(PostgresMetricsCollector::new(encryption_key)),
-            redis_collector: Arc::new(RedisMetricsCollector::new(encryption_key, false)),
-            valkey_collector: Arc::new(RedisMetricsCollector::new(encryption_key, true)),
+            collectors,
         }
     }
 
+    fn collector(&self, database: &Database) -> AppResult<&Arc<dyn MetricsCollector>> {
+        let database_type = engine_for(&database.database_type).database_type();
+        self.collectors.get(database_type).ok_or_else(|| {
+            AppError::Internal(format!("No metrics collector for {}", database_type))
+        })
+    }
+
     pub async fn check_access(&self, database_id: &str, user_id: &str) -> AppResult<bool> {
         let project_id = self
             .database_repo
@@ -127,18 +139,15 @@ impl MetricsService {
             ));
         }
 
-        if database.database_type != "postgres" {
-            return Err(AppError::Validation(
-                "Query logs are only available for PostgreSQL databases".to_string(),
-            ));
-        }
-
-        let entries = self
-            .postgres_collector
-            .get_query_logs(&database, limit, sort_by)
-            .await?;
-
-        let total_queries = self.postgres_collector.count_query_logs(&database).await?;
+        let (entries, total_queries) = self
+            .collector(&database)?
+            .query_logs(&database, limit, sort_by)
+            .await?
+            .ok_or_else(|| {
+                AppError::Validation(
+                    "Query logs are only available for PostgreSQL databases".to_string(),
+                )
+            })?;
 
         Ok(QueryLogsResponse {
             database_id: database_id.to_string(),
@@ -174,27 +183,8 @@ impl MetricsService {
 
         let docker_stats = self.docker.get_container_stats(container_id).await?;
 
-        match database.database_type.as_str() {
-            "postgres" => {
-                self.postgres_collector
-                    .collect_metrics(database, &docker_stats)
-                    .await
-            },
-            "redis" => {
-                self.redis_collector
-                    .collect_metrics(database, &docker_stats)
-                    .await
-            },
-            "valkey" => {
-                self.valkey_collector
-                    .collect_metrics(database, &docker_stats)
-                    .await
-            },
-            _ => {
-                self.postgres_collector
-                    .collect_metrics(database, &docker_stats)
-                    .await
-            },
-        }
+        self.collector(database)?
+            .collect_metrics(database, &docker_stats)
+            .await
     }
 }
@@ -1,13 +1,43 @@
+mod app;
 mod audit_log;
 mod auth;
+mod backup;
+mod backup_storage;
+mod branch;
+mod branch_data;
+mod common;
 mod database;
+mod document;
+mod export;
+mod extension;
+mod import;
+mod masking;
 pub mod metrics;
+mod operation;
 mod project;
+mod quota;
+mod schema_diff;
 mod sql;
+pub(crate) mod sql_clickhouse;
+pub(crate) mod sql_mysql;
+pub(crate) mod sql_postgres;
+mod update;
+mod upgrade;
 
+pub use app::AppServices;
 pub use audit_log::*;
 pub use auth::*;
+pub use backup::*;
+pub use backup_storage::BackupStores;
+pub use branch::*;
 pub use database::*;
+pub use export::*;
+pub use extension::*;
+pub use import::*;
 pub use metrics::MetricsService;
+pub use operation::*;
 pub use project::*;
+pub use quota::*;
 pub use sql::*;
+pub use update::*;
+pub use upgrade::*;
@@ -0,0 +1,244 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use super::{BRANCH_OPERATION_KIND, PITR_BRANCH_OPERATION_KIND, RESTORE_BRANCH_OPERATION_KIND};
+use crate::domain::models::{Operation, OperationResponse};
+use crate::error::{AppError, AppResult};
+use crate::infrastructure::docker::DockerManager;
+use crate::repositories::{DatabaseRepository, OperationRepository, ProjectRepository};
+
+#[derive(Clone)]
+pub struct OperationService {
+    operation_repo: OperationRepository,
+    database_repo: DatabaseRepository,
+    project_repo: ProjectRepository,
+    docker: Arc<DockerManager>,
+}
+
+impl OperationService {
+    pub fn new(
+        operation_repo: OperationRepository,
+        database_repo: DatabaseRepository,
+        project_repo: ProjectRepository,
+        docker: Arc<DockerManager>,
+    ) -> Self {
+        Self {
+            operation_repo,
+            database_repo,
+            project_repo,
+            docker,
+        }
+    }
+
+    async fn check_access(
+        &self,
+        database_id: &str,
+        user_id: &str,
+        is_admin: bool,
+    ) -> AppResult<()> {
+        let project_id = self
+            .database_repo
+            .get_project_id(database_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("Database '{}' not found", database_id)))?;
+
+        if !is_admin && !self.project_repo.is_owner(&project_id, user_id).await? {
+            return Err(AppError::Forbidden);
+        }
+
+        Ok(())
+    }
+
+    pub async fn list(
+        &self,
+        database_id: &str,
+        user_id: &str,
+        is_admin: bool,
+    ) -> AppResult<Vec<OperationResponse>> {
+        self.check_access(database_id, user_id, is_admin).await?;
+
+        let operations = self.operation_repo.find_by_database_id(database_id).await?;
+        Ok(operations.iter().map(|o| o.to_response()).collect())
+    }
+
+    pub async fn get(
+        &self,
+        database_id: &str,
+        operation_id: &str,
+        user_id: &str,
+        is_admin: bool,
+    ) -> AppResult<OperationResponse> {
+        self.check_access(database_id, user_id, is_admin).await?;
+        self.find(database_id, operation_id).await
+    }
+
+    /// Looks up an operation on or targeting `database_id` without checking access.
+    pub async fn find(
+        &self,
+        database_id: &str,
+        operation_id: &str,
+    ) -> AppResult<OperationResponse> {
+        let operation = self
+            .operation_repo
+            .find_by_id(operation_id)
+            .await?
+            .filter(|o: &Operation| {
+                o.database_id == database_id || o.target_database_id.as_deref() == Some(database_id)
+            })
+            .ok_or_else(|| AppError::NotFound(format!("Operation '{}' not found", operation_id)))?;
+
+        Ok(operation.to_response())
+    }
+
+    /// Fails operations a restart interrupted and resets the status they left on their
+    /// databases, such as `restoring` or `upgrading`, from the container's actual state.
+    pub async fn recover_interrupted(&self) -> AppResult<()> {
+        let interrupted = self.operation_repo.find_running().await?;
+        let count = self.operation_repo.fail_interrupted().await?;
+        if count > 0 {
+            tracing::warn!("Marked {} interrupted operations as failed", count);
+        }
+
+        for (database_id, new_branch) in interrupted_databases(&interrupted) {
+            if let Err(e) = self.recover_status(&database_id, new_branch).await {
+                tracing::error!(
+                    "Failed to recover status of database {}: {}",
+                    database_id,
+                    e
+                );
+            }
+        }
+        Ok(())
+    }
+
+    async fn recover_status(&self, database_id: &str, new_branch: bool) -> AppResult<()> {
+        let Some(database) = self.database_repo.find_by_id(database_id).await? else {
+            return Ok(());
+        };
+
+        let container_state = match &database.container_id {
+            Some(container_id) if self.docker.container_exists(container_id).await? => {
+                Some(self.docker.get_container_status(container_id).await?)
+            },
+            _ => None,
+        };
+        let status = recovered_status(new_branch, container_state.as_deref());
+        if status != database.container_status {
+            tracing::warn!(
+                "Database {} was left {} by an interrupted operation, marking it {}",
+                database.id,
+                database.container_status,
+                status
+            );
+            self.database_repo
+                .update_status(&database.id, status)
+                .await?;
+        }
+        Ok(())
+    }
+}
+
+/// Sets a database that an operation left `upgrading` or `updating` back to `running` or
+/// `stopped`, whichever its container is in.
+pub(crate) async fn reset_status_from_container(
+    database_repo: &DatabaseRepository,
+    docker: &DockerManager,
+    database_id: &str,
+) {
+    let container_id = database_repo
+        .find_by_id(database_id)
+        .await
+        .ok()
+        .flatten()
+        .and_then(|database| database.container_id);
+    let status = match container_id {
+        Some(container_id) => docker
+            .get_container_status(&container_id)
+            .await
+            .unwrap_or_else(|_| "error".to_string()),
+        None => "error".to_string(),
+    };
+    let status = if status == "running" {
+        "running"
+    } else {
+        "stopped"
+    };
+    if let Err(e) = database_repo.update_status(database_id, status).await {
+        tracing::error!("Failed to update status of database {}: {}", database_id, e);
+    }
+}
+
+/// Databases touched by `operations`, flagged when the operation was creating them as a
+/// branch and their data is therefore incomplete.
+fn interrupted_databases(operations: &[Operation]) -> HashMap<String, bool> {
+    let mut databases = HashMap::new();
+    for operation in operations {
+        let creates_branch = matches!(
+            operation.kind.as_str(),
+            BRANCH_OPERATION_KIND | RESTORE_BRANCH_OPERATION_KIND | PITR_BRANCH_OPERATION_KIND
+        );
+        let new_branch = match &operation.target_database_id {
+            Some(target) => {
+                *databases.entry(target.clone()).or_default() |= creates_branch;
+                false
+            },
+            None => creates_branch,
+        };
+        *databases.entry(operation.database_id.clone()).or_default() |= new_branch;
+    }
+    databases
+}
+
+fn recovered_status(new_branch: bool, container_state: Option<&str>) -> &'static str {
+    match container_state {
+        _ if new_branch => "error",
+        Some("running") => "running",
+        Some(_) => "stopped",
+        None => "error",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn operation(kind: &str, database_id: &str, target: Option<&str>) -> Operation {
+        Operation {
+            id: String::new(),
+            database_id: database_id.to_string(),
+            kind: kind.to_string(),
+            status: "running".to_string(),
+            backup_id: None,
+            target_database_id: target.map(str::to_string),
+            error: None,
+            phase: None,
+            progress_bytes: 0,
+            total_bytes: None,
+            created_at: String::new(),
+            completed_at: None,
+        }
+    }
+
+    #[test]
+    fn test_interrupted_databases() {
+        let databases = interrupted_databases(&[
+            operation("restore", "a", None),
+            operation(BRANCH_OPERATION_KIND, "b", None),
+            operation(RESTORE_BRANCH_OPERATION_KIND, "c", Some("d")),
+        ]);
+
+        assert_eq!(databases.len(), 4);
+        assert!(!databases["a"]);
+        assert!(databases["b"]);
+        assert!(!databases["c"]);
+        assert!(databases["d"]);
+    }
+
+    #[test]
+    fn test_recovered_status() {
+        assert_eq!(recovered_status(false, Some("running")), "running");
+        assert_eq!(recovered_status(false, Some("exited")), "stopped");
+        assert_eq!(recovered_status(false, None), "error");
+        assert_eq!(recovered_status(true, Some("running")), "error");
+    }
+}
@@ -1,7 +1,10 @@
 use slug::slugify;
 
-use crate::domain::models::{Project, ProjectResponse, ProjectWithStats};
+use crate::domain::models::{
+    restore_settings_secrets, MaskingConfig, Project, ProjectResponse, ProjectWithStats,
+};
 use crate::error::{AppError, AppResult};
+use crate::infrastructure::storage::BackupStorageConfig;
 use crate::repositories::{DatabaseRepository, ProjectRepository};
 
 #[derive(Clone)]
@@ -37,6 +40,10 @@ impl ProjectService {
             ));
         }
 
+        if let Some(settings) = settings {
+            Self::validate_settings(settings)?;
+        }
+
         let base_slug = slugify(name);
         let mut slug = base_slug.clone();
         let mut counter = 1;
@@ -167,14 +174,36 @@ impl ProjectService {
             }
         }
 
+        let settings = match settings {
+            Some(settings) => {
+                let mut settings: serde_json::Value = serde_json::from_str(settings)
+                    .map_err(|e| AppError::Validation(format!("Invalid settings: {}", e)))?;
+                if let Some(existing) = self.project_repo.find_by_id(id).await? {
+                    restore_settings_secrets(&mut settings, &existing.settings_json());
+                }
+                let settings = settings.to_string();
+                Self::validate_settings(&settings)?;
+                Some(settings)
+            },
+            None => None,
+        };
+
         let project = self
             .project_repo
-            .update(id, name, description, settings)
+            .update(id, name, description, settings.as_deref())
             .await?;
 
         Ok(project.into())
     }
 
+    fn validate_settings(settings: &str) -> AppResult<()> {
+        let settings: serde_json::Value = serde_json::from_str(settings)
+            .map_err(|e| AppError::Validation(format!("Invalid settings: {}", e)))?;
+        BackupStorageConfig::from_project_settings(&settings)?;
+        MaskingConfig::from_project_settings(&settings)?;
+        Ok(())
+    }
+
     pub async fn delete(&self, id: &str, user_id: &str, is_admin: bool) -> AppResult<()> {
         if !is_admin && !self.project_repo.is_owner(id, user_id).await? {
             return Err(AppError::Forbidden);
@@ -0,0 +1,378 @@
+use std::collections::HashMap;
+use std::os::unix::fs::MetadataExt;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use tokio::process::Command;
+
+use crate::domain::engines::engine_for;
+use crate::domain::models::Database;
+use crate::error::{AppError, AppResult};
+use crate::infrastructure::docker::DockerManager;
+use crate::repositories::DatabaseRepository;
+use crate::utils::crypto;
+
+pub const STORAGE_STATE_OK: &str = "ok";
+pub const STORAGE_STATE_WARNING: &str = "warning";
+pub const STORAGE_STATE_READ_ONLY: &str = "read_only";
+
+const WARNING_PERCENT: i64 = 90;
+/// Headroom of the filesystem cap above the limit, so the read-only switch happens first.
+const QUOTA_HEADROOM_PERCENT: i64 = 10;
+
+#[derive(Clone)]
+pub struct QuotaService {
+    database_repo: DatabaseRepository,
+    docker: Arc<DockerManager>,
+    data_dir: String,
+    xfs_quota: bool,
+    encryption_key: [u8; 32],
+    applied_quotas: Arc<Mutex<HashMap<String, i32>>>,
+}
+
+impl QuotaService {
+    pub fn new(
+        database_repo: DatabaseRepository,
+        docker: Arc<DockerManager>,
+        data_dir: String,
+        storage_quota: &str,
+        encryption_key_hex: &str,
+    ) -> Self {
+        let encryption_key: [u8; 32] = crypto::parse_encryption_key(encryption_key_hex);
+
+        Self {
+            database_repo,
+            docker,
+            data_dir,
+            xfs_quota: storage_quota == "xfs",
+            encryption_key,
+            applied_quotas: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    fn decrypt_password(&self, encrypted: &str) -> AppResult<String> {
+        crypto::decrypt_password(&self.encryption_key, encrypted)
+    }
+
+    fn data_path(&self, database_id: &str) -> String {
+        format!("{}/{}", self.data_dir, database_id)
+    }
+
+    /// Measures every provisioned database and applies its storage state. Returns how many are
+    /// over their limit.
+    pub async fn enforce_all(&self) -> AppResult<usize> {
+        let mut over_limit = 0;
+
+        for database in self.database_repo.find_all_with_container().await? {
+            if !matches!(database.container_status.as_str(), "running" | "stopped") {
+                continue;
+            }
+
+            match self.enforce(&database).await {
+                Ok(state) if state == STORAGE_STATE_READ_ONLY => over_limit += 1,
+                Ok(_) => {},
+                Err(e) => {
+                    tracing::warn!(
+                        "Failed to enforce storage limit of database {}: {}",
+                        database.id,
+                        e
+                    );
+                },
+            }
+        }
+
+        Ok(over_limit)
+    }
+
+    async fn enforce(&self, database: &Database) -> AppResult<&'static str> {
+        let used_bytes = self.measure(&database.id).await?;
+        let state = storage_state(used_bytes, database.storage_limit_mb);
+
+        if self.xfs_quota {
+            if let Err(e) = self.apply_quota(database).await {
+                tracing::warn!(
+                    "Failed to apply storage quota to database {}: {}",
+                    database.id,
+                    e
+                );
+            }
+        }
+
+        let previous = database.storage_state.as_str();
+        let applied = if state != previous {
+            match self.apply_state(database, previous, state).await {
+                Ok(()) => {
+                    log_transition(database, used_bytes, state);
+                    state
+                },
+                Err(e) => {
+                    tracing::warn!(
+                        "Failed to switch database {} to storage state {}: {}",
+                        database.id,
+                        state,
+                        e
+                    );
+                    storage_state_name(previous)
+                },
+            }
+        } else {
+            // Some engines lose the block on a restart or a recreated container.
+            if state == STORAGE_STATE_READ_ONLY && database.container_status == "running" {
+                let password = self.password(database)?;
+                engine_for(&database.database_type)
+                    .reapply_write_block(&self.docker, database, &password)
+                    .await?;
+            }
+            state
+        };
+
+        self.database_repo
+            .update_storage_usage(&database.id, used_bytes, applied)
+            .await?;
+
+        Ok(applied)
+    }
+
+    async fn measure(&self, database_id: &str) -> AppResult<i64> {
+        let path = PathBuf::from(self.data_path(database_id));
+        let size = tokio::task::spawn_blocking(move || directory_size(&path))
+            .await
+            .map_err(|e| AppError::Internal(format!("Storage measurement failed: {}", e)))?
+            .map_err(|e| AppError::Internal(format!("Failed to measure data directory: {}", e)))?;
+
+        Ok(size as i64)
+    }
+
+    async fn apply_state(&self, database: &Database, from: &str, to: &str) -> AppResult<()> {
+        let block = if to == STORAGE_STATE_READ_ONLY {
+            true
+        } else if from == STORAGE_STATE_READ_ONLY {
+            false
+        } else {
+            return Ok(());
+        };
+
+        if database.container_status != "running" {
+            return Err(AppError::Conflict("Database is not running".to_string()));
+        }
+
+        let password = self.password(database)?;
+        engine_for(&database.database_type)
+            .set_write_block(&self.docker, database, &password, block)
+            .await
+    }
+
+    fn password(&self, database: &Database) -> AppResult<String> {
+        let encrypted = database
+            .password_encrypted
+            .as_ref()
+            .ok_or_else(|| AppError::Internal("Database has no password".to_string()))?;
+        self.decrypt_password(encrypted)
+    }
+
+    /// Assigns the data directory to an XFS project and caps it, so the limit holds even between
+    /// measurements. Reapplied when the limit changes, or when the directory lost its project
+    /// because an upgrade, reset or rollback swapped in a new one.
+    async fn apply_quota(&self, database: &Database) -> AppResult<()> {
+        let limit_mb = database.storage_limit_mb;
+        let path = self.data_path(&database.id);
+        let project = project_id(&database.id);
+        if self.applied_quotas.lock().unwrap().get(&database.id) == Some(&limit_mb)
+            && directory_project(&path).await? == project
+        {
+            return Ok(());
+        }
+
+        let mount = mount_point(&path).await?;
+        let hard_mb = limit_mb as i64 + limit_mb as i64 * QUOTA_HEADROOM_PERCENT / 100;
+
+        xfs_quota(&mount, &format!("project -s -p {} {}", path, project)).await?;
+        xfs_quota(&mount, &format!("limit -p bhard={}m {}", hard_mb, project)).await?;
+
+        tracing::info!(
+            "Applied {} MB XFS project quota to database {}",
+            hard_mb,
+            database.id
+        );
+        self.applied_quotas
+            .lock()
+            .unwrap()
+            .insert(database.id.clone(), limit_mb);
+
+        Ok(())
+    }
+}
+
+fn log_transition(database: &Database, used_bytes: i64, state: &str) {
+    let used_mb = used_bytes / (1024 * 1024);
+    match state {
+        STORAGE_STATE_READ_ONLY => tracing::warn!(
+            "Database {} exceeded its storage limit ({} of {} MB), writes are blocked",
+            database.id,
+            used_mb,
+            database.storage_limit_mb
+        ),
+        STORAGE_STATE_WARNING => tracing::warn!(
+            "Database {} is close to its storage limit ({} of {} MB)",
+            database.id,
+            used_mb,
+            database.storage_limit_mb
+        ),
+        _ => tracing::info!(
+            "Database {} is back under its storage limit ({} of {} MB)",
+            database.id,
+            used_mb,
+            database.storage_limit_mb
+        ),
+    }
+}
+
+fn storage_state(used_bytes: i64, limit_mb: i32) -> &'static str {
+    if limit_mb <= 0 {
+        return STORAGE_STATE_OK;
+    }
+
+    let limit_bytes = limit_mb as i64 * 1024 * 1024;
+    if used_bytes >= limit_bytes {
+        STORAGE_STATE_READ_ONLY
+    } else if used_bytes * 100 >= limit_bytes * WARNING_PERCENT {
+        STORAGE_STATE_WARNING
+    } else {
+        STORAGE_STATE_OK
+    }
+}
+
+fn storage_state_name(state: &str) -> &'static str {
+    match state {
+        STORAGE_STATE_READ_ONLY => STORAGE_STATE_READ_ONLY,
+        STORAGE_STATE_WARNING => STORAGE_STATE_WARNING,
+        _ => STORAGE_STATE_OK,
+    }
+}
+
+/// Allocated size of everything below `path`, like `du`. Files removed during the walk are
+/// skipped, since the database keeps writing while it is measured.
+fn directory_size(path: &Path) -> std::io::Result<u64> {
+    let mut total = 0;
+    let mut pending = vec![path.to_path_buf()];
+
+    while let Some(dir) = pending.pop() {
+        let entries = match std::fs::read_dir(&dir) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound && dir != path => continue,
+            Err(e) => return Err(e),
+        };
+
+        for entry in entries {
+            let (entry, metadata) = match entry.and_then(|e| e.metadata().map(|m| (e, m))) {
+                Ok(found) => found,
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => continue,
+                Err(e) => return Err(e),
+            };
+
+            if metadata.is_dir() {
+                pending.push(entry.path());
+            } else {
+                total += metadata.blocks() * 512;
+            }
+        }
+    }
+
+    Ok(total)
+}
+
+async fn mount_point(path: &str) -> AppResult<String> {
+    let output = Command::new("df")
+        .args(["--output=target", path])
+        .output()
+        .await
+        .map_err(|e| AppError::Internal(format!("Failed to run df: {}", e)))?;
+
+    if !output.status.success() {
+        return Err(AppError::Internal(format!(
+            "df failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .nth(1)
+        .map(|line| line.trim().to_string())
+        .ok_or_else(|| AppError::Internal(format!("No mount point found for {}", path)))
+}
+
+async fn xfs_quota(mount: &str, command: &str) -> AppResult<()> {
+    let output = Command::new("xfs_quota")
+        .args(["-x", "-c", command, mount])
+        .output()
+        .await
+        .map_err(|e| AppError::Internal(format!("Failed to run xfs_quota: {}", e)))?;
+
+    if !output.status.success() {
+        return Err(AppError::Internal(format!(
+            "xfs_quota '{}' failed: {}",
+            command,
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+
+    Ok(())
+}
+
+/// XFS project ID the directory is assigned to, 0 when it has none.
+async fn directory_project(path: &str) -> AppResult<u32> {
+    let output = Command::new("xfs_io")
+        .args(["-c", "lsproj", path])
+        .output()
+        .await
+        .map_err(|e| AppError::Internal(format!("Failed to run xfs_io: {}", e)))?;
+
+    if !output.status.success() {
+        return Err(AppError::Internal(format!(
+            "xfs_io lsproj failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+
+    parse_project(&String::from_utf8_lossy(&output.stdout))
+        .ok_or_else(|| AppError::Internal(format!("No project ID found for {}", path)))
+}
+
+/// Reads `projid = 10` as printed by `xfs_io -c lsproj`.
+fn parse_project(output: &str) -> Option<u32> {
+    output
+        .lines()
+        .find_map(|line| line.trim().strip_prefix("projid"))
+        .and_then(|rest| rest.trim().strip_prefix('='))
+        .and_then(|id| id.trim().parse().ok())
+}
+
+/// Stable XFS project ID derived from the leading bits of the database UUID.
+fn project_id(database_id: &str) -> u32 {
+    let hex: String = database_id
+        .chars()
+        .filter(|c| c.is_ascii_hexdigit())
+        .take(8)
+        .collect();
+    u32::from_str_radix(&hex, 16).unwrap_or(0).max(1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_storage_state() {
+        let mb = 1024 * 1024;
+
+        assert_eq!(storage_state(100 * mb, 1024), STORAGE_STATE_OK);
+        assert_eq!(storage_state(922 * mb, 1024), STORAGE_STATE_WARNING);
+        assert_eq!(storage_state(1024 * mb, 1024), STORAGE_STATE_READ_ONLY);
+        assert_eq!(storage_state(4096 * mb, 0), STORAGE_STATE_OK);
+        assert_eq!(project_id("0000000a-bcde-4f00-8000-000000000000"), 10);
+        assert_eq!(parse_project("projid = 10\n"), Some(10));
+        assert_eq!(parse_project("projid = 0"), Some(0));
+        assert_eq!(parse_project("foo: Inappropriate ioctl for device"), None);
+    }
+}
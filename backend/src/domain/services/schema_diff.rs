@@ -0,0 +1,781 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use super::common::quote_ident;
+use crate::domain::models::{
+    ColumnDetail, ColumnDiff, IndexDiff, IndexInfo, RelationName, SchemaChange, SchemaInfo,
+    TableDiff, TableInfo, ViewDiff, ViewInfo,
+};
+
+/// Compares two introspected schemas. `before` is the database the migration is meant for,
+/// `after` the one it should end up matching.
+pub fn diff_schemas(before: &SchemaInfo, after: &SchemaInfo) -> (Vec<TableDiff>, Vec<ViewDiff>) {
+    let before_tables = by_name(&before.tables, |t| (&t.schema, &t.name));
+    let after_tables = by_name(&after.tables, |t| (&t.schema, &t.name));
+
+    let mut tables = Vec::new();
+    for key in before_tables
+        .keys()
+        .chain(after_tables.keys())
+        .collect::<BTreeSet<_>>()
+    {
+        let diff = match (before_tables.get(key), after_tables.get(key)) {
+            (None, Some(table)) => whole_table(table, SchemaChange::Added),
+            (Some(table), None) => whole_table(table, SchemaChange::Removed),
+            (Some(old), Some(new)) => {
+                let columns = diff_named(&old.columns, &new.columns, |c| &c.name, column_changed)
+                    .into_iter()
+                    .map(|(name, change, before, after)| ColumnDiff {
+                        name,
+                        change,
+                        before,
+                        after,
+                    })
+                    .collect::<Vec<_>>();
+                let indexes = diff_named(&old.indexes, &new.indexes, |i| &i.name, index_changed)
+                    .into_iter()
+                    .map(|(name, change, before, after)| IndexDiff {
+                        name,
+                        change,
+                        before,
+                        after,
+                    })
+                    .collect::<Vec<_>>();
+                if columns.is_empty() && indexes.is_empty() {
+                    continue;
+                }
+                TableDiff {
+                    schema: new.schema.clone(),
+                    name: new.name.clone(),
+                    change: SchemaChange::Modified,
+                    columns,
+                    indexes,
+                }
+            },
+            (None, None) => continue,
+        };
+        tables.push(diff);
+    }
+
+    let before_views = by_name(&before.views, |v| (&v.schema, &v.name));
+    let after_views = by_name(&after.views, |v| (&v.schema, &v.name));
+
+    let mut views = Vec::new();
+    for key in before_views
+        .keys()
+        .chain(after_views.keys())
+        .collect::<BTreeSet<_>>()
+    {
+        let old = before_views.get(key);
+        let new = after_views.get(key);
+        let change = match (old, new) {
+            (None, Some(_)) => SchemaChange::Added,
+            (Some(_), None) => SchemaChange::Removed,
+            (Some(old), Some(new)) if view_changed(old, new) => SchemaChange::Modified,
+            _ => continue,
+        };
+        views.push(ViewDiff {
+            schema: key.0.to_string(),
+            name: key.1.to_string(),
+            change,
+            before: old.and_then(|v| v.definition.clone()),
+            after: new.and_then(|v| v.definition.clone()),
+        });
+    }
+
+    (tables, views)
+}
+
+/// Ordered DDL applying the differences `diff_schemas` found between `before` and `after`, and
+/// the statements among it that drop data.
+pub fn migration_statements(
+    before: &SchemaInfo,
+    after: &SchemaInfo,
+    tables: &[TableDiff],
+    views: &[ViewDiff],
+) -> (Vec<String>, Vec<String>) {
+    let mut statements = Vec::new();
+    let mut warnings = Vec::new();
+
+    // Views go first and come back last, as they may depend on any of the tables changed below.
+    let (dropped, created) = rebuilt_views(before, after, tables, views);
+    for view in &dropped {
+        statements.push(format!(
+            "DROP VIEW IF EXISTS {};",
+            qualified(&view.schema, &view.name)
+        ));
+    }
+
+    let added = tables
+        .iter()
+        .filter(|t| t.change == SchemaChange::Added)
+        .collect::<Vec<_>>();
+    let schemas = added
+        .iter()
+        .map(|t| t.schema.as_str())
+        .chain(
+            views
+                .iter()
+                .filter(|v| v.change == SchemaChange::Added)
+                .map(|v| v.schema.as_str()),
+        )
+        .filter(|schema| *schema != "public")
+        .collect::<BTreeSet<_>>();
+    for schema in schemas {
+        statements.push(format!(
+            "CREATE SCHEMA IF NOT EXISTS {};",
+            quote_ident(schema)
+        ));
+    }
+
+    for table in &added {
+        let name = qualified(&table.schema, &table.name);
+        let columns = table
+            .columns
+            .iter()
+            .filter_map(|c| c.after.as_ref())
+            .collect::<Vec<_>>();
+
+        for column in &columns {
+            create_sequence(column, &mut statements);
+        }
+
+        let mut definitions = columns
+            .iter()
+            .map(|c| format!("    {}", column_definition(c)))
+            .collect::<Vec<_>>();
+        for index in table.indexes.iter().filter_map(|i| i.after.as_ref()) {
+            if index.is_primary {
+                definitions.push(format!(
+                    "    CONSTRAINT {} PRIMARY KEY ({})",
+                    quote_ident(&index.name),
+                    column_list(&index.columns)
+                ));
+            }
+        }
+        statements.push(format!(
+            "CREATE TABLE {} (\n{}\n);",
+            name,
+            definitions.join(",\n")
+        ));
+
+        for index in table.indexes.iter().filter_map(|i| i.after.as_ref()) {
+            if !index.is_primary {
+                statements.push(format!("{};", index.definition));
+            }
+        }
+    }
+
+    for table in tables.iter().filter(|t| t.change == SchemaChange::Modified) {
+        let name = qualified(&table.schema, &table.name);
+
+        for index in table
+            .indexes
+            .iter()
+            .filter(|i| i.change != SchemaChange::Added)
+        {
+            let Some(old) = index.before.as_ref() else {
+                continue;
+            };
+            if old.is_primary {
+                statements.push(format!(
+                    "ALTER TABLE {} DROP CONSTRAINT IF EXISTS {};",
+                    name,
+                    quote_ident(&old.name)
+                ));
+            } else {
+                statements.push(format!(
+                    "DROP INDEX IF EXISTS {};",
+                    qualified(&table.schema, &old.name)
+                ));
+            }
+        }
+
+        for column in &table.columns {
+            match (column.change, &column.before, &column.after) {
+                (SchemaChange::Added, _, Some(new)) => {
+                    create_sequence(new, &mut statements);
+                    statements.push(format!(
+                        "ALTER TABLE {} ADD COLUMN {};",
+                        name,
+                        column_definition(new)
+                    ));
+                },
+                (SchemaChange::Modified, Some(old), Some(new)) => {
+                    alter_column(&name, old, new, &mut statements);
+                },
+                (SchemaChange::Removed, Some(old), _) => {
+                    let statement = format!(
+                        "ALTER TABLE {} DROP COLUMN {};",
+                        name,
+                        quote_ident(&old.name)
+                    );
+                    warnings.push(statement.clone());
+                    statements.push(statement);
+                },
+                _ => {},
+            }
+        }
+
+        for index in table
+            .indexes
+            .iter()
+            .filter(|i| i.change != SchemaChange::Removed)
+        {
+            let Some(new) = index.after.as_ref() else {
+                continue;
+            };
+            if new.is_primary {
+                statements.push(format!(
+                    "ALTER TABLE {} ADD CONSTRAINT {} PRIMARY KEY ({});",
+                    name,
+                    quote_ident(&new.name),
+                    column_list(&new.columns)
+                ));
+            } else {
+                statements.push(format!("{};", new.definition));
+            }
+        }
+    }
+
+    for table in tables.iter().filter(|t| t.change == SchemaChange::Removed) {
+        let statement = format!("DROP TABLE {};", qualified(&table.schema, &table.name));
+        warnings.push(statement.clone());
+        statements.push(statement);
+    }
+
+    for view in &created {
+        if let Some(definition) = &view.definition {
+            statements.push(format!(
+                "CREATE VIEW {} AS\n{};",
+                qualified(&view.schema, &view.name),
+                definition.trim().trim_end_matches(';')
+            ));
+        }
+    }
+
+    (statements, warnings)
+}
+
+/// Views to drop, dependents before the views they read from, and views to create, each after
+/// the views it reads from. Besides the views that changed, every view reading from a modified
+/// or removed table, directly or through other dropped views, is dropped and created again as
+/// it is, since PostgreSQL refuses to alter a table a view depends on. Dropping all dependents
+/// explicitly means no `CASCADE` is needed that could take views along which are not recreated.
+fn rebuilt_views<'a>(
+    before: &'a SchemaInfo,
+    after: &'a SchemaInfo,
+    tables: &[TableDiff],
+    views: &[ViewDiff],
+) -> (Vec<&'a ViewInfo>, Vec<&'a ViewInfo>) {
+    let before_views = view_map(&before.views);
+    let after_views = view_map(&after.views);
+
+    let mut dropped = views
+        .iter()
+        .filter(|v| v.change != SchemaChange::Added)
+        .map(|v| relation(&v.schema, &v.name))
+        .collect::<BTreeSet<_>>();
+    let altered = tables
+        .iter()
+        .filter(|t| t.change != SchemaChange::Added)
+        .map(|t| relation(&t.schema, &t.name))
+        .collect::<BTreeSet<_>>();
+    loop {
+        let dependents = before_views
+            .iter()
+            .filter(|(key, view)| {
+                !dropped.contains(*key)
+                    && view
+                        .depends_on
+                        .iter()
+                        .any(|dep| altered.contains(dep) || dropped.contains(dep))
+            })
+            .map(|(key, _)| key.clone())
+            .collect::<Vec<_>>();
+        if dependents.is_empty() {
+            break;
+        }
+        dropped.extend(dependents);
+    }
+
+    let created = dropped
+        .iter()
+        .filter(|key| after_views.contains_key(*key))
+        .cloned()
+        .chain(
+            views
+                .iter()
+                .filter(|v| v.change == SchemaChange::Added)
+                .map(|v| relation(&v.schema, &v.name)),
+        )
+        .collect::<BTreeSet<_>>();
+
+    let mut drop_order = dependency_order(&dropped, &before_views);
+    drop_order.reverse();
+    (drop_order, dependency_order(&created, &after_views))
+}
+
+fn view_map(views: &[ViewInfo]) -> BTreeMap<RelationName, &ViewInfo> {
+    views
+        .iter()
+        .map(|view| (relation(&view.schema, &view.name), view))
+        .collect()
+}
+
+/// The views of `keys`, each after the views among them it depends on.
+fn dependency_order<'a>(
+    keys: &BTreeSet<RelationName>,
+    views: &BTreeMap<RelationName, &'a ViewInfo>,
+) -> Vec<&'a ViewInfo> {
+    fn visit<'a>(
+        key: &RelationName,
+        keys: &BTreeSet<RelationName>,
+        views: &BTreeMap<RelationName, &'a ViewInfo>,
+        visited: &mut BTreeSet<RelationName>,
+        order: &mut Vec<&'a ViewInfo>,
+    ) {
+        if !keys.contains(key) || !visited.insert(key.clone()) {
+            return;
+        }
+        let Some(view) = views.get(key) else {
+            return;
+        };
+        for dep in &view.depends_on {
+            visit(dep, keys, views, visited, order);
+        }
+        order.push(view);
+    }
+
+    let mut visited = BTreeSet::new();
+    let mut order = Vec::new();
+    for key in keys {
+        visit(key, keys, views, &mut visited, &mut order);
+    }
+    order
+}
+
+fn relation(schema: &str, name: &str) -> RelationName {
+    RelationName {
+        schema: schema.to_string(),
+        name: name.to_string(),
+    }
+}
+
+fn by_name<T>(items: &[T], key: impl Fn(&T) -> (&String, &String)) -> BTreeMap<(&str, &str), &T> {
+    items
+        .iter()
+        .map(|item| {
+            let (schema, name) = key(item);
+            ((schema.as_str(), name.as_str()), item)
+        })
+        .collect()
+}
+
+type NamedChange<T> = (String, SchemaChange, Option<T>, Option<T>);
+
+/// Pairs up items by name, keeping the order of `after` followed by anything only in `before`.
+fn diff_named<T: Clone>(
+    before: &[T],
+    after: &[T],
+    name: impl Fn(&T) -> &String,
+    changed: impl Fn(&T, &T) -> bool,
+) -> Vec<NamedChange<T>> {
+    let mut changes = Vec::new();
+
+    for new in after {
+        match before.iter().find(|old| name(old) == name(new)) {
+            None => changes.push((
+                name(new).clone(),
+                SchemaChange::Added,
+                None,
+                Some(new.clone()),
+            )),
+            Some(old) if changed(old, new) => changes.push((
+                name(new).clone(),
+                SchemaChange::Modified,
+                Some(old.clone()),
+                Some(new.clone()),
+            )),
+            Some(_) => {},
+        }
+    }
+
+    for old in before {
+        if !after.iter().any(|new| name(new) == name(old)) {
+            changes.push((
+                name(old).clone(),
+                SchemaChange::Removed,
+                Some(old.clone()),
+                None,
+            ));
+        }
+    }
+
+    changes
+}
+
+fn whole_table(table: &TableInfo, change: SchemaChange) -> TableDiff {
+    let side = |present: bool| present == (change == SchemaChange::Added);
+    TableDiff {
+        schema: table.schema.clone(),
+        name: table.name.clone(),
+        change,
+        columns: table
+            .columns
+            .iter()
+            .map(|c| ColumnDiff {
+                name: c.name.clone(),
+                change,
+                before: side(false).then(|| c.clone()),
+                after: side(true).then(|| c.clone()),
+            })
+            .collect(),
+        indexes: table
+            .indexes
+            .iter()
+            .map(|i| IndexDiff {
+                name: i.name.clone(),
+                change,
+                before: side(false).then(|| i.clone()),
+                after: side(true).then(|| i.clone()),
+            })
+            .collect(),
+    }
+}
+
+fn column_changed(old: &ColumnDetail, new: &ColumnDetail) -> bool {
+    column_type(old) != column_type(new)
+        || old.nullable != new.nullable
+        || old.default_value != new.default_value
+        || old.is_primary_key != new.is_primary_key
+}
+
+fn index_changed(old: &IndexInfo, new: &IndexInfo) -> bool {
+    old.definition != new.definition
+        || old.columns != new.columns
+        || old.is_unique != new.is_unique
+        || old.is_primary != new.is_primary
+}
+
+fn view_changed(old: &ViewInfo, new: &ViewInfo) -> bool {
+    let normalize = |definition: &Option<String>| {
+        definition
+            .as_deref()
+            .map(|d| d.split_whitespace().collect::<Vec<_>>().join(" "))
+    };
+    normalize(&old.definition) != normalize(&new.definition)
+}
+
+fn column_type(column: &ColumnDetail) -> &str {
+    if column.sql_type.is_empty() {
+        &column.data_type
+    } else {
+        &column.sql_type
+    }
+}
+
+fn column_definition(column: &ColumnDetail) -> String {
+    let mut definition = format!("{} {}", quote_ident(&column.name), column_type(column));
+    if !column.nullable {
+        definition.push_str(" NOT NULL");
+    }
+    if let Some(default) = &column.default_value {
+        definition.push_str(" DEFAULT ");
+        definition.push_str(default);
+    }
+    definition
+}
+
+fn alter_column(table: &str, old: &ColumnDetail, new: &ColumnDetail, statements: &mut Vec<String>) {
+    let column = quote_ident(&new.name);
+
+    if column_type(old) != column_type(new) {
+        statements.push(format!(
+            "ALTER TABLE {} ALTER COLUMN {} TYPE {} USING {}::{};",
+            table,
+            column,
+            column_type(new),
+            column,
+            column_type(new)
+        ));
+    }
+
+    if old.default_value != new.default_value {
+        match &new.default_value {
+            Some(default) => {
+                create_sequence(new, statements);
+                statements.push(format!(
+                    "ALTER TABLE {} ALTER COLUMN {} SET DEFAULT {};",
+                    table, column, default
+                ));
+            },
+            None => statements.push(format!(
+                "ALTER TABLE {} ALTER COLUMN {} DROP DEFAULT;",
+                table, column
+            )),
+        }
+    }
+
+    if old.nullable != new.nullable {
+        let action = if new.nullable { "DROP" } else { "SET" };
+        statements.push(format!(
+            "ALTER TABLE {} ALTER COLUMN {} {} NOT NULL;",
+            table, column, action
+        ));
+    }
+}
+
+/// Serial columns default to `nextval('sequence'::regclass)`, which has to exist first.
+fn create_sequence(column: &ColumnDetail, statements: &mut Vec<String>) {
+    let Some(sequence) = column
+        .default_value
+        .as_deref()
+        .and_then(|default| default.strip_prefix("nextval('"))
+        .and_then(|rest| rest.split_once("'::regclass)"))
+        .map(|(sequence, _)| sequence.replace("''", "'"))
+    else {
+        return;
+    };
+    statements.push(format!("CREATE SEQUENCE IF NOT EXISTS {};", sequence));
+}
+
+fn column_list(columns: &[String]) -> String {
+    columns
+        .iter()
+        .map(|c| quote_ident(c))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn qualified(schema: &str, name: &str) -> String {
+    format!("{}.{}", quote_ident(schema), quote_ident(name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn column(name: &str, sql_type: &str, nullable: bool, is_primary_key: bool) -> ColumnDetail {
+        ColumnDetail {
+            name: name.to_string(),
+            data_type: sql_type.to_string(),
+            nullable,
+            default_value: None,
+            is_primary_key,
+            sql_type: sql_type.to_string(),
+        }
+    }
+
+    fn table(name: &str, columns: Vec<ColumnDetail>, indexes: Vec<IndexInfo>) -> TableInfo {
+        TableInfo {
+            schema: "public".to_string(),
+            name: name.to_string(),
+            columns,
+            indexes,
+            row_count_estimate: 0,
+            size_bytes: 0,
+        }
+    }
+
+    fn primary_key(table: &str) -> IndexInfo {
+        IndexInfo {
+            name: format!("{}_pkey", table),
+            columns: vec!["id".to_string()],
+            is_unique: true,
+            is_primary: true,
+            definition: format!(
+                "CREATE UNIQUE INDEX {}_pkey ON public.{} USING btree (id)",
+                table, table
+            ),
+        }
+    }
+
+    #[test]
+    fn test_diff_schemas_migration() {
+        let parent = SchemaInfo {
+            tables: vec![
+                table(
+                    "users",
+                    vec![
+                        column("id", "integer", false, true),
+                        column("nickname", "text", true, false),
+                        column("email", "character varying(100)", true, false),
+                    ],
+                    vec![primary_key("users")],
+                ),
+                table(
+                    "legacy",
+                    vec![column("id", "integer", false, false)],
+                    vec![],
+                ),
+            ],
+            views: vec![ViewInfo {
+                schema: "public".to_string(),
+                name: "active_users".to_string(),
+                columns: vec![],
+                definition: Some(" SELECT id FROM users;".to_string()),
+                depends_on: vec![],
+            }],
+        };
+
+        let mut serial = column("id", "integer", false, true);
+        serial.default_value = Some("nextval('orders_id_seq'::regclass)".to_string());
+        let email_index = IndexInfo {
+            name: "users_email_idx".to_string(),
+            columns: vec!["email".to_string()],
+            is_unique: true,
+            is_primary: false,
+            definition: "CREATE UNIQUE INDEX users_email_idx ON public.users USING btree (email)"
+                .to_string(),
+        };
+        let branch = SchemaInfo {
+            tables: vec![
+                table(
+                    "users",
+                    vec![
+                        column("id", "integer", false, true),
+                        column("email", "character varying(255)", false, false),
+                        column("created_at", "timestamp with time zone", true, false),
+                    ],
+                    vec![primary_key("users"), email_index],
+                ),
+                table("orders", vec![serial], vec![primary_key("orders")]),
+            ],
+            views: vec![ViewInfo {
+                schema: "public".to_string(),
+                name: "active_users".to_string(),
+                columns: vec![],
+                definition: Some(" SELECT id\n   FROM users;".to_string()),
+                depends_on: vec![],
+            }],
+        };
+
+        let (tables, views) = diff_schemas(&parent, &branch);
+        assert!(views.is_empty());
+        let changes = tables
+            .iter()
+            .map(|t| (t.name.as_str(), t.change))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            changes,
+            vec![
+                ("legacy", SchemaChange::Removed),
+                ("orders", SchemaChange::Added),
+                ("users", SchemaChange::Modified),
+            ]
+        );
+        let users = &tables[2];
+        let columns = users
+            .columns
+            .iter()
+            .map(|c| (c.name.as_str(), c.change))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            columns,
+            vec![
+                ("email", SchemaChange::Modified),
+                ("created_at", SchemaChange::Added),
+                ("nickname", SchemaChange::Removed),
+            ]
+        );
+        assert_eq!(users.indexes.len(), 1);
+
+        let (migration, warnings) = migration_statements(&parent, &branch, &tables, &views);
+        assert_eq!(
+            migration,
+            vec![
+                "CREATE SEQUENCE IF NOT EXISTS orders_id_seq;",
+                "CREATE TABLE \"public\".\"orders\" (\n    \"id\" integer NOT NULL DEFAULT nextval('orders_id_seq'::regclass),\n    CONSTRAINT \"orders_pkey\" PRIMARY KEY (\"id\")\n);",
+                "ALTER TABLE \"public\".\"users\" ALTER COLUMN \"email\" TYPE character varying(255) USING \"email\"::character varying(255);",
+                "ALTER TABLE \"public\".\"users\" ALTER COLUMN \"email\" SET NOT NULL;",
+                "ALTER TABLE \"public\".\"users\" ADD COLUMN \"created_at\" timestamp with time zone;",
+                "ALTER TABLE \"public\".\"users\" DROP COLUMN \"nickname\";",
+                "CREATE UNIQUE INDEX users_email_idx ON public.users USING btree (email);",
+                "DROP TABLE \"public\".\"legacy\";",
+            ]
+        );
+        assert_eq!(
+            warnings,
+            vec![
+                "ALTER TABLE \"public\".\"users\" DROP COLUMN \"nickname\";",
+                "DROP TABLE \"public\".\"legacy\";",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_views_over_altered_tables_are_rebuilt() {
+        let view = |name: &str, definition: &str, depends_on: &[&str]| ViewInfo {
+            schema: "public".to_string(),
+            name: name.to_string(),
+            columns: vec![],
+            definition: Some(definition.to_string()),
+            depends_on: depends_on.iter().map(|d| relation("public", d)).collect(),
+        };
+        let views = vec![
+            view(
+                "order_totals",
+                " SELECT id, amount FROM orders;",
+                &["orders"],
+            ),
+            view(
+                "big_orders",
+                " SELECT id FROM order_totals WHERE amount > 100;",
+                &["order_totals"],
+            ),
+            view(
+                "customer_names",
+                " SELECT name FROM customers;",
+                &["customers"],
+            ),
+        ];
+        let customers = table(
+            "customers",
+            vec![column("name", "text", true, false)],
+            vec![],
+        );
+        let parent = SchemaInfo {
+            tables: vec![
+                customers.clone(),
+                table(
+                    "orders",
+                    vec![
+                        column("id", "integer", false, true),
+                        column("amount", "integer", true, false),
+                    ],
+                    vec![],
+                ),
+            ],
+            views: views.clone(),
+        };
+        let branch = SchemaInfo {
+            tables: vec![
+                customers,
+                table(
+                    "orders",
+                    vec![
+                        column("id", "integer", false, true),
+                        column("amount", "numeric(12,2)", true, false),
+                    ],
+                    vec![],
+                ),
+            ],
+            views,
+        };
+
+        let (tables, views) = diff_schemas(&parent, &branch);
+        assert!(views.is_empty());
+        let (migration, warnings) = migration_statements(&parent, &branch, &tables, &views);
+        assert_eq!(
+            migration,
+            vec![
+                "DROP VIEW IF EXISTS \"public\".\"big_orders\";",
+                "DROP VIEW IF EXISTS \"public\".\"order_totals\";",
+                "ALTER TABLE \"public\".\"orders\" ALTER COLUMN \"amount\" TYPE numeric(12,2) USING \"amount\"::numeric(12,2);",
+                "CREATE VIEW \"public\".\"order_totals\" AS\nSELECT id, amount FROM orders;",
+                "CREATE VIEW \"public\".\"big_orders\" AS\nSELECT id FROM order_totals WHERE amount > 100;",
+            ]
+        );
+        assert!(warnings.is_empty());
+    }
+}
@@ -0,0 +1,359 @@
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use serde::Deserialize;
+use serde_json::Value;
+
+use super::common::quote_clickhouse_ident;
+use crate::domain::models::{
+    ColumnDetail, ColumnInfo, Database, IndexInfo, QueryResult, SchemaInfo, TableInfo,
+    TablePreview, ViewInfo, CLICKHOUSE_DEFAULT_DATABASE,
+};
+use crate::error::{AppError, AppResult};
+
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+/// Extra time for the server to cancel a query before the client gives up on it.
+const TIMEOUT_GRACE: Duration = Duration::from_secs(2);
+
+/// Statements that read or write files on the server, including the backup disk, or stop it.
+pub(crate) const CLICKHOUSE_BLOCKED_STATEMENTS: &[&str] = &[
+    "INTO OUTFILE",
+    "FROM INFILE",
+    "TO DISK(",
+    "FROM DISK(",
+    "SYSTEM SHUTDOWN",
+    "SYSTEM KILL",
+];
+
+pub(crate) const USER_DATABASES: &str =
+    "database NOT IN ('system', 'information_schema', 'INFORMATION_SCHEMA')";
+
+/// Client for the HTTP interface of a ClickHouse database, which answers in `JSONCompact`.
+pub(crate) struct ClickhouseHttp {
+    client: reqwest::Client,
+    url: String,
+    username: String,
+    password: String,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub(crate) struct CompactResult {
+    #[serde(default)]
+    pub meta: Vec<CompactColumn>,
+    #[serde(default)]
+    pub data: Vec<Vec<Value>>,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct CompactColumn {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub data_type: String,
+}
+
+impl ClickhouseHttp {
+    pub(crate) fn new(database: &Database, password: &str) -> AppResult<Self> {
+        let client = reqwest::Client::builder()
+            .connect_timeout(CONNECT_TIMEOUT)
+            .build()
+            .map_err(|e| AppError::Internal(format!("Failed to build HTTP client: {}", e)))?;
+
+        Ok(Self {
+            client,
+            url: format!("http://{}:8123/", database.container_name()),
+            username: database.username.clone(),
+            password: password.to_string(),
+        })
+    }
+
+    /// Runs one statement with extra query `settings`. Statements without a result, such as DDL,
+    /// come back empty. `wait_end_of_query` makes errors raised mid-query fail the response.
+    pub(crate) async fn query(
+        &self,
+        sql: &str,
+        settings: &[(&str, String)],
+        timeout: Duration,
+    ) -> Result<CompactResult, String> {
+        let response = self
+            .client
+            .post(&self.url)
+            .timeout(timeout)
+            .header("X-ClickHouse-User", &self.username)
+            .header("X-ClickHouse-Key", &self.password)
+            .query(&[
+                ("database", CLICKHOUSE_DEFAULT_DATABASE),
+                ("default_format", "JSONCompact"),
+                ("output_format_json_quote_64bit_integers", "0"),
+                ("wait_end_of_query", "1"),
+            ])
+            .query(settings)
+            .body(sql.to_string())
+            .send()
+            .await
+            .map_err(|e| {
+                if e.is_timeout() {
+                    "Query execution timed out".to_string()
+                } else {
+                    e.to_string()
+                }
+            })?;
+
+        let status = response.status();
+        let body = response.text().await.map_err(|e| e.to_string())?;
+        if !status.is_success() {
+            return Err(body.trim().to_string());
+        }
+        if body.trim().is_empty() {
+            return Ok(CompactResult::default());
+        }
+
+        serde_json::from_str(&body).map_err(|e| format!("Unexpected response: {}", e))
+    }
+
+    /// Runs a statement of our own that is expected to answer quickly.
+    pub(crate) async fn fetch(&self, sql: &str) -> Result<CompactResult, String> {
+        self.query(sql, &[], REQUEST_TIMEOUT).await
+    }
+}
+
+pub(crate) async fn get_schema(http: &ClickhouseHttp) -> AppResult<SchemaInfo> {
+    let mut columns = get_columns(http).await?;
+    let mut indexes = get_indexes(http).await?;
+
+    let table_rows = http
+        .fetch(&format!(
+            "SELECT database, name, engine LIKE '%View', \
+                    toInt64(ifNull(total_rows, 0)), toInt64(ifNull(total_bytes, 0)), as_select \
+             FROM system.tables \
+             WHERE {} AND NOT is_temporary \
+             ORDER BY database, name",
+            USER_DATABASES
+        ))
+        .await
+        .map_err(|e| AppError::Internal(format!("Failed to query tables: {}", e)))?;
+
+    let mut tables = Vec::new();
+    let mut views = Vec::new();
+    for row in &table_rows.data {
+        let schema = text(row, 0).unwrap_or_default();
+        let name = text(row, 1).unwrap_or_default();
+        let key = (schema.clone(), name.clone());
+        if int(row, 2) != 0 {
+            views.push(ViewInfo {
+                columns: columns.remove(&key).unwrap_or_default(),
+                schema,
+                name,
+                definition: text(row, 5).filter(|s| !s.is_empty()),
+                depends_on: Vec::new(),
+            });
+        } else {
+            tables.push(TableInfo {
+                columns: columns.remove(&key).unwrap_or_default(),
+                indexes: indexes.remove(&key).unwrap_or_default(),
+                schema,
+                name,
+                row_count_estimate: int(row, 3),
+                size_bytes: int(row, 4),
+            });
+        }
+    }
+
+    Ok(SchemaInfo { tables, views })
+}
+
+type TableKey = (String, String);
+
+async fn get_columns(http: &ClickhouseHttp) -> AppResult<HashMap<TableKey, Vec<ColumnDetail>>> {
+    let result = http
+        .fetch(&format!(
+            "SELECT database, table, name, type, default_expression, is_in_primary_key \
+             FROM system.columns WHERE {} \
+             ORDER BY database, table, position",
+            USER_DATABASES
+        ))
+        .await
+        .map_err(|e| AppError::Internal(format!("Failed to query columns: {}", e)))?;
+
+    let mut columns: HashMap<TableKey, Vec<ColumnDetail>> = HashMap::new();
+    for row in &result.data {
+        let key = (
+            text(row, 0).unwrap_or_default(),
+            text(row, 1).unwrap_or_default(),
+        );
+        let sql_type = text(row, 3).unwrap_or_default();
+        columns.entry(key).or_default().push(ColumnDetail {
+            name: text(row, 2).unwrap_or_default(),
+            nullable: sql_type.starts_with("Nullable("),
+            data_type: sql_type.clone(),
+            default_value: text(row, 4).filter(|s| !s.is_empty()),
+            is_primary_key: int(row, 5) != 0,
+            sql_type,
+        });
+    }
+
+    Ok(columns)
+}
+
+/// Data skipping indexes; the primary key is part of the table definition and shows on the
+/// columns instead.
+async fn get_indexes(http: &ClickhouseHttp) -> AppResult<HashMap<TableKey, Vec<IndexInfo>>> {
+    let result = http
+        .fetch(&format!(
+            "SELECT database, table, name, expr, type_full, toInt64(granularity) \
+             FROM system.data_skipping_indices WHERE {} \
+             ORDER BY database, table, name",
+            USER_DATABASES
+        ))
+        .await
+        .map_err(|e| AppError::Internal(format!("Failed to query indexes: {}", e)))?;
+
+    let mut indexes: HashMap<TableKey, Vec<IndexInfo>> = HashMap::new();
+    for row in &result.data {
+        let schema = text(row, 0).unwrap_or_default();
+        let table = text(row, 1).unwrap_or_default();
+        let name = text(row, 2).unwrap_or_default();
+        let expr = text(row, 3).unwrap_or_default();
+        let definition = format!(
+            "ALTER TABLE {}.{} ADD INDEX {} {} TYPE {} GRANULARITY {}",
+            quote_clickhouse_ident(&schema),
+            quote_clickhouse_ident(&table),
+            quote_clickhouse_ident(&name),
+            expr,
+            text(row, 4).unwrap_or_default(),
+            int(row, 5)
+        );
+
+        indexes.entry((schema, table)).or_default().push(IndexInfo {
+            name,
+            columns: vec![expr],
+            is_unique: false,
+            is_primary: false,
+            definition,
+        });
+    }
+
+    Ok(indexes)
+}
+
+/// Runs one statement and returns up to `limit` rows. `result_overflow_mode = break` stops the
+/// server once the limit is passed instead of failing the query.
+pub(crate) async fn execute_query(
+    http: &ClickhouseHttp,
+    sql: &str,
+    limit: i32,
+    timeout_ms: i32,
+) -> AppResult<QueryResult> {
+    let timeout = Duration::from_millis(timeout_ms.max(0) as u64);
+    let settings = [
+        (
+            "max_execution_time",
+            timeout.as_secs_f64().ceil().max(1.0).to_string(),
+        ),
+        ("max_result_rows", (limit + 1).to_string()),
+        ("result_overflow_mode", "break".to_string()),
+    ];
+    let statement = sql.trim().trim_end_matches(';');
+
+    let start = Instant::now();
+    let result = http
+        .query(statement, &settings, timeout + TIMEOUT_GRACE)
+        .await
+        .map_err(|e| AppError::Validation(format!("Query execution failed: {}", e)))?;
+    let execution_time_ms = start.elapsed().as_secs_f64() * 1000.0;
+
+    let total_rows = result.data.len() as i64;
+    let truncated = total_rows > limit as i64;
+
+    Ok(QueryResult {
+        columns: column_infos(&result.meta),
+        rows: result.data.into_iter().take(limit as usize).collect(),
+        row_count: total_rows,
+        execution_time_ms,
+        truncated,
+    })
+}
+
+pub(crate) async fn preview_table(
+    http: &ClickhouseHttp,
+    schema: &str,
+    table: &str,
+    limit: i32,
+    offset: i32,
+) -> AppResult<TablePreview> {
+    let target = format!(
+        "{}.{}",
+        quote_clickhouse_ident(schema),
+        quote_clickhouse_ident(table)
+    );
+
+    let count = http
+        .fetch(&format!("SELECT toInt64(count()) FROM {}", target))
+        .await
+        .map_err(|e| AppError::Internal(format!("Failed to count rows: {}", e)))?;
+    let total_rows = count.data.first().map(|row| int(row, 0)).unwrap_or(0);
+
+    let result = http
+        .fetch(&format!(
+            "SELECT * FROM {} LIMIT {} OFFSET {}",
+            target, limit, offset
+        ))
+        .await
+        .map_err(|e| AppError::Internal(format!("Failed to query table: {}", e)))?;
+
+    Ok(TablePreview {
+        schema: schema.to_string(),
+        table: table.to_string(),
+        columns: column_infos(&result.meta),
+        rows: result.data,
+        total_rows,
+        limit,
+        offset,
+    })
+}
+
+fn column_infos(meta: &[CompactColumn]) -> Vec<ColumnInfo> {
+    meta.iter()
+        .map(|col| ColumnInfo {
+            name: col.name.clone(),
+            data_type: col.data_type.clone(),
+        })
+        .collect()
+}
+
+pub(crate) fn text(row: &[Value], index: usize) -> Option<String> {
+    row.get(index).and_then(Value::as_str).map(str::to_string)
+}
+
+/// Reads an integer whether the server sent it as a number or, for 64-bit types, a string.
+pub(crate) fn int(row: &[Value], index: usize) -> i64 {
+    match row.get(index) {
+        Some(Value::Number(n)) => n
+            .as_i64()
+            .or_else(|| n.as_f64().map(|f| f as i64))
+            .unwrap_or(0),
+        Some(Value::String(s)) => s.parse().unwrap_or(0),
+        Some(Value::Bool(b)) => *b as i64,
+        _ => 0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compact_result_values() {
+        let result: CompactResult = serde_json::from_str(
+            r#"{"meta":[{"name":"n","type":"UInt64"},{"name":"s","type":"String"}],
+                "data":[["18446744073709551615","a`b"],[42,null]],"rows":2}"#,
+        )
+        .unwrap();
+
+        assert_eq!(result.meta[0].data_type, "UInt64");
+        assert_eq!(int(&result.data[1], 0), 42);
+        assert_eq!(text(&result.data[0], 1).as_deref(), Some("a`b"));
+        assert_eq!(text(&result.data[1], 1), None);
+        assert_eq!(quote_clickhouse_ident("a`b"), "`a\\`b`");
+    }
+}
@@ -0,0 +1,456 @@
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use futures::{StreamExt, TryStreamExt};
+use sqlx::mysql::types::MySqlTime;
+use sqlx::mysql::{MySqlConnectOptions, MySqlConnection, MySqlRow};
+use sqlx::{Column, Connection, Executor, Row, Statement, TypeInfo, ValueRef};
+
+use super::common::quote_mysql_ident;
+use crate::domain::models::{
+    ColumnDetail, ColumnInfo, Database, IndexInfo, QueryResult, SchemaInfo, TableInfo,
+    TablePreview, ViewInfo, MYSQL_DEFAULT_DATABASE,
+};
+use crate::error::{AppError, AppResult};
+
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+/// Extra time for the server to cancel a statement before the client gives up on it.
+const TIMEOUT_GRACE: Duration = Duration::from_secs(2);
+
+/// Statements that read or write files on the server, or stop it.
+pub(crate) const MYSQL_BLOCKED_STATEMENTS: &[&str] =
+    &["LOAD DATA", "INTO OUTFILE", "INTO DUMPFILE", "SHUTDOWN"];
+
+const USER_SCHEMAS: &str =
+    "table_schema NOT IN ('mysql', 'information_schema', 'performance_schema', 'sys')";
+
+pub(crate) async fn connect(database: &Database, password: &str) -> AppResult<MySqlConnection> {
+    let container_name = database.container_name();
+    let options = MySqlConnectOptions::new()
+        .host(&container_name)
+        .port(3306)
+        .username(&database.username)
+        .password(password)
+        .database(MYSQL_DEFAULT_DATABASE);
+
+    match tokio::time::timeout(CONNECT_TIMEOUT, MySqlConnection::connect_with(&options)).await {
+        Ok(Ok(conn)) => Ok(conn),
+        Ok(Err(e)) => {
+            tracing::error!("MySQL connection failed to {}: {}", container_name, e);
+            Err(AppError::Internal(format!(
+                "Failed to connect to database: {}",
+                e
+            )))
+        },
+        Err(_) => Err(AppError::Internal(
+            "Timed out connecting to the database".to_string(),
+        )),
+    }
+}
+
+pub(crate) async fn get_schema(conn: &mut MySqlConnection) -> AppResult<SchemaInfo> {
+    let mut columns = get_columns(conn).await?;
+    let mut indexes = get_indexes(conn).await?;
+
+    let table_rows = sqlx::query(&format!(
+        "SELECT CAST(table_schema AS CHAR), CAST(table_name AS CHAR), \
+                CAST(COALESCE(table_rows, 0) AS SIGNED), \
+                CAST(COALESCE(data_length, 0) + COALESCE(index_length, 0) AS SIGNED) \
+         FROM information_schema.tables \
+         WHERE table_type = 'BASE TABLE' AND {} \
+         ORDER BY table_schema, table_name",
+        USER_SCHEMAS
+    ))
+    .fetch_all(&mut *conn)
+    .await
+    .map_err(|e| AppError::Internal(format!("Failed to query tables: {}", e)))?;
+
+    let tables = table_rows
+        .iter()
+        .map(|row| {
+            let schema = text(row, 0).unwrap_or_default();
+            let name = text(row, 1).unwrap_or_default();
+            let key = (schema.clone(), name.clone());
+            TableInfo {
+                columns: columns.remove(&key).unwrap_or_default(),
+                indexes: indexes.remove(&key).unwrap_or_default(),
+                schema,
+                name,
+                row_count_estimate: row.try_get(2).unwrap_or(0),
+                size_bytes: row.try_get(3).unwrap_or(0),
+            }
+        })
+        .collect();
+
+    let view_rows = sqlx::query(&format!(
+        "SELECT CAST(table_schema AS CHAR), CAST(table_name AS CHAR), \
+                CAST(view_definition AS CHAR) \
+         FROM information_schema.views WHERE {} \
+         ORDER BY table_schema, table_name",
+        USER_SCHEMAS
+    ))
+    .fetch_all(&mut *conn)
+    .await
+    .map_err(|e| AppError::Internal(format!("Failed to query views: {}", e)))?;
+
+    let views = view_rows
+        .iter()
+        .map(|row| {
+            let schema = text(row, 0).unwrap_or_default();
+            let name = text(row, 1).unwrap_or_default();
+            ViewInfo {
+                columns: columns
+                    .remove(&(schema.clone(), name.clone()))
+                    .unwrap_or_default(),
+                schema,
+                name,
+                definition: text(row, 2),
+                depends_on: Vec::new(),
+            }
+        })
+        .collect();
+
+    Ok(SchemaInfo { tables, views })
+}
+
+type TableKey = (String, String);
+
+async fn get_columns(
+    conn: &mut MySqlConnection,
+) -> AppResult<HashMap<TableKey, Vec<ColumnDetail>>> {
+    let rows = sqlx::query(&format!(
+        "SELECT CAST(table_schema AS CHAR), CAST(table_name AS CHAR), CAST(column_name AS CHAR), \
+                CAST(data_type AS CHAR), CAST(is_nullable = 'YES' AS SIGNED), \
+                CAST(column_default AS CHAR), CAST(column_key = 'PRI' AS SIGNED), \
+                CAST(column_type AS CHAR) \
+         FROM information_schema.columns WHERE {} \
+         ORDER BY table_schema, table_name, ordinal_position",
+        USER_SCHEMAS
+    ))
+    .fetch_all(conn)
+    .await
+    .map_err(|e| AppError::Internal(format!("Failed to query columns: {}", e)))?;
+
+    let mut columns: HashMap<TableKey, Vec<ColumnDetail>> = HashMap::new();
+    for row in &rows {
+        let key = (
+            text(row, 0).unwrap_or_default(),
+            text(row, 1).unwrap_or_default(),
+        );
+        columns.entry(key).or_default().push(ColumnDetail {
+            name: text(row, 2).unwrap_or_default(),
+            data_type: text(row, 3).unwrap_or_default(),
+            nullable: row.try_get::<i64, _>(4).unwrap_or(1) != 0,
+            default_value: text(row, 5),
+            is_primary_key: row.try_get::<i64, _>(6).unwrap_or(0) != 0,
+            sql_type: text(row, 7).unwrap_or_default(),
+        });
+    }
+
+    Ok(columns)
+}
+
+async fn get_indexes(conn: &mut MySqlConnection) -> AppResult<HashMap<TableKey, Vec<IndexInfo>>> {
+    let rows = sqlx::query(&format!(
+        "SELECT CAST(table_schema AS CHAR), CAST(table_name AS CHAR), CAST(index_name AS CHAR), \
+                CAST(GROUP_CONCAT(column_name ORDER BY seq_in_index SEPARATOR ',') AS CHAR), \
+                CAST(MIN(non_unique) = 0 AS SIGNED) \
+         FROM information_schema.statistics WHERE {} \
+         GROUP BY table_schema, table_name, index_name \
+         ORDER BY table_schema, table_name, index_name",
+        USER_SCHEMAS
+    ))
+    .fetch_all(conn)
+    .await
+    .map_err(|e| AppError::Internal(format!("Failed to query indexes: {}", e)))?;
+
+    let mut indexes: HashMap<TableKey, Vec<IndexInfo>> = HashMap::new();
+    for row in &rows {
+        let schema = text(row, 0).unwrap_or_default();
+        let table = text(row, 1).unwrap_or_default();
+        let name = text(row, 2).unwrap_or_default();
+        let columns: Vec<String> = text(row, 3)
+            .unwrap_or_default()
+            .split(',')
+            .map(str::to_string)
+            .collect();
+        let is_unique = row.try_get::<i64, _>(4).unwrap_or(0) != 0;
+        let is_primary = name == "PRIMARY";
+        let definition = index_definition(&schema, &table, &name, &columns, is_unique);
+
+        indexes.entry((schema, table)).or_default().push(IndexInfo {
+            name,
+            columns,
+            is_unique,
+            is_primary,
+            definition,
+        });
+    }
+
+    Ok(indexes)
+}
+
+fn index_definition(
+    schema: &str,
+    table: &str,
+    name: &str,
+    columns: &[String],
+    is_unique: bool,
+) -> String {
+    let target = format!("{}.{}", quote_mysql_ident(schema), quote_mysql_ident(table));
+    let columns = columns
+        .iter()
+        .map(|c| quote_mysql_ident(c))
+        .collect::<Vec<_>>()
+        .join(", ");
+    if name == "PRIMARY" {
+        format!("ALTER TABLE {} ADD PRIMARY KEY ({})", target, columns)
+    } else {
+        format!(
+            "CREATE {}INDEX {} ON {} ({})",
+            if is_unique { "UNIQUE " } else { "" },
+            quote_mysql_ident(name),
+            target,
+            columns
+        )
+    }
+}
+
+/// Runs one statement and returns up to `limit` rows. `max_statement_time` is MariaDB's name
+/// for `max_execution_time`, which MySQL only applies to `SELECT`s; the client-side timeout
+/// covers everything else and kills the statement on the server through a second connection.
+pub(crate) async fn execute_query(
+    conn: &mut MySqlConnection,
+    database: &Database,
+    password: &str,
+    is_mariadb: bool,
+    sql: &str,
+    limit: i32,
+    timeout_ms: i32,
+) -> AppResult<QueryResult> {
+    let set_timeout = if is_mariadb {
+        format!(
+            "SET SESSION max_statement_time = {}",
+            timeout_ms as f64 / 1000.0
+        )
+    } else {
+        format!("SET SESSION max_execution_time = {}", timeout_ms)
+    };
+    conn.execute(set_timeout.as_str())
+        .await
+        .map_err(|e| AppError::Internal(format!("Failed to set timeout: {}", e)))?;
+    let connection_id: u64 = sqlx::query_scalar("SELECT CONNECTION_ID()")
+        .fetch_one(&mut *conn)
+        .await
+        .map_err(|e| AppError::Internal(format!("Failed to read connection id: {}", e)))?;
+
+    let start = Instant::now();
+    let deadline = Duration::from_millis(timeout_ms.max(0) as u64) + TIMEOUT_GRACE;
+    let statement = sql.trim().trim_end_matches(';');
+
+    let run = async {
+        let stmt = conn
+            .prepare(statement)
+            .await
+            .map_err(|e| AppError::Validation(format!("Query preparation failed: {}", e)))?;
+        let columns = column_infos(stmt.columns());
+
+        // Stops reading once the limit is passed rather than buffering the whole result.
+        let rows: Vec<MySqlRow> = stmt
+            .query()
+            .fetch(&mut *conn)
+            .take(limit as usize + 1)
+            .try_collect()
+            .await
+            .map_err(|e| AppError::Validation(format!("Query execution failed: {}", e)))?;
+
+        Ok::<_, AppError>((columns, rows))
+    };
+
+    let (columns, rows) = match tokio::time::timeout(deadline, run).await {
+        Ok(result) => result?,
+        Err(_) => {
+            if let Err(e) = kill_query(database, password, connection_id).await {
+                tracing::warn!(
+                    "Failed to kill timed out query on database {}: {}",
+                    database.id,
+                    e
+                );
+            }
+            return Err(AppError::Validation(
+                "Query execution timed out".to_string(),
+            ));
+        },
+    };
+
+    let execution_time_ms = start.elapsed().as_secs_f64() * 1000.0;
+    let total_rows = rows.len() as i64;
+    let truncated = total_rows > limit as i64;
+
+    Ok(QueryResult {
+        columns,
+        rows: rows
+            .iter()
+            .take(limit as usize)
+            .map(|row| (0..row.len()).map(|i| row_value_to_json(row, i)).collect())
+            .collect(),
+        row_count: total_rows,
+        execution_time_ms,
+        truncated,
+    })
+}
+
+/// Dropping the client future leaves the statement running, so it is killed explicitly.
+async fn kill_query(database: &Database, password: &str, connection_id: u64) -> AppResult<()> {
+    let mut conn = connect(database, password).await?;
+    conn.execute(format!("KILL QUERY {}", connection_id).as_str())
+        .await
+        .map_err(|e| AppError::Internal(format!("Failed to kill query: {}", e)))?;
+    Ok(())
+}
+
+pub(crate) async fn preview_table(
+    conn: &mut MySqlConnection,
+    schema: &str,
+    table: &str,
+    limit: i32,
+    offset: i32,
+) -> AppResult<TablePreview> {
+    let target = format!("{}.{}", quote_mysql_ident(schema), quote_mysql_ident(table));
+
+    let total_rows: i64 = sqlx::query_scalar(&format!("SELECT COUNT(*) FROM {}", target))
+        .fetch_one(&mut *conn)
+        .await
+        .map_err(|e| AppError::Internal(format!("Failed to count rows: {}", e)))?;
+
+    let rows = sqlx::query(&format!(
+        "SELECT * FROM {} LIMIT {} OFFSET {}",
+        target, limit, offset
+    ))
+    .fetch_all(&mut *conn)
+    .await
+    .map_err(|e| AppError::Internal(format!("Failed to query table: {}", e)))?;
+
+    let columns = match rows.first() {
+        Some(row) => column_infos(row.columns()),
+        None => sqlx::query(
+            "SELECT CAST(column_name AS CHAR), CAST(column_type AS CHAR) \
+             FROM information_schema.columns \
+             WHERE table_schema = ? AND table_name = ? ORDER BY ordinal_position",
+        )
+        .bind(schema)
+        .bind(table)
+        .fetch_all(&mut *conn)
+        .await
+        .map_err(|e| AppError::Internal(format!("Failed to get columns: {}", e)))?
+        .iter()
+        .map(|row| ColumnInfo {
+            name: text(row, 0).unwrap_or_default(),
+            data_type: text(row, 1).unwrap_or_default(),
+        })
+        .collect(),
+    };
+
+    Ok(TablePreview {
+        schema: schema.to_string(),
+        table: table.to_string(),
+        columns,
+        rows: rows
+            .iter()
+            .map(|row| (0..row.len()).map(|i| row_value_to_json(row, i)).collect())
+            .collect(),
+        total_rows,
+        limit,
+        offset,
+    })
+}
+
+fn column_infos(columns: &[sqlx::mysql::MySqlColumn]) -> Vec<ColumnInfo> {
+    columns
+        .iter()
+        .map(|col| ColumnInfo {
+            name: col.name().to_string(),
+            data_type: col.type_info().name().to_lowercase(),
+        })
+        .collect()
+}
+
+/// Reads a text value whatever its collation; `information_schema` reports some as binary.
+fn text(row: &MySqlRow, index: usize) -> Option<String> {
+    row.try_get_unchecked::<Option<String>, _>(index)
+        .ok()
+        .flatten()
+}
+
+fn row_value_to_json(row: &MySqlRow, index: usize) -> serde_json::Value {
+    use serde_json::Value;
+
+    let type_name = match row.try_get_raw(index) {
+        Ok(raw) if raw.is_null() => return Value::Null,
+        Ok(raw) => raw.type_info().name().to_string(),
+        Err(_) => return Value::Null,
+    };
+
+    let number = |v: Option<f64>| {
+        v.and_then(serde_json::Number::from_f64)
+            .map(Value::Number)
+            .unwrap_or(Value::Null)
+    };
+
+    match type_name.as_str() {
+        "BOOLEAN" => row
+            .try_get::<bool, _>(index)
+            .map(Value::Bool)
+            .unwrap_or(Value::Null),
+        "TINYINT" | "SMALLINT" | "MEDIUMINT" | "INT" | "BIGINT" => row
+            .try_get::<i64, _>(index)
+            .map(|v| Value::Number(v.into()))
+            .unwrap_or(Value::Null),
+        "YEAR" | "TINYINT UNSIGNED" | "SMALLINT UNSIGNED" | "MEDIUMINT UNSIGNED"
+        | "INT UNSIGNED" | "BIGINT UNSIGNED" => row
+            .try_get::<u64, _>(index)
+            .map(|v| Value::Number(v.into()))
+            .unwrap_or(Value::Null),
+        "FLOAT" => number(row.try_get::<f32, _>(index).ok().map(f64::from)),
+        "DOUBLE" => number(row.try_get::<f64, _>(index).ok()),
+        "DATE" => row
+            .try_get::<chrono::NaiveDate, _>(index)
+            .map(|v| Value::String(v.to_string()))
+            .unwrap_or(Value::Null),
+        "DATETIME" | "TIMESTAMP" => row
+            .try_get::<chrono::NaiveDateTime, _>(index)
+            .map(|v| Value::String(v.to_string()))
+            .unwrap_or(Value::Null),
+        "TIME" => row
+            .try_get::<MySqlTime, _>(index)
+            .map(|v| Value::String(v.to_string()))
+            .unwrap_or(Value::Null),
+        "JSON" => text(row, index)
+            .and_then(|s| serde_json::from_str(&s).ok())
+            .unwrap_or(Value::Null),
+        "BINARY" | "VARBINARY" | "TINYBLOB" | "BLOB" | "MEDIUMBLOB" | "LONGBLOB" | "BIT"
+        | "GEOMETRY" => row
+            .try_get_unchecked::<Vec<u8>, _>(index)
+            .map(|v| Value::String(format!("0x{}", hex::encode(v))))
+            .unwrap_or(Value::Null),
+        _ => text(row, index).map(Value::String).unwrap_or(Value::Null),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_index_definition() {
+        let columns = vec!["tenant_id".to_string(), "e`mail".to_string()];
+        assert_eq!(
+            index_definition("app", "users", "users_email", &columns, true),
+            "CREATE UNIQUE INDEX `users_email` ON `app`.`users` (`tenant_id`, `e``mail`)"
+        );
+        assert_eq!(
+            index_definition("app", "users", "PRIMARY", &columns[..1], true),
+            "ALTER TABLE `app`.`users` ADD PRIMARY KEY (`tenant_id`)"
+        );
+    }
+}
@@ -0,0 +1,316 @@
+use std::sync::Arc;
+
+use super::{reset_status_from_container, DatabaseService};
+use crate::domain::engines::engine_for;
+use crate::domain::models::{Database, Operation, OperationResponse};
+use crate::error::{AppError, AppResult};
+use crate::infrastructure::docker::DockerManager;
+use crate::infrastructure::version_catalog;
+use crate::repositories::{
+    BackupRepository, DatabaseRepository, OperationRepository, ProjectRepository,
+};
+
+pub const UPDATE_OPERATION_KIND: &str = "update";
+
+const UPDATE_KIND_MINOR: &str = "minor";
+const UPDATE_KIND_PATCH: &str = "patch";
+const HEALTH_TIMEOUT_SECONDS: u64 = 60;
+
+#[derive(Clone)]
+pub struct UpdateService {
+    database_repo: DatabaseRepository,
+    project_repo: ProjectRepository,
+    backup_repo: BackupRepository,
+    operation_repo: OperationRepository,
+    database_service: Arc<DatabaseService>,
+    docker: Arc<DockerManager>,
+}
+
+impl UpdateService {
+    pub fn new(
+        database_repo: DatabaseRepository,
+        project_repo: ProjectRepository,
+        backup_repo: BackupRepository,
+        operation_repo: OperationRepository,
+        database_service: Arc<DatabaseService>,
+        docker: Arc<DockerManager>,
+    ) -> Self {
+        Self {
+            database_repo,
+            project_repo,
+            backup_repo,
+            operation_repo,
+            database_service,
+            docker,
+        }
+    }
+
+    /// Refreshes the available update of every provisioned database. Returns how many have one.
+    pub async fn check_all(&self) -> AppResult<usize> {
+        let mut available = 0;
+
+        for database in self.database_repo.find_all_with_container().await? {
+            if !matches!(database.container_status.as_str(), "running" | "stopped") {
+                continue;
+            }
+
+            let update = match self.detect(&database).await {
+                Ok(update) => update,
+                Err(e) => {
+                    tracing::warn!(
+                        "Failed to check updates for database {}: {}",
+                        database.id,
+                        e
+                    );
+                    continue;
+                },
+            };
+            if update.is_some() {
+                available += 1;
+            }
+
+            let (image, kind) = match &update {
+                Some((image, kind)) => (Some(image.as_str()), Some(*kind)),
+                None => (None, None),
+            };
+            if database.update_image.as_deref() != image || database.update_kind.as_deref() != kind
+            {
+                self.database_repo
+                    .set_available_update(&database.id, image, kind)
+                    .await?;
+            }
+        }
+
+        Ok(available)
+    }
+
+    /// A later minor version from the catalog wins over a rebuilt image of the current tag.
+    async fn detect(&self, database: &Database) -> AppResult<Option<(String, &'static str)>> {
+        if let Some(version) = self.newer_minor_version(database).await {
+            let mut updated = database.clone();
+            updated.version = version;
+            return Ok(Some((
+                engine_for(&database.database_type).image(&updated),
+                UPDATE_KIND_MINOR,
+            )));
+        }
+
+        let Some(container_id) = &database.container_id else {
+            return Ok(None);
+        };
+        let image = engine_for(&database.database_type).image(database);
+        let Some(remote) = version_catalog::tag_digest(&image).await else {
+            return Ok(None);
+        };
+
+        let local = self.docker.image_repo_digests(container_id).await?;
+        let outdated = !local.is_empty()
+            && !local
+                .iter()
+                .any(|digest| digest.rsplit('@').next() == Some(remote.as_str()));
+
+        Ok(outdated.then_some((image, UPDATE_KIND_PATCH)))
+    }
+
+    async fn newer_minor_version(&self, database: &Database) -> Option<String> {
+        let available = engine_for(&database.database_type)
+            .in_place_versions()
+            .await;
+        newest_minor(&database.version, &available)
+    }
+
+    pub async fn apply(
+        &self,
+        database_id: &str,
+        user_id: &str,
+        is_admin: bool,
+    ) -> AppResult<OperationResponse> {
+        let database = self
+            .database_repo
+            .find_by_id(database_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("Database '{}' not found", database_id)))?;
+
+        if !is_admin
+            && !self
+                .project_repo
+                .is_owner(&database.project_id, user_id)
+                .await?
+        {
+            return Err(AppError::Forbidden);
+        }
+
+        if database.update_image.is_none() {
+            return Err(AppError::Conflict(
+                "No update is available for this database".to_string(),
+            ));
+        }
+        if database.container_id.is_none() {
+            return Err(AppError::Conflict("Database has no container".to_string()));
+        }
+        if !matches!(database.container_status.as_str(), "running" | "stopped") {
+            return Err(AppError::Conflict(format!(
+                "Database is {}",
+                database.container_status
+            )));
+        }
+        if self.backup_repo.has_running(&database.id).await?
+            || self.operation_repo.has_running(&database.id).await?
+        {
+            return Err(AppError::Conflict(
+                "Another operation is running for this database".to_string(),
+            ));
+        }
+
+        let operation = self
+            .operation_repo
+            .start(&database.id, UPDATE_OPERATION_KIND, None, None)
+            .await?;
+        self.database_repo
+            .update_status(&database.id, "updating")
+            .await?;
+
+        let service = self.clone();
+        let running = operation.clone();
+        tokio::spawn(async move {
+            service.execute_update(database, running).await;
+        });
+
+        Ok(operation.to_response())
+    }
+
+    async fn execute_update(&self, database: Database, operation: Operation) {
+        let result = self.perform_update(&database).await;
+        reset_status_from_container(&self.database_repo, &self.docker, &database.id).await;
+        self.operation_repo.finish(&operation, result).await;
+    }
+
+    /// Pulls the new image before touching the container, then recreates it on the same data
+    /// directory. A minor update that does not come up healthy is reverted to the old version.
+    async fn perform_update(&self, database: &Database) -> AppResult<()> {
+        let (Some(image), Some(kind)) = (&database.update_image, &database.update_kind) else {
+            return Err(AppError::Conflict(
+                "No update is available for this database".to_string(),
+            ));
+        };
+        let was_running = database.container_status == "running";
+
+        tracing::info!(
+            "Updating database {} to {} ({} update)",
+            database.id,
+            image,
+            kind
+        );
+        self.docker.pull_image(image).await?;
+
+        let minor_version = (kind == UPDATE_KIND_MINOR)
+            .then(|| image_version(image))
+            .flatten();
+        if let Some(version) = &minor_version {
+            self.database_repo
+                .update_version(&database.id, version)
+                .await?;
+        }
+
+        if let Err(e) = self.recreate(&database.id, was_running).await {
+            if minor_version.is_some() {
+                tracing::warn!(
+                    "Update of database {} failed, reverting to {}: {}",
+                    database.id,
+                    database.version,
+                    e
+                );
+                self.database_repo
+                    .update_version(&database.id, &database.version)
+                    .await?;
+                if let Err(revert_error) = self.recreate(&database.id, was_running).await {
+                    tracing::error!(
+                        "Failed to revert update of database {}: {}",
+                        database.id,
+                        revert_error
+                    );
+                }
+            }
+            return Err(e);
+        }
+
+        self.database_repo
+            .set_available_update(&database.id, None, None)
+            .await
+    }
+
+    async fn recreate(&self, database_id: &str, start: bool) -> AppResult<()> {
+        let database = self
+            .database_repo
+            .find_by_id(database_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("Database '{}' not found", database_id)))?;
+        self.database_service.recreate_container(&database).await?;
+        if !start {
+            return Ok(());
+        }
+
+        let container_id = self
+            .database_repo
+            .find_by_id(database_id)
+            .await?
+            .and_then(|database| database.container_id)
+            .ok_or_else(|| AppError::Internal("Database has no container".to_string()))?;
+        self.docker.start_container(&container_id).await?;
+
+        if !self
+            .docker
+            .wait_for_healthy(&container_id, HEALTH_TIMEOUT_SECONDS)
+            .await?
+        {
+            return Err(AppError::Docker(
+                "Database did not become healthy after the update".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+/// `7.4` from `redis:7.4-alpine` or `valkey/valkey:7.4-alpine`.
+fn image_version(image: &str) -> Option<String> {
+    let (_, tag) = image.rsplit_once(':')?;
+    Some(tag.trim_end_matches("-alpine").to_string())
+}
+
+fn newest_minor(current: &str, available: &[String]) -> Option<String> {
+    let parse = |version: &str| -> Option<(u32, u32)> {
+        let (major, minor) = version.split_once('.')?;
+        Some((major.parse().ok()?, minor.parse().ok()?))
+    };
+    let (major, minor) = parse(current)?;
+
+    available
+        .iter()
+        .filter_map(|version| parse(version).map(|parsed| (parsed, version)))
+        .filter(|((m, n), _)| *m == major && *n > minor)
+        .max_by_key(|(parsed, _)| *parsed)
+        .map(|(_, version)| version.clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_newest_minor() {
+        let available: Vec<String> = ["7.0", "7.2", "7.4", "8.0"]
+            .iter()
+            .map(|v| v.to_string())
+            .collect();
+
+        assert_eq!(newest_minor("7.2", &available), Some("7.4".to_string()));
+        assert_eq!(newest_minor("7.4", &available), None);
+        assert_eq!(newest_minor("8.0", &available), None);
+        assert_eq!(newest_minor("latest", &available), None);
+        assert_eq!(
+            image_version("valkey/valkey:8.1-alpine"),
+            Some("8.1".to_string())
+        );
+    }
+}
@@ -0,0 +1,785 @@
+use std::path::Path;
+use std::sync::Arc;
+
+use chrono::{Duration, Utc};
+use futures::StreamExt;
+use tokio_util::io::StreamReader;
+
+use super::common::{owned_database, psql_command, quote_ident};
+use super::{reset_status_from_container, DatabaseService};
+use crate::domain::engines::engine_for;
+use crate::domain::models::{Database, Operation, OperationResponse, PostgresVersion};
+use crate::error::{AppError, AppResult};
+use crate::infrastructure::docker::{ContainerConfig, DockerManager};
+use crate::infrastructure::volumes::DataVolumes;
+use crate::repositories::{
+    BackupRepository, DatabaseRepository, OperationRepository, ProjectRepository,
+};
+use crate::utils::crypto;
+
+pub const UPGRADE_OPERATION_KIND: &str = "upgrade";
+pub const ROLLBACK_UPGRADE_OPERATION_KIND: &str = "rollback_upgrade";
+
+const ROLLBACK_WINDOW_DAYS: i64 = 7;
+const READY_TIMEOUT_SECONDS: u64 = 120;
+
+#[derive(Clone)]
+pub struct UpgradeService {
+    database_repo: DatabaseRepository,
+    project_repo: ProjectRepository,
+    backup_repo: BackupRepository,
+    operation_repo: OperationRepository,
+    database_service: Arc<DatabaseService>,
+    docker: Arc<DockerManager>,
+    data_dir: String,
+    encryption_key: [u8; 32],
+}
+
+impl UpgradeService {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        database_repo: DatabaseRepository,
+        project_repo: ProjectRepository,
+        backup_repo: BackupRepository,
+        operation_repo: OperationRepository,
+        database_service: Arc<DatabaseService>,
+        docker: Arc<DockerManager>,
+        data_dir: String,
+        encryption_key_hex: &str,
+    ) -> Self {
+        let encryption_key = crypto::parse_encryption_key(encryption_key_hex);
+
+        Self {
+            database_repo,
+            project_repo,
+            backup_repo,
+            operation_repo,
+            database_service,
+            docker,
+            data_dir,
+            encryption_key,
+        }
+    }
+
+    fn decrypt_password(&self, encrypted: &str) -> AppResult<String> {
+        crypto::decrypt_password(&self.encryption_key, encrypted)
+    }
+
+    fn data_path(&self, database_id: &str) -> String {
+        format!("{}/{}", self.data_dir, database_id)
+    }
+
+    fn volumes(&self) -> &Arc<dyn DataVolumes> {
+        self.database_service.volumes()
+    }
+
+    /// Where the data directory of `version` is kept while it is not the live one.
+    fn version_data_path(&self, database_id: &str, version: &str) -> String {
+        format!("{}/{}.pg{}", self.data_dir, database_id, version)
+    }
+
+    fn version_wal_archive_path(&self, database_id: &str, version: &str) -> String {
+        format!(
+            "{}.pg{}",
+            self.database_service.wal_archive_path(database_id),
+            version
+        )
+    }
+
+    async fn get_database(
+        &self,
+        database_id: &str,
+        user_id: &str,
+        is_admin: bool,
+    ) -> AppResult<Database> {
+        owned_database(
+            &self.database_repo,
+            &self.project_repo,
+            database_id,
+            user_id,
+            is_admin,
+        )
+        .await
+    }
+
+    async fn check_idle(&self, database: &Database) -> AppResult<()> {
+        if database.container_id.is_none() {
+            return Err(AppError::Conflict("Database has no container".to_string()));
+        }
+
+        if !matches!(database.container_status.as_str(), "running" | "stopped") {
+            return Err(AppError::Conflict(format!(
+                "Database is {}",
+                database.container_status
+            )));
+        }
+
+        if self.backup_repo.has_running(&database.id).await?
+            || self.operation_repo.has_running(&database.id).await?
+        {
+            return Err(AppError::Conflict(
+                "Another operation is running for this database".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+
+    pub async fn upgrade(
+        &self,
+        database_id: &str,
+        user_id: &str,
+        is_admin: bool,
+        target_version: &str,
+    ) -> AppResult<OperationResponse> {
+        let database = self.get_database(database_id, user_id, is_admin).await?;
+
+        let engine = engine_for(&database.database_type);
+        if !engine.supports_upgrades() {
+            return Err(AppError::Validation(format!(
+                "{} databases cannot be upgraded",
+                engine.display_name()
+            )));
+        }
+        // Streaming replication only works between servers of the same major version.
+        if database.is_replica {
+            return Err(AppError::Conflict(
+                "Detach the replica before upgrading it".to_string(),
+            ));
+        }
+        let replicas = self.database_repo.find_children(&database.id).await?;
+        if let Some(replica) = replicas.iter().find(|branch| branch.is_replica) {
+            return Err(AppError::Conflict(format!(
+                "Branch '{}' replicates this database. Detach it before upgrading",
+                replica.name
+            )));
+        }
+        validate_target_version(&database.version, target_version)?;
+        if let Some(variant) = database.variant() {
+            if !variant.supports_version(target_version) {
+                let (min, max) = variant.versions();
+                return Err(AppError::Validation(format!(
+                    "{} images are available for PostgreSQL {} to {}",
+                    variant.display_name(),
+                    min,
+                    max
+                )));
+            }
+        }
+        self.check_idle(&database).await?;
+
+        let operation = self
+            .operation_repo
+            .start(&database.id, UPGRADE_OPERATION_KIND, None, None)
+            .await?;
+        self.database_repo
+            .update_status(&database.id, "upgrading")
+            .await?;
+
+        let service = self.clone();
+        let running = operation.clone();
+        let target_version = target_version.to_string();
+        tokio::spawn(async move {
+            service
+                .execute_upgrade(database, target_version, running)
+                .await;
+        });
+
+        Ok(operation.to_response())
+    }
+
+    pub async fn rollback(
+        &self,
+        database_id: &str,
+        user_id: &str,
+        is_admin: bool,
+    ) -> AppResult<OperationResponse> {
+        let database = self.get_database(database_id, user_id, is_admin).await?;
+
+        let previous_version = database.previous_postgres_version.clone().ok_or_else(|| {
+            AppError::Conflict("Database has no upgrade to roll back".to_string())
+        })?;
+        let expired = database
+            .rollback_expires_at
+            .as_deref()
+            .and_then(|expires| chrono::DateTime::parse_from_rfc3339(expires).ok())
+            .is_none_or(|expires| expires < Utc::now());
+        let previous_path = self.version_data_path(&database.id, &previous_version);
+        if expired || !Path::new(&previous_path).exists() {
+            return Err(AppError::Conflict(
+                "The rollback window for this upgrade has ended".to_string(),
+            ));
+        }
+        self.check_idle(&database).await?;
+
+        let operation = self
+            .operation_repo
+            .start(&database.id, ROLLBACK_UPGRADE_OPERATION_KIND, None, None)
+            .await?;
+        self.database_repo
+            .update_status(&database.id, "upgrading")
+            .await?;
+
+        let service = self.clone();
+        let running = operation.clone();
+        tokio::spawn(async move {
+            service
+                .execute_rollback(database, previous_version, running)
+                .await;
+        });
+
+        Ok(operation.to_response())
+    }
+
+    /// Removes the kept data directories of upgrades whose rollback window has ended.
+    pub async fn prune_expired_rollbacks(&self) -> AppResult<usize> {
+        let databases = self.database_repo.find_expired_rollbacks().await?;
+
+        for database in &databases {
+            if let Some(version) = &database.previous_postgres_version {
+                let _ = self
+                    .volumes()
+                    .remove(&self.version_data_path(&database.id, version))
+                    .await;
+                let _ =
+                    std::fs::remove_dir_all(self.version_wal_archive_path(&database.id, version));
+            }
+            self.database_repo.clear_rollback(&database.id).await?;
+        }
+
+        Ok(databases.len())
+    }
+
+    async fn execute_upgrade(
+        &self,
+        database: Database,
+        target_version: String,
+        operation: Operation,
+    ) {
+        let result = self.perform_upgrade(&database, &target_version).await;
+        reset_status_from_container(&self.database_repo, &self.docker, &database.id).await;
+        self.operation_repo.finish(&operation, result).await;
+    }
+
+    async fn execute_rollback(
+        &self,
+        database: Database,
+        previous_version: String,
+        operation: Operation,
+    ) {
+        let result = self.perform_rollback(&database, &previous_version).await;
+        reset_status_from_container(&self.database_repo, &self.docker, &database.id).await;
+        self.operation_repo.finish(&operation, result).await;
+    }
+
+    /// Restores the new version's data into a fresh data directory next to the live one, then
+    /// swaps the directories and recreates the container. A failed swap is reverted.
+    async fn perform_upgrade(&self, database: &Database, target_version: &str) -> AppResult<()> {
+        let container_id = database
+            .container_id
+            .as_ref()
+            .ok_or_else(|| AppError::Internal("Database has no container".to_string()))?;
+        let password = self.decrypt_password(
+            database
+                .password_encrypted
+                .as_ref()
+                .ok_or_else(|| AppError::Internal("Database password not found".to_string()))?,
+        )?;
+
+        let was_running = database.container_status == "running";
+        if !was_running {
+            self.docker
+                .start_postgres(container_id, &database.username, READY_TIMEOUT_SECONDS)
+                .await?;
+        }
+
+        tracing::info!(
+            "Upgrading database {} from PostgreSQL {} to {}",
+            database.id,
+            database.version,
+            target_version
+        );
+
+        let staging_path = self.version_data_path(&database.id, target_version);
+        let migrated = match self
+            .block_writes(container_id, &database.username, &password)
+            .await
+        {
+            Ok(()) => {
+                self.build_upgraded_data(database, &password, target_version, &staging_path)
+                    .await
+            },
+            Err(e) => Err(e),
+        };
+
+        // The kept data directory must not come back read-only on rollback. Without a reload the
+        // running server stays read-only until it is stopped.
+        let unblocked = self
+            .unblock_writes(
+                container_id,
+                &database.username,
+                &password,
+                migrated.is_err(),
+            )
+            .await;
+        let migrated = migrated.and(unblocked);
+
+        let _ = self.docker.stop_container(container_id).await;
+        if let Err(e) = migrated {
+            let _ = self.volumes().remove(&staging_path).await;
+            if was_running {
+                let _ = self
+                    .docker
+                    .start_postgres(container_id, &database.username, READY_TIMEOUT_SECONDS)
+                    .await;
+            }
+            return Err(e);
+        }
+
+        let old_version = &database.version;
+        let expires_at = (Utc::now() + Duration::days(ROLLBACK_WINDOW_DAYS)).to_rfc3339();
+        let swapped = self
+            .swap_version(
+                database,
+                old_version,
+                target_version,
+                Some(&expires_at),
+                was_running,
+            )
+            .await;
+
+        if let Err(e) = swapped {
+            tracing::error!(
+                "Upgrade of database {} failed after the swap, reverting: {}",
+                database.id,
+                e
+            );
+            let previous = self
+                .database_repo
+                .find_by_id(&database.id)
+                .await?
+                .unwrap_or_else(|| database.clone());
+            if let Err(revert_error) = self
+                .swap_version(&previous, target_version, old_version, None, was_running)
+                .await
+            {
+                tracing::error!(
+                    "Failed to revert upgrade of database {}: {}",
+                    database.id,
+                    revert_error
+                );
+            }
+            let _ = self.volumes().remove(&staging_path).await;
+            return Err(e);
+        }
+
+        // Only the most recent upgrade can be rolled back.
+        if let Some(version) = &database.previous_postgres_version {
+            let _ = self
+                .volumes()
+                .remove(&self.version_data_path(&database.id, version))
+                .await;
+            let _ = std::fs::remove_dir_all(self.version_wal_archive_path(&database.id, version));
+        }
+
+        Ok(())
+    }
+
+    async fn perform_rollback(&self, database: &Database, previous_version: &str) -> AppResult<()> {
+        tracing::info!(
+            "Rolling back database {} from PostgreSQL {} to {}",
+            database.id,
+            database.version,
+            previous_version
+        );
+
+        let was_running = database.container_status == "running";
+        if let Some(container_id) = &database.container_id {
+            let _ = self.docker.stop_container(container_id).await;
+        }
+
+        let current_version = &database.version;
+        self.swap_version(
+            database,
+            current_version,
+            previous_version,
+            None,
+            was_running,
+        )
+        .await?;
+
+        let _ = self
+            .volumes()
+            .remove(&self.version_data_path(&database.id, current_version))
+            .await;
+        let _ =
+            std::fs::remove_dir_all(self.version_wal_archive_path(&database.id, current_version));
+        Ok(())
+    }
+
+    /// Makes new transactions read-only and disconnects every client, so nothing written
+    /// during the dump is left behind on the old version.
+    async fn block_writes(
+        &self,
+        container_id: &str,
+        username: &str,
+        password: &str,
+    ) -> AppResult<()> {
+        let output = self
+            .docker
+            .run_exec(
+                container_id,
+                psql_command(username, "postgres", &write_block_sql(true, true)),
+                Some(vec![format!("PGPASSWORD={}", password)]),
+            )
+            .await?;
+        output.check("Blocking writes")
+    }
+
+    async fn unblock_writes(
+        &self,
+        container_id: &str,
+        username: &str,
+        password: &str,
+        reload: bool,
+    ) -> AppResult<()> {
+        let output = self
+            .docker
+            .run_exec(
+                container_id,
+                psql_command(username, "postgres", &write_block_sql(false, reload)),
+                Some(vec![format!("PGPASSWORD={}", password)]),
+            )
+            .await?;
+        output.check("Unblocking writes")
+    }
+
+    /// Creates a temporary container on the target image and copies every role and database
+    /// into it.
+    async fn build_upgraded_data(
+        &self,
+        database: &Database,
+        password: &str,
+        target_version: &str,
+        staging_path: &str,
+    ) -> AppResult<()> {
+        let source = database
+            .container_id
+            .as_deref()
+            .ok_or_else(|| AppError::Internal("Database has no container".to_string()))?;
+
+        let _ = self.volumes().remove(staging_path).await;
+        self.volumes().create(staging_path).await?;
+
+        let engine = engine_for(&database.database_type);
+        let upgraded = Database {
+            version: target_version.to_string(),
+            ..database.clone()
+        };
+        let config = ContainerConfig {
+            name: format!("{}-upgrade", database.container_name()),
+            image: engine.image(&upgraded),
+            env: engine.container_env(&upgraded),
+            data_path: staging_path.to_string(),
+            cpu_limit: database.cpu_limit,
+            memory_limit_mb: database.memory_limit_mb as i64,
+            internal_port: engine.internal_port(),
+            exposed_port: None,
+            cmd: engine.container_cmd(&upgraded),
+            wal_archive_path: None,
+            wal_restore_path: None,
+        };
+        let target = self
+            .docker
+            .create_postgres_container(config, password)
+            .await?;
+
+        let result = async {
+            self.docker
+                .start_postgres(&target, &database.username, READY_TIMEOUT_SECONDS)
+                .await?;
+            self.copy_cluster(source, &target, &database.username, password)
+                .await
+        }
+        .await;
+
+        let _ = self.docker.stop_container(&target).await;
+        let _ = self.docker.remove_container(&target, true).await;
+        result
+    }
+
+    async fn copy_cluster(
+        &self,
+        source: &str,
+        target: &str,
+        username: &str,
+        password: &str,
+    ) -> AppResult<()> {
+        let env = vec![format!("PGPASSWORD={}", password)];
+
+        // The superuser already exists on the target, so "role already exists" is expected here
+        // and psql must not stop at the first error.
+        self.pipe(
+            source,
+            vec![
+                "pg_dumpall".to_string(),
+                "-U".to_string(),
+                username.to_string(),
+                "--roles-only".to_string(),
+            ],
+            target,
+            vec![
+                "psql".to_string(),
+                "-U".to_string(),
+                username.to_string(),
+                "-d".to_string(),
+                "postgres".to_string(),
+            ],
+            &env,
+        )
+        .await?;
+
+        let listed = self
+            .docker
+            .run_exec(
+                source,
+                psql_command(
+                    username,
+                    "postgres",
+                    &["SELECT datname FROM pg_database WHERE NOT datistemplate ORDER BY 1"],
+                ),
+                Some(env.clone()),
+            )
+            .await?;
+        if listed.exit_code != Some(0) {
+            return Err(AppError::Docker(format!(
+                "Failed to list databases: {}",
+                listed.stderr.trim()
+            )));
+        }
+
+        for name in listed
+            .stdout
+            .lines()
+            .map(str::trim)
+            .filter(|n| !n.is_empty())
+        {
+            if name != "postgres" {
+                let create = format!("CREATE DATABASE {}", quote_ident(name));
+                let output = self
+                    .docker
+                    .run_exec(
+                        target,
+                        psql_command(username, "postgres", &[&create]),
+                        Some(env.clone()),
+                    )
+                    .await?;
+                output.check("CREATE DATABASE")?;
+            }
+
+            self.pipe(
+                source,
+                vec![
+                    "pg_dump".to_string(),
+                    "-U".to_string(),
+                    username.to_string(),
+                    "-Fc".to_string(),
+                    format!("--dbname={}", name),
+                ],
+                target,
+                vec![
+                    "pg_restore".to_string(),
+                    "-U".to_string(),
+                    username.to_string(),
+                    format!("--dbname={}", name),
+                    "--single-transaction".to_string(),
+                    "--exit-on-error".to_string(),
+                ],
+                &env,
+            )
+            .await?;
+        }
+
+        Ok(())
+    }
+
+    /// Streams the stdout of `source_cmd` into the stdin of `target_cmd`.
+    async fn pipe(
+        &self,
+        source: &str,
+        source_cmd: Vec<String>,
+        target: &str,
+        target_cmd: Vec<String>,
+        env: &[String],
+    ) -> AppResult<()> {
+        let name = source_cmd[0].clone();
+        let exec = self
+            .docker
+            .stream_exec(source, source_cmd, Some(env.to_vec()))
+            .await?;
+
+        let (stdout, exit) = exec.split();
+        let stdout = stdout.map(|chunk| chunk.map_err(|e| std::io::Error::other(e.to_string())));
+
+        let restored = self
+            .docker
+            .exec_with_input(
+                target,
+                target_cmd.clone(),
+                Some(env.to_vec()),
+                StreamReader::new(stdout),
+            )
+            .await;
+
+        exit.check(&self.docker, &name).await?;
+        restored?.check(&target_cmd[0])
+    }
+
+    /// Moves the live data directory aside as `from_version` and puts the `to_version` one in
+    /// its place, then recreates the container on the matching image.
+    async fn swap_version(
+        &self,
+        database: &Database,
+        from_version: &str,
+        to_version: &str,
+        rollback_expires_at: Option<&str>,
+        start: bool,
+    ) -> AppResult<()> {
+        let live = self.data_path(&database.id);
+        let kept = self.version_data_path(&database.id, from_version);
+        let incoming = self.version_data_path(&database.id, to_version);
+        let volumes = self.volumes();
+        let _ = volumes.remove(&kept).await;
+        volumes.rename(&live, &kept).await?;
+        if let Err(e) = volumes.rename(&incoming, &live).await {
+            let _ = volumes.rename(&kept, &live).await;
+            return Err(e);
+        }
+
+        // WAL segment names restart with the new cluster, so the archive must not be shared.
+        if database.wal_archiving {
+            let archive = self.database_service.wal_archive_path(&database.id);
+            let kept_archive = self.version_wal_archive_path(&database.id, from_version);
+            let incoming_archive = self.version_wal_archive_path(&database.id, to_version);
+            let _ = std::fs::remove_dir_all(&kept_archive);
+            if Path::new(&archive).exists() {
+                rename(&archive, &kept_archive)?;
+            }
+            if Path::new(&incoming_archive).exists() {
+                rename(&incoming_archive, &archive)?;
+            }
+            std::fs::create_dir_all(&archive).map_err(|e| {
+                AppError::Internal(format!("Failed to create WAL archive directory: {}", e))
+            })?;
+        }
+
+        let previous_version = rollback_expires_at.map(|_| from_version);
+        self.database_repo
+            .update_postgres_version(
+                &database.id,
+                to_version,
+                previous_version,
+                rollback_expires_at,
+            )
+            .await?;
+
+        let updated = self
+            .database_repo
+            .find_by_id(&database.id)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("Database '{}' not found", database.id)))?;
+        self.database_service.recreate_container(&updated).await?;
+
+        if start {
+            let updated = self
+                .database_repo
+                .find_by_id(&database.id)
+                .await?
+                .ok_or_else(|| {
+                    AppError::NotFound(format!("Database '{}' not found", database.id))
+                })?;
+            let container_id = updated
+                .container_id
+                .as_ref()
+                .ok_or_else(|| AppError::Internal("Database has no container".to_string()))?;
+            self.docker
+                .start_postgres(container_id, &updated.username, READY_TIMEOUT_SECONDS)
+                .await?;
+        }
+
+        Ok(())
+    }
+}
+
+fn validate_target_version(current: &str, target: &str) -> AppResult<()> {
+    if !PostgresVersion::is_valid(target) {
+        return Err(AppError::Validation(format!(
+            "Invalid PostgreSQL version '{}'. Use a major version such as 17",
+            target
+        )));
+    }
+
+    let current: u32 = current.parse().unwrap_or(0);
+    let target: u32 = target.parse().unwrap_or(0);
+    if target <= current {
+        return Err(AppError::Validation(format!(
+            "Target version {} must be newer than the current version {}",
+            target, current
+        )));
+    }
+
+    Ok(())
+}
+
+fn write_block_sql(blocked: bool, reload: bool) -> Vec<&'static str> {
+    let mut statements = vec![if blocked {
+        "ALTER SYSTEM SET default_transaction_read_only = on"
+    } else {
+        "ALTER SYSTEM RESET default_transaction_read_only"
+    }];
+    if reload {
+        statements.push("SELECT pg_reload_conf()");
+    }
+    if blocked {
+        statements.push(
+            "SELECT pg_terminate_backend(pid) FROM pg_stat_activity \
+             WHERE backend_type = 'client backend' AND pid <> pg_backend_pid()",
+        );
+    }
+    statements
+}
+
+fn rename(from: &str, to: &str) -> AppResult<()> {
+    std::fs::rename(from, to)
+        .map_err(|e| AppError::Internal(format!("Failed to move data directory {}: {}", from, e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_target_version() {
+        assert!(validate_target_version("15", "17").is_ok());
+        assert!(validate_target_version("17", "17").is_err());
+        assert!(validate_target_version("17", "16").is_err());
+        assert!(validate_target_version("15", "17.2").is_err());
+        assert_eq!(quote_ident(r#"my"db"#), r#""my""db""#);
+    }
+
+    #[test]
+    fn test_write_block_sql() {
+        let blocked = write_block_sql(true, true);
+        assert_eq!(
+            blocked[0],
+            "ALTER SYSTEM SET default_transaction_read_only = on"
+        );
+        assert_eq!(blocked[1], "SELECT pg_reload_conf()");
+        assert!(blocked[2].contains("pg_terminate_backend"));
+
+        assert_eq!(
+            write_block_sql(false, false),
+            vec!["ALTER SYSTEM RESET default_transaction_read_only"]
+        );
+    }
+}
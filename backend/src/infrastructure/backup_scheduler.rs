@@ -0,0 +1,68 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use tokio::time;
+use tokio_util::sync::CancellationToken;
+
+use crate::domain::services::BackupService;
+
+pub struct BackupScheduler {
+    backup_service: Arc<BackupService>,
+    interval: Duration,
+    cancel_token: CancellationToken,
+}
+
+impl BackupScheduler {
+    pub fn new(
+        backup_service: Arc<BackupService>,
+        interval_secs: u64,
+        cancel_token: CancellationToken,
+    ) -> Self {
+        Self {
+            backup_service,
+            interval: Duration::from_secs(interval_secs),
+            cancel_token,
+        }
+    }
+
+    pub async fn run(self) {
+        tracing::info!(
+            "Starting backup scheduler with {}s interval",
+            self.interval.as_secs()
+        );
+
+        if let Err(e) = self.backup_service.recover_interrupted().await {
+            tracing::error!("Error recovering interrupted backups: {}", e);
+        }
+
+        let mut interval = time::interval(self.interval);
+
+        loop {
+            tokio::select! {
+                _ = self.cancel_token.cancelled() => {
+                    tracing::info!("Backup scheduler shutting down");
+                    break;
+                }
+                _ = interval.tick() => {
+                    match self.backup_service.run_scheduled_backups().await {
+                        Ok(0) => {},
+                        Ok(count) => tracing::info!("Started {} scheduled backups", count),
+                        Err(e) => tracing::error!("Error running scheduled backups: {}", e),
+                    }
+                }
+            }
+        }
+    }
+}
+
+pub fn spawn_backup_scheduler(
+    backup_service: Arc<BackupService>,
+    interval_secs: u64,
+    cancel_token: CancellationToken,
+) -> tokio::task::JoinHandle<()> {
+    let scheduler = BackupScheduler::new(backup_service, interval_secs, cancel_token);
+
+    tokio::spawn(async move {
+        scheduler.run().await;
+    })
+}
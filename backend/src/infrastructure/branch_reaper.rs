@@ -0,0 +1,87 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use tokio::time;
+use tokio_util::sync::CancellationToken;
+
+use crate::domain::models::{AuditAction, AuditEntityType, AuditStatus};
+use crate::domain::services::{AuditLogService, BranchService};
+
+pub struct BranchReaper {
+    branch_service: Arc<BranchService>,
+    audit_service: Arc<AuditLogService>,
+    interval: Duration,
+    cancel_token: CancellationToken,
+}
+
+impl BranchReaper {
+    pub fn new(
+        branch_service: Arc<BranchService>,
+        audit_service: Arc<AuditLogService>,
+        interval_secs: u64,
+        cancel_token: CancellationToken,
+    ) -> Self {
+        Self {
+            branch_service,
+            audit_service,
+            interval: Duration::from_secs(interval_secs),
+            cancel_token,
+        }
+    }
+
+    pub async fn run(self) {
+        tracing::info!(
+            "Starting branch reaper with {}s interval",
+            self.interval.as_secs()
+        );
+
+        let mut interval = time::interval(self.interval);
+
+        loop {
+            tokio::select! {
+                _ = self.cancel_token.cancelled() => {
+                    tracing::info!("Branch reaper shutting down");
+                    break;
+                }
+                _ = interval.tick() => {
+                    match self.branch_service.delete_expired().await {
+                        Ok(deleted) => {
+                            for (branch, owner_id) in deleted {
+                                tracing::info!("Deleted expired branch {} ({})", branch.id, branch.name);
+                                self.audit_service.log(
+                                    owner_id,
+                                    AuditAction::ExpireBranch,
+                                    AuditEntityType::Branch,
+                                    Some(branch.id),
+                                    Some(serde_json::json!({
+                                        "name": branch.name,
+                                        "parent_id": branch.parent_branch_id,
+                                        "expires_at": branch.expires_at,
+                                        "automatic": true,
+                                    })),
+                                    AuditStatus::Success,
+                                    None,
+                                    None,
+                                );
+                            }
+                        },
+                        Err(e) => tracing::error!("Error deleting expired branches: {}", e),
+                    }
+                }
+            }
+        }
+    }
+}
+
+pub fn spawn_branch_reaper(
+    branch_service: Arc<BranchService>,
+    audit_service: Arc<AuditLogService>,
+    interval_secs: u64,
+    cancel_token: CancellationToken,
+) -> tokio::task::JoinHandle<()> {
+    let reaper = BranchReaper::new(branch_service, audit_service, interval_secs, cancel_token);
+
+    tokio::spawn(async move {
+        reaper.run().await;
+    })
+}
@@ -0,0 +1,146 @@
+use bollard::models::{ContainerCreateBody, HealthConfig};
+use bollard::query_parameters::CreateContainerOptionsBuilder;
+use bollard::Docker;
+
+use super::{create_host_config, create_port_bindings, ContainerConfig, ContainerProvider};
+use crate::error::{AppError, AppResult};
+
+const HEALTHCHECK_INTERVAL_NS: i64 = 2_000_000_000;
+const HEALTHCHECK_TIMEOUT_NS: i64 = 5_000_000_000;
+const HEALTHCHECK_START_PERIOD_NS: i64 = 60_000_000_000;
+
+/// Local disk `BACKUP` and `RESTORE` write to, inside the data directory.
+pub const CLICKHOUSE_BACKUP_DISK: &str = "backups";
+pub const CLICKHOUSE_BACKUP_PATH: &str = "/var/lib/clickhouse/backups/";
+
+/// Databases of a ClickHouse server that describe the server rather than hold data.
+pub const CLICKHOUSE_SYSTEM_DATABASES: &[&str] =
+    &["system", "information_schema", "INFORMATION_SCHEMA"];
+
+/// Runs ClickHouse with its HTTP interface as the only published port; the native protocol
+/// stays inside the container network.
+pub struct ClickhouseContainer;
+
+impl ContainerProvider for ClickhouseContainer {
+    fn default_image(version: &str) -> String {
+        format!("clickhouse/clickhouse-server:{}", version)
+    }
+
+    fn internal_port() -> u16 {
+        8123
+    }
+
+    fn data_mount_point() -> &'static str {
+        "/var/lib/clickhouse"
+    }
+
+    fn cli_command() -> Vec<&'static str> {
+        vec!["clickhouse-client"]
+    }
+
+    /// Server settings after `--` override the bundled config; these declare the backup disk.
+    fn build_cmd(_password: &str) -> Vec<String> {
+        vec![
+            "--".to_string(),
+            format!(
+                "--storage_configuration.disks.{}.type=local",
+                CLICKHOUSE_BACKUP_DISK
+            ),
+            format!(
+                "--storage_configuration.disks.{}.path={}",
+                CLICKHOUSE_BACKUP_DISK, CLICKHOUSE_BACKUP_PATH
+            ),
+            format!("--backups.allowed_disk={}", CLICKHOUSE_BACKUP_DISK),
+        ]
+    }
+}
+
+impl ClickhouseContainer {
+    /// `clickhouse-client` running one query as the default user. The password is read from
+    /// `client_env` so it does not show up in the exec's arguments.
+    pub fn client_command(query: &str) -> Vec<String> {
+        vec![
+            "sh".to_string(),
+            "-c".to_string(),
+            "exec clickhouse-client --password \"$CLICKHOUSE_PASSWORD\" --query \"$1\"".to_string(),
+            "sh".to_string(),
+            query.to_string(),
+        ]
+    }
+
+    pub fn client_env(password: &str) -> Vec<String> {
+        vec![format!("CLICKHOUSE_PASSWORD={}", password)]
+    }
+
+    fn healthcheck() -> HealthConfig {
+        HealthConfig {
+            test: Some(vec![
+                "CMD-SHELL".to_string(),
+                "clickhouse-client --password \"$CLICKHOUSE_PASSWORD\" --query 'SELECT 1'"
+                    .to_string(),
+            ]),
+            interval: Some(HEALTHCHECK_INTERVAL_NS),
+            timeout: Some(HEALTHCHECK_TIMEOUT_NS),
+            retries: Some(5),
+            start_period: Some(HEALTHCHECK_START_PERIOD_NS),
+            start_interval: None,
+        }
+    }
+
+    pub async fn create(
+        docker: &Docker,
+        config: ContainerConfig,
+        password: &str,
+        network_name: &str,
+    ) -> AppResult<String> {
+        let mut env = config.env.clone();
+        env.push(format!("CLICKHOUSE_PASSWORD={}", password));
+        env.push("CLICKHOUSE_DEFAULT_ACCESS_MANAGEMENT=1".to_string());
+
+        let port_bindings = create_port_bindings(config.internal_port, config.exposed_port);
+        let host_config = create_host_config(
+            &config.data_path,
+            Self::data_mount_point(),
+            port_bindings,
+            network_name,
+            config.memory_limit_mb,
+            config.cpu_limit,
+        );
+
+        let exposed_ports = vec![format!("{}/tcp", config.internal_port)];
+        let mut cmd = Self::build_cmd(password);
+        if let Some(extra) = &config.cmd {
+            cmd.extend(extra.iter().cloned());
+        }
+
+        let container_body = ContainerCreateBody {
+            image: Some(config.image.clone()),
+            hostname: Some(config.name.clone()),
+            env: Some(env),
+            host_config: Some(host_config),
+            exposed_ports: Some(exposed_ports),
+            cmd: Some(cmd),
+            healthcheck: Some(Self::healthcheck()),
+            ..Default::default()
+        };
+
+        let options = CreateContainerOptionsBuilder::default()
+            .name(&config.name)
+            .build();
+
+        let container = docker
+            .create_container(Some(options), container_body)
+            .await
+            .map_err(|e| {
+                AppError::Docker(format!("Failed to create ClickHouse container: {}", e))
+            })?;
+
+        tracing::info!(
+            "Created ClickHouse container {} with ID {}",
+            config.name,
+            container.id
+        );
+
+        Ok(container.id)
+    }
+}
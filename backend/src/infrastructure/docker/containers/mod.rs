@@ -1,3 +1,6 @@
+mod clickhouse;
+mod mongo;
+mod mysql;
 mod postgres;
 mod redis;
 mod valkey;
@@ -5,6 +8,9 @@ mod valkey;
 use std::collections::HashMap;
 
 use bollard::models::{HostConfig, PortBinding, RestartPolicy, RestartPolicyNameEnum};
+pub use clickhouse::*;
+pub use mongo::*;
+pub use mysql::*;
 pub use postgres::*;
 pub use redis::*;
 pub use valkey::*;
@@ -20,6 +26,9 @@ pub struct ContainerConfig {
     pub internal_port: u16,
     pub exposed_port: Option<u16>,
     pub cmd: Option<Vec<String>>,
+    pub wal_archive_path: Option<String>,
+    /// Another database's WAL archive, mounted read-only for point-in-time recovery.
+    pub wal_restore_path: Option<String>,
 }
 
 pub trait ContainerProvider {
@@ -30,6 +39,23 @@ pub trait ContainerProvider {
     fn build_cmd(password: &str) -> Vec<String>;
 }
 
+pub const KV_PERSISTENCE_MODES: &[&str] = &["aof", "rdb"];
+
+/// Extra server arguments for a KV persistence mode; AOF is the server default set in `build_cmd`.
+pub fn kv_persistence_args(persistence: Option<&str>) -> Option<Vec<String>> {
+    match persistence {
+        Some("rdb") => Some(vec!["--appendonly".to_string(), "no".to_string()]),
+        _ => None,
+    }
+}
+
+pub fn kv_info_field<'a>(info: &'a str, key: &str) -> Option<&'a str> {
+    info.lines().find_map(|line| {
+        let (name, value) = line.split_once(':')?;
+        (name == key).then(|| value.trim())
+    })
+}
+
 pub fn create_port_bindings(
     internal_port: u16,
     exposed_port: Option<u16>,
@@ -68,3 +94,21 @@ pub fn create_host_config(
         ..Default::default()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_kv_info_field() {
+        let info = "# Persistence\r\nloading:0\r\nrdb_bgsave_in_progress:0\r\nrdb_last_save_time:1736467200\r\nrdb_last_bgsave_status:ok\r\n";
+
+        assert_eq!(kv_info_field(info, "rdb_bgsave_in_progress"), Some("0"));
+        assert_eq!(
+            kv_info_field(info, "rdb_last_save_time"),
+            Some("1736467200")
+        );
+        assert_eq!(kv_info_field(info, "rdb_last_bgsave_status"), Some("ok"));
+        assert_eq!(kv_info_field(info, "aof_enabled"), None);
+    }
+}
@@ -0,0 +1,128 @@
+use bollard::models::{ContainerCreateBody, HealthConfig};
+use bollard::query_parameters::CreateContainerOptionsBuilder;
+use bollard::Docker;
+
+use super::{create_host_config, create_port_bindings, ContainerConfig, ContainerProvider};
+use crate::error::{AppError, AppResult};
+
+const HEALTHCHECK_INTERVAL_NS: i64 = 2_000_000_000;
+const HEALTHCHECK_TIMEOUT_NS: i64 = 5_000_000_000;
+const HEALTHCHECK_START_PERIOD_NS: i64 = 60_000_000_000;
+
+/// Databases of a MongoDB server that hold users and cluster state rather than data.
+pub const MONGO_SYSTEM_DATABASES: &[&str] = &["admin", "config", "local"];
+
+pub struct MongoContainer;
+
+impl ContainerProvider for MongoContainer {
+    fn default_image(version: &str) -> String {
+        format!("mongo:{}", version)
+    }
+
+    fn internal_port() -> u16 {
+        27017
+    }
+
+    fn data_mount_point() -> &'static str {
+        "/data/db"
+    }
+
+    fn cli_command() -> Vec<&'static str> {
+        vec!["mongosh", "--quiet"]
+    }
+
+    fn build_cmd(_password: &str) -> Vec<String> {
+        vec!["mongod".to_string(), "--bind_ip_all".to_string()]
+    }
+}
+
+impl MongoContainer {
+    /// `mongosh` logged in as root, ready for an `--eval` script. The password is read from
+    /// `shell_env` so it does not show up in the exec's arguments.
+    pub fn shell_command() -> Vec<String> {
+        vec![
+            "sh".to_string(),
+            "-c".to_string(),
+            "exec mongosh --quiet --norc -u root -p \"$MONGO_PASSWORD\" \
+             --authenticationDatabase admin \"$@\""
+                .to_string(),
+            "sh".to_string(),
+        ]
+    }
+
+    pub fn shell_env(password: &str) -> Vec<String> {
+        vec![format!("MONGO_PASSWORD={}", password)]
+    }
+
+    /// Pings the container's own hostname: the entrypoint's temporary init server only listens
+    /// on localhost, so the container turns healthy once the real server is up.
+    fn healthcheck() -> HealthConfig {
+        HealthConfig {
+            test: Some(vec![
+                "CMD-SHELL".to_string(),
+                "mongosh --quiet --norc --host \"$HOSTNAME\" --eval \"quit(db.adminCommand('ping').ok ? 0 : 1)\""
+                    .to_string(),
+            ]),
+            interval: Some(HEALTHCHECK_INTERVAL_NS),
+            timeout: Some(HEALTHCHECK_TIMEOUT_NS),
+            retries: Some(5),
+            start_period: Some(HEALTHCHECK_START_PERIOD_NS),
+            start_interval: None,
+        }
+    }
+
+    pub async fn create(
+        docker: &Docker,
+        config: ContainerConfig,
+        password: &str,
+        network_name: &str,
+    ) -> AppResult<String> {
+        let mut env = config.env.clone();
+        env.push("MONGO_INITDB_ROOT_USERNAME=root".to_string());
+        env.push(format!("MONGO_INITDB_ROOT_PASSWORD={}", password));
+
+        let port_bindings = create_port_bindings(config.internal_port, config.exposed_port);
+        let host_config = create_host_config(
+            &config.data_path,
+            Self::data_mount_point(),
+            port_bindings,
+            network_name,
+            config.memory_limit_mb,
+            config.cpu_limit,
+        );
+
+        let exposed_ports = vec![format!("{}/tcp", config.internal_port)];
+        let mut cmd = Self::build_cmd(password);
+        if let Some(extra) = &config.cmd {
+            cmd.extend(extra.iter().cloned());
+        }
+
+        let container_body = ContainerCreateBody {
+            image: Some(config.image.clone()),
+            hostname: Some(config.name.clone()),
+            env: Some(env),
+            host_config: Some(host_config),
+            exposed_ports: Some(exposed_ports),
+            cmd: Some(cmd),
+            healthcheck: Some(Self::healthcheck()),
+            ..Default::default()
+        };
+
+        let options = CreateContainerOptionsBuilder::default()
+            .name(&config.name)
+            .build();
+
+        let container = docker
+            .create_container(Some(options), container_body)
+            .await
+            .map_err(|e| AppError::Docker(format!("Failed to create MongoDB container: {}", e)))?;
+
+        tracing::info!(
+            "Created MongoDB container {} with ID {}",
+            config.name,
+            container.id
+        );
+
+        Ok(container.id)
+    }
+}
@@ -0,0 +1,170 @@
+use bollard::models::{ContainerCreateBody, HealthConfig};
+use bollard::query_parameters::CreateContainerOptionsBuilder;
+use bollard::Docker;
+
+use super::{create_host_config, create_port_bindings, ContainerConfig, ContainerProvider};
+use crate::error::{AppError, AppResult};
+
+const HEALTHCHECK_INTERVAL_NS: i64 = 2_000_000_000;
+const HEALTHCHECK_TIMEOUT_NS: i64 = 5_000_000_000;
+const HEALTHCHECK_START_PERIOD_NS: i64 = 60_000_000_000;
+
+/// Client programs of a MySQL-compatible server. Recent MariaDB images only ship them under
+/// their `mariadb` names.
+#[derive(Debug, Clone, Copy)]
+pub struct MysqlTools {
+    pub client: &'static str,
+    pub dump: &'static str,
+    pub admin: &'static str,
+}
+
+pub const MYSQL_TOOLS: MysqlTools = MysqlTools {
+    client: "mysql",
+    dump: "mysqldump",
+    admin: "mysqladmin",
+};
+
+pub const MARIADB_TOOLS: MysqlTools = MysqlTools {
+    client: "mariadb",
+    dump: "mariadb-dump",
+    admin: "mariadb-admin",
+};
+
+/// Runs MySQL and MariaDB, whose images share the data directory, port and environment.
+pub struct MysqlContainer;
+
+impl ContainerProvider for MysqlContainer {
+    fn default_image(version: &str) -> String {
+        format!("mysql:{}", version)
+    }
+
+    fn internal_port() -> u16 {
+        3306
+    }
+
+    fn data_mount_point() -> &'static str {
+        "/var/lib/mysql"
+    }
+
+    fn cli_command() -> Vec<&'static str> {
+        vec!["mysql", "-uroot"]
+    }
+
+    fn build_cmd(_password: &str) -> Vec<String> {
+        vec!["--performance-schema=ON".to_string()]
+    }
+}
+
+impl MysqlContainer {
+    pub fn mariadb_image(version: &str) -> String {
+        format!("mariadb:{}", version)
+    }
+
+    pub fn is_mariadb_image(image: &str) -> bool {
+        image
+            .rsplit('/')
+            .next()
+            .unwrap_or(image)
+            .starts_with("mariadb")
+    }
+
+    pub fn tools_for_image(image: &str) -> MysqlTools {
+        if Self::is_mariadb_image(image) {
+            MARIADB_TOOLS
+        } else {
+            MYSQL_TOOLS
+        }
+    }
+
+    /// Pings the server over TCP, which the entrypoint's temporary init server does not listen
+    /// on, so the container only turns healthy once the real server is up.
+    fn healthcheck(image: &str) -> HealthConfig {
+        HealthConfig {
+            test: Some(vec![
+                "CMD-SHELL".to_string(),
+                format!(
+                    "{} ping -h 127.0.0.1 -uroot -p\"$MYSQL_ROOT_PASSWORD\" --silent",
+                    Self::tools_for_image(image).admin
+                ),
+            ]),
+            interval: Some(HEALTHCHECK_INTERVAL_NS),
+            timeout: Some(HEALTHCHECK_TIMEOUT_NS),
+            retries: Some(5),
+            start_period: Some(HEALTHCHECK_START_PERIOD_NS),
+            start_interval: None,
+        }
+    }
+
+    pub async fn create(
+        docker: &Docker,
+        config: ContainerConfig,
+        password: &str,
+        network_name: &str,
+    ) -> AppResult<String> {
+        let mut env = config.env.clone();
+        env.push(format!("MYSQL_ROOT_PASSWORD={}", password));
+
+        let port_bindings = create_port_bindings(config.internal_port, config.exposed_port);
+        let host_config = create_host_config(
+            &config.data_path,
+            Self::data_mount_point(),
+            port_bindings,
+            network_name,
+            config.memory_limit_mb,
+            config.cpu_limit,
+        );
+
+        let exposed_ports = vec![format!("{}/tcp", config.internal_port)];
+        let mut cmd = Self::build_cmd(password);
+        if let Some(extra) = &config.cmd {
+            cmd.extend(extra.iter().cloned());
+        }
+
+        let container_body = ContainerCreateBody {
+            image: Some(config.image.clone()),
+            hostname: Some(config.name.clone()),
+            env: Some(env),
+            host_config: Some(host_config),
+            exposed_ports: Some(exposed_ports),
+            cmd: Some(cmd),
+            healthcheck: Some(Self::healthcheck(&config.image)),
+            ..Default::default()
+        };
+
+        let options = CreateContainerOptionsBuilder::default()
+            .name(&config.name)
+            .build();
+
+        let container = docker
+            .create_container(Some(options), container_body)
+            .await
+            .map_err(|e| AppError::Docker(format!("Failed to create MySQL container: {}", e)))?;
+
+        tracing::info!(
+            "Created MySQL container {} with ID {}",
+            config.name,
+            container.id
+        );
+
+        Ok(container.id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mysql_tools_for_image() {
+        assert_eq!(MysqlContainer::default_image("8.4"), "mysql:8.4");
+        assert_eq!(
+            MysqlContainer::tools_for_image("mariadb:11.4").dump,
+            "mariadb-dump"
+        );
+        assert_eq!(
+            MysqlContainer::tools_for_image("library/mariadb:10.11").admin,
+            "mariadb-admin"
+        );
+        assert_eq!(MysqlContainer::tools_for_image("mysql:8.4").client, "mysql");
+    }
+}
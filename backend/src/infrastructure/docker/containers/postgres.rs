@@ -5,6 +5,9 @@ use bollard::Docker;
 use super::{create_port_bindings, ContainerConfig, ContainerProvider};
 use crate::error::{AppError, AppResult};
 
+pub const WAL_ARCHIVE_MOUNT_POINT: &str = "/wal_archive";
+pub const WAL_RESTORE_MOUNT_POINT: &str = "/wal_restore";
+
 pub struct PostgresContainer;
 
 impl ContainerProvider for PostgresContainer {
@@ -36,16 +39,34 @@ impl ContainerProvider for PostgresContainer {
 }
 
 impl PostgresContainer {
+    /// Reads the major version from tags like `18-alpine`, `17-3.5` or `latest-pg17`.
     pub fn is_postgres_18_or_later(image: &str) -> bool {
         if let Some(tag) = image.split(':').nth(1) {
-            let version_str = tag.split('-').next().unwrap_or(tag);
-            if let Ok(version) = version_str.parse::<u32>() {
+            let version = tag
+                .split('-')
+                .find_map(|part| part.trim_start_matches("pg").parse::<u32>().ok());
+            if let Some(version) = version {
                 return version >= 18;
             }
         }
         false
     }
 
+    pub fn wal_archive_args() -> Vec<String> {
+        [
+            "wal_level=replica".to_string(),
+            "archive_mode=on".to_string(),
+            "archive_timeout=60".to_string(),
+            format!(
+                "archive_command=test ! -f {dir}/%f && cp %p {dir}/%f",
+                dir = WAL_ARCHIVE_MOUNT_POINT
+            ),
+        ]
+        .into_iter()
+        .flat_map(|setting| ["-c".to_string(), setting])
+        .collect()
+    }
+
     pub fn get_mount_point(image: &str) -> &'static str {
         if Self::is_postgres_18_or_later(image) {
             "/var/lib/postgresql"
@@ -67,8 +88,22 @@ impl PostgresContainer {
         let port_bindings = create_port_bindings(config.internal_port, config.exposed_port);
         let mount_point = Self::get_mount_point(&config.image);
 
+        let mut binds = vec![format!("{}:{}", config.data_path, mount_point)];
+        let mut cmd = config.cmd.clone();
+        if let Some(wal_archive_path) = &config.wal_archive_path {
+            binds.push(format!("{}:{}", wal_archive_path, WAL_ARCHIVE_MOUNT_POINT));
+            cmd.get_or_insert_with(|| vec!["postgres".to_string()])
+                .extend(Self::wal_archive_args());
+        }
+        if let Some(wal_restore_path) = &config.wal_restore_path {
+            binds.push(format!(
+                "{}:{}:ro",
+                wal_restore_path, WAL_RESTORE_MOUNT_POINT
+            ));
+        }
+
         let host_config = HostConfig {
-            binds: Some(vec![format!("{}:{}", config.data_path, mount_point)]),
+            binds: Some(binds),
             port_bindings: Some(port_bindings),
             network_mode: Some(network_name.to_string()),
             memory: Some(config.memory_limit_mb * 1024 * 1024),
@@ -88,7 +123,7 @@ impl PostgresContainer {
             env: Some(env),
             host_config: Some(host_config),
             exposed_ports: Some(exposed_ports),
-            cmd: config.cmd.clone(),
+            cmd,
             ..Default::default()
         };
 
@@ -62,7 +62,10 @@ impl RedisContainer {
         };
 
         let exposed_ports = vec![format!("{}/tcp", config.internal_port)];
-        let cmd = Self::build_cmd(password);
+        let mut cmd = Self::build_cmd(password);
+        if let Some(extra) = &config.cmd {
+            cmd.extend(extra.iter().cloned());
+        }
 
         let container_body = ContainerCreateBody {
             image: Some(config.image.clone()),
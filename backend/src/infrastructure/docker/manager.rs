@@ -1,11 +1,13 @@
 use std::collections::HashMap;
 use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::Arc;
 
+use bollard::container::LogOutput;
 use bollard::exec::StartExecOptions;
 use bollard::models::{
-    ContainerCreateBody, CreateImageInfo, ExecConfig, HostConfig, NetworkCreateRequest,
-    PortBinding, RestartPolicy, RestartPolicyNameEnum,
+    ContainerCreateBody, ContainerStateStatusEnum, CreateImageInfo, ExecConfig, HealthStatusEnum,
+    HostConfig, NetworkCreateRequest, PortBinding, RestartPolicy, RestartPolicyNameEnum,
 };
 use bollard::query_parameters::{
     CreateContainerOptionsBuilder, CreateImageOptionsBuilder, ListContainersOptionsBuilder,
@@ -16,8 +18,15 @@ use bollard::query_parameters::{
 use bollard::Docker;
 use bytes::Bytes;
 use futures::{Stream, StreamExt, TryStreamExt};
-
-use super::containers::{ContainerConfig, PostgresContainer, RedisContainer, ValkeyContainer};
+use tokio::io::AsyncWriteExt;
+use tokio_util::io::StreamReader;
+
+use super::containers::{
+    kv_info_field, ClickhouseContainer, ContainerConfig, ContainerProvider, MongoContainer,
+    MysqlContainer, MysqlTools, PostgresContainer, RedisContainer, ValkeyContainer,
+    CLICKHOUSE_BACKUP_DISK, CLICKHOUSE_BACKUP_PATH, CLICKHOUSE_SYSTEM_DATABASES,
+    WAL_ARCHIVE_MOUNT_POINT,
+};
 use crate::config::Settings;
 use crate::error::{AppError, AppResult};
 
@@ -50,6 +59,38 @@ impl From<CreateImageInfo> for ImagePullProgress {
     }
 }
 
+/// Progress of a PostgreSQL fork, read from `dd` in the middle of the dump pipeline.
+#[derive(Debug, Default)]
+pub struct ForkProgress {
+    bytes: AtomicU64,
+    dumped: AtomicBool,
+}
+
+impl ForkProgress {
+    /// Bytes of dump read from the source so far.
+    pub fn bytes(&self) -> u64 {
+        self.bytes.load(Ordering::Relaxed)
+    }
+
+    /// Whether the whole dump has been read and only the restore is still running.
+    pub fn dumped(&self) -> bool {
+        self.dumped.load(Ordering::Relaxed)
+    }
+
+    fn record(&self, output: &str) {
+        for line in output.split(['\r', '\n']) {
+            let mut words = line.split_whitespace();
+            if let (Some(count), Some("bytes")) = (words.next(), words.next()) {
+                if let Ok(bytes) = count.parse::<u64>() {
+                    self.bytes.fetch_max(bytes, Ordering::Relaxed);
+                }
+            } else if line.contains("records out") {
+                self.dumped.store(true, Ordering::Relaxed);
+            }
+        }
+    }
+}
+
 #[derive(Debug, Clone, serde::Serialize)]
 pub struct LogEntry {
     pub timestamp: Option<String>,
@@ -71,6 +112,82 @@ pub struct ExecOutput {
     pub exit_code: Option<i64>,
 }
 
+impl ExecOutput {
+    /// Fails with the command's stderr unless it exited with 0.
+    pub fn check(&self, name: &str) -> AppResult<()> {
+        if self.exit_code != Some(0) {
+            return Err(AppError::Docker(format!(
+                "{} failed with exit code {:?}: {}",
+                name,
+                self.exit_code,
+                self.stderr.trim()
+            )));
+        }
+        Ok(())
+    }
+}
+
+pub struct ExecStream {
+    pub exec_id: String,
+    pub output: Pin<Box<dyn Stream<Item = Result<LogOutput, AppError>> + Send>>,
+}
+
+pub type ExecStdout = Pin<Box<dyn Stream<Item = AppResult<Bytes>> + Send>>;
+
+impl ExecStream {
+    /// Splits the output into stdout, to be consumed by the caller, and the exit of the
+    /// command, which keeps its stderr to report a failure with.
+    pub fn split(self) -> (ExecStdout, ExecExit) {
+        let stderr = Arc::new(std::sync::Mutex::new(String::new()));
+        let captured = stderr.clone();
+        let stdout = self.output.filter_map(move |chunk| {
+            let chunk = match chunk {
+                Ok(LogOutput::StdOut { message }) => Some(Ok(message)),
+                Ok(LogOutput::StdErr { message }) => {
+                    if let Ok(mut stderr) = captured.lock() {
+                        stderr.push_str(&String::from_utf8_lossy(&message));
+                    }
+                    None
+                },
+                Ok(_) => None,
+                Err(e) => Some(Err(e)),
+            };
+            futures::future::ready(chunk)
+        });
+
+        let exit = ExecExit {
+            exec_id: self.exec_id,
+            stderr,
+        };
+        (Box::pin(stdout), exit)
+    }
+}
+
+/// The exit of a streaming exec, checked once its stdout has been consumed.
+pub struct ExecExit {
+    exec_id: String,
+    stderr: Arc<std::sync::Mutex<String>>,
+}
+
+impl ExecExit {
+    /// Fails with the stderr the command wrote unless it exited with 0.
+    pub async fn check(&self, docker: &DockerManager, name: &str) -> AppResult<()> {
+        let exit_code = docker.exec_exit_code(&self.exec_id).await?;
+        if exit_code == Some(0) {
+            return Ok(());
+        }
+        let stderr = self
+            .stderr
+            .lock()
+            .map(|s| s.trim().to_string())
+            .unwrap_or_default();
+        Err(AppError::Docker(format!(
+            "{} failed with exit code {:?}: {}",
+            name, exit_code, stderr
+        )))
+    }
+}
+
 #[derive(Clone)]
 pub struct DockerManager {
     docker: Arc<Docker>,
@@ -195,6 +312,51 @@ impl DockerManager {
         .await
     }
 
+    pub async fn create_mysql_container(
+        &self,
+        config: ContainerConfig,
+        password: &str,
+    ) -> AppResult<String> {
+        self.pull_image(&config.image).await?;
+        MysqlContainer::create(
+            &self.docker,
+            config,
+            password,
+            &self.settings.docker.network_name,
+        )
+        .await
+    }
+
+    pub async fn create_mongo_container(
+        &self,
+        config: ContainerConfig,
+        password: &str,
+    ) -> AppResult<String> {
+        self.pull_image(&config.image).await?;
+        MongoContainer::create(
+            &self.docker,
+            config,
+            password,
+            &self.settings.docker.network_name,
+        )
+        .await
+    }
+
+    pub async fn create_clickhouse_container(
+        &self,
+        config: ContainerConfig,
+        password: &str,
+    ) -> AppResult<String> {
+        self.pull_image(&config.image).await?;
+        ClickhouseContainer::create(
+            &self.docker,
+            config,
+            password,
+            &self.settings.docker.network_name,
+        )
+        .await
+    }
+
     pub fn postgres_image(&self) -> &str {
         &self.settings.docker.postgres_image
     }
@@ -297,6 +459,20 @@ impl DockerManager {
         Ok(())
     }
 
+    pub async fn pause_container(&self, container_id: &str) -> AppResult<()> {
+        self.docker
+            .pause_container(container_id)
+            .await
+            .map_err(|e| AppError::Docker(format!("Failed to pause container: {}", e)))
+    }
+
+    pub async fn unpause_container(&self, container_id: &str) -> AppResult<()> {
+        self.docker
+            .unpause_container(container_id)
+            .await
+            .map_err(|e| AppError::Docker(format!("Failed to unpause container: {}", e)))
+    }
+
     pub async fn remove_container(&self, container_id: &str, force: bool) -> AppResult<()> {
         let options = RemoveContainerOptionsBuilder::default()
             .force(force)
@@ -326,6 +502,26 @@ impl DockerManager {
         Ok(())
     }
 
+    /// Registry digests (`repo@sha256:...`) of the image the container was created from.
+    pub async fn image_repo_digests(&self, container_id: &str) -> AppResult<Vec<String>> {
+        let container = self
+            .docker
+            .inspect_container(container_id, None)
+            .await
+            .map_err(|e| AppError::Docker(format!("Failed to inspect container: {}", e)))?;
+        let Some(image_id) = container.image else {
+            return Ok(Vec::new());
+        };
+
+        let image = self
+            .docker
+            .inspect_image(&image_id)
+            .await
+            .map_err(|e| AppError::Docker(format!("Failed to inspect image: {}", e)))?;
+
+        Ok(image.repo_digests.unwrap_or_default())
+    }
+
     pub async fn get_container_status(&self, container_id: &str) -> AppResult<String> {
         let container = self
             .docker
@@ -414,6 +610,7 @@ impl DockerManager {
         Ok(infos)
     }
 
+    /// Waits for the container to run and, if it has a healthcheck, to report healthy.
     pub async fn wait_for_healthy(
         &self,
         container_id: &str,
@@ -423,14 +620,25 @@ impl DockerManager {
         let timeout = std::time::Duration::from_secs(timeout_seconds);
 
         while start.elapsed() < timeout {
-            let status = self.get_container_status(container_id).await?;
-
-            if status == "running" {
-                return Ok(true);
-            }
-
-            if status == "exited" || status == "dead" {
-                return Ok(false);
+            let container = self
+                .docker
+                .inspect_container(container_id, None)
+                .await
+                .map_err(|e| AppError::Docker(format!("Failed to inspect container: {}", e)))?;
+            let state = container.state.unwrap_or_default();
+
+            match state.status {
+                Some(ContainerStateStatusEnum::RUNNING) => {
+                    match state.health.and_then(|h| h.status) {
+                        Some(HealthStatusEnum::STARTING) => {},
+                        Some(HealthStatusEnum::UNHEALTHY) => return Ok(false),
+                        _ => return Ok(true),
+                    }
+                },
+                Some(ContainerStateStatusEnum::EXITED) | Some(ContainerStateStatusEnum::DEAD) => {
+                    return Ok(false);
+                },
+                _ => {},
             }
 
             tokio::time::sleep(std::time::Duration::from_secs(1)).await;
@@ -687,6 +895,458 @@ impl DockerManager {
         })
     }
 
+    pub async fn stream_exec(
+        &self,
+        container_id: &str,
+        cmd: Vec<String>,
+        env: Option<Vec<String>>,
+    ) -> AppResult<ExecStream> {
+        let config = ExecConfig {
+            attach_stdin: Some(false),
+            attach_stdout: Some(true),
+            attach_stderr: Some(true),
+            tty: Some(false),
+            cmd: Some(cmd),
+            env,
+            ..Default::default()
+        };
+
+        let exec = self
+            .docker
+            .create_exec(container_id, config)
+            .await
+            .map_err(|e| AppError::Docker(format!("Failed to create exec: {}", e)))?;
+
+        let output = self
+            .docker
+            .start_exec(&exec.id, None)
+            .await
+            .map_err(|e| AppError::Docker(format!("Failed to start exec: {}", e)))?;
+
+        match output {
+            bollard::exec::StartExecResults::Attached { output, .. } => Ok(ExecStream {
+                exec_id: exec.id,
+                output: Box::pin(
+                    output.map_err(|e| AppError::Docker(format!("Exec stream error: {}", e))),
+                ),
+            }),
+            bollard::exec::StartExecResults::Detached => Err(AppError::Docker(
+                "Exec started in detached mode".to_string(),
+            )),
+        }
+    }
+
+    pub async fn exec_exit_code(&self, exec_id: &str) -> AppResult<Option<i64>> {
+        let exec_inspect = self
+            .docker
+            .inspect_exec(exec_id)
+            .await
+            .map_err(|e| AppError::Docker(format!("Failed to inspect exec: {}", e)))?;
+
+        Ok(exec_inspect.exit_code)
+    }
+
+    pub async fn exec_with_input<R>(
+        &self,
+        container_id: &str,
+        cmd: Vec<String>,
+        env: Option<Vec<String>>,
+        mut input: R,
+    ) -> AppResult<ExecOutput>
+    where
+        R: tokio::io::AsyncRead + Unpin + Send,
+    {
+        let config = ExecConfig {
+            attach_stdin: Some(true),
+            attach_stdout: Some(true),
+            attach_stderr: Some(true),
+            tty: Some(false),
+            cmd: Some(cmd),
+            env,
+            ..Default::default()
+        };
+
+        let exec = self
+            .docker
+            .create_exec(container_id, config)
+            .await
+            .map_err(|e| AppError::Docker(format!("Failed to create exec: {}", e)))?;
+
+        let output = self
+            .docker
+            .start_exec(&exec.id, None)
+            .await
+            .map_err(|e| AppError::Docker(format!("Failed to start exec: {}", e)))?;
+
+        let bollard::exec::StartExecResults::Attached {
+            mut output,
+            input: mut stdin,
+        } = output
+        else {
+            return Err(AppError::Docker(
+                "Exec started in detached mode".to_string(),
+            ));
+        };
+
+        let write_input = async {
+            tokio::io::copy(&mut input, &mut stdin).await?;
+            stdin.shutdown().await
+        };
+
+        let read_output = async {
+            let mut stdout = String::new();
+            let mut stderr = String::new();
+            while let Some(result) = output.next().await {
+                match result {
+                    Ok(LogOutput::StdErr { message }) => {
+                        stderr.push_str(&String::from_utf8_lossy(&message));
+                    },
+                    Ok(LogOutput::StdOut { message }) | Ok(LogOutput::Console { message }) => {
+                        stdout.push_str(&String::from_utf8_lossy(&message));
+                    },
+                    Ok(LogOutput::StdIn { .. }) => {},
+                    Err(e) => {
+                        return Err(AppError::Docker(format!("Exec stream error: {}", e)));
+                    },
+                }
+            }
+            Ok((stdout, stderr))
+        };
+
+        let (written, read) = tokio::join!(write_input, read_output);
+        let (stdout, stderr) = read?;
+        written.map_err(|e| AppError::Docker(format!("Failed to write exec input: {}", e)))?;
+
+        let exit_code = self.exec_exit_code(&exec.id).await?;
+
+        Ok(ExecOutput {
+            stdout,
+            stderr,
+            exit_code,
+        })
+    }
+
+    /// Starts a PostgreSQL container and waits until it is healthy and accepts connections.
+    pub async fn start_postgres(
+        &self,
+        container_id: &str,
+        username: &str,
+        timeout_seconds: u64,
+    ) -> AppResult<()> {
+        self.start_container(container_id).await?;
+
+        if !self.wait_for_healthy(container_id, timeout_seconds).await?
+            || !self
+                .wait_for_postgres_ready(container_id, username, timeout_seconds)
+                .await?
+        {
+            return Err(AppError::Docker(
+                "Database did not become ready in time".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+
+    pub async fn wait_for_postgres_ready(
+        &self,
+        container_id: &str,
+        username: &str,
+        timeout_seconds: u64,
+    ) -> AppResult<bool> {
+        let start = std::time::Instant::now();
+        let timeout = std::time::Duration::from_secs(timeout_seconds);
+
+        while start.elapsed() < timeout {
+            let output = self
+                .run_exec(
+                    container_id,
+                    vec![
+                        "pg_isready".to_string(),
+                        "-U".to_string(),
+                        username.to_string(),
+                        "-d".to_string(),
+                        "postgres".to_string(),
+                    ],
+                    None,
+                )
+                .await;
+
+            if let Ok(ExecOutput {
+                exit_code: Some(0), ..
+            }) = output
+            {
+                return Ok(true);
+            }
+
+            tokio::time::sleep(std::time::Duration::from_secs(1)).await;
+        }
+
+        Ok(false)
+    }
+
+    pub async fn run_oneoff_container(
+        &self,
+        name: &str,
+        image: &str,
+        binds: Vec<String>,
+        script: &str,
+    ) -> AppResult<ExecOutput> {
+        self.create_oneoff_container(name, image, binds, script, "none", None)
+            .await
+    }
+
+    /// Like `run_oneoff_container`, but on the database network so the script can reach other
+    /// database containers by name.
+    pub async fn run_networked_oneoff_container(
+        &self,
+        name: &str,
+        image: &str,
+        binds: Vec<String>,
+        script: &str,
+        env: Vec<String>,
+    ) -> AppResult<ExecOutput> {
+        let network = self.settings.docker.network_name.clone();
+        self.create_oneoff_container(name, image, binds, script, &network, Some(env))
+            .await
+    }
+
+    async fn create_oneoff_container(
+        &self,
+        name: &str,
+        image: &str,
+        binds: Vec<String>,
+        script: &str,
+        network_mode: &str,
+        env: Option<Vec<String>>,
+    ) -> AppResult<ExecOutput> {
+        let container_body = ContainerCreateBody {
+            image: Some(image.to_string()),
+            entrypoint: Some(vec!["sh".to_string(), "-c".to_string()]),
+            cmd: Some(vec![script.to_string()]),
+            env,
+            host_config: Some(HostConfig {
+                binds: Some(binds),
+                network_mode: Some(network_mode.to_string()),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+
+        let options = CreateContainerOptionsBuilder::default().name(name).build();
+        let container = self
+            .docker
+            .create_container(Some(options), container_body)
+            .await
+            .map_err(|e| AppError::Docker(format!("Failed to create helper container: {}", e)))?;
+
+        let result = self.wait_oneoff_container(&container.id).await;
+        let _ = self.remove_container(&container.id, true).await;
+        result
+    }
+
+    async fn wait_oneoff_container(&self, container_id: &str) -> AppResult<ExecOutput> {
+        self.start_container(container_id).await?;
+
+        let mut exit_code = None;
+        let mut wait_stream = self.docker.wait_container(container_id, None);
+        while let Some(result) = wait_stream.next().await {
+            match result {
+                Ok(response) => exit_code = Some(response.status_code),
+                Err(bollard::errors::Error::DockerContainerWaitError { code, .. }) => {
+                    exit_code = Some(code)
+                },
+                Err(e) => {
+                    return Err(AppError::Docker(format!(
+                        "Failed to wait for helper container: {}",
+                        e
+                    )))
+                },
+            }
+        }
+
+        let options = LogsOptionsBuilder::default()
+            .stdout(true)
+            .stderr(true)
+            .build();
+        let mut log_stream = self.docker.logs(container_id, Some(options));
+        let mut stdout = String::new();
+        let mut stderr = String::new();
+        while let Some(Ok(output)) = log_stream.next().await {
+            match output {
+                LogOutput::StdErr { message } => {
+                    stderr.push_str(&String::from_utf8_lossy(&message))
+                },
+                other => stdout.push_str(&String::from_utf8_lossy(&other.into_bytes())),
+            }
+        }
+
+        Ok(ExecOutput {
+            stdout,
+            stderr,
+            exit_code,
+        })
+    }
+
+    pub async fn kv_command(
+        &self,
+        container_id: &str,
+        cli: &str,
+        password: &str,
+        args: &[&str],
+    ) -> AppResult<String> {
+        let mut cmd = vec![
+            cli.to_string(),
+            "--no-auth-warning".to_string(),
+            "-a".to_string(),
+            password.to_string(),
+        ];
+        cmd.extend(args.iter().map(|a| a.to_string()));
+
+        let output = self.run_exec(container_id, cmd, None).await?;
+        if output.exit_code != Some(0) {
+            return Err(AppError::Docker(format!(
+                "{} {} failed: {}",
+                cli,
+                args.join(" "),
+                output.stderr.trim()
+            )));
+        }
+
+        Ok(output.stdout)
+    }
+
+    /// Triggers a background RDB save and waits until a save newer than the request finished.
+    pub async fn kv_bgsave(
+        &self,
+        container_id: &str,
+        cli: &str,
+        password: &str,
+        timeout_seconds: u64,
+    ) -> AppResult<()> {
+        let before: i64 = self
+            .kv_command(container_id, cli, password, &["LASTSAVE"])
+            .await?
+            .trim()
+            .parse()
+            .unwrap_or(0);
+        // LASTSAVE has second resolution, so a save finishing within the same second would go unnoticed.
+        if before >= chrono::Utc::now().timestamp() {
+            tokio::time::sleep(std::time::Duration::from_secs(1)).await;
+        }
+
+        let reply = self
+            .kv_command(container_id, cli, password, &["BGSAVE", "SCHEDULE"])
+            .await?;
+        if reply.contains("ERR") && !reply.contains("already in progress") {
+            return Err(AppError::Docker(format!("BGSAVE failed: {}", reply.trim())));
+        }
+
+        let started = std::time::Instant::now();
+        loop {
+            let info = self
+                .kv_command(container_id, cli, password, &["INFO", "persistence"])
+                .await?;
+            let last_save: i64 = kv_info_field(&info, "rdb_last_save_time")
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(0);
+
+            if kv_info_field(&info, "rdb_bgsave_in_progress") == Some("0") && last_save > before {
+                if kv_info_field(&info, "rdb_last_bgsave_status") != Some("ok") {
+                    return Err(AppError::Docker("BGSAVE did not succeed".to_string()));
+                }
+                return Ok(());
+            }
+
+            if started.elapsed().as_secs() > timeout_seconds {
+                return Err(AppError::Docker(
+                    "Snapshot did not finish in time".to_string(),
+                ));
+            }
+
+            tokio::time::sleep(std::time::Duration::from_millis(500)).await;
+        }
+    }
+
+    /// Places the RDB snapshot at `snapshot_path` into the KV data directory at `data_path`. With
+    /// `rebuild_aof` the append-only files are rebuilt from the snapshot, since the server would
+    /// ignore the RDB.
+    pub async fn load_kv_snapshot(
+        &self,
+        name: &str,
+        image: &str,
+        cli: &str,
+        data_path: &str,
+        snapshot_path: &str,
+        rebuild_aof: bool,
+    ) -> AppResult<()> {
+        let mount_point = RedisContainer::data_mount_point();
+        let server = format!("{}-server", cli.trim_end_matches("-cli"));
+
+        let binds = vec![
+            format!("{}:{}", data_path, mount_point),
+            format!("{}:/restore/dump.rdb:ro", snapshot_path),
+        ];
+
+        let mut script = format!(
+            r#"set -e
+rm -rf {dir}/appendonlydir {dir}/appendonly.aof
+cp /restore/dump.rdb {dir}/dump.rdb"#,
+            dir = mount_point
+        );
+        if rebuild_aof {
+            script.push_str(&format!(
+                r#"
+{server} --dir {dir} --port 0 --unixsocket /tmp/restore.sock --appendonly no --daemonize yes
+until {cli} -s /tmp/restore.sock PING 2>/dev/null | grep -q PONG; do sleep 0.2; done
+{cli} -s /tmp/restore.sock CONFIG SET appendonly yes
+while {cli} -s /tmp/restore.sock INFO persistence | grep -q 'aof_rewrite_in_progress:1'; do sleep 0.2; done
+{cli} -s /tmp/restore.sock SHUTDOWN SAVE || true"#,
+                server = server,
+                cli = cli,
+                dir = mount_point
+            ));
+        }
+
+        let output = self
+            .run_oneoff_container(name, image, binds, &script)
+            .await?;
+
+        if output.exit_code != Some(0) {
+            return Err(AppError::Docker(format!(
+                "Loading snapshot failed with exit code {:?}: {}",
+                output.exit_code,
+                output.stderr.trim()
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// Hands the WAL archive directory to the image's postgres user. This runs before the
+    /// database container starts so the first `archive_command` can already write to it.
+    pub async fn prepare_wal_archive(
+        &self,
+        name: &str,
+        image: &str,
+        archive_path: &str,
+    ) -> AppResult<()> {
+        let binds = vec![format!("{}:{}", archive_path, WAL_ARCHIVE_MOUNT_POINT)];
+        let script = format!("chown postgres:postgres {}", WAL_ARCHIVE_MOUNT_POINT);
+        let output = self
+            .run_oneoff_container(name, image, binds, &script)
+            .await?;
+
+        if output.exit_code != Some(0) {
+            return Err(AppError::Docker(format!(
+                "Failed to prepare WAL archive: {}",
+                output.stderr.trim()
+            )));
+        }
+
+        Ok(())
+    }
+
     pub fn docker(&self) -> &Docker {
         &self.docker
     }
@@ -699,6 +1359,33 @@ impl DockerManager {
         source_password: &str,
         target_username: &str,
         target_password: &str,
+    ) -> AppResult<()> {
+        self.fork_database_with(
+            source_container,
+            target_container,
+            source_username,
+            source_password,
+            target_username,
+            target_password,
+            &[],
+            None,
+        )
+        .await
+    }
+
+    /// Like `fork_database`, passing extra arguments such as `--schema-only` to `pg_dump` and
+    /// reporting how far the dump got to `progress`.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn fork_database_with(
+        &self,
+        source_container: &str,
+        target_container: &str,
+        source_username: &str,
+        source_password: &str,
+        target_username: &str,
+        target_password: &str,
+        dump_args: &[&str],
+        progress: Option<&ForkProgress>,
     ) -> AppResult<()> {
         tracing::info!(
             "Forking database from {} to {}",
@@ -706,10 +1393,14 @@ impl DockerManager {
             target_container
         );
 
-        let dump_cmd = format!(
+        let mut dump_cmd = format!(
             "PGPASSWORD='{}' PGCONNECT_TIMEOUT=10 pg_dump -h {} -U {} -d postgres -Fc",
             source_password, source_container, source_username
         );
+        for arg in dump_args {
+            dump_cmd.push(' ');
+            dump_cmd.push_str(arg);
+        }
 
         let restore_cmd = format!(
             "PGPASSWORD='{}' PGCONNECT_TIMEOUT=10 pg_restore -h localhost -U {} -d postgres \
@@ -717,7 +1408,10 @@ impl DockerManager {
             target_password, target_username
         );
 
-        let full_cmd = format!("{} | {}", dump_cmd, restore_cmd);
+        let full_cmd = match progress {
+            Some(_) => format!("{} | dd bs=1M status=progress | {}", dump_cmd, restore_cmd),
+            None => format!("{} | {}", dump_cmd, restore_cmd),
+        };
 
         let exec_config = ExecConfig {
             attach_stdin: Some(false),
@@ -740,6 +1434,7 @@ impl DockerManager {
             .await
             .map_err(|e| AppError::Docker(format!("Failed to start fork exec: {}", e)))?;
 
+        let mut last_error = String::new();
         if let bollard::exec::StartExecResults::Attached { mut output, .. } = output {
             while let Some(result) = output.next().await {
                 match result {
@@ -755,6 +1450,12 @@ impl DockerManager {
                         };
                         if !msg.is_empty() {
                             tracing::debug!("Fork output: {}", msg.trim());
+                            if let Some(progress) = progress {
+                                progress.record(&msg);
+                            }
+                            if let Some(line) = msg.lines().rev().find(|l| l.contains("error")) {
+                                last_error = line.trim().to_string();
+                            }
                         }
                     },
                     Err(e) => {
@@ -772,14 +1473,295 @@ impl DockerManager {
 
         if let Some(exit_code) = exec_inspect.exit_code {
             if exit_code != 0 {
+                let mut message = format!("Fork failed with exit code {}", exit_code);
+                if !last_error.is_empty() {
+                    message.push_str(&format!(": {}", last_error));
+                }
+                return Err(AppError::Docker(message));
+            }
+        }
+
+        tracing::info!("Database fork completed successfully");
+        Ok(())
+    }
+
+    /// Replaces the user schemas of the target MySQL server with a `mysqldump` of the source's.
+    /// Runs in the target container, which reaches the source over the database network.
+    pub async fn fork_mysql_database(
+        &self,
+        source_container: &str,
+        target_container: &str,
+        source_password: &str,
+        target_password: &str,
+        tools: MysqlTools,
+        schema_only: bool,
+    ) -> AppResult<()> {
+        tracing::info!(
+            "Forking MySQL database from {} to {}",
+            source_container,
+            target_container
+        );
+
+        let script = format!(
+            "list=\"SELECT schema_name FROM information_schema.schemata WHERE schema_name NOT IN ('mysql', 'information_schema', 'performance_schema', 'sys')\"; \
+             dbs=$(MYSQL_PWD=\"$SOURCE_PASSWORD\" {client} -h {source} -uroot -N -B -e \"$list\") || exit 1; \
+             old=$(MYSQL_PWD=\"$TARGET_PASSWORD\" {client} -h 127.0.0.1 -uroot -N -B -e \"$list\") || exit 1; \
+             for db in $old; do MYSQL_PWD=\"$TARGET_PASSWORD\" {client} -h 127.0.0.1 -uroot -e \"DROP DATABASE \\`$db\\`\" || exit 1; done; \
+             [ -z \"$dbs\" ] && exit 0; \
+             status=$(mktemp) && \
+             {{ MYSQL_PWD=\"$SOURCE_PASSWORD\" {dump} -h {source} -uroot --single-transaction --routines --triggers --events {no_data} --databases $dbs; echo $? > \"$status\"; }} \
+             | MYSQL_PWD=\"$TARGET_PASSWORD\" {client} -h 127.0.0.1 -uroot \
+             && [ \"$(cat \"$status\")\" = 0 ]; rc=$?; rm -f \"$status\"; exit $rc",
+            client = tools.client,
+            dump = tools.dump,
+            source = source_container,
+            no_data = if schema_only { "--no-data" } else { "" },
+        );
+
+        let output = self
+            .run_exec(
+                target_container,
+                vec!["sh".to_string(), "-c".to_string(), script],
+                Some(vec![
+                    format!("SOURCE_PASSWORD={}", source_password),
+                    format!("TARGET_PASSWORD={}", target_password),
+                ]),
+            )
+            .await?;
+
+        if output.exit_code != Some(0) {
+            return Err(AppError::Docker(format!(
+                "MySQL fork failed: {}",
+                output.stderr.trim()
+            )));
+        }
+
+        tracing::info!("MySQL database fork completed successfully");
+        Ok(())
+    }
+
+    /// Replaces the user databases of the target MongoDB server with a `mongodump` archive of
+    /// the source's. Users in `admin` are left alone so the target keeps its own root password.
+    pub async fn fork_mongo_database(
+        &self,
+        source_container: &str,
+        target_container: &str,
+        source_password: &str,
+        target_password: &str,
+    ) -> AppResult<()> {
+        tracing::info!(
+            "Forking MongoDB database from {} to {}",
+            source_container,
+            target_container
+        );
+
+        let drop_databases = "db.getMongo().getDBNames()\
+             .filter(name => !['admin', 'config', 'local'].includes(name))\
+             .forEach(name => db.getSiblingDB(name).dropDatabase())";
+        let script = format!(
+            "auth='-u root --authenticationDatabase admin'; \
+             mongosh --quiet --norc $auth -p \"$TARGET_PASSWORD\" --eval \"{drop}\" || exit 1; \
+             status=$(mktemp) && \
+             {{ mongodump --quiet --host {source} $auth -p \"$SOURCE_PASSWORD\" --archive; echo $? > \"$status\"; }} \
+             | mongorestore --quiet $auth -p \"$TARGET_PASSWORD\" --archive --drop \
+               --nsExclude 'admin.*' --nsExclude 'config.*' --nsExclude 'local.*' \
+             && [ \"$(cat \"$status\")\" = 0 ]; rc=$?; rm -f \"$status\"; exit $rc",
+            drop = drop_databases,
+            source = source_container,
+        );
+
+        let output = self
+            .run_exec(
+                target_container,
+                vec!["sh".to_string(), "-c".to_string(), script],
+                Some(vec![
+                    format!("SOURCE_PASSWORD={}", source_password),
+                    format!("TARGET_PASSWORD={}", target_password),
+                ]),
+            )
+            .await?;
+
+        if output.exit_code != Some(0) {
+            return Err(AppError::Docker(format!(
+                "MongoDB fork failed: {}",
+                output.stderr.trim()
+            )));
+        }
+
+        tracing::info!("MongoDB database fork completed successfully");
+        Ok(())
+    }
+
+    /// Replaces the user databases of the target ClickHouse server with a `BACKUP` of the
+    /// source's. The archive is written to the source's backup disk, copied onto the target's and
+    /// restored there; both copies are removed afterwards.
+    pub async fn fork_clickhouse_database(
+        &self,
+        source_container: &str,
+        target_container: &str,
+        source_password: &str,
+        target_password: &str,
+        schema_only: bool,
+    ) -> AppResult<()> {
+        tracing::info!(
+            "Forking ClickHouse database from {} to {}",
+            source_container,
+            target_container
+        );
+
+        let archive = format!("fork-{}.zip", uuid::Uuid::new_v4());
+        let archive_path = format!("{}{}", CLICKHOUSE_BACKUP_PATH, archive);
+        let system_databases = CLICKHOUSE_SYSTEM_DATABASES.join(", ");
+
+        let backup = format!(
+            "BACKUP ALL EXCEPT DATABASES {} TO Disk('{}', '{}')",
+            system_databases, CLICKHOUSE_BACKUP_DISK, archive
+        );
+        let output = self
+            .run_exec(
+                source_container,
+                ClickhouseContainer::client_command(&backup),
+                Some(ClickhouseContainer::client_env(source_password)),
+            )
+            .await?;
+        if output.exit_code != Some(0) {
+            return Err(AppError::Docker(format!(
+                "ClickHouse backup failed: {}",
+                output.stderr.trim()
+            )));
+        }
+
+        let restored = async {
+            self.copy_between_containers(
+                source_container,
+                target_container,
+                &archive_path,
+                "clickhouse:clickhouse",
+            )
+            .await?;
+
+            // `default` always exists, so only its tables are dropped.
+            let drops = format!(
+                "SELECT format('DROP DATABASE `{{}}` SYNC;', name) FROM system.databases \
+                 WHERE name NOT IN ({system}, 'default') \
+                 UNION ALL SELECT format('DROP TABLE default.`{{}}` SYNC;', name) \
+                 FROM system.tables WHERE database = 'default'",
+                system = CLICKHOUSE_SYSTEM_DATABASES
+                    .iter()
+                    .map(|name| format!("'{}'", name))
+                    .collect::<Vec<_>>()
+                    .join(", "),
+            );
+            let restore = format!(
+                "RESTORE ALL EXCEPT DATABASES {} FROM Disk('{}', '{}'){}",
+                system_databases,
+                CLICKHOUSE_BACKUP_DISK,
+                archive,
+                if schema_only {
+                    " SETTINGS structure_only = 1"
+                } else {
+                    ""
+                }
+            );
+            let script = "client() { clickhouse-client --password \"$TARGET_PASSWORD\" \"$@\"; }; \
+                 drops=$(client --format TSVRaw --query \"$DROPS\") || exit 1; \
+                 if [ -n \"$drops\" ]; then printf '%s\\n' \"$drops\" | client --multiquery || exit 1; fi; \
+                 client --query \"$RESTORE\"";
+
+            let output = self
+                .run_exec(
+                    target_container,
+                    vec!["sh".to_string(), "-c".to_string(), script.to_string()],
+                    Some(vec![
+                        format!("TARGET_PASSWORD={}", target_password),
+                        format!("DROPS={}", drops),
+                        format!("RESTORE={}", restore),
+                    ]),
+                )
+                .await?;
+            if output.exit_code != Some(0) {
                 return Err(AppError::Docker(format!(
-                    "Fork failed with exit code {}",
-                    exit_code
+                    "ClickHouse restore failed: {}",
+                    output.stderr.trim()
                 )));
             }
+            Ok(())
+        }
+        .await;
+
+        for container in [source_container, target_container] {
+            let removed = self
+                .run_exec(
+                    container,
+                    vec!["rm".to_string(), "-f".to_string(), archive_path.clone()],
+                    None,
+                )
+                .await;
+            if let Err(e) = removed {
+                tracing::warn!("Failed to remove backup archive in {}: {}", container, e);
+            }
+        }
+
+        restored?;
+        tracing::info!("ClickHouse database fork completed successfully");
+        Ok(())
+    }
+
+    /// Streams a file from one container to the same path in another, owned by `owner` there.
+    async fn copy_between_containers(
+        &self,
+        source_container: &str,
+        target_container: &str,
+        path: &str,
+        owner: &str,
+    ) -> AppResult<()> {
+        let exec = self
+            .stream_exec(
+                source_container,
+                vec!["cat".to_string(), path.to_string()],
+                None,
+            )
+            .await?;
+        let content = exec.output.filter_map(|chunk| {
+            futures::future::ready(match chunk {
+                Ok(LogOutput::StdOut { message }) => Some(Ok(message)),
+                Ok(_) => None,
+                Err(e) => Some(Err(std::io::Error::other(e.to_string()))),
+            })
+        });
+
+        let written = self
+            .exec_with_input(
+                target_container,
+                vec![
+                    "sh".to_string(),
+                    "-c".to_string(),
+                    "mkdir -p \"$(dirname \"$TARGET\")\" && cat > \"$TARGET\" && chown \"$OWNER\" \"$TARGET\""
+                        .to_string(),
+                ],
+                Some(vec![
+                    format!("TARGET={}", path),
+                    format!("OWNER={}", owner),
+                ]),
+                StreamReader::new(content),
+            )
+            .await?;
+
+        if self.exec_exit_code(&exec.exec_id).await? != Some(0) {
+            return Err(AppError::Docker(format!(
+                "Failed to read {} from {}",
+                path, source_container
+            )));
+        }
+        if written.exit_code != Some(0) {
+            return Err(AppError::Docker(format!(
+                "Failed to write {} to {}: {}",
+                path,
+                target_container,
+                written.stderr.trim()
+            )));
         }
 
-        tracing::info!("Database fork completed successfully");
         Ok(())
     }
 
@@ -790,12 +1772,13 @@ impl DockerManager {
         source_password: &str,
         target_password: &str,
     ) -> AppResult<()> {
-        self.fork_kv_database(
+        self.replicate_kv_database(
             source_container,
             target_container,
             source_password,
             target_password,
             "redis-cli",
+            true,
         )
         .await
     }
@@ -807,23 +1790,27 @@ impl DockerManager {
         source_password: &str,
         target_password: &str,
     ) -> AppResult<()> {
-        self.fork_kv_database(
+        self.replicate_kv_database(
             source_container,
             target_container,
             source_password,
             target_password,
             "valkey-cli",
+            true,
         )
         .await
     }
 
-    async fn fork_kv_database(
+    /// Makes the target a replica of the source and waits for the initial sync. Unless the
+    /// target should keep following the source, it is then promoted to a standalone server.
+    pub async fn replicate_kv_database(
         &self,
         source_container: &str,
         target_container: &str,
         source_password: &str,
         target_password: &str,
         cli_cmd: &str,
+        promote: bool,
     ) -> AppResult<()> {
         tracing::info!(
             "Forking KV database from {} to {} using {}",
@@ -942,6 +1929,28 @@ impl DockerManager {
             tokio::time::sleep(poll_interval).await;
         }
 
+        if !promote {
+            tracing::info!(
+                "KV replica {} is following {}",
+                target_container,
+                source_container
+            );
+            return Ok(());
+        }
+
+        self.promote_kv_replica(target_container, target_password, cli_cmd)
+            .await?;
+
+        tracing::info!("KV database fork completed successfully");
+        Ok(())
+    }
+
+    pub async fn promote_kv_replica(
+        &self,
+        target_container: &str,
+        target_password: &str,
+        cli_cmd: &str,
+    ) -> AppResult<()> {
         let promote_cmd = format!("{} -a '{}' REPLICAOF NO ONE", cli_cmd, target_password);
 
         let exec_config = ExecConfig {
@@ -984,7 +1993,6 @@ impl DockerManager {
             }
         }
 
-        tracing::info!("KV database fork completed successfully");
         Ok(())
     }
 
@@ -1116,3 +2124,21 @@ fn calculate_memory_stats(stats: &bollard::models::ContainerStatsResponse) -> (i
 
     (memory_used, memory_limit, memory_percent)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fork_progress() {
+        let progress = ForkProgress::default();
+        progress.record("1048576 bytes (1.0 MB, 1.0 MiB) copied, 1 s, 1.0 MB/s\r");
+        progress.record("pg_restore: creating TABLE \"public.orders\"\n");
+        assert_eq!(progress.bytes(), 1_048_576);
+        assert!(!progress.dumped());
+
+        progress.record("0+40 records in\n0+40 records out\n3145728 bytes (3.1 MB, 3.0 MiB) copied, 2.1 s, 1.5 MB/s\n");
+        assert_eq!(progress.bytes(), 3_145_728);
+        assert!(progress.dumped());
+    }
+}
@@ -1,2 +1,10 @@
+pub mod backup_scheduler;
+pub mod branch_reaper;
 pub mod docker;
 pub mod metrics_collector;
+pub mod quota_monitor;
+pub mod rollback_cleanup;
+pub mod storage;
+pub mod update_checker;
+pub mod version_catalog;
+pub mod volumes;
@@ -0,0 +1,64 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use tokio::time;
+use tokio_util::sync::CancellationToken;
+
+use crate::domain::services::QuotaService;
+
+pub struct QuotaMonitor {
+    quota_service: Arc<QuotaService>,
+    interval: Duration,
+    cancel_token: CancellationToken,
+}
+
+impl QuotaMonitor {
+    pub fn new(
+        quota_service: Arc<QuotaService>,
+        interval_secs: u64,
+        cancel_token: CancellationToken,
+    ) -> Self {
+        Self {
+            quota_service,
+            interval: Duration::from_secs(interval_secs),
+            cancel_token,
+        }
+    }
+
+    pub async fn run(self) {
+        tracing::info!(
+            "Starting storage quota monitor with {}s interval",
+            self.interval.as_secs()
+        );
+
+        let mut interval = time::interval(self.interval);
+
+        loop {
+            tokio::select! {
+                _ = self.cancel_token.cancelled() => {
+                    tracing::info!("Storage quota monitor shutting down");
+                    break;
+                }
+                _ = interval.tick() => {
+                    match self.quota_service.enforce_all().await {
+                        Ok(0) => {},
+                        Ok(count) => tracing::warn!("{} databases are over their storage limit", count),
+                        Err(e) => tracing::error!("Error enforcing storage limits: {}", e),
+                    }
+                }
+            }
+        }
+    }
+}
+
+pub fn spawn_quota_monitor(
+    quota_service: Arc<QuotaService>,
+    interval_secs: u64,
+    cancel_token: CancellationToken,
+) -> tokio::task::JoinHandle<()> {
+    let monitor = QuotaMonitor::new(quota_service, interval_secs, cancel_token);
+
+    tokio::spawn(async move {
+        monitor.run().await;
+    })
+}
@@ -0,0 +1,64 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use tokio::time;
+use tokio_util::sync::CancellationToken;
+
+use crate::domain::services::UpgradeService;
+
+pub struct RollbackCleanup {
+    upgrade_service: Arc<UpgradeService>,
+    interval: Duration,
+    cancel_token: CancellationToken,
+}
+
+impl RollbackCleanup {
+    pub fn new(
+        upgrade_service: Arc<UpgradeService>,
+        interval_secs: u64,
+        cancel_token: CancellationToken,
+    ) -> Self {
+        Self {
+            upgrade_service,
+            interval: Duration::from_secs(interval_secs),
+            cancel_token,
+        }
+    }
+
+    pub async fn run(self) {
+        tracing::info!(
+            "Starting upgrade rollback cleanup with {}s interval",
+            self.interval.as_secs()
+        );
+
+        let mut interval = time::interval(self.interval);
+
+        loop {
+            tokio::select! {
+                _ = self.cancel_token.cancelled() => {
+                    tracing::info!("Upgrade rollback cleanup shutting down");
+                    break;
+                }
+                _ = interval.tick() => {
+                    match self.upgrade_service.prune_expired_rollbacks().await {
+                        Ok(0) => {},
+                        Ok(count) => tracing::info!("Removed {} expired upgrade rollbacks", count),
+                        Err(e) => tracing::error!("Error removing expired upgrade rollbacks: {}", e),
+                    }
+                }
+            }
+        }
+    }
+}
+
+pub fn spawn_rollback_cleanup(
+    upgrade_service: Arc<UpgradeService>,
+    interval_secs: u64,
+    cancel_token: CancellationToken,
+) -> tokio::task::JoinHandle<()> {
+    let cleanup = RollbackCleanup::new(upgrade_service, interval_secs, cancel_token);
+
+    tokio::spawn(async move {
+        cleanup.run().await;
+    })
+}
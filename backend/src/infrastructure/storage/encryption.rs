@@ -0,0 +1,258 @@
+use std::path::Path;
+
+use aes_gcm::{
+    aead::{Aead, KeyInit, Payload},
+    Aes256Gcm, Nonce,
+};
+use hkdf::Hkdf;
+use rand::RngCore;
+use sha2::Sha256;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt, BufReader, BufWriter};
+
+use crate::error::{AppError, AppResult};
+
+const MAGIC: &[u8; 8] = b"DATIFYE1";
+const CHUNK_SIZE: usize = 1024 * 1024;
+const NONCE_SIZE: usize = 12;
+const TAG_SIZE: usize = 16;
+const KEY_INFO: &[u8] = b"datify-backup-encryption";
+
+pub const ENCRYPTED_EXTENSION: &str = "enc";
+
+/// Derives the backup encryption key so backups never share a key with stored passwords.
+pub fn derive_backup_key(master_key: &[u8; 32]) -> [u8; 32] {
+    let mut key = [0u8; 32];
+    Hkdf::<Sha256>::new(None, master_key)
+        .expand(KEY_INFO, &mut key)
+        .expect("32 bytes is a valid HKDF output length");
+    key
+}
+
+pub async fn encrypt_file(key: &[u8; 32], source: &Path, destination: &Path) -> AppResult<()> {
+    let input = tokio::fs::File::open(source)
+        .await
+        .map_err(|e| AppError::Internal(format!("Failed to open backup file: {}", e)))?;
+    let total_len = input
+        .metadata()
+        .await
+        .map_err(|e| AppError::Internal(format!("Failed to read backup file: {}", e)))?
+        .len();
+    let output = tokio::fs::File::create(destination)
+        .await
+        .map_err(|e| AppError::Internal(format!("Failed to create encrypted file: {}", e)))?;
+
+    let mut writer = BufWriter::new(output);
+    encrypt_stream(key, BufReader::new(input), &mut writer, total_len).await?;
+    writer
+        .into_inner()
+        .sync_all()
+        .await
+        .map_err(|e| AppError::Internal(format!("Failed to flush encrypted file: {}", e)))
+}
+
+pub async fn decrypt_file(key: &[u8; 32], source: &Path, destination: &Path) -> AppResult<()> {
+    let input = tokio::fs::File::open(source)
+        .await
+        .map_err(|e| AppError::Internal(format!("Failed to open encrypted file: {}", e)))?;
+    let output = tokio::fs::File::create(destination)
+        .await
+        .map_err(|e| AppError::Internal(format!("Failed to create backup file: {}", e)))?;
+
+    let mut writer = BufWriter::new(output);
+    decrypt_stream(key, BufReader::new(input), &mut writer).await?;
+    writer
+        .into_inner()
+        .sync_all()
+        .await
+        .map_err(|e| AppError::Internal(format!("Failed to flush backup file: {}", e)))
+}
+
+/// Each chunk is sealed separately; its index and a final-chunk flag are authenticated so
+/// chunks cannot be reordered, dropped or truncated.
+async fn encrypt_stream<R, W>(
+    key: &[u8; 32],
+    mut reader: R,
+    writer: &mut W,
+    total_len: u64,
+) -> AppResult<()>
+where
+    R: AsyncRead + Unpin,
+    W: AsyncWrite + Unpin,
+{
+    let cipher = Aes256Gcm::new(key.into());
+    let chunk_count = total_len.div_ceil(CHUNK_SIZE as u64).max(1);
+    let mut buf = vec![0u8; CHUNK_SIZE];
+
+    write_all(writer, MAGIC).await?;
+
+    for index in 0..chunk_count {
+        let len = read_full(&mut reader, &mut buf).await?;
+        let is_final = index + 1 == chunk_count;
+
+        let mut nonce = [0u8; NONCE_SIZE];
+        rand::thread_rng().fill_bytes(&mut nonce);
+        let ciphertext = cipher
+            .encrypt(
+                Nonce::from_slice(&nonce),
+                Payload {
+                    msg: &buf[..len],
+                    aad: &chunk_aad(index, is_final),
+                },
+            )
+            .map_err(|e| AppError::Internal(format!("Backup encryption failed: {}", e)))?;
+
+        write_all(writer, &(ciphertext.len() as u32).to_be_bytes()).await?;
+        write_all(writer, &nonce).await?;
+        write_all(writer, &ciphertext).await?;
+    }
+
+    writer
+        .flush()
+        .await
+        .map_err(|e| AppError::Internal(format!("Failed to write encrypted data: {}", e)))
+}
+
+async fn decrypt_stream<R, W>(key: &[u8; 32], mut reader: R, writer: &mut W) -> AppResult<()>
+where
+    R: AsyncRead + Unpin,
+    W: AsyncWrite + Unpin,
+{
+    let cipher = Aes256Gcm::new(key.into());
+
+    let mut magic = [0u8; MAGIC.len()];
+    if read_full(&mut reader, &mut magic).await? != MAGIC.len() || &magic != MAGIC {
+        return Err(AppError::Internal(
+            "Backup file is not in the encrypted format".to_string(),
+        ));
+    }
+
+    let mut next_len = read_chunk_len(&mut reader).await?;
+    let mut index = 0u64;
+
+    while let Some(len) = next_len {
+        if len > CHUNK_SIZE + TAG_SIZE {
+            return Err(AppError::Internal(
+                "Encrypted backup chunk is too large".to_string(),
+            ));
+        }
+
+        let mut nonce = [0u8; NONCE_SIZE];
+        let mut ciphertext = vec![0u8; len];
+        if read_full(&mut reader, &mut nonce).await? != NONCE_SIZE
+            || read_full(&mut reader, &mut ciphertext).await? != len
+        {
+            return Err(AppError::Internal(
+                "Encrypted backup is truncated".to_string(),
+            ));
+        }
+
+        next_len = read_chunk_len(&mut reader).await?;
+        let plaintext = cipher
+            .decrypt(
+                Nonce::from_slice(&nonce),
+                Payload {
+                    msg: &ciphertext,
+                    aad: &chunk_aad(index, next_len.is_none()),
+                },
+            )
+            .map_err(|_| {
+                AppError::Internal("Encrypted backup failed authentication".to_string())
+            })?;
+
+        write_all(writer, &plaintext).await?;
+        index += 1;
+    }
+
+    if index == 0 {
+        return Err(AppError::Internal(
+            "Encrypted backup is truncated".to_string(),
+        ));
+    }
+
+    writer
+        .flush()
+        .await
+        .map_err(|e| AppError::Internal(format!("Failed to write backup data: {}", e)))
+}
+
+fn chunk_aad(index: u64, is_final: bool) -> [u8; 9] {
+    let mut aad = [0u8; 9];
+    aad[..8].copy_from_slice(&index.to_be_bytes());
+    aad[8] = is_final as u8;
+    aad
+}
+
+async fn read_chunk_len<R: AsyncRead + Unpin>(reader: &mut R) -> AppResult<Option<usize>> {
+    let mut len = [0u8; 4];
+    match read_full(reader, &mut len).await? {
+        0 => Ok(None),
+        4 => Ok(Some(u32::from_be_bytes(len) as usize)),
+        _ => Err(AppError::Internal(
+            "Encrypted backup is truncated".to_string(),
+        )),
+    }
+}
+
+async fn read_full<R: AsyncRead + Unpin>(reader: &mut R, buf: &mut [u8]) -> AppResult<usize> {
+    let mut filled = 0;
+    while filled < buf.len() {
+        let read = reader
+            .read(&mut buf[filled..])
+            .await
+            .map_err(|e| AppError::Internal(format!("Failed to read backup data: {}", e)))?;
+        if read == 0 {
+            break;
+        }
+        filled += read;
+    }
+    Ok(filled)
+}
+
+async fn write_all<W: AsyncWrite + Unpin>(writer: &mut W, data: &[u8]) -> AppResult<()> {
+    writer
+        .write_all(data)
+        .await
+        .map_err(|e| AppError::Internal(format!("Failed to write backup data: {}", e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn encrypt(key: &[u8; 32], data: &[u8]) -> Vec<u8> {
+        let mut out = Vec::new();
+        encrypt_stream(key, data, &mut out, data.len() as u64)
+            .await
+            .unwrap();
+        out
+    }
+
+    async fn decrypt(key: &[u8; 32], data: &[u8]) -> AppResult<Vec<u8>> {
+        let mut out = Vec::new();
+        decrypt_stream(key, data, &mut out).await?;
+        Ok(out)
+    }
+
+    #[tokio::test]
+    async fn test_round_trip_across_chunks() {
+        let key = derive_backup_key(&[7u8; 32]);
+        for len in [0, 1, CHUNK_SIZE, CHUNK_SIZE * 2 + 17] {
+            let data: Vec<u8> = (0..len).map(|i| (i % 251) as u8).collect();
+            let encrypted = encrypt(&key, &data).await;
+            assert_eq!(decrypt(&key, &encrypted).await.unwrap(), data);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_rejects_wrong_key_and_truncation() {
+        let key = derive_backup_key(&[7u8; 32]);
+        let data = vec![42u8; CHUNK_SIZE + 10];
+        let encrypted = encrypt(&key, &data).await;
+
+        let other_key = derive_backup_key(&[8u8; 32]);
+        assert!(decrypt(&other_key, &encrypted).await.is_err());
+
+        let first_chunk_end = MAGIC.len() + 4 + NONCE_SIZE + CHUNK_SIZE + TAG_SIZE;
+        assert!(decrypt(&key, &encrypted[..first_chunk_end]).await.is_err());
+    }
+}
@@ -0,0 +1,64 @@
+use std::path::{Path, PathBuf};
+
+use async_trait::async_trait;
+
+use super::BackupStorage;
+use crate::error::{AppError, AppResult};
+
+pub struct LocalStorage {
+    root: PathBuf,
+}
+
+impl LocalStorage {
+    pub fn new(root: &str) -> Self {
+        Self {
+            root: PathBuf::from(root),
+        }
+    }
+}
+
+#[async_trait]
+impl BackupStorage for LocalStorage {
+    fn kind(&self) -> &'static str {
+        "local"
+    }
+
+    async fn store(&self, key: &str, source: &Path) -> AppResult<String> {
+        let destination = self.root.join(key);
+        if let Some(parent) = destination.parent() {
+            tokio::fs::create_dir_all(parent).await.map_err(|e| {
+                AppError::Internal(format!("Failed to create backup directory: {}", e))
+            })?;
+        }
+
+        if destination != source {
+            tokio::fs::rename(source, &destination)
+                .await
+                .map_err(|e| AppError::Internal(format!("Failed to store backup file: {}", e)))?;
+        }
+
+        Ok(destination.to_string_lossy().into_owned())
+    }
+
+    async fn fetch(&self, location: &str, destination: &Path) -> AppResult<()> {
+        tokio::fs::copy(location, destination).await.map_err(|e| {
+            AppError::Internal(format!("Failed to read backup file {}: {}", location, e))
+        })?;
+        Ok(())
+    }
+
+    async fn remove(&self, location: &str) -> AppResult<()> {
+        match tokio::fs::remove_file(location).await {
+            Ok(()) => Ok(()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+            Err(e) => Err(AppError::Internal(format!(
+                "Failed to remove backup file {}: {}",
+                location, e
+            ))),
+        }
+    }
+
+    fn local_path(&self, location: &str) -> Option<PathBuf> {
+        Some(PathBuf::from(location))
+    }
+}
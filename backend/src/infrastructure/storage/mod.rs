@@ -0,0 +1,90 @@
+mod encryption;
+mod local;
+mod s3;
+
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+
+pub use encryption::*;
+pub use local::*;
+pub use s3::*;
+
+use crate::error::{AppError, AppResult};
+
+pub const BACKUP_STORAGE_SETTINGS_KEY: &str = "backup_storage";
+
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+/// Longest pause in a response before a transfer is abandoned.
+const READ_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// HTTP client for remote storage targets. Downloads can be large, so only stalls are timed
+/// out here; requests with a bounded body set their own overall timeout.
+pub fn storage_http_client() -> reqwest::Client {
+    reqwest::Client::builder()
+        .connect_timeout(CONNECT_TIMEOUT)
+        .read_timeout(READ_TIMEOUT)
+        .build()
+        .expect("Failed to build storage HTTP client")
+}
+
+#[async_trait]
+pub trait BackupStorage: Send + Sync {
+    fn kind(&self) -> &'static str;
+
+    /// Moves the finished file at `source` into storage under `key` and returns its location.
+    async fn store(&self, key: &str, source: &Path) -> AppResult<String>;
+
+    async fn fetch(&self, location: &str, destination: &Path) -> AppResult<()>;
+
+    async fn remove(&self, location: &str) -> AppResult<()>;
+
+    /// Path of a stored file that can be read in place, without fetching a copy.
+    fn local_path(&self, _location: &str) -> Option<PathBuf> {
+        None
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum StorageTarget {
+    #[default]
+    Local,
+    S3(S3Config),
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct BackupStorageConfig {
+    #[serde(flatten)]
+    pub target: StorageTarget,
+    #[serde(default)]
+    pub encrypt: bool,
+}
+
+impl BackupStorageConfig {
+    pub fn from_project_settings(settings: &JsonValue) -> AppResult<Self> {
+        match settings.get(BACKUP_STORAGE_SETTINGS_KEY) {
+            None | Some(JsonValue::Null) => Ok(Self::default()),
+            Some(value) => serde_json::from_value(value.clone()).map_err(|e| {
+                AppError::Validation(format!("Invalid backup storage settings: {}", e))
+            }),
+        }
+    }
+
+    pub fn build(&self, local_root: &str, http: &reqwest::Client) -> Arc<dyn BackupStorage> {
+        self.target.build(local_root, http)
+    }
+}
+
+impl StorageTarget {
+    pub fn build(&self, local_root: &str, http: &reqwest::Client) -> Arc<dyn BackupStorage> {
+        match self {
+            Self::Local => Arc::new(LocalStorage::new(local_root)),
+            Self::S3(config) => Arc::new(S3Storage::new(config.clone(), http.clone())),
+        }
+    }
+}
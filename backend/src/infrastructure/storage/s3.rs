@@ -0,0 +1,553 @@
+use std::path::Path;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use chrono::Utc;
+use hmac::{Hmac, Mac};
+use reqwest::{Method, StatusCode, Url};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+use super::BackupStorage;
+use crate::error::{AppError, AppResult};
+
+/// Parts must be at least 5 MiB for S3, except for the last one.
+const MIN_PART_SIZE: u64 = 8 * 1024 * 1024;
+/// S3 accepts at most this many parts per upload.
+const MAX_PARTS: u64 = 10_000;
+/// Overall limit for requests other than downloads, which are bounded by the read timeout.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(300);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct S3Config {
+    pub endpoint: String,
+    #[serde(default = "default_region")]
+    pub region: String,
+    pub bucket: String,
+    #[serde(default)]
+    pub prefix: String,
+    pub access_key_id: String,
+    pub secret_access_key: String,
+    #[serde(default = "default_path_style")]
+    pub path_style: bool,
+}
+
+fn default_region() -> String {
+    "us-east-1".to_string()
+}
+
+fn default_path_style() -> bool {
+    true
+}
+
+pub struct S3Storage {
+    config: S3Config,
+    http: reqwest::Client,
+}
+
+impl S3Storage {
+    pub fn new(config: S3Config, http: reqwest::Client) -> Self {
+        Self { config, http }
+    }
+
+    fn object_key(&self, key: &str) -> String {
+        let prefix = self.config.prefix.trim_matches('/');
+        if prefix.is_empty() {
+            key.to_string()
+        } else {
+            format!("{}/{}", prefix, key)
+        }
+    }
+
+    async fn send(
+        &self,
+        method: Method,
+        key: &str,
+        query: &[(&str, &str)],
+        body: Vec<u8>,
+    ) -> AppResult<reqwest::Response> {
+        let endpoint = Url::parse(&self.config.endpoint).map_err(|e| {
+            AppError::Validation(format!(
+                "Invalid S3 endpoint '{}': {}",
+                self.config.endpoint, e
+            ))
+        })?;
+        let endpoint_host = endpoint
+            .host_str()
+            .ok_or_else(|| AppError::Validation("S3 endpoint has no host".to_string()))?;
+        let host = match endpoint.port() {
+            Some(port) => format!("{}:{}", endpoint_host, port),
+            None => endpoint_host.to_string(),
+        };
+
+        let (host, path) = if self.config.path_style {
+            (
+                host,
+                format!("/{}/{}", self.config.bucket, uri_encode(key, false)),
+            )
+        } else {
+            (
+                format!("{}.{}", self.config.bucket, host),
+                format!("/{}", uri_encode(key, false)),
+            )
+        };
+
+        let mut params: Vec<(String, String)> = query
+            .iter()
+            .map(|(k, v)| (uri_encode(k, true), uri_encode(v, true)))
+            .collect();
+        params.sort();
+        let canonical_query = params
+            .iter()
+            .map(|(k, v)| format!("{}={}", k, v))
+            .collect::<Vec<_>>()
+            .join("&");
+
+        let now = Utc::now();
+        let amz_date = now.format("%Y%m%dT%H%M%SZ").to_string();
+        let payload_hash = hex::encode(Sha256::digest(&body));
+
+        let authorization = sign_request(
+            &self.config,
+            method.as_str(),
+            &path,
+            &canonical_query,
+            &host,
+            &amz_date,
+            &payload_hash,
+        );
+
+        let mut url = format!("{}://{}{}", endpoint.scheme(), host, path);
+        if !canonical_query.is_empty() {
+            url.push('?');
+            url.push_str(&canonical_query);
+        }
+
+        let mut request = self
+            .http
+            .request(method.clone(), &url)
+            .header("host", &host)
+            .header("x-amz-date", &amz_date)
+            .header("x-amz-content-sha256", &payload_hash)
+            .header("authorization", authorization)
+            .body(body);
+        if method != Method::GET {
+            request = request.timeout(REQUEST_TIMEOUT);
+        }
+        let response = request
+            .send()
+            .await
+            .map_err(|e| AppError::ExternalService(format!("S3 request failed: {}", e)))?;
+
+        let status = response.status();
+        let already_deleted = method == Method::DELETE && status == StatusCode::NOT_FOUND;
+        if !status.is_success() && !already_deleted {
+            let message = response.text().await.unwrap_or_default();
+            return Err(AppError::ExternalService(format!(
+                "S3 {} {} failed with {}: {}",
+                method,
+                key,
+                status,
+                message.trim()
+            )));
+        }
+
+        Ok(response)
+    }
+
+    async fn upload_multipart(
+        &self,
+        key: &str,
+        file: &mut tokio::fs::File,
+        part_size: u64,
+    ) -> AppResult<()> {
+        let response = self
+            .send(Method::POST, key, &[("uploads", "")], Vec::new())
+            .await?;
+        let body = response
+            .text()
+            .await
+            .map_err(|e| AppError::ExternalService(format!("S3 request failed: {}", e)))?;
+        let upload_id = xml_value(&body, "UploadId").ok_or_else(|| {
+            AppError::ExternalService("S3 did not return a multipart upload id".to_string())
+        })?;
+
+        match self.upload_parts(key, &upload_id, file, part_size).await {
+            Ok(()) => Ok(()),
+            Err(e) => {
+                let _ = self
+                    .send(Method::DELETE, key, &[("uploadId", &upload_id)], Vec::new())
+                    .await;
+                Err(e)
+            },
+        }
+    }
+
+    async fn upload_parts(
+        &self,
+        key: &str,
+        upload_id: &str,
+        file: &mut tokio::fs::File,
+        part_size: u64,
+    ) -> AppResult<()> {
+        let mut etags = Vec::new();
+
+        loop {
+            let part = read_part(file, part_size).await?;
+            if part.is_empty() {
+                break;
+            }
+
+            let part_number = (etags.len() + 1).to_string();
+            let response = self
+                .send(
+                    Method::PUT,
+                    key,
+                    &[("partNumber", &part_number), ("uploadId", upload_id)],
+                    part,
+                )
+                .await?;
+            let etag = response
+                .headers()
+                .get("etag")
+                .and_then(|v| v.to_str().ok())
+                .ok_or_else(|| {
+                    AppError::ExternalService("S3 did not return a part ETag".to_string())
+                })?
+                .to_string();
+            etags.push(etag);
+        }
+
+        let parts: String = etags
+            .iter()
+            .enumerate()
+            .map(|(i, etag)| {
+                format!(
+                    "<Part><PartNumber>{}</PartNumber><ETag>{}</ETag></Part>",
+                    i + 1,
+                    etag
+                )
+            })
+            .collect();
+        let body = format!(
+            "<CompleteMultipartUpload>{}</CompleteMultipartUpload>",
+            parts
+        );
+
+        let response = self
+            .send(
+                Method::POST,
+                key,
+                &[("uploadId", upload_id)],
+                body.into_bytes(),
+            )
+            .await?;
+
+        // S3 can report a failed completion inside a 200 response.
+        let body = response.text().await.unwrap_or_default();
+        if xml_root(&body) == Some("Error") {
+            return Err(AppError::ExternalService(format!(
+                "S3 multipart upload failed: {}",
+                xml_value(&body, "Message").unwrap_or(body)
+            )));
+        }
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl BackupStorage for S3Storage {
+    fn kind(&self) -> &'static str {
+        "s3"
+    }
+
+    async fn store(&self, key: &str, source: &Path) -> AppResult<String> {
+        let object_key = self.object_key(key);
+        let mut file = tokio::fs::File::open(source)
+            .await
+            .map_err(|e| AppError::Internal(format!("Failed to open backup file: {}", e)))?;
+        let size = file
+            .metadata()
+            .await
+            .map_err(|e| AppError::Internal(format!("Failed to read backup file: {}", e)))?
+            .len();
+
+        let part_size = part_size(size);
+        if size <= part_size {
+            let body = read_part(&mut file, part_size).await?;
+            self.send(Method::PUT, &object_key, &[], body).await?;
+        } else {
+            self.upload_multipart(&object_key, &mut file, part_size)
+                .await?;
+        }
+
+        drop(file);
+        let _ = tokio::fs::remove_file(source).await;
+
+        Ok(object_key)
+    }
+
+    async fn fetch(&self, location: &str, destination: &Path) -> AppResult<()> {
+        let mut response = self.send(Method::GET, location, &[], Vec::new()).await?;
+        let mut file = tokio::fs::File::create(destination)
+            .await
+            .map_err(|e| AppError::Internal(format!("Failed to create backup file: {}", e)))?;
+
+        while let Some(chunk) = response
+            .chunk()
+            .await
+            .map_err(|e| AppError::ExternalService(format!("S3 download failed: {}", e)))?
+        {
+            file.write_all(&chunk)
+                .await
+                .map_err(|e| AppError::Internal(format!("Failed to write backup file: {}", e)))?;
+        }
+
+        file.sync_all()
+            .await
+            .map_err(|e| AppError::Internal(format!("Failed to flush backup file: {}", e)))
+    }
+
+    async fn remove(&self, location: &str) -> AppResult<()> {
+        self.send(Method::DELETE, location, &[], Vec::new())
+            .await
+            .map(|_| ())
+    }
+}
+
+/// Size of the parts a file is uploaded in, grown for large files to stay within the part limit.
+fn part_size(size: u64) -> u64 {
+    MIN_PART_SIZE.max(size.div_ceil(MAX_PARTS))
+}
+
+async fn read_part(file: &mut tokio::fs::File, part_size: u64) -> AppResult<Vec<u8>> {
+    let mut part = Vec::with_capacity(part_size as usize);
+    (&mut *file)
+        .take(part_size)
+        .read_to_end(&mut part)
+        .await
+        .map_err(|e| AppError::Internal(format!("Failed to read backup file: {}", e)))?;
+    Ok(part)
+}
+
+fn sign_request(
+    config: &S3Config,
+    method: &str,
+    path: &str,
+    canonical_query: &str,
+    host: &str,
+    amz_date: &str,
+    payload_hash: &str,
+) -> String {
+    let date = &amz_date[..8];
+    let scope = format!("{}/{}/s3/aws4_request", date, config.region);
+    let signed_headers = "host;x-amz-content-sha256;x-amz-date";
+
+    let canonical_request = format!(
+        "{}\n{}\n{}\nhost:{}\nx-amz-content-sha256:{}\nx-amz-date:{}\n\n{}\n{}",
+        method, path, canonical_query, host, payload_hash, amz_date, signed_headers, payload_hash
+    );
+    let string_to_sign = format!(
+        "AWS4-HMAC-SHA256\n{}\n{}\n{}",
+        amz_date,
+        scope,
+        hex::encode(Sha256::digest(canonical_request.as_bytes()))
+    );
+
+    let key = signing_key(&config.secret_access_key, date, &config.region, "s3");
+    let signature = hex::encode(hmac_sha256(&key, string_to_sign.as_bytes()));
+
+    format!(
+        "AWS4-HMAC-SHA256 Credential={}/{}, SignedHeaders={}, Signature={}",
+        config.access_key_id, scope, signed_headers, signature
+    )
+}
+
+fn signing_key(secret: &str, date: &str, region: &str, service: &str) -> Vec<u8> {
+    let key = hmac_sha256(format!("AWS4{}", secret).as_bytes(), date.as_bytes());
+    let key = hmac_sha256(&key, region.as_bytes());
+    let key = hmac_sha256(&key, service.as_bytes());
+    hmac_sha256(&key, b"aws4_request")
+}
+
+fn hmac_sha256(key: &[u8], data: &[u8]) -> Vec<u8> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC accepts keys of any length");
+    mac.update(data);
+    mac.finalize().into_bytes().to_vec()
+}
+
+fn uri_encode(value: &str, encode_slash: bool) -> String {
+    let mut encoded = String::with_capacity(value.len());
+    for byte in value.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                encoded.push(byte as char)
+            },
+            b'/' if !encode_slash => encoded.push('/'),
+            _ => encoded.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    encoded
+}
+
+/// Start tags of an XML document in order, as the tag name and what follows it. Declarations,
+/// comments, CDATA sections and end tags are skipped.
+fn xml_start_tags(body: &str) -> impl Iterator<Item = (&str, &str)> {
+    let mut rest = body;
+    std::iter::from_fn(move || loop {
+        let open = rest.find('<')?;
+        rest = &rest[open + 1..];
+        let skip_to = if rest.starts_with("!--") {
+            "-->"
+        } else if rest.starts_with("![CDATA[") {
+            "]]>"
+        } else {
+            ">"
+        };
+        let close = rest.find(skip_to)?;
+        let tag = &rest[..close];
+        rest = &rest[close + skip_to.len()..];
+        if skip_to != ">" || tag.starts_with(['/', '?', '!']) {
+            continue;
+        }
+        let name = tag
+            .trim_end_matches('/')
+            .split(|c: char| c.is_ascii_whitespace())
+            .next()
+            .unwrap_or_default();
+        return Some((name, rest));
+    })
+}
+
+/// Local name of the document's root element, without a namespace prefix.
+fn xml_root(body: &str) -> Option<&str> {
+    let (name, _) = xml_start_tags(body).next()?;
+    name.rsplit(':').next()
+}
+
+/// Text of the first element named `tag`, matched by local name so namespace prefixes and
+/// attributes do not matter, with entities and CDATA sections decoded.
+fn xml_value(body: &str, tag: &str) -> Option<String> {
+    let (name, content) =
+        xml_start_tags(body).find(|(name, _)| name.rsplit(':').next() == Some(tag))?;
+    let end = content.find(&format!("</{}", name))?;
+    Some(xml_text(&content[..end]))
+}
+
+fn xml_text(raw: &str) -> String {
+    let mut text = String::with_capacity(raw.len());
+    let mut rest = raw;
+    while let Some(start) = rest.find(['&', '<']) {
+        text.push_str(&rest[..start]);
+        rest = &rest[start..];
+        if let Some(cdata) = rest.strip_prefix("<![CDATA[") {
+            let end = cdata.find("]]>").unwrap_or(cdata.len());
+            text.push_str(&cdata[..end]);
+            rest = cdata.get(end + 3..).unwrap_or_default();
+            continue;
+        }
+        let Some(end) = rest.find(';').filter(|_| rest.starts_with('&')) else {
+            text.push_str(&rest[..1]);
+            rest = &rest[1..];
+            continue;
+        };
+        let entity = &rest[1..end];
+        let decoded = match entity {
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "amp" => Some('&'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            _ => entity
+                .strip_prefix("#x")
+                .map(|hex| u32::from_str_radix(hex, 16).ok())
+                .unwrap_or_else(|| entity.strip_prefix('#').and_then(|dec| dec.parse().ok()))
+                .and_then(char::from_u32),
+        };
+        match decoded {
+            Some(c) => {
+                text.push(c);
+                rest = &rest[end + 1..];
+            },
+            None => {
+                text.push('&');
+                rest = &rest[1..];
+            },
+        }
+    }
+    text.push_str(rest);
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_signing_key_matches_aws_example() {
+        let key = signing_key(
+            "wJalrXUtnFEMI/K7MDENG+bPxRfiCYEXAMPLEKEY",
+            "20120215",
+            "us-east-1",
+            "iam",
+        );
+        assert_eq!(
+            hex::encode(key),
+            "f4780e2d9f65fa895f9c67b32ce1baf0b0d8a43505a000a1a9e090d414db404d"
+        );
+    }
+
+    #[test]
+    fn test_uri_encode_and_xml_value() {
+        assert_eq!(
+            uri_encode("backups/db 1/a+b.dump", false),
+            "backups/db%201/a%2Bb.dump"
+        );
+        assert_eq!(uri_encode("a/b", true), "a%2Fb");
+        assert_eq!(
+            xml_value(
+                "<InitiateMultipartUploadResult><UploadId>abc-123</UploadId></InitiateMultipartUploadResult>",
+                "UploadId"
+            ),
+            Some("abc-123".to_string())
+        );
+        assert_eq!(
+            xml_value(
+                "<?xml version=\"1.0\"?><s3:Result xmlns:s3=\"x\"><s3:UploadId id=\"1\">a&amp;b&#x2F;c</s3:UploadId></s3:Result>",
+                "UploadId"
+            ),
+            Some("a&b/c".to_string())
+        );
+        assert_eq!(
+            xml_value(
+                "<Error><Message><![CDATA[<bad> & worse]]></Message></Error>",
+                "Message"
+            ),
+            Some("<bad> & worse".to_string())
+        );
+        assert_eq!(
+            xml_value("<UploadIdList>x</UploadIdList>", "UploadId"),
+            None
+        );
+
+        assert_eq!(
+            xml_root("<?xml version=\"1.0\"?>\n<!-- <Error> --><CompleteMultipartUploadResult><Error>no</Error></CompleteMultipartUploadResult>"),
+            Some("CompleteMultipartUploadResult")
+        );
+        assert_eq!(
+            xml_root("<Error><Code>InternalError</Code></Error>"),
+            Some("Error")
+        );
+    }
+
+    #[test]
+    fn test_part_size() {
+        let mib = 1024 * 1024;
+        assert_eq!(part_size(0), 8 * mib);
+        assert_eq!(part_size(50_000 * mib), 8 * mib);
+        assert_eq!(part_size(100_000 * mib), 10 * mib);
+        assert_eq!(part_size(100_000 * mib + 1), 10 * mib + 1);
+    }
+}
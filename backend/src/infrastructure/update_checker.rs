@@ -0,0 +1,64 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use tokio::time;
+use tokio_util::sync::CancellationToken;
+
+use crate::domain::services::UpdateService;
+
+pub struct UpdateChecker {
+    update_service: Arc<UpdateService>,
+    interval: Duration,
+    cancel_token: CancellationToken,
+}
+
+impl UpdateChecker {
+    pub fn new(
+        update_service: Arc<UpdateService>,
+        interval_secs: u64,
+        cancel_token: CancellationToken,
+    ) -> Self {
+        Self {
+            update_service,
+            interval: Duration::from_secs(interval_secs),
+            cancel_token,
+        }
+    }
+
+    pub async fn run(self) {
+        tracing::info!(
+            "Starting update checker with {}s interval",
+            self.interval.as_secs()
+        );
+
+        let mut interval = time::interval(self.interval);
+
+        loop {
+            tokio::select! {
+                _ = self.cancel_token.cancelled() => {
+                    tracing::info!("Update checker shutting down");
+                    break;
+                }
+                _ = interval.tick() => {
+                    match self.update_service.check_all().await {
+                        Ok(0) => {},
+                        Ok(count) => tracing::info!("{} databases have an update available", count),
+                        Err(e) => tracing::error!("Error checking for database updates: {}", e),
+                    }
+                }
+            }
+        }
+    }
+}
+
+pub fn spawn_update_checker(
+    update_service: Arc<UpdateService>,
+    interval_secs: u64,
+    cancel_token: CancellationToken,
+) -> tokio::task::JoinHandle<()> {
+    let checker = UpdateChecker::new(update_service, interval_secs, cancel_token);
+
+    tokio::spawn(async move {
+        checker.run().await;
+    })
+}
@@ -0,0 +1,405 @@
+use std::collections::HashMap;
+use std::future::Future;
+use std::time::{Duration, Instant};
+
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use tokio::sync::RwLock;
+use utoipa::ToSchema;
+
+const CACHE_TTL: Duration = Duration::from_secs(24 * 60 * 60);
+const DOCKER_HUB_TIMEOUT: Duration = Duration::from_secs(10);
+const TAG_DIGEST_TTL: Duration = Duration::from_secs(60 * 60);
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct PostgresVersionInfo {
+    pub version: String,
+    pub tag: String,
+    pub is_latest: bool,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ValkeyVersionInfo {
+    pub version: String,
+    pub tag: String,
+    pub is_latest: bool,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct RedisVersionInfo {
+    pub version: String,
+    pub tag: String,
+    pub is_latest: bool,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct MongoVersionInfo {
+    pub version: String,
+    pub tag: String,
+    pub is_latest: bool,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ClickhouseVersionInfo {
+    pub version: String,
+    pub tag: String,
+    pub is_latest: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct DockerHubResponse {
+    results: Vec<DockerHubTag>,
+    next: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct DockerHubTag {
+    name: String,
+}
+
+struct VersionCache<T> {
+    versions: Vec<T>,
+    fetched_at: Instant,
+}
+
+#[derive(Debug, Deserialize)]
+struct DockerHubTagDetail {
+    digest: Option<String>,
+}
+
+static POSTGRES_VERSION_CACHE: Lazy<RwLock<Option<VersionCache<PostgresVersionInfo>>>> =
+    Lazy::new(|| RwLock::new(None));
+
+static VALKEY_VERSION_CACHE: Lazy<RwLock<Option<VersionCache<ValkeyVersionInfo>>>> =
+    Lazy::new(|| RwLock::new(None));
+
+static REDIS_VERSION_CACHE: Lazy<RwLock<Option<VersionCache<RedisVersionInfo>>>> =
+    Lazy::new(|| RwLock::new(None));
+
+static MONGO_VERSION_CACHE: Lazy<RwLock<Option<VersionCache<MongoVersionInfo>>>> =
+    Lazy::new(|| RwLock::new(None));
+
+static CLICKHOUSE_VERSION_CACHE: Lazy<RwLock<Option<VersionCache<ClickhouseVersionInfo>>>> =
+    Lazy::new(|| RwLock::new(None));
+
+static TAG_DIGEST_CACHE: Lazy<RwLock<HashMap<String, (String, Instant)>>> =
+    Lazy::new(|| RwLock::new(HashMap::new()));
+
+async fn fetch_docker_hub_tags(repo: &str, pages: usize) -> Result<Vec<String>, reqwest::Error> {
+    let client = reqwest::Client::builder()
+        .timeout(DOCKER_HUB_TIMEOUT)
+        .build()?;
+
+    let mut all_tags = Vec::new();
+    let mut url = format!(
+        "https://hub.docker.com/v2/repositories/{}/tags?page_size=100",
+        repo
+    );
+
+    for _ in 0..pages {
+        let resp: DockerHubResponse = client.get(&url).send().await?.json().await?;
+        all_tags.extend(resp.results.into_iter().map(|t| t.name));
+
+        match resp.next {
+            Some(next_url) => url = next_url,
+            None => break,
+        }
+    }
+
+    Ok(all_tags)
+}
+
+async fn fetch_postgres_versions() -> Result<Vec<PostgresVersionInfo>, reqwest::Error> {
+    let all_tags = fetch_docker_hub_tags("library/postgres", 3).await?;
+
+    let mut major_versions: Vec<u32> = all_tags
+        .iter()
+        .filter_map(|tag| tag.parse::<u32>().ok())
+        .filter(|&v| (13..=25).contains(&v))
+        .collect();
+
+    major_versions.sort();
+    major_versions.dedup();
+
+    let latest_version = major_versions.last().copied();
+
+    let versions = major_versions
+        .into_iter()
+        .map(|v| PostgresVersionInfo {
+            version: v.to_string(),
+            tag: format!("postgres:{}-alpine", v),
+            is_latest: Some(v) == latest_version,
+        })
+        .collect();
+
+    Ok(versions)
+}
+
+async fn fetch_valkey_versions() -> Result<Vec<ValkeyVersionInfo>, reqwest::Error> {
+    let all_tags = fetch_docker_hub_tags("valkey/valkey", 3).await?;
+
+    let mut versions: Vec<(u32, u32)> = all_tags
+        .iter()
+        .filter(|tag| tag.ends_with("-alpine"))
+        .filter_map(|tag| {
+            let version_part = tag.trim_end_matches("-alpine");
+            let parts: Vec<&str> = version_part.split('.').collect();
+            if parts.len() >= 2 {
+                let major = parts[0].parse::<u32>().ok()?;
+                let minor = parts[1].parse::<u32>().ok()?;
+                Some((major, minor))
+            } else {
+                None
+            }
+        })
+        .collect();
+
+    versions.sort();
+    versions.dedup();
+
+    let latest_version = versions.last().copied();
+
+    let versions = versions
+        .into_iter()
+        .map(|(major, minor)| {
+            let version = format!("{}.{}", major, minor);
+            ValkeyVersionInfo {
+                tag: format!("valkey/valkey:{}-alpine", version),
+                is_latest: Some((major, minor)) == latest_version,
+                version,
+            }
+        })
+        .collect();
+
+    Ok(versions)
+}
+
+async fn fetch_redis_versions() -> Result<Vec<RedisVersionInfo>, reqwest::Error> {
+    let all_tags = fetch_docker_hub_tags("library/redis", 3).await?;
+
+    let mut versions: Vec<(u32, u32)> = all_tags
+        .iter()
+        .filter(|tag| tag.ends_with("-alpine"))
+        .filter_map(|tag| {
+            let version_part = tag.trim_end_matches("-alpine");
+            let parts: Vec<&str> = version_part.split('.').collect();
+            if parts.len() >= 2 {
+                let major = parts[0].parse::<u32>().ok()?;
+                let minor = parts[1].parse::<u32>().ok()?;
+                if major >= 6 {
+                    Some((major, minor))
+                } else {
+                    None
+                }
+            } else {
+                None
+            }
+        })
+        .collect();
+
+    versions.sort();
+    versions.dedup();
+
+    let latest_version = versions.last().copied();
+
+    let versions = versions
+        .into_iter()
+        .map(|(major, minor)| {
+            let version = format!("{}.{}", major, minor);
+            RedisVersionInfo {
+                tag: format!("redis:{}-alpine", version),
+                is_latest: Some((major, minor)) == latest_version,
+                version,
+            }
+        })
+        .collect();
+
+    Ok(versions)
+}
+
+async fn fetch_mongo_versions() -> Result<Vec<MongoVersionInfo>, reqwest::Error> {
+    let all_tags = fetch_docker_hub_tags("library/mongo", 3).await?;
+
+    let mut versions: Vec<(u32, u32)> = all_tags
+        .iter()
+        .filter_map(|tag| {
+            let (major, minor) = tag.split_once('.')?;
+            let major = major.parse::<u32>().ok()?;
+            let minor = minor.parse::<u32>().ok()?;
+            (major >= 6).then_some((major, minor))
+        })
+        .collect();
+
+    versions.sort();
+    versions.dedup();
+
+    let latest_version = versions.last().copied();
+
+    let versions = versions
+        .into_iter()
+        .map(|(major, minor)| {
+            let version = format!("{}.{}", major, minor);
+            MongoVersionInfo {
+                tag: format!("mongo:{}", version),
+                is_latest: Some((major, minor)) == latest_version,
+                version,
+            }
+        })
+        .collect();
+
+    Ok(versions)
+}
+
+/// ClickHouse names releases `YY.M`; every release line also has a tag of that form.
+async fn fetch_clickhouse_versions() -> Result<Vec<ClickhouseVersionInfo>, reqwest::Error> {
+    let all_tags = fetch_docker_hub_tags("clickhouse/clickhouse-server", 5).await?;
+
+    let mut versions: Vec<(u32, u32)> = all_tags
+        .iter()
+        .filter_map(|tag| {
+            let (year, month) = tag.split_once('.')?;
+            let year = year.parse::<u32>().ok()?;
+            let month = month.parse::<u32>().ok()?;
+            (year >= 24).then_some((year, month))
+        })
+        .collect();
+
+    versions.sort();
+    versions.dedup();
+
+    let latest_version = versions.last().copied();
+
+    let versions = versions
+        .into_iter()
+        .map(|(year, month)| {
+            let version = format!("{}.{}", year, month);
+            ClickhouseVersionInfo {
+                tag: format!("clickhouse/clickhouse-server:{}", version),
+                is_latest: Some((year, month)) == latest_version,
+                version,
+            }
+        })
+        .collect();
+
+    Ok(versions)
+}
+
+async fn cached_versions<T, F, Fut>(
+    cache: &RwLock<Option<VersionCache<T>>>,
+    name: &str,
+    fetch: F,
+) -> Vec<T>
+where
+    T: Clone,
+    F: FnOnce() -> Fut,
+    Fut: Future<Output = Result<Vec<T>, reqwest::Error>>,
+{
+    {
+        let cache = cache.read().await;
+        if let Some(ref cached) = *cache {
+            if cached.fetched_at.elapsed() < CACHE_TTL {
+                return cached.versions.clone();
+            }
+        }
+    }
+
+    match fetch().await {
+        Ok(v) => {
+            let mut cache = cache.write().await;
+            *cache = Some(VersionCache {
+                versions: v.clone(),
+                fetched_at: Instant::now(),
+            });
+            v
+        },
+        Err(e) => {
+            tracing::warn!("Failed to fetch {} versions from Docker Hub: {}", name, e);
+            vec![]
+        },
+    }
+}
+
+pub async fn postgres_versions() -> Vec<PostgresVersionInfo> {
+    cached_versions(
+        &POSTGRES_VERSION_CACHE,
+        "PostgreSQL",
+        fetch_postgres_versions,
+    )
+    .await
+}
+
+pub async fn valkey_versions() -> Vec<ValkeyVersionInfo> {
+    cached_versions(&VALKEY_VERSION_CACHE, "Valkey", fetch_valkey_versions).await
+}
+
+pub async fn redis_versions() -> Vec<RedisVersionInfo> {
+    cached_versions(&REDIS_VERSION_CACHE, "Redis", fetch_redis_versions).await
+}
+
+pub async fn mongo_versions() -> Vec<MongoVersionInfo> {
+    cached_versions(&MONGO_VERSION_CACHE, "MongoDB", fetch_mongo_versions).await
+}
+
+pub async fn clickhouse_versions() -> Vec<ClickhouseVersionInfo> {
+    cached_versions(
+        &CLICKHOUSE_VERSION_CACHE,
+        "ClickHouse",
+        fetch_clickhouse_versions,
+    )
+    .await
+}
+
+/// Returns the digest Docker Hub currently publishes for an image reference such as
+/// `postgres:16` or `valkey/valkey:8.0-alpine`.
+pub async fn tag_digest(image: &str) -> Option<String> {
+    {
+        let cache = TAG_DIGEST_CACHE.read().await;
+        if let Some((digest, fetched_at)) = cache.get(image) {
+            if fetched_at.elapsed() < TAG_DIGEST_TTL {
+                return Some(digest.clone());
+            }
+        }
+    }
+
+    let (repo, tag) = image.rsplit_once(':')?;
+    let repo = if repo.contains('/') {
+        repo.to_string()
+    } else {
+        format!("library/{}", repo)
+    };
+    let url = format!(
+        "https://hub.docker.com/v2/repositories/{}/tags/{}",
+        repo, tag
+    );
+
+    let fetched = async {
+        let client = reqwest::Client::builder()
+            .timeout(DOCKER_HUB_TIMEOUT)
+            .build()?;
+        client
+            .get(&url)
+            .send()
+            .await?
+            .error_for_status()?
+            .json::<DockerHubTagDetail>()
+            .await
+    }
+    .await;
+
+    match fetched {
+        Ok(DockerHubTagDetail {
+            digest: Some(digest),
+        }) => {
+            TAG_DIGEST_CACHE
+                .write()
+                .await
+                .insert(image.to_string(), (digest.clone(), Instant::now()));
+            Some(digest)
+        },
+        Ok(_) => None,
+        Err(e) => {
+            tracing::warn!("Failed to fetch digest of {} from Docker Hub: {}", image, e);
+            None
+        },
+    }
+}
@@ -0,0 +1,58 @@
+use std::path::Path;
+
+use async_trait::async_trait;
+
+use super::{remove_dir, rename_dir, run_command, DataVolumes};
+use crate::error::AppResult;
+
+/// One btrfs subvolume per data directory. Directories created before switching to btrfs stay
+/// plain directories and cannot be snapshotted.
+pub struct BtrfsVolumes;
+
+impl BtrfsVolumes {
+    async fn is_subvolume(path: &str) -> bool {
+        run_command("btrfs", &["subvolume", "show", path])
+            .await
+            .is_ok()
+    }
+}
+
+#[async_trait]
+impl DataVolumes for BtrfsVolumes {
+    fn kind(&self) -> &'static str {
+        "btrfs"
+    }
+
+    fn supports_snapshots(&self) -> bool {
+        true
+    }
+
+    fn atomic_snapshots(&self) -> bool {
+        true
+    }
+
+    async fn create(&self, path: &str) -> AppResult<()> {
+        if Path::new(path).exists() {
+            return Ok(());
+        }
+        run_command("btrfs", &["subvolume", "create", path]).await?;
+        Ok(())
+    }
+
+    async fn snapshot(&self, source: &str, target: &str) -> AppResult<()> {
+        run_command("btrfs", &["subvolume", "snapshot", source, target]).await?;
+        Ok(())
+    }
+
+    async fn rename(&self, from: &str, to: &str) -> AppResult<()> {
+        rename_dir(from, to).await
+    }
+
+    async fn remove(&self, path: &str) -> AppResult<()> {
+        if Self::is_subvolume(path).await {
+            run_command("btrfs", &["subvolume", "delete", path]).await?;
+            return Ok(());
+        }
+        remove_dir(path).await
+    }
+}
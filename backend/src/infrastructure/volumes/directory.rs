@@ -0,0 +1,26 @@
+use async_trait::async_trait;
+
+use super::{create_dir, remove_dir, rename_dir, DataVolumes};
+use crate::error::AppResult;
+
+/// Plain directories on any filesystem.
+pub struct DirectoryVolumes;
+
+#[async_trait]
+impl DataVolumes for DirectoryVolumes {
+    fn kind(&self) -> &'static str {
+        "directory"
+    }
+
+    async fn create(&self, path: &str) -> AppResult<()> {
+        create_dir(path).await
+    }
+
+    async fn rename(&self, from: &str, to: &str) -> AppResult<()> {
+        rename_dir(from, to).await
+    }
+
+    async fn remove(&self, path: &str) -> AppResult<()> {
+        remove_dir(path).await
+    }
+}
@@ -0,0 +1,130 @@
+mod btrfs;
+mod directory;
+mod reflink;
+mod zfs;
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use tokio::process::Command;
+
+pub use btrfs::*;
+pub use directory::*;
+pub use reflink::*;
+pub use zfs::*;
+
+use crate::error::{AppError, AppResult};
+
+/// How database data directories below `data_dir` are created, copied and removed.
+#[async_trait]
+pub trait DataVolumes: Send + Sync {
+    fn kind(&self) -> &'static str;
+
+    /// Whether `snapshot` is available. Without it branches fall back to a logical copy.
+    fn supports_snapshots(&self) -> bool {
+        false
+    }
+
+    /// Whether a snapshot captures a single instant. Otherwise writers must be paused while it
+    /// is taken.
+    fn atomic_snapshots(&self) -> bool {
+        false
+    }
+
+    /// Creates an empty data directory. Existing directories are left as they are.
+    async fn create(&self, path: &str) -> AppResult<()>;
+
+    /// Creates `target` as a copy-on-write snapshot of `source`.
+    async fn snapshot(&self, _source: &str, _target: &str) -> AppResult<()> {
+        Err(AppError::Internal(format!(
+            "Snapshots are not supported by the {} storage backend",
+            self.kind()
+        )))
+    }
+
+    async fn rename(&self, from: &str, to: &str) -> AppResult<()>;
+
+    async fn remove(&self, path: &str) -> AppResult<()>;
+}
+
+pub fn build_data_volumes(backend: &str, data_dir: &str) -> Arc<dyn DataVolumes> {
+    match backend {
+        "zfs" => Arc::new(ZfsVolumes::new(data_dir)),
+        "btrfs" => Arc::new(BtrfsVolumes),
+        "reflink" => Arc::new(ReflinkVolumes),
+        _ => Arc::new(DirectoryVolumes),
+    }
+}
+
+async fn run_command(program: &str, args: &[&str]) -> AppResult<String> {
+    let output = Command::new(program)
+        .args(args)
+        .output()
+        .await
+        .map_err(|e| AppError::Internal(format!("Failed to run {}: {}", program, e)))?;
+
+    if !output.status.success() {
+        return Err(AppError::Internal(format!(
+            "{} {} failed: {}",
+            program,
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+async fn create_dir(path: &str) -> AppResult<()> {
+    tokio::fs::create_dir_all(path)
+        .await
+        .map_err(|e| AppError::Internal(format!("Failed to create data directory: {}", e)))
+}
+
+async fn rename_dir(from: &str, to: &str) -> AppResult<()> {
+    tokio::fs::rename(from, to)
+        .await
+        .map_err(|e| AppError::Internal(format!("Failed to move data directory {}: {}", from, e)))
+}
+
+async fn remove_dir(path: &str) -> AppResult<()> {
+    match tokio::fs::remove_dir_all(path).await {
+        Ok(()) => Ok(()),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+        Err(e) => Err(AppError::Internal(format!(
+            "Failed to remove data directory {}: {}",
+            path, e
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_directory_volumes_lifecycle() {
+        let root = std::env::temp_dir().join(format!("datify-volumes-{}", uuid::Uuid::new_v4()));
+        let volumes = build_data_volumes("directory", root.to_str().unwrap());
+        let live = root.join("live").to_string_lossy().into_owned();
+        let kept = root.join("kept").to_string_lossy().into_owned();
+
+        volumes.create(&live).await.unwrap();
+        tokio::fs::write(format!("{}/PG_VERSION", live), "17")
+            .await
+            .unwrap();
+        volumes.create(&live).await.unwrap();
+        assert!(!volumes.supports_snapshots());
+        assert!(volumes.snapshot(&live, &kept).await.is_err());
+
+        volumes.rename(&live, &kept).await.unwrap();
+        assert!(Path::new(&format!("{}/PG_VERSION", kept)).exists());
+
+        volumes.remove(&kept).await.unwrap();
+        volumes.remove(&kept).await.unwrap();
+        assert!(!Path::new(&kept).exists());
+        let _ = tokio::fs::remove_dir_all(&root).await;
+    }
+}
@@ -0,0 +1,46 @@
+use std::path::Path;
+
+use async_trait::async_trait;
+
+use super::{create_dir, remove_dir, rename_dir, run_command, DataVolumes};
+use crate::error::{AppError, AppResult};
+
+/// Plain directories copied with shared extents, on filesystems such as XFS or bcachefs that
+/// support reflinks but not snapshots.
+pub struct ReflinkVolumes;
+
+#[async_trait]
+impl DataVolumes for ReflinkVolumes {
+    fn kind(&self) -> &'static str {
+        "reflink"
+    }
+
+    fn supports_snapshots(&self) -> bool {
+        true
+    }
+
+    async fn create(&self, path: &str) -> AppResult<()> {
+        create_dir(path).await
+    }
+
+    async fn snapshot(&self, source: &str, target: &str) -> AppResult<()> {
+        if Path::new(target).exists() {
+            return Err(AppError::Conflict(format!("{} already exists", target)));
+        }
+
+        if let Err(e) = run_command("cp", &["-a", "--reflink=always", source, target]).await {
+            let _ = remove_dir(target).await;
+            return Err(e);
+        }
+
+        Ok(())
+    }
+
+    async fn rename(&self, from: &str, to: &str) -> AppResult<()> {
+        rename_dir(from, to).await
+    }
+
+    async fn remove(&self, path: &str) -> AppResult<()> {
+        remove_dir(path).await
+    }
+}
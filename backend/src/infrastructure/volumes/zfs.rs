@@ -0,0 +1,158 @@
+use std::path::Path;
+
+use async_trait::async_trait;
+use tokio::sync::OnceCell;
+
+use super::{remove_dir, rename_dir, run_command, DataVolumes};
+use crate::error::{AppError, AppResult};
+
+/// One ZFS dataset per data directory, below the dataset mounted at `data_dir`. Branches are
+/// clones of a snapshot of their parent's dataset.
+pub struct ZfsVolumes {
+    data_dir: String,
+    root: OnceCell<String>,
+}
+
+impl ZfsVolumes {
+    pub fn new(data_dir: &str) -> Self {
+        Self {
+            data_dir: data_dir.trim_end_matches('/').to_string(),
+            root: OnceCell::new(),
+        }
+    }
+
+    async fn root(&self) -> AppResult<&str> {
+        let root = self
+            .root
+            .get_or_try_init(|| async {
+                let name =
+                    run_command("zfs", &["list", "-H", "-o", "name", &self.data_dir]).await?;
+                Ok::<_, AppError>(name.trim().to_string())
+            })
+            .await?;
+        Ok(root)
+    }
+
+    /// Name of the dataset for a data directory directly below `data_dir`.
+    async fn dataset(&self, path: &str) -> AppResult<String> {
+        let name = Path::new(path)
+            .strip_prefix(&self.data_dir)
+            .ok()
+            .and_then(|relative| relative.to_str())
+            .filter(|relative| !relative.is_empty() && !relative.contains('/'))
+            .ok_or_else(|| {
+                AppError::Internal(format!("{} is not directly below {}", path, self.data_dir))
+            })?;
+        Ok(format!("{}/{}", self.root().await?, name))
+    }
+
+    /// The dataset of `path`, if it has one of its own rather than being a plain directory
+    /// inside the root dataset.
+    async fn existing_dataset(&self, path: &str) -> AppResult<Option<String>> {
+        let dataset = self.dataset(path).await?;
+        let found = run_command("zfs", &["list", "-H", "-o", "name", &dataset]).await;
+        Ok(found.ok().map(|_| dataset))
+    }
+
+    async fn origin(dataset: &str) -> AppResult<String> {
+        let origin = run_command("zfs", &["get", "-H", "-o", "value", "origin", dataset]).await?;
+        Ok(origin.trim().to_string())
+    }
+
+    /// Promotes the clones of the dataset's snapshots so the dataset itself can be destroyed.
+    async fn release_clones(dataset: &str) -> AppResult<()> {
+        let clones = run_command(
+            "zfs",
+            &[
+                "list", "-H", "-t", "snapshot", "-d", "1", "-o", "clones", dataset,
+            ],
+        )
+        .await?;
+
+        for clone in clones
+            .split(|c: char| c == ',' || c.is_whitespace())
+            .filter(|clone| !clone.is_empty() && *clone != "-")
+        {
+            run_command("zfs", &["promote", clone]).await?;
+        }
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl DataVolumes for ZfsVolumes {
+    fn kind(&self) -> &'static str {
+        "zfs"
+    }
+
+    fn supports_snapshots(&self) -> bool {
+        true
+    }
+
+    fn atomic_snapshots(&self) -> bool {
+        true
+    }
+
+    async fn create(&self, path: &str) -> AppResult<()> {
+        if Path::new(path).exists() {
+            return Ok(());
+        }
+        let dataset = self.dataset(path).await?;
+        run_command("zfs", &["create", &dataset]).await?;
+        Ok(())
+    }
+
+    async fn snapshot(&self, source: &str, target: &str) -> AppResult<()> {
+        let source_dataset = self
+            .existing_dataset(source)
+            .await?
+            .ok_or_else(|| AppError::Internal(format!("{} is not a ZFS dataset", source)))?;
+        let target_dataset = self.dataset(target).await?;
+        let name = target_dataset.rsplit('/').next().unwrap_or_default();
+        let snapshot = format!("{}@{}", source_dataset, name);
+
+        run_command("zfs", &["snapshot", &snapshot]).await?;
+        if let Err(e) = run_command("zfs", &["clone", &snapshot, &target_dataset]).await {
+            let _ = run_command("zfs", &["destroy", &snapshot]).await;
+            return Err(e);
+        }
+
+        Ok(())
+    }
+
+    async fn rename(&self, from: &str, to: &str) -> AppResult<()> {
+        match self.existing_dataset(from).await? {
+            Some(dataset) => {
+                let target = self.dataset(to).await?;
+                run_command("zfs", &["rename", &dataset, &target]).await?;
+                Ok(())
+            },
+            None => rename_dir(from, to).await,
+        }
+    }
+
+    async fn remove(&self, path: &str) -> AppResult<()> {
+        let Some(dataset) = self.existing_dataset(path).await? else {
+            return remove_dir(path).await;
+        };
+
+        let mut origin = Self::origin(&dataset).await?;
+        if run_command("zfs", &["destroy", "-r", &dataset])
+            .await
+            .is_err()
+        {
+            // Promoting a clone turns this dataset into a clone of it.
+            Self::release_clones(&dataset).await?;
+            origin = Self::origin(&dataset).await?;
+            run_command("zfs", &["destroy", "-r", &dataset]).await?;
+        }
+
+        // The snapshot a clone was made from is only needed while the clone exists.
+        if origin != "-" && !origin.is_empty() {
+            let _ = run_command("zfs", &["destroy", &origin]).await;
+        }
+
+        Ok(())
+    }
+}
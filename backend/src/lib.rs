@@ -10,8 +10,16 @@ pub mod utils;
 
 pub use api::create_router;
 pub use config::Settings;
-pub use domain::services::MetricsService;
+pub use domain::services::{
+    AppServices, AuditLogService, BackupService, BranchService, DatabaseService, MetricsService,
+    OperationService, QuotaService, SqlService, UpdateService, UpgradeService,
+};
 pub use error::{AppError, AppResult};
+pub use infrastructure::backup_scheduler::spawn_backup_scheduler;
+pub use infrastructure::branch_reaper::spawn_branch_reaper;
 pub use infrastructure::metrics_collector::spawn_metrics_collector;
+pub use infrastructure::quota_monitor::spawn_quota_monitor;
+pub use infrastructure::rollback_cleanup::spawn_rollback_cleanup;
+pub use infrastructure::update_checker::spawn_update_checker;
 pub use openapi::{generate_openapi_json, get_openapi_spec};
 pub use repositories::{DatabaseRepository, MetricsRepository, Repositories};
@@ -4,7 +4,10 @@ use std::time::Duration;
 
 use datify::config::Settings;
 use datify::infrastructure::docker::DockerManager;
-use datify::{spawn_metrics_collector, MetricsService, Repositories};
+use datify::{
+    spawn_backup_scheduler, spawn_branch_reaper, spawn_metrics_collector, spawn_quota_monitor,
+    spawn_rollback_cleanup, spawn_update_checker, AppServices, Repositories,
+};
 use sqlx::sqlite::{SqliteConnectOptions, SqlitePoolOptions};
 use tokio::net::TcpListener;
 use tokio::signal;
@@ -41,27 +44,54 @@ async fn main() -> anyhow::Result<()> {
     tracing::info!("Docker connection established");
 
     let repositories = Repositories::new(db_pool.clone());
-    let docker_arc = std::sync::Arc::new(docker.clone());
-
-    let metrics_service = std::sync::Arc::new(MetricsService::new(
-        repositories.databases.clone(),
-        repositories.projects.clone(),
-        repositories.metrics.clone(),
-        docker_arc.clone(),
-        &settings.security.encryption_key,
-    ));
+    let docker = Arc::new(docker);
+    let services = AppServices::new(&repositories, docker.clone(), settings.clone());
 
     let shutdown_token = CancellationToken::new();
 
     let _metrics_collector = spawn_metrics_collector(
-        metrics_service,
+        services.metrics.clone(),
         repositories.databases.clone(),
         15,
         shutdown_token.clone(),
     );
     tracing::info!("Background metrics collector started");
 
-    let app = datify::create_router(db_pool, docker, settings.clone()).await;
+    let _quota_monitor = spawn_quota_monitor(services.quota.clone(), 300, shutdown_token.clone());
+    tracing::info!("Background storage quota monitor started");
+
+    if let Err(e) = services.operation.recover_interrupted().await {
+        tracing::error!("Error recovering interrupted operations: {}", e);
+    }
+
+    let database_service = services.database.clone();
+    tokio::spawn(async move {
+        if let Err(e) = database_service.initialize_running().await {
+            tracing::error!("Error initializing running databases: {}", e);
+        }
+    });
+
+    let _backup_scheduler =
+        spawn_backup_scheduler(services.backup.clone(), 60, shutdown_token.clone());
+    tracing::info!("Background backup scheduler started");
+
+    let _branch_reaper = spawn_branch_reaper(
+        services.branch.clone(),
+        services.audit_log.clone(),
+        60,
+        shutdown_token.clone(),
+    );
+    tracing::info!("Background branch reaper started");
+
+    let _rollback_cleanup =
+        spawn_rollback_cleanup(services.upgrade.clone(), 3600, shutdown_token.clone());
+    tracing::info!("Background upgrade rollback cleanup started");
+
+    let _update_checker =
+        spawn_update_checker(services.update.clone(), 6 * 3600, shutdown_token.clone());
+    tracing::info!("Background update checker started");
+
+    let app = datify::create_router(db_pool, docker, settings.clone(), &services).await;
 
     let addr: SocketAddr = settings.server.address().parse()?;
     tracing::info!("Starting HTTP server on {}", addr);
@@ -35,6 +35,7 @@ use utoipa::OpenApi;
         crate::api::handlers::update_project,
         crate::api::handlers::delete_project,
         crate::api::handlers::create_database,
+        crate::api::handlers::upload_import,
         crate::api::handlers::list_databases,
         crate::api::handlers::get_database,
         crate::api::handlers::update_database,
@@ -46,6 +47,7 @@ use utoipa::OpenApi;
         crate::api::handlers::get_database_logs,
         crate::api::handlers::stream_database_logs,
         crate::api::handlers::database_terminal,
+        crate::api::handlers::database_cli,
         crate::api::handlers::database_psql,
         crate::api::handlers::get_database_metrics,
         crate::api::handlers::get_database_metrics_history,
@@ -54,10 +56,42 @@ use utoipa::OpenApi;
         crate::api::handlers::list_branches,
         crate::api::handlers::create_branch,
         crate::api::handlers::sync_from_parent,
+        crate::api::handlers::promote_branch,
+        crate::api::handlers::extend_branch,
+        crate::api::handlers::detach_replica,
+        crate::api::handlers::get_branch_tree,
+        crate::api::handlers::reset_branch,
+        crate::api::handlers::list_snapshots,
+        crate::api::handlers::create_snapshot,
+        crate::api::handlers::delete_snapshot,
         crate::api::handlers::get_database_schema,
+        crate::api::handlers::get_schema_diff,
         crate::api::handlers::execute_query,
         crate::api::handlers::execute_kv_command,
+        crate::api::handlers::execute_document_query,
         crate::api::handlers::preview_table,
+        crate::api::handlers::list_backups,
+        crate::api::handlers::create_backup,
+        crate::api::handlers::get_backup,
+        crate::api::handlers::download_backup,
+        crate::api::handlers::delete_backup,
+        crate::api::handlers::get_backup_schedule,
+        crate::api::handlers::update_backup_schedule,
+        crate::api::handlers::restore_backup,
+        crate::api::handlers::restore_backup_to_branch,
+        crate::api::handlers::get_pitr_status,
+        crate::api::handlers::restore_to_point_in_time,
+        crate::api::handlers::branch_from_point_in_time,
+        crate::api::handlers::export_database,
+        crate::api::handlers::upgrade_database,
+        crate::api::handlers::rollback_upgrade,
+        crate::api::handlers::apply_update,
+        crate::api::handlers::list_extensions,
+        crate::api::handlers::install_extension,
+        crate::api::handlers::remove_extension,
+        crate::api::handlers::update_preload_libraries,
+        crate::api::handlers::list_operations,
+        crate::api::handlers::get_operation,
         crate::api::handlers::list_audit_logs,
     ),
     components(schemas(
@@ -78,6 +112,8 @@ use utoipa::OpenApi;
         crate::domain::models::UpdateProjectRequest,
         crate::domain::models::DatabaseResponse,
         crate::domain::models::CreateDatabaseRequest,
+        crate::domain::models::DatabaseImportRequest,
+        crate::domain::models::ImportUploadResponse,
         crate::domain::models::UpdateDatabaseRequest,
         crate::domain::models::DatabaseConfigResponse,
         crate::domain::models::UpdateDatabaseConfigRequest,
@@ -91,12 +127,18 @@ use utoipa::OpenApi;
         crate::domain::models::LogEntryResponse,
         crate::domain::models::LogType,
         crate::domain::models::DatabaseMetrics,
+        crate::domain::models::StandbyMetrics,
         crate::domain::models::QueryMetrics,
         crate::domain::models::RowMetrics,
         crate::domain::models::TableMetrics,
         crate::domain::models::StorageMetrics,
         crate::domain::models::ConnectionMetrics,
         crate::domain::models::ResourceMetrics,
+        crate::domain::models::DocumentMetrics,
+        crate::domain::models::OperationCounterMetrics,
+        crate::domain::models::DocumentCounterMetrics,
+        crate::domain::models::CollectionMetrics,
+        crate::domain::models::DocumentMemoryMetrics,
         crate::domain::models::MetricsResponse,
         crate::domain::models::MetricsHistory,
         crate::domain::models::MetricsHistoryPoint,
@@ -109,18 +151,64 @@ use utoipa::OpenApi;
         crate::domain::models::BranchInfo,
         crate::domain::models::BranchResponse,
         crate::domain::models::CreateBranchRequest,
+        crate::domain::models::PromoteBranchRequest,
+        crate::domain::models::ExtendBranchRequest,
+        crate::domain::models::BranchDataMode,
+        crate::domain::models::BranchDataOptions,
+        crate::domain::models::BranchTreeNode,
+        crate::domain::models::ChildBranchPolicy,
+        crate::domain::models::AppliedMasking,
+        crate::domain::models::MaskingConfig,
+        crate::domain::models::MaskingRule,
+        crate::domain::models::MaskingStrategy,
+        crate::domain::models::FakeFormat,
+        crate::domain::models::ResetMode,
+        crate::domain::models::ResetBranchRequest,
+        crate::domain::models::ResetPlan,
+        crate::domain::models::SnapshotResponse,
+        crate::domain::models::CreateSnapshotRequest,
         crate::domain::models::SchemaInfo,
         crate::domain::models::TableInfo,
         crate::domain::models::ViewInfo,
+        crate::domain::models::RelationName,
         crate::domain::models::ColumnDetail,
         crate::domain::models::IndexInfo,
+        crate::domain::models::SchemaDiffQuery,
+        crate::domain::models::SchemaChange,
+        crate::domain::models::SchemaDiff,
+        crate::domain::models::TableDiff,
+        crate::domain::models::ColumnDiff,
+        crate::domain::models::IndexDiff,
+        crate::domain::models::ViewDiff,
         crate::domain::models::ExecuteQueryRequest,
         crate::domain::models::ExecuteKvCommandRequest,
         crate::domain::models::ColumnInfo,
         crate::domain::models::QueryResult,
         crate::domain::models::KvCommandResult,
+        crate::domain::models::DocumentOperation,
+        crate::domain::models::ExecuteDocumentQueryRequest,
+        crate::domain::models::DocumentQueryResult,
         crate::domain::models::TablePreviewQuery,
         crate::domain::models::TablePreview,
+        crate::domain::models::BackupResponse,
+        crate::domain::models::BackupScheduleResponse,
+        crate::domain::models::UpdateBackupScheduleRequest,
+        crate::domain::models::RestoreBackupToBranchRequest,
+        crate::domain::models::CreateBackupRequest,
+        crate::domain::models::PitrStatusResponse,
+        crate::domain::models::PointInTimeRestoreRequest,
+        crate::domain::models::PointInTimeBranchRequest,
+        crate::domain::models::ExportQuery,
+        crate::domain::models::UpgradeDatabaseRequest,
+        crate::domain::models::UpgradeRollbackInfo,
+        crate::domain::models::AvailableUpdateInfo,
+        crate::domain::models::PostgresVariant,
+        crate::domain::models::ExtensionInfo,
+        crate::domain::models::ExtensionsResponse,
+        crate::domain::models::InstallExtensionRequest,
+        crate::domain::models::UpdatePreloadLibrariesRequest,
+        crate::domain::models::OperationResponse,
+        crate::domain::models::OperationStreamMessage,
         crate::domain::models::AuditLogResponse,
         crate::domain::models::AuditAction,
         crate::domain::models::AuditEntityType,
@@ -138,6 +226,13 @@ use utoipa::OpenApi;
         (name = "Metrics", description = "Database metrics and query statistics endpoints"),
         (name = "SQL", description = "SQL query execution and schema introspection endpoints"),
         (name = "Key-Value", description = "Redis/Valkey command execution endpoints"),
+        (name = "Documents", description = "MongoDB document query endpoints"),
+        (name = "Backups", description = "Database backup, schedule and retention endpoints"),
+        (name = "Export", description = "Streaming database export endpoints"),
+        (name = "Upgrades", description = "PostgreSQL major-version upgrade and rollback endpoints"),
+        (name = "Updates", description = "Image and minor-version update endpoints"),
+        (name = "Extensions", description = "PostgreSQL extension and preload library endpoints"),
+        (name = "Operations", description = "Long-running database operation tracking endpoints"),
         (name = "Audit Logs", description = "Audit log retrieval endpoints")
     ),
     modifiers(&SecurityAddon)
@@ -0,0 +1,237 @@
+use sqlx::sqlite::SqlitePool;
+use uuid::Uuid;
+
+use crate::domain::models::{Backup, BackupSchedule};
+use crate::error::{AppError, AppResult};
+
+const BACKUP_COLUMNS: &str = r#"
+    id, database_id, status, kind, format, file_path, size_bytes, duration_ms,
+    checksum, error, created_at, completed_at, storage, encrypted, storage_target
+"#;
+
+const SCHEDULE_COLUMNS: &str = r#"
+    database_id, enabled, interval_hours, retention_count, retention_days, format,
+    last_run_at, created_at, updated_at
+"#;
+
+#[derive(Clone)]
+pub struct BackupRepository {
+    pool: SqlitePool,
+}
+
+impl BackupRepository {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+
+    pub async fn create(&self, database_id: &str, kind: &str, format: &str) -> AppResult<Backup> {
+        let id = Uuid::new_v4().to_string();
+
+        sqlx::query(
+            r#"
+            INSERT INTO backups (id, database_id, status, kind, format)
+            VALUES (?, ?, 'running', ?, ?)
+            "#,
+        )
+        .bind(&id)
+        .bind(database_id)
+        .bind(kind)
+        .bind(format)
+        .execute(&self.pool)
+        .await?;
+
+        self.find_by_id(&id)
+            .await?
+            .ok_or_else(|| AppError::Internal("Failed to retrieve created backup".to_string()))
+    }
+
+    pub async fn find_by_id(&self, id: &str) -> AppResult<Option<Backup>> {
+        let query = format!("SELECT {} FROM backups WHERE id = ?", BACKUP_COLUMNS);
+        let backup = sqlx::query_as::<_, Backup>(&query)
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        Ok(backup)
+    }
+
+    pub async fn find_by_database_id(&self, database_id: &str) -> AppResult<Vec<Backup>> {
+        let query = format!(
+            "SELECT {} FROM backups WHERE database_id = ? ORDER BY created_at DESC, rowid DESC",
+            BACKUP_COLUMNS
+        );
+        let backups = sqlx::query_as::<_, Backup>(&query)
+            .bind(database_id)
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(backups)
+    }
+
+    pub async fn has_running(&self, database_id: &str) -> AppResult<bool> {
+        let result: Option<(i32,)> = sqlx::query_as(
+            r#"SELECT 1 FROM backups WHERE database_id = ? AND status = 'running' LIMIT 1"#,
+        )
+        .bind(database_id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(result.is_some())
+    }
+
+    pub async fn mark_completed(
+        &self,
+        id: &str,
+        file_path: &str,
+        size_bytes: i64,
+        duration_ms: i64,
+        checksum: &str,
+    ) -> AppResult<()> {
+        sqlx::query(
+            r#"
+            UPDATE backups
+            SET status = 'completed', file_path = ?, size_bytes = ?, duration_ms = ?,
+                checksum = ?, error = NULL, completed_at = datetime('now')
+            WHERE id = ?
+            "#,
+        )
+        .bind(file_path)
+        .bind(size_bytes)
+        .bind(duration_ms)
+        .bind(checksum)
+        .bind(id)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn set_storage(
+        &self,
+        id: &str,
+        storage: &str,
+        encrypted: bool,
+        storage_target: Option<&str>,
+    ) -> AppResult<()> {
+        sqlx::query(
+            "UPDATE backups SET storage = ?, encrypted = ?, storage_target = ? WHERE id = ?",
+        )
+        .bind(storage)
+        .bind(encrypted)
+        .bind(storage_target)
+        .bind(id)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn mark_failed(&self, id: &str, error: &str, duration_ms: i64) -> AppResult<()> {
+        sqlx::query(
+            r#"
+            UPDATE backups
+            SET status = 'failed', error = ?, duration_ms = ?, completed_at = datetime('now')
+            WHERE id = ?
+            "#,
+        )
+        .bind(error)
+        .bind(duration_ms)
+        .bind(id)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn fail_interrupted(&self) -> AppResult<u64> {
+        let result = sqlx::query(
+            r#"
+            UPDATE backups
+            SET status = 'failed', error = 'Interrupted by server restart',
+                completed_at = datetime('now')
+            WHERE status = 'running'
+            "#,
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    pub async fn delete(&self, id: &str) -> AppResult<()> {
+        sqlx::query("DELETE FROM backups WHERE id = ?")
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    pub async fn find_schedule(&self, database_id: &str) -> AppResult<Option<BackupSchedule>> {
+        let query = format!(
+            "SELECT {} FROM backup_schedules WHERE database_id = ?",
+            SCHEDULE_COLUMNS
+        );
+        let schedule = sqlx::query_as::<_, BackupSchedule>(&query)
+            .bind(database_id)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        Ok(schedule)
+    }
+
+    pub async fn upsert_schedule(&self, schedule: &BackupSchedule) -> AppResult<BackupSchedule> {
+        sqlx::query(
+            r#"
+            INSERT INTO backup_schedules (database_id, enabled, interval_hours, retention_count, retention_days, format)
+            VALUES (?, ?, ?, ?, ?, ?)
+            ON CONFLICT(database_id) DO UPDATE SET
+                enabled = excluded.enabled,
+                interval_hours = excluded.interval_hours,
+                retention_count = excluded.retention_count,
+                retention_days = excluded.retention_days,
+                format = excluded.format
+            "#,
+        )
+        .bind(&schedule.database_id)
+        .bind(schedule.enabled)
+        .bind(schedule.interval_hours)
+        .bind(schedule.retention_count)
+        .bind(schedule.retention_days)
+        .bind(&schedule.format)
+        .execute(&self.pool)
+        .await?;
+
+        self.find_schedule(&schedule.database_id)
+            .await?
+            .ok_or_else(|| AppError::Internal("Failed to retrieve backup schedule".to_string()))
+    }
+
+    pub async fn find_due_schedules(&self) -> AppResult<Vec<BackupSchedule>> {
+        let query = format!(
+            r#"
+            SELECT {} FROM backup_schedules
+            WHERE enabled = 1
+              AND (last_run_at IS NULL
+                   OR last_run_at <= datetime('now', '-' || interval_hours || ' hours'))
+            "#,
+            SCHEDULE_COLUMNS
+        );
+        let schedules = sqlx::query_as::<_, BackupSchedule>(&query)
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(schedules)
+    }
+
+    pub async fn touch_schedule(&self, database_id: &str) -> AppResult<()> {
+        sqlx::query(
+            "UPDATE backup_schedules SET last_run_at = datetime('now') WHERE database_id = ?",
+        )
+        .bind(database_id)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+}
@@ -6,10 +6,13 @@ use crate::domain::models::Database;
 use crate::error::{AppError, AppResult};
 
 const DATABASE_COLUMNS: &str = r#"
-    id, project_id, name, database_type, postgres_version, valkey_version, redis_version,
-    container_id, container_status, host, port, username, password_encrypted,
+    id, project_id, name, database_type, version, container_id, container_status, host, port, username, password_encrypted,
     cpu_limit, memory_limit_mb, storage_limit_mb, public_exposed,
-    created_at, updated_at, parent_branch_id, branch_name, is_default_branch, forked_at
+    created_at, updated_at, parent_branch_id, branch_name, is_default_branch, forked_at,
+    wal_archiving, persistence, previous_postgres_version, rollback_expires_at,
+    update_image, update_kind, storage_used_bytes, storage_state, expires_at,
+    masking_rule_set, masking_rules, masked_at, is_replica, postgres_variant,
+    shared_preload_libraries
 "#;
 
 #[derive(Clone)]
@@ -28,9 +31,7 @@ impl DatabaseRepository {
         project_id: &str,
         name: &str,
         database_type: &str,
-        postgres_version: &str,
-        valkey_version: Option<&str>,
-        redis_version: Option<&str>,
+        version: &str,
         cpu_limit: f64,
         memory_limit_mb: i32,
         storage_limit_mb: i32,
@@ -48,17 +49,16 @@ impl DatabaseRepository {
 
         sqlx::query(
             r#"
-            INSERT INTO databases (id, project_id, name, database_type, postgres_version, valkey_version, redis_version, cpu_limit, memory_limit_mb, storage_limit_mb, public_exposed, branch_name, is_default_branch, parent_branch_id, forked_at)
-            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+            INSERT INTO databases (id, project_id, name, database_type, version, username, cpu_limit, memory_limit_mb, storage_limit_mb, public_exposed, branch_name, is_default_branch, parent_branch_id, forked_at)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
             "#,
         )
         .bind(&id)
         .bind(project_id)
         .bind(name)
         .bind(database_type)
-        .bind(postgres_version)
-        .bind(valkey_version)
-        .bind(redis_version)
+        .bind(version)
+        .bind(Database::default_username_for(database_type))
         .bind(cpu_limit)
         .bind(memory_limit_mb)
         .bind(storage_limit_mb)
@@ -329,6 +329,18 @@ impl DatabaseRepository {
         Ok(databases)
     }
 
+    pub async fn find_all_with_container(&self) -> AppResult<Vec<Database>> {
+        let query = format!(
+            "SELECT {} FROM databases WHERE container_id IS NOT NULL ORDER BY created_at DESC",
+            DATABASE_COLUMNS
+        );
+        let databases = sqlx::query_as::<_, Database>(&query)
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(databases)
+    }
+
     pub async fn find_branches(&self, database_id: &str) -> AppResult<Vec<Database>> {
         let root = self.find_root_database(database_id).await?;
         let root_id = root
@@ -378,6 +390,128 @@ impl DatabaseRepository {
         Ok(databases)
     }
 
+    /// The database and every branch below it, at any depth.
+    pub async fn find_tree(&self, root_id: &str) -> AppResult<Vec<Database>> {
+        let query = format!(
+            "WITH RECURSIVE tree(id) AS ( \
+                 SELECT id FROM databases WHERE id = ? \
+                 UNION ALL \
+                 SELECT d.id FROM databases d JOIN tree t ON d.parent_branch_id = t.id \
+             ) \
+             SELECT {} FROM databases WHERE id IN (SELECT id FROM tree) ORDER BY created_at ASC",
+            DATABASE_COLUMNS
+        );
+        let databases = sqlx::query_as::<_, Database>(&query)
+            .bind(root_id)
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(databases)
+    }
+
+    pub async fn reparent_children(&self, parent_id: &str, new_parent_id: &str) -> AppResult<()> {
+        sqlx::query(r#"UPDATE databases SET parent_branch_id = ? WHERE parent_branch_id = ?"#)
+            .bind(new_parent_id)
+            .bind(parent_id)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn update_wal_archiving(&self, id: &str, enabled: bool) -> AppResult<()> {
+        sqlx::query(r#"UPDATE databases SET wal_archiving = ? WHERE id = ?"#)
+            .bind(enabled)
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn update_persistence(&self, id: &str, persistence: &str) -> AppResult<()> {
+        sqlx::query(r#"UPDATE databases SET persistence = ? WHERE id = ?"#)
+            .bind(persistence)
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    /// Makes `branch_id` the root of its tree. The previous root and its other children become
+    /// children of the promoted branch.
+    pub async fn promote_branch(&self, branch_id: &str, root_id: &str) -> AppResult<()> {
+        let now = Utc::now().to_rfc3339();
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query(
+            r#"UPDATE databases SET parent_branch_id = ? WHERE parent_branch_id = ? AND id != ?"#,
+        )
+        .bind(branch_id)
+        .bind(root_id)
+        .bind(branch_id)
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            r#"UPDATE databases SET is_default_branch = 0, parent_branch_id = ?, forked_at = ? WHERE id = ?"#,
+        )
+        .bind(branch_id)
+        .bind(&now)
+        .bind(root_id)
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            r#"UPDATE databases SET is_default_branch = 1, parent_branch_id = NULL, forked_at = NULL, expires_at = NULL WHERE id = ?"#,
+        )
+        .bind(branch_id)
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// Swaps name, public port and password between two databases of the same project.
+    pub async fn swap_endpoints(&self, a: &Database, b: &Database) -> AppResult<()> {
+        let mut tx = self.pool.begin().await?;
+
+        // Names are unique per project, so one side is parked on a temporary name first.
+        sqlx::query(r#"UPDATE databases SET name = ? WHERE id = ?"#)
+            .bind(format!("{}-swap", a.id))
+            .bind(&a.id)
+            .execute(&mut *tx)
+            .await?;
+
+        for (target, source) in [(b, a), (a, b)] {
+            sqlx::query(
+                r#"
+                UPDATE databases
+                SET name = ?, public_exposed = ?, port = ?, password_encrypted = ?
+                WHERE id = ?
+                "#,
+            )
+            .bind(&source.name)
+            .bind(source.public_exposed)
+            .bind(source.port)
+            .bind(&source.password_encrypted)
+            .bind(&target.id)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    pub async fn set_forked_at(&self, id: &str, forked_at: &str) -> AppResult<()> {
+        sqlx::query(r#"UPDATE databases SET forked_at = ? WHERE id = ?"#)
+            .bind(forked_at)
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
     pub async fn update_forked_at(&self, id: &str) -> AppResult<()> {
         let now = Utc::now().to_rfc3339();
         sqlx::query(r#"UPDATE databases SET forked_at = ? WHERE id = ?"#)
@@ -387,4 +521,337 @@ impl DatabaseRepository {
             .await?;
         Ok(())
     }
+
+    pub async fn update_postgres_version(
+        &self,
+        id: &str,
+        version: &str,
+        previous_postgres_version: Option<&str>,
+        rollback_expires_at: Option<&str>,
+    ) -> AppResult<()> {
+        sqlx::query(
+            r#"
+            UPDATE databases
+            SET version = ?, previous_postgres_version = ?, rollback_expires_at = ?,
+                updated_at = CURRENT_TIMESTAMP
+            WHERE id = ?
+            "#,
+        )
+        .bind(version)
+        .bind(previous_postgres_version)
+        .bind(rollback_expires_at)
+        .bind(id)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn clear_rollback(&self, id: &str) -> AppResult<()> {
+        sqlx::query(
+            r#"UPDATE databases SET previous_postgres_version = NULL, rollback_expires_at = NULL WHERE id = ?"#,
+        )
+        .bind(id)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn set_expires_at(&self, id: &str, expires_at: Option<&str>) -> AppResult<()> {
+        sqlx::query(r#"UPDATE databases SET expires_at = ? WHERE id = ?"#)
+            .bind(expires_at)
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn update_postgres_variant(&self, id: &str, variant: Option<&str>) -> AppResult<()> {
+        sqlx::query(r#"UPDATE databases SET postgres_variant = ? WHERE id = ?"#)
+            .bind(variant)
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    /// Stores the comma-separated libraries the container is started with.
+    pub async fn update_preload_libraries(&self, id: &str, libraries: &str) -> AppResult<()> {
+        sqlx::query(
+            r#"UPDATE databases SET shared_preload_libraries = ?, updated_at = CURRENT_TIMESTAMP WHERE id = ?"#,
+        )
+        .bind(libraries)
+        .bind(id)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn set_replica(&self, id: &str, is_replica: bool) -> AppResult<()> {
+        sqlx::query(r#"UPDATE databases SET is_replica = ? WHERE id = ?"#)
+            .bind(is_replica)
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn set_masking(
+        &self,
+        id: &str,
+        rule_set: Option<&str>,
+        rules: Option<&str>,
+    ) -> AppResult<()> {
+        let masked_at = rule_set.map(|_| Utc::now().to_rfc3339());
+        sqlx::query(
+            r#"UPDATE databases SET masking_rule_set = ?, masking_rules = ?, masked_at = ? WHERE id = ?"#,
+        )
+        .bind(rule_set)
+        .bind(rules)
+        .bind(masked_at)
+        .bind(id)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn find_expired_branches(&self) -> AppResult<Vec<Database>> {
+        let now = Utc::now().to_rfc3339();
+        let query = format!(
+            "SELECT {} FROM databases WHERE expires_at IS NOT NULL AND expires_at < ? ORDER BY expires_at ASC",
+            DATABASE_COLUMNS
+        );
+        let databases = sqlx::query_as::<_, Database>(&query)
+            .bind(&now)
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(databases)
+    }
+
+    pub async fn find_expired_rollbacks(&self) -> AppResult<Vec<Database>> {
+        let now = Utc::now().to_rfc3339();
+        let query = format!(
+            "SELECT {} FROM databases WHERE rollback_expires_at IS NOT NULL AND rollback_expires_at < ?",
+            DATABASE_COLUMNS
+        );
+        let databases = sqlx::query_as::<_, Database>(&query)
+            .bind(&now)
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(databases)
+    }
+
+    pub async fn update_version(&self, id: &str, version: &str) -> AppResult<()> {
+        sqlx::query(
+            r#"UPDATE databases SET version = ?, updated_at = CURRENT_TIMESTAMP WHERE id = ?"#,
+        )
+        .bind(version)
+        .bind(id)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn set_available_update(
+        &self,
+        id: &str,
+        image: Option<&str>,
+        kind: Option<&str>,
+    ) -> AppResult<()> {
+        sqlx::query(r#"UPDATE databases SET update_image = ?, update_kind = ? WHERE id = ?"#)
+            .bind(image)
+            .bind(kind)
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn update_storage_usage(
+        &self,
+        id: &str,
+        used_bytes: i64,
+        state: &str,
+    ) -> AppResult<()> {
+        sqlx::query(
+            r#"UPDATE databases SET storage_used_bytes = ?, storage_state = ? WHERE id = ?"#,
+        )
+        .bind(used_bytes)
+        .bind(state)
+        .bind(id)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::models::BranchTreeNode;
+    use crate::repositories::test_pool;
+
+    async fn create(repo: &DatabaseRepository, name: &str, parent: Option<&str>) -> Database {
+        repo.create(
+            "project-1",
+            name,
+            "postgres",
+            "17",
+            1.0,
+            512,
+            1024,
+            false,
+            name,
+            parent.is_none(),
+            parent,
+        )
+        .await
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_promote_branch() {
+        let repo = DatabaseRepository::new(test_pool().await);
+        let root = create(&repo, "main", None).await;
+        let branch = create(&repo, "feature", Some(&root.id)).await;
+        let sibling = create(&repo, "other", Some(&root.id)).await;
+        repo.set_expires_at(&branch.id, Some("2030-01-01T00:00:00Z"))
+            .await
+            .unwrap();
+
+        repo.promote_branch(&branch.id, &root.id).await.unwrap();
+
+        let promoted = repo.find_by_id(&branch.id).await.unwrap().unwrap();
+        assert!(promoted.is_default_branch);
+        assert_eq!(promoted.parent_branch_id, None);
+        assert_eq!(promoted.expires_at, None);
+
+        let root = repo.find_by_id(&root.id).await.unwrap().unwrap();
+        assert!(!root.is_default_branch);
+        assert_eq!(root.parent_branch_id.as_deref(), Some(branch.id.as_str()));
+        let sibling = repo.find_by_id(&sibling.id).await.unwrap().unwrap();
+        assert_eq!(
+            sibling.parent_branch_id.as_deref(),
+            Some(branch.id.as_str())
+        );
+    }
+
+    #[tokio::test]
+    async fn test_branch_tree() {
+        let repo = DatabaseRepository::new(test_pool().await);
+        let root = create(&repo, "main", None).await;
+        let feature = create(&repo, "feature", Some(&root.id)).await;
+        create(&repo, "other", Some(&root.id)).await;
+        let nested = create(&repo, "nested", Some(&feature.id)).await;
+        create(&repo, "unrelated", None).await;
+
+        let tree = BranchTreeNode::build(&root, &repo.find_tree(&root.id).await.unwrap());
+        assert_eq!(tree.id, root.id);
+        assert!(tree.is_default);
+        let mut names: Vec<_> = tree.children.iter().map(|c| c.name.as_str()).collect();
+        names.sort();
+        assert_eq!(names, ["feature", "other"]);
+
+        let feature_node = tree.children.iter().find(|c| c.id == feature.id).unwrap();
+        assert_eq!(feature_node.children.len(), 1);
+        assert_eq!(feature_node.children[0].id, nested.id);
+        assert!(feature_node.children[0].children.is_empty());
+
+        let subtree = BranchTreeNode::build(&feature, &repo.find_tree(&feature.id).await.unwrap());
+        assert_eq!(subtree.children.len(), 1);
+        assert_eq!(subtree.children[0].id, nested.id);
+    }
+
+    #[tokio::test]
+    async fn test_reparent_children() {
+        let repo = DatabaseRepository::new(test_pool().await);
+        let root = create(&repo, "main", None).await;
+        let feature = create(&repo, "feature", Some(&root.id)).await;
+        let first = create(&repo, "first", Some(&feature.id)).await;
+        let second = create(&repo, "second", Some(&feature.id)).await;
+        let nested = create(&repo, "nested", Some(&first.id)).await;
+
+        repo.reparent_children(&feature.id, &root.id).await.unwrap();
+
+        assert!(repo.find_children(&feature.id).await.unwrap().is_empty());
+        let mut children: Vec<_> = repo
+            .find_children(&root.id)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|d| d.id)
+            .collect();
+        children.sort();
+        let mut expected = vec![feature.id.clone(), first.id.clone(), second.id.clone()];
+        expected.sort();
+        assert_eq!(children, expected);
+
+        // Only direct children move; deeper branches stay under their own parent.
+        let nested = repo.find_by_id(&nested.id).await.unwrap().unwrap();
+        assert_eq!(nested.parent_branch_id.as_deref(), Some(first.id.as_str()));
+    }
+
+    #[tokio::test]
+    async fn test_swap_endpoints() {
+        let repo = DatabaseRepository::new(test_pool().await);
+        let root = create(&repo, "main", None).await;
+        let branch = create(&repo, "feature", Some(&root.id)).await;
+        let root = repo
+            .update_container(&root.id, "c-1", "running", "localhost", 5433, "pw-root")
+            .await
+            .unwrap();
+        let branch = repo
+            .update_container(&branch.id, "c-2", "running", "localhost", 5434, "pw-branch")
+            .await
+            .unwrap();
+
+        repo.swap_endpoints(&root, &branch).await.unwrap();
+
+        let swapped_root = repo.find_by_id(&root.id).await.unwrap().unwrap();
+        let swapped_branch = repo.find_by_id(&branch.id).await.unwrap().unwrap();
+        assert_eq!(swapped_root.name, "feature");
+        assert_eq!(swapped_root.port, Some(5434));
+        assert_eq!(
+            swapped_root.password_encrypted.as_deref(),
+            Some("pw-branch")
+        );
+        assert_eq!(swapped_branch.name, "main");
+        assert_eq!(swapped_branch.port, Some(5433));
+        assert_eq!(
+            swapped_branch.password_encrypted.as_deref(),
+            Some("pw-root")
+        );
+
+        // Swapping the current rows again puts both back, which is how a failed move reverts.
+        repo.swap_endpoints(&swapped_root, &swapped_branch)
+            .await
+            .unwrap();
+        let reverted = repo.find_by_id(&root.id).await.unwrap().unwrap();
+        assert_eq!(reverted.name, "main");
+        assert_eq!(reverted.port, Some(5433));
+    }
+
+    #[tokio::test]
+    async fn test_detach_replica() {
+        let repo = DatabaseRepository::new(test_pool().await);
+        let root = create(&repo, "main", None).await;
+        let replica = create(&repo, "replica", Some(&root.id)).await;
+        repo.set_replica(&replica.id, true).await.unwrap();
+        assert!(
+            repo.find_by_id(&replica.id)
+                .await
+                .unwrap()
+                .unwrap()
+                .is_replica
+        );
+
+        repo.set_replica(&replica.id, false).await.unwrap();
+        repo.update_forked_at(&replica.id).await.unwrap();
+
+        let detached = repo.find_by_id(&replica.id).await.unwrap().unwrap();
+        assert!(!detached.is_replica);
+        assert!(detached.forked_at.is_some());
+        assert_eq!(detached.parent_branch_id.as_deref(), Some(root.id.as_str()));
+    }
 }
@@ -23,7 +23,10 @@ impl MetricsRepository {
         let id = Uuid::new_v4().to_string();
 
         match metrics {
-            UnifiedMetrics::Postgres(m) => {
+            UnifiedMetrics::Postgres(m)
+            | UnifiedMetrics::Mysql(m)
+            | UnifiedMetrics::Mariadb(m)
+            | UnifiedMetrics::Clickhouse(m) => {
                 sqlx::query(
                     r#"
                     INSERT INTO metrics_snapshots (
@@ -49,7 +52,7 @@ impl MetricsRepository {
                 .bind(m.resources.memory_percent)
                 .bind(m.resources.memory_used_bytes)
                 .bind(m.connections.active_connections)
-                .bind(m.storage.database_size_bytes)
+                .bind(m.storage.container_storage_bytes)
                 .execute(&self.pool)
                 .await?;
             },
@@ -86,7 +89,7 @@ impl MetricsRepository {
                 .bind(m.resources.memory_percent)
                 .bind(m.resources.memory_used_bytes)
                 .bind(m.clients.connected_clients)
-                .bind(m.memory.used_memory)
+                .bind(m.storage.container_storage_bytes)
                 .bind(m.keys.total_keys)
                 .bind(m.commands.keyspace_hits)
                 .bind(m.commands.keyspace_misses)
@@ -97,6 +100,36 @@ impl MetricsRepository {
                 .execute(&self.pool)
                 .await?;
             },
+            UnifiedMetrics::Mongodb(m) => {
+                sqlx::query(
+                    r#"
+                    INSERT INTO metrics_snapshots (
+                        id, database_id, database_type, timestamp,
+                        total_queries, queries_per_sec, avg_latency_ms,
+                        rows_read, rows_written,
+                        cpu_percent, memory_percent, memory_used_bytes,
+                        active_connections, storage_used_bytes
+                    )
+                    VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+                    "#,
+                )
+                .bind(&id)
+                .bind(database_id)
+                .bind(database_type)
+                .bind(&m.timestamp)
+                .bind(m.operations.total_operations)
+                .bind(m.operations.ops_per_sec)
+                .bind(m.operations.avg_latency_ms)
+                .bind(m.documents.returned)
+                .bind(m.documents.inserted + m.documents.updated + m.documents.deleted)
+                .bind(m.resources.cpu_percent)
+                .bind(m.resources.memory_percent)
+                .bind(m.resources.memory_used_bytes)
+                .bind(m.connections.active_connections)
+                .bind(m.storage.container_storage_bytes)
+                .execute(&self.pool)
+                .await?;
+            },
         }
 
         Ok(())
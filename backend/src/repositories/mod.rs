@@ -1,14 +1,20 @@
 mod audit_log;
+mod backup;
 mod database;
 mod metrics;
+mod operation;
 mod project;
+mod snapshot;
 mod token;
 mod user;
 
 pub use audit_log::AuditLogRepository;
+pub use backup::BackupRepository;
 pub use database::DatabaseRepository;
 pub use metrics::MetricsRepository;
+pub use operation::OperationRepository;
 pub use project::ProjectRepository;
+pub use snapshot::SnapshotRepository;
 use sqlx::sqlite::SqlitePool;
 pub use token::TokenRepository;
 pub use user::UserRepository;
@@ -21,6 +27,9 @@ pub struct Repositories {
     pub metrics: MetricsRepository,
     pub tokens: TokenRepository,
     pub audit_logs: AuditLogRepository,
+    pub backups: BackupRepository,
+    pub operations: OperationRepository,
+    pub snapshots: SnapshotRepository,
 }
 
 impl Repositories {
@@ -31,7 +40,30 @@ impl Repositories {
             databases: DatabaseRepository::new(pool.clone()),
             metrics: MetricsRepository::new(pool.clone()),
             tokens: TokenRepository::new(pool.clone()),
-            audit_logs: AuditLogRepository::new(pool),
+            audit_logs: AuditLogRepository::new(pool.clone()),
+            backups: BackupRepository::new(pool.clone()),
+            operations: OperationRepository::new(pool.clone()),
+            snapshots: SnapshotRepository::new(pool),
         }
     }
 }
+
+/// A migrated in-memory database for repository tests. Foreign keys are off so tests only
+/// insert the rows they are about.
+#[cfg(test)]
+pub(crate) async fn test_pool() -> SqlitePool {
+    use std::str::FromStr;
+
+    use sqlx::sqlite::{SqliteConnectOptions, SqlitePoolOptions};
+
+    let options = SqliteConnectOptions::from_str("sqlite::memory:")
+        .unwrap()
+        .foreign_keys(false);
+    let pool = SqlitePoolOptions::new()
+        .max_connections(1)
+        .connect_with(options)
+        .await
+        .unwrap();
+    sqlx::migrate!("./migrations").run(&pool).await.unwrap();
+    pool
+}
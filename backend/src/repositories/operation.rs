@@ -0,0 +1,272 @@
+use sqlx::sqlite::SqlitePool;
+use uuid::Uuid;
+
+use crate::domain::models::Operation;
+use crate::error::{AppError, AppResult};
+
+const OPERATION_COLUMNS: &str = r#"
+    id, database_id, kind, status, backup_id, target_database_id, error, phase,
+    progress_bytes, total_bytes, created_at, completed_at
+"#;
+
+#[derive(Clone)]
+pub struct OperationRepository {
+    pool: SqlitePool,
+}
+
+impl OperationRepository {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+
+    pub async fn create(
+        &self,
+        database_id: &str,
+        kind: &str,
+        backup_id: Option<&str>,
+        target_database_id: Option<&str>,
+    ) -> AppResult<Operation> {
+        let id = Uuid::new_v4().to_string();
+
+        sqlx::query(
+            r#"
+            INSERT INTO operations (id, database_id, kind, status, backup_id, target_database_id)
+            VALUES (?, ?, ?, 'running', ?, ?)
+            "#,
+        )
+        .bind(&id)
+        .bind(database_id)
+        .bind(kind)
+        .bind(backup_id)
+        .bind(target_database_id)
+        .execute(&self.pool)
+        .await?;
+
+        self.find_by_id(&id)
+            .await?
+            .ok_or_else(|| AppError::Internal("Failed to retrieve created operation".to_string()))
+    }
+
+    /// Creates a running operation unless the database already has a running operation or
+    /// backup. The check and the insert are one statement, so concurrent requests cannot both
+    /// start.
+    pub async fn start(
+        &self,
+        database_id: &str,
+        kind: &str,
+        backup_id: Option<&str>,
+        target_database_id: Option<&str>,
+    ) -> AppResult<Operation> {
+        let id = Uuid::new_v4().to_string();
+
+        let result = sqlx::query(
+            r#"
+            INSERT INTO operations (id, database_id, kind, status, backup_id, target_database_id)
+            SELECT ?, ?, ?, 'running', ?, ?
+            WHERE NOT EXISTS (
+                SELECT 1 FROM operations
+                WHERE (database_id = ? OR target_database_id = ?) AND status = 'running'
+            )
+            AND NOT EXISTS (
+                SELECT 1 FROM backups WHERE database_id = ? AND status = 'running'
+            )
+            "#,
+        )
+        .bind(&id)
+        .bind(database_id)
+        .bind(kind)
+        .bind(backup_id)
+        .bind(target_database_id)
+        .bind(database_id)
+        .bind(database_id)
+        .bind(database_id)
+        .execute(&self.pool)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::Conflict(
+                "Another operation is running for this database".to_string(),
+            ));
+        }
+
+        self.find_by_id(&id)
+            .await?
+            .ok_or_else(|| AppError::Internal("Failed to retrieve created operation".to_string()))
+    }
+
+    pub async fn find_by_id(&self, id: &str) -> AppResult<Option<Operation>> {
+        let query = format!("SELECT {} FROM operations WHERE id = ?", OPERATION_COLUMNS);
+        let operation = sqlx::query_as::<_, Operation>(&query)
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        Ok(operation)
+    }
+
+    pub async fn find_by_database_id(&self, database_id: &str) -> AppResult<Vec<Operation>> {
+        let query = format!(
+            "SELECT {} FROM operations WHERE database_id = ? OR target_database_id = ? \
+             ORDER BY created_at DESC, rowid DESC",
+            OPERATION_COLUMNS
+        );
+        let operations = sqlx::query_as::<_, Operation>(&query)
+            .bind(database_id)
+            .bind(database_id)
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(operations)
+    }
+
+    pub async fn has_running(&self, database_id: &str) -> AppResult<bool> {
+        let result: Option<(i32,)> = sqlx::query_as(
+            r#"
+            SELECT 1 FROM operations
+            WHERE (database_id = ? OR target_database_id = ?) AND status = 'running'
+            LIMIT 1
+            "#,
+        )
+        .bind(database_id)
+        .bind(database_id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(result.is_some())
+    }
+
+    pub async fn update_progress(
+        &self,
+        id: &str,
+        progress_bytes: i64,
+        total_bytes: Option<i64>,
+    ) -> AppResult<()> {
+        sqlx::query(
+            r#"
+            UPDATE operations
+            SET progress_bytes = ?, total_bytes = ?
+            WHERE id = ?
+            "#,
+        )
+        .bind(progress_bytes)
+        .bind(total_bytes)
+        .bind(id)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn set_phase(&self, id: &str, phase: &str) -> AppResult<()> {
+        sqlx::query("UPDATE operations SET phase = ? WHERE id = ?")
+            .bind(phase)
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Records how a background operation ended. Failures to record it are only logged, as
+    /// nobody is left to report them to.
+    pub async fn finish(&self, operation: &Operation, result: AppResult<()>) {
+        let recorded = match result {
+            Ok(()) => {
+                tracing::info!("Operation {} ({}) completed", operation.id, operation.kind);
+                self.mark_completed(&operation.id).await
+            },
+            Err(e) => {
+                tracing::error!(
+                    "Operation {} ({}) failed: {}",
+                    operation.id,
+                    operation.kind,
+                    e
+                );
+                self.mark_failed(&operation.id, &e.to_string()).await
+            },
+        };
+
+        if let Err(e) = recorded {
+            tracing::error!("Failed to record operation {}: {}", operation.id, e);
+        }
+    }
+
+    pub async fn mark_completed(&self, id: &str) -> AppResult<()> {
+        sqlx::query(
+            r#"
+            UPDATE operations
+            SET status = 'completed', error = NULL, completed_at = datetime('now')
+            WHERE id = ?
+            "#,
+        )
+        .bind(id)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn mark_failed(&self, id: &str, error: &str) -> AppResult<()> {
+        sqlx::query(
+            r#"
+            UPDATE operations
+            SET status = 'failed', error = ?, completed_at = datetime('now')
+            WHERE id = ?
+            "#,
+        )
+        .bind(error)
+        .bind(id)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn find_running(&self) -> AppResult<Vec<Operation>> {
+        let query = format!(
+            "SELECT {} FROM operations WHERE status = 'running'",
+            OPERATION_COLUMNS
+        );
+        let operations = sqlx::query_as::<_, Operation>(&query)
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(operations)
+    }
+
+    pub async fn fail_interrupted(&self) -> AppResult<u64> {
+        let result = sqlx::query(
+            r#"
+            UPDATE operations
+            SET status = 'failed', error = 'Interrupted by server restart',
+                completed_at = datetime('now')
+            WHERE status = 'running'
+            "#,
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::repositories::test_pool;
+
+    #[tokio::test]
+    async fn test_start_is_exclusive() {
+        let repo = OperationRepository::new(test_pool().await);
+
+        let first = repo.start("db-1", "restore", None, None).await.unwrap();
+        assert!(matches!(
+            repo.start("db-1", "upgrade", None, None).await,
+            Err(AppError::Conflict(_))
+        ));
+        assert!(repo.start("db-2", "upgrade", None, None).await.is_ok());
+
+        repo.mark_completed(&first.id).await.unwrap();
+        assert!(repo.start("db-1", "upgrade", None, None).await.is_ok());
+    }
+}
@@ -0,0 +1,111 @@
+use sqlx::sqlite::SqlitePool;
+use uuid::Uuid;
+
+use crate::domain::models::Snapshot;
+use crate::error::{AppError, AppResult};
+
+#[derive(Clone)]
+pub struct SnapshotRepository {
+    pool: SqlitePool,
+}
+
+impl SnapshotRepository {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+
+    /// Only records the snapshot; the caller takes it at the path derived from the returned ID.
+    pub async fn create(&self, database_id: &str, name: &str) -> AppResult<Snapshot> {
+        let id = Uuid::new_v4().to_string();
+
+        sqlx::query(r#"INSERT INTO snapshots (id, database_id, name) VALUES (?, ?, ?)"#)
+            .bind(&id)
+            .bind(database_id)
+            .bind(name)
+            .execute(&self.pool)
+            .await?;
+
+        self.find_by_id(&id)
+            .await?
+            .ok_or_else(|| AppError::Internal("Failed to retrieve created snapshot".to_string()))
+    }
+
+    pub async fn find_by_id(&self, id: &str) -> AppResult<Option<Snapshot>> {
+        let snapshot = sqlx::query_as::<_, Snapshot>(
+            r#"SELECT id, database_id, name, created_at FROM snapshots WHERE id = ?"#,
+        )
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(snapshot)
+    }
+
+    pub async fn find_by_name(&self, database_id: &str, name: &str) -> AppResult<Option<Snapshot>> {
+        let snapshot = sqlx::query_as::<_, Snapshot>(
+            r#"
+            SELECT id, database_id, name, created_at FROM snapshots
+            WHERE database_id = ? AND name = ?
+            "#,
+        )
+        .bind(database_id)
+        .bind(name)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(snapshot)
+    }
+
+    pub async fn find_by_database_id(&self, database_id: &str) -> AppResult<Vec<Snapshot>> {
+        let snapshots = sqlx::query_as::<_, Snapshot>(
+            r#"
+            SELECT id, database_id, name, created_at FROM snapshots
+            WHERE database_id = ?
+            ORDER BY created_at DESC, rowid DESC
+            "#,
+        )
+        .bind(database_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(snapshots)
+    }
+
+    pub async fn delete(&self, id: &str) -> AppResult<()> {
+        sqlx::query(r#"DELETE FROM snapshots WHERE id = ?"#)
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::repositories::test_pool;
+
+    #[tokio::test]
+    async fn test_snapshot_names_are_per_database() {
+        let repo = SnapshotRepository::new(test_pool().await);
+
+        let snapshot = repo.create("db-1", "before-migration").await.unwrap();
+        assert!(repo.create("db-1", "before-migration").await.is_err());
+        assert!(repo.create("db-2", "before-migration").await.is_ok());
+
+        let found = repo
+            .find_by_name("db-1", "before-migration")
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(found.id, snapshot.id);
+        assert_eq!(repo.find_by_database_id("db-1").await.unwrap().len(), 1);
+
+        repo.delete(&snapshot.id).await.unwrap();
+        assert!(repo
+            .find_by_name("db-1", "before-migration")
+            .await
+            .unwrap()
+            .is_none());
+    }
+}
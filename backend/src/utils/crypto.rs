@@ -0,0 +1,71 @@
+use aes_gcm::{
+    aead::{Aead, KeyInit},
+    Aes256Gcm, Nonce,
+};
+use rand::RngCore;
+
+use crate::error::{AppError, AppResult};
+
+/// Parses the hex `ENCRYPTION_KEY` setting. Panics on an invalid key, since no database
+/// password could be read without it.
+pub fn parse_encryption_key(encryption_key_hex: &str) -> [u8; 32] {
+    hex::decode(encryption_key_hex)
+        .expect("Invalid encryption key hex")
+        .try_into()
+        .expect("Encryption key must be 32 bytes")
+}
+
+/// Encrypts a database password as hex of the nonce followed by the AES-256-GCM ciphertext.
+pub fn encrypt_password(key: &[u8; 32], password: &str) -> AppResult<String> {
+    let cipher = Aes256Gcm::new_from_slice(key)
+        .map_err(|e| AppError::Internal(format!("Encryption init failed: {}", e)))?;
+
+    let mut nonce_bytes = [0u8; 12];
+    rand::thread_rng().fill_bytes(&mut nonce_bytes);
+    let nonce = Nonce::from_slice(&nonce_bytes);
+
+    let ciphertext = cipher
+        .encrypt(nonce, password.as_bytes())
+        .map_err(|e| AppError::Internal(format!("Encryption failed: {}", e)))?;
+
+    let mut result = nonce_bytes.to_vec();
+    result.extend(ciphertext);
+    Ok(hex::encode(result))
+}
+
+pub fn decrypt_password(key: &[u8; 32], encrypted: &str) -> AppResult<String> {
+    let data = hex::decode(encrypted)
+        .map_err(|e| AppError::Internal(format!("Invalid encrypted data: {}", e)))?;
+
+    if data.len() < 12 {
+        return Err(AppError::Internal("Encrypted data too short".to_string()));
+    }
+
+    let (nonce_bytes, ciphertext) = data.split_at(12);
+    let nonce = Nonce::from_slice(nonce_bytes);
+
+    let cipher = Aes256Gcm::new_from_slice(key)
+        .map_err(|e| AppError::Internal(format!("Decryption init failed: {}", e)))?;
+
+    let plaintext = cipher
+        .decrypt(nonce, ciphertext)
+        .map_err(|e| AppError::Internal(format!("Decryption failed: {}", e)))?;
+
+    String::from_utf8(plaintext)
+        .map_err(|e| AppError::Internal(format!("Invalid UTF-8 in password: {}", e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_password_roundtrip() {
+        let key = [7u8; 32];
+        let encrypted = encrypt_password(&key, "s3cret").unwrap();
+
+        assert_eq!(decrypt_password(&key, &encrypted).unwrap(), "s3cret");
+        assert!(decrypt_password(&[8u8; 32], &encrypted).is_err());
+        assert!(decrypt_password(&key, "abcd").is_err());
+    }
+}
@@ -57,7 +57,8 @@ export function BranchPanel({ databaseId, currentBranchId, onCreateBranch }: Bra
   });
 
   const deleteMutation = useMutation({
-    mutationFn: (branchId: string) => databasesApi.delete(branchId),
+    // A deleted branch hands its own branches to its parent instead of taking them along.
+    mutationFn: (branchId: string) => databasesApi.delete(branchId, "reparent"),
     onSuccess: (_, deletedBranchId) => {
       queryClient.invalidateQueries({ queryKey: ["branches"] });
       queryClient.invalidateQueries({ queryKey: ["databases"] });
@@ -251,7 +252,8 @@ function BranchRow({
                     <AlertDialogTitle>Delete Branch</AlertDialogTitle>
                     <AlertDialogDescription>
                       Are you sure you want to delete the branch "{branch.name}"? This action cannot
-                      be undone and all data will be permanently lost.
+                      be undone and all data will be permanently lost. Branches created from it move
+                      to its parent.
                     </AlertDialogDescription>
                   </AlertDialogHeader>
                   <AlertDialogFooter>
@@ -2,6 +2,7 @@ import { apiClient } from "./client";
 import type {
   BranchResponse,
   ChangePasswordRequest,
+  ChildBranchPolicy,
   CreateBranchRequest,
   CreateDatabaseRequest,
   DatabaseConfigResponse,
@@ -40,7 +41,8 @@ export const databasesApi = {
   update: (id: string, data: UpdateDatabaseRequest) =>
     apiClient.put<DatabaseResponse>(`/databases/${id}`, data),
 
-  delete: (id: string) => apiClient.delete<void>(`/databases/${id}`),
+  delete: (id: string, children?: ChildBranchPolicy) =>
+    apiClient.delete<void>(`/databases/${id}${children ? `?children=${children}` : ""}`),
 
   start: (id: string) => apiClient.post<DatabaseResponse>(`/databases/${id}/start`),
 
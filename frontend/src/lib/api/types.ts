@@ -99,9 +99,7 @@ export interface ConnectionInfo {
 export interface CreateDatabaseRequest {
   name: string;
   database_type?: DatabaseType;
-  postgres_version?: string;
-  valkey_version?: string;
-  redis_version?: string;
+  version?: string;
   password?: string;
   cpu_limit?: number;
   memory_limit_mb?: number;
@@ -134,8 +132,12 @@ export interface DatabaseResponse {
   project_id: string;
   name: string;
   database_type: DatabaseType;
-  postgres_version: string;
+  version: string;
+  /** @deprecated Use `version`. */
+  postgres_version?: string;
+  /** @deprecated Use `version`. */
   valkey_version?: string | null;
+  /** @deprecated Use `version`. */
   redis_version?: string | null;
   status: string;
   resources: ResourceLimits;
@@ -182,6 +184,9 @@ export interface BranchResponse {
   created_at: string;
 }
 
+/** What deleting a database does to its branches. The server refuses by default. */
+export type ChildBranchPolicy = "refuse" | "cascade" | "reparent";
+
 export interface CreateBranchRequest {
   name: string;
   include_data?: boolean;
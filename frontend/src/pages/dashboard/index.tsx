@@ -156,8 +156,8 @@ export function DashboardPage() {
                       <span className="font-medium font-mono text-sm">{db.name}</span>
                       <Badge variant="outline" className="text-[10px] h-5">
                         {db.database_type === "valkey"
-                          ? `Valkey ${db.valkey_version}`
-                          : `PG ${db.postgres_version}`}
+                          ? `Valkey ${db.version}`
+                          : `PG ${db.version}`}
                       </Badge>
                     </div>
                     <p className="text-xs text-muted-foreground">
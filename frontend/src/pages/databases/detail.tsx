@@ -17,6 +17,7 @@ import { Card, CardContent } from "@/components/ui/card";
 import { Progress } from "@/components/ui/progress";
 import { Skeleton } from "@/components/ui/skeleton";
 import { Spinner } from "@/components/ui/spinner";
+import { Switch } from "@/components/ui/switch";
 import { Tabs, TabsContent, TabsList, TabsTrigger } from "@/components/ui/tabs";
 import { useMetricsStream } from "@/hooks/use-metrics-stream";
 import { databasesApi, getErrorMessage, projectsApi } from "@/lib/api";
@@ -90,6 +91,7 @@ export function DatabaseDetailPage() {
   const queryClient = useQueryClient();
   const [terminalType, setTerminalType] = React.useState<TerminalType>("psql");
   const [isCreateBranchOpen, setIsCreateBranchOpen] = React.useState(false);
+  const [deleteBranches, setDeleteBranches] = React.useState(false);
   const openCreateBranch = React.useCallback(() => setIsCreateBranchOpen(true), []);
 
   const {
@@ -119,6 +121,13 @@ export function DatabaseDetailPage() {
     enabled: !!database?.branch?.parent_id,
   });
 
+  // Lists the root and its direct branches, which is all the delete dialog needs for a root.
+  const { data: branches } = useQuery({
+    queryKey: ["branches", id],
+    queryFn: () => (id ? databasesApi.listBranches(id) : Promise.reject()),
+    enabled: !!id,
+  });
+
   const { metrics: realtimeMetrics } = useMetricsStream(id ?? "", {
     enabled: !!id && database?.status === "running",
   });
@@ -203,7 +212,13 @@ export function DatabaseDetailPage() {
   const deleteMutation = useMutation({
     mutationFn: () => {
       if (!id) return Promise.reject(new Error("Database ID is required"));
-      return databasesApi.delete(id);
+      // A branch hands its own branches to its parent; a root only takes them along when asked.
+      const children = database?.branch?.parent_id
+        ? "reparent"
+        : deleteBranches
+          ? "cascade"
+          : "refuse";
+      return databasesApi.delete(id, children);
     },
     onSuccess: () => {
       const projectId = database?.project_id;
@@ -233,6 +248,7 @@ export function DatabaseDetailPage() {
     onError: (err) => toast.error(getErrorMessage(err, "Failed to sync from parent")),
   });
 
+  const childCount = branches?.filter((b) => b.parent_id === id).length ?? 0;
   const isRunning = database?.status === "running";
   const isTransitioning = database?.status === "starting" || database?.status === "stopping";
   const isActionLoading =
@@ -390,10 +406,10 @@ export function DatabaseDetailPage() {
           <p className="text-muted-foreground mt-1">
             <span className="font-mono">
               {database.database_type === "valkey"
-                ? `Valkey ${database.valkey_version}`
+                ? `Valkey ${database.version}`
                 : database.database_type === "redis"
-                  ? `Redis ${database.redis_version}`
-                  : `PostgreSQL ${database.postgres_version}`}
+                  ? `Redis ${database.version}`
+                  : `PostgreSQL ${database.version}`}
             </span>
             {project && (
               <>
@@ -495,12 +511,25 @@ export function DatabaseDetailPage() {
                 <AlertDialogDescription>
                   Are you sure you want to delete "{database.name}"? This action cannot be undone
                   and all data will be permanently lost.
+                  {database.branch?.parent_id
+                    ? " Branches created from it move to its parent."
+                    : childCount > 0 &&
+                      ` It has ${childCount} branch${childCount !== 1 ? "es" : ""}, which must be deleted with it.`}
                 </AlertDialogDescription>
               </AlertDialogHeader>
+              {!database.branch?.parent_id && childCount > 0 && (
+                <div className="flex items-center justify-between gap-4 rounded-lg border p-3">
+                  <span className="text-sm font-medium">
+                    Delete its {childCount} branch{childCount !== 1 ? "es" : ""} too
+                  </span>
+                  <Switch checked={deleteBranches} onCheckedChange={setDeleteBranches} />
+                </div>
+              )}
               <AlertDialogFooter>
                 <AlertDialogCancel>Cancel</AlertDialogCancel>
                 <AlertDialogAction
                   onClick={() => deleteMutation.mutate()}
+                  disabled={!database.branch?.parent_id && childCount > 0 && !deleteBranches}
                   className="bg-destructive text-destructive-foreground hover:bg-destructive/90"
                 >
                   {deleteMutation.isPending ? <Spinner className="size-4" /> : "Delete"}
@@ -30,6 +30,7 @@ import { Skeleton } from "@/components/ui/skeleton";
 import { Spinner } from "@/components/ui/spinner";
 import { Switch } from "@/components/ui/switch";
 import {
+  type ChildBranchPolicy,
   type DatabaseResponse,
   type DatabaseType,
   databasesApi,
@@ -69,7 +70,8 @@ const statusDots: Record<string, string> = {
 function DatabaseRow({
   database,
   isChild = false,
-}: { database: DatabaseResponse; isChild?: boolean }) {
+  branchCount = 0,
+}: { database: DatabaseResponse; isChild?: boolean; branchCount?: number }) {
   const queryClient = useQueryClient();
 
   const startMutation = useMutation({
@@ -129,7 +131,8 @@ function DatabaseRow({
   });
 
   const deleteMutation = useMutation({
-    mutationFn: () => databasesApi.delete(database.id),
+    // A branch hands its own branches to its parent; a root only takes them along when asked.
+    mutationFn: (children: ChildBranchPolicy) => databasesApi.delete(database.id, children),
     onSuccess: () => {
       queryClient.invalidateQueries({ queryKey: ["databases", database.project_id] });
       queryClient.invalidateQueries({ queryKey: ["projects"] });
@@ -192,10 +195,10 @@ function DatabaseRow({
             <span>·</span>
             <span className="font-mono">
               {database.database_type === "valkey"
-                ? `Valkey ${database.valkey_version}`
+                ? `Valkey ${database.version}`
                 : database.database_type === "redis"
-                  ? `Redis ${database.redis_version}`
-                  : `PostgreSQL ${database.postgres_version}`}
+                  ? `Redis ${database.version}`
+                  : `PostgreSQL ${database.version}`}
             </span>
           </div>
         </div>
@@ -256,7 +259,7 @@ function DatabaseRow({
             <DropdownMenuItem
               onClick={(e) => {
                 e.preventDefault();
-                deleteMutation.mutate();
+                deleteMutation.mutate(database.branch?.parent_id ? "reparent" : "refuse");
               }}
               className="text-destructive focus:text-destructive"
               disabled={deleteMutation.isPending}
@@ -264,6 +267,19 @@ function DatabaseRow({
               <HugeiconsIcon icon={Delete01Icon} className="size-3.5" strokeWidth={2} />
               Delete
             </DropdownMenuItem>
+            {!database.branch?.parent_id && branchCount > 0 && (
+              <DropdownMenuItem
+                onClick={(e) => {
+                  e.preventDefault();
+                  deleteMutation.mutate("cascade");
+                }}
+                className="text-destructive focus:text-destructive"
+                disabled={deleteMutation.isPending}
+              >
+                <HugeiconsIcon icon={Delete01Icon} className="size-3.5" strokeWidth={2} />
+                Delete with branches
+              </DropdownMenuItem>
+            )}
           </DropdownMenuContent>
         </DropdownMenu>
 
@@ -286,7 +302,7 @@ function DatabaseGroup({
 }) {
   return (
     <div className="space-y-2">
-      <DatabaseRow database={main} />
+      <DatabaseRow database={main} branchCount={branches.length} />
       {branches.map((branch) => (
         <DatabaseRow key={branch.id} database={branch} isChild />
       ))}
@@ -344,9 +360,7 @@ export function ProjectDetailPage() {
     mutationFn: (data: {
       name: string;
       database_type?: DatabaseType;
-      postgres_version?: string;
-      valkey_version?: string;
-      redis_version?: string;
+      version?: string;
       password?: string;
       public_exposed?: boolean;
     }) => {
@@ -374,12 +388,7 @@ export function ProjectDetailPage() {
     createMutation.mutate({
       name: formData.get("name") as string,
       database_type: databaseType,
-      postgres_version:
-        databaseType === "postgres" ? (formData.get("postgres_version") as string) : undefined,
-      valkey_version:
-        databaseType === "valkey" ? (formData.get("valkey_version") as string) : undefined,
-      redis_version:
-        databaseType === "redis" ? (formData.get("redis_version") as string) : undefined,
+      version: (formData.get(`${databaseType}_version`) as string | null) || undefined,
       password: password || undefined,
       public_exposed: publicExposed,
     });